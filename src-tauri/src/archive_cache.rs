@@ -0,0 +1,256 @@
+// archive_cache.rs 模块 - 按校验和保存解压成功的压缩包，重新安装同一地图时直接使用缓存而不重新下载
+//
+// 压缩包以 SHA-256 命名保存在下载缓存目录的 archives 子目录中，index.json 记录下载URL到校验和的映射，
+// 多个URL指向同一压缩包时只保存一份。缓存按 cache_retention_days 和 archive_cache_max_mb 清理。
+
+// 标准库导入
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// 内部模块导入
+use crate::{
+    cancellation::CancellationToken, dir_manager::get_global_cache_dir, log_debug, log_info,
+    log_warn,
+};
+
+/// 缓存目录名（位于下载缓存目录下）
+pub(crate) const ARCHIVE_CACHE_DIR: &str = "archives";
+
+/// 缓存索引文件名
+const INDEX_FILE: &str = "index.json";
+
+lazy_static! {
+    /// 保护缓存目录和索引文件的读写
+    static ref ARCHIVE_CACHE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// 缓存索引中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedArchive {
+    /// 压缩包的 SHA-256（十六进制小写）
+    sha256: String,
+    /// 缓存目录中的文件名（"<sha256>.<扩展名>"）
+    file_name: String,
+}
+
+/// 获取缓存目录，不存在时创建
+fn cache_dir() -> Result<PathBuf, String> {
+    let dir = get_global_cache_dir()?.join(ARCHIVE_CACHE_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("创建压缩包缓存目录失败: {}", e))?;
+    Ok(dir)
+}
+
+/// 读取缓存索引：下载URL -> 缓存记录
+fn read_index(dir: &Path) -> HashMap<String, CachedArchive> {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 写入缓存索引
+fn write_index(dir: &Path, index: &HashMap<String, CachedArchive>) {
+    let result = serde_json::to_string_pretty(index)
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs::write(dir.join(INDEX_FILE), content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log_warn!("保存压缩包缓存索引失败: {}", e);
+    }
+}
+
+/// 计算文件的 SHA-256
+fn file_sha256(path: &Path) -> Result<String, String> {
+    let file = fs::File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut BufReader::new(file), &mut hasher).map_err(|e| format!("读取文件失败: {}", e))?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// 更新文件的修改时间，缓存清理时按修改时间判断最近是否使用过
+fn touch(path: &Path) {
+    let result = fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(e) = result {
+        log_debug!("更新缓存文件修改时间失败: {}, 错误: {}", path.display(), e);
+    }
+}
+
+/// 压缩包缓存是否启用
+pub fn is_enabled() -> bool {
+    crate::settings::current_settings().archive_cache_enabled
+}
+
+/// 文件是否位于压缩包缓存目录中（解压完成后不删除）
+pub fn is_cached_file(path: &Path) -> bool {
+    let Ok(dir) = get_global_cache_dir().map(|dir| dir.join(ARCHIVE_CACHE_DIR)) else {
+        return false;
+    };
+    path.parent().is_some_and(|parent| parent == dir)
+}
+
+/// 查找URL对应的缓存压缩包，文件不存在或校验和不一致时删除记录
+///
+/// # 参数
+/// - `url`: 下载URL
+///
+/// # 返回值
+/// - 可以直接解压的缓存文件路径
+pub fn lookup(url: &str) -> Option<PathBuf> {
+    if !is_enabled() {
+        return None;
+    }
+
+    let _guard = ARCHIVE_CACHE_LOCK.lock().ok()?;
+    let dir = cache_dir().ok()?;
+    let mut index = read_index(&dir);
+    let entry = index.get(url)?.clone();
+
+    let path = dir.join(&entry.file_name);
+    match file_sha256(&path) {
+        Ok(sha256) if sha256 == entry.sha256 => {
+            log_info!("使用缓存的压缩包: {} -> {}", url, path.display());
+            touch(&path);
+            crate::telemetry::record("archive_cache.hit");
+            Some(path)
+        }
+        result => {
+            log_warn!(
+                "缓存的压缩包不可用，重新下载: {}, {}",
+                path.display(),
+                result.err().unwrap_or_else(|| "校验和不一致".to_string())
+            );
+            let _ = fs::remove_file(&path);
+            index.retain(|_, cached| cached.file_name != entry.file_name);
+            write_index(&dir, &index);
+            None
+        }
+    }
+}
+
+/// 将解压成功的压缩包移动到缓存目录，已有相同内容的缓存时删除该文件
+///
+/// # 参数
+/// - `url`: 下载URL
+/// - `file_path`: 压缩包路径
+///
+/// # 返回值
+/// - 成功时返回缓存文件路径
+pub fn store(url: &str, file_path: &Path) -> Result<PathBuf, String> {
+    let guard = ARCHIVE_CACHE_LOCK
+        .lock()
+        .map_err(|e| format!("无法锁定压缩包缓存: {:?}", e))?;
+    let dir = cache_dir()?;
+
+    let sha256 = file_sha256(file_path)?;
+    let file_name = match file_path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}.{}", sha256, ext.to_lowercase()),
+        None => sha256.clone(),
+    };
+    let target = dir.join(&file_name);
+
+    if target != file_path {
+        if target.exists() {
+            fs::remove_file(file_path).map_err(|e| format!("删除临时文件失败: {}", e))?;
+            touch(&target);
+        } else {
+            // 下载目录和缓存目录可能不在同一磁盘
+            crate::file_mover::move_path(file_path, &target, &CancellationToken::new(), |_, _| {})
+                .map_err(|e| format!("移动文件到缓存失败: {}", e))?;
+        }
+    }
+
+    let mut index = read_index(&dir);
+    index.insert(url.to_string(), CachedArchive { sha256, file_name });
+    write_index(&dir, &index);
+    log_info!("压缩包已加入缓存: {} -> {}", url, target.display());
+    drop(guard);
+
+    let settings = crate::settings::current_settings();
+    enforce_limits(settings.cache_retention_days, settings.archive_cache_max_mb);
+    Ok(target)
+}
+
+/// 按保留天数和大小上限清理压缩包缓存 - 先删除过期的文件，总大小仍超过上限时从最久未使用的开始删除
+///
+/// # 参数
+/// - `retention_days`: 保留天数
+/// - `max_mb`: 缓存总大小上限（MB），为 0 时不限制
+///
+/// # 返回值
+/// - 删除的文件数
+pub fn enforce_limits(retention_days: u32, max_mb: u64) -> usize {
+    let Ok(_guard) = ARCHIVE_CACHE_LOCK.lock() else {
+        return 0;
+    };
+    let Ok(dir) = cache_dir() else {
+        return 0;
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log_warn!("读取压缩包缓存目录失败: {}", e);
+            return 0;
+        }
+    };
+
+    // (文件名, 大小, 修改时间)，按修改时间从旧到新排列
+    let mut files = entries
+        .flatten()
+        .filter(|entry| entry.file_name() != INDEX_FILE)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                metadata.len(),
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            ))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by_key(|(_, _, modified)| *modified);
+
+    let retention = Duration::from_secs(retention_days as u64 * 24 * 60 * 60);
+    let max_bytes = max_mb.saturating_mul(1024 * 1024);
+    let mut total = files.iter().map(|(_, size, _)| size).sum::<u64>();
+    let mut removed = Vec::new();
+    for (file_name, size, modified) in &files {
+        let expired = modified.elapsed().is_ok_and(|age| age >= retention);
+        if !expired && (max_bytes == 0 || total <= max_bytes) {
+            continue;
+        }
+        match fs::remove_file(dir.join(file_name)) {
+            Ok(_) => {
+                log_info!("删除缓存的压缩包: {}", file_name);
+                total -= size;
+                removed.push(file_name.clone());
+            }
+            Err(e) => log_warn!("删除缓存的压缩包失败: {}, 错误: {}", file_name, e),
+        }
+    }
+
+    // 删除已不存在的文件对应的索引记录
+    let mut index = read_index(&dir);
+    let before = index.len();
+    index.retain(|_, cached| dir.join(&cached.file_name).is_file());
+    if index.len() != before {
+        write_index(&dir, &index);
+    }
+
+    removed.len()
+}
@@ -0,0 +1,195 @@
+// aria2_instance.rs 模块 - 跨实例协调 aria2c 守护进程
+//
+// 使用应用数据目录下的锁文件保证同一时间只有一个进程在创建 aria2c，并把当前 aria2c 的信息（LAST_ARIA2_INFO）
+// 写入同目录的 aria2c.json。即使单实例插件被绕过，多个进程也能据此判断守护进程属于哪个实例，保证只有一个 aria2c 被管理。
+// 应用崩溃或被强制结束后 aria2c 可能仍在运行，重新启动应用时校验进程和RPC密钥后直接接管它和其中的下载任务。
+
+// 标准库导入
+use std::{
+    fs::{File, OpenOptions},
+    os::windows::fs::OpenOptionsExt,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::Manager;
+
+// 内部模块导入
+use crate::{init::GLOBAL_APP_HANDLE, log_debug, log_error, log_warn};
+
+/// 锁文件名
+const LOCK_FILE_NAME: &str = "aria2c.lock";
+
+/// 记录 aria2c 信息的文件名
+const INFO_FILE_NAME: &str = "aria2c.json";
+
+/// 记录下载任务与 aria2c 任务对应关系的文件名
+const TRANSFERS_FILE_NAME: &str = "aria2c_transfers.json";
+
+/// 等待其他实例释放锁的最长时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Windows 错误码：文件被其他进程以独占方式打开
+const ERROR_SHARING_VIOLATION: i32 = 32;
+
+/// aria2c 守护进程信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Aria2Info {
+    /// aria2c 进程ID
+    pub pid: u32,
+    /// RPC 端口
+    pub port: u16,
+    /// RPC 密钥
+    pub secret: String,
+    /// 启动并管理该 aria2c 的应用进程ID
+    pub owner_pid: u32,
+}
+
+lazy_static! {
+    /// 当前实例最近一次启动的 aria2c 信息，与 aria2c.json 中的记录保持一致
+    pub static ref LAST_ARIA2_INFO: Mutex<Option<Aria2Info>> = Mutex::new(None);
+}
+
+/// aria2c 启动锁 - 持有期间其他进程无法获取，释放（drop）时自动解锁
+pub struct SpawnLock {
+    _file: File,
+}
+
+/// 获取保存锁文件和 aria2c 信息的目录 - 使用应用数据目录，与数据目录配置无关，保证所有实例使用同一位置
+fn state_dir() -> PathBuf {
+    GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| {
+            guard
+                .as_ref()
+                .and_then(|handle| handle.path().app_local_data_dir().ok())
+        })
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// 获取 aria2c 启动锁，其他实例持有锁时等待，超时返回错误
+///
+/// 锁文件以不共享的方式打开，进程退出（包括崩溃）时系统会自动关闭句柄并释放锁
+///
+/// # 返回值
+/// - 成功时返回锁，失败时返回错误信息
+pub fn acquire_spawn_lock() -> Result<SpawnLock, String> {
+    let dir = state_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    let lock_path = dir.join(LOCK_FILE_NAME);
+
+    let start_time = Instant::now();
+    loop {
+        match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .share_mode(0)
+            .open(&lock_path)
+        {
+            Ok(file) => {
+                log_debug!("已获取aria2c启动锁: {}", lock_path.display());
+                return Ok(SpawnLock { _file: file });
+            }
+            Err(e) if e.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => {
+                if start_time.elapsed() >= LOCK_TIMEOUT {
+                    return Err("等待其他实例释放aria2c启动锁超时".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => return Err(format!("获取aria2c启动锁失败: {}", e)),
+        }
+    }
+}
+
+/// 读取记录的 aria2c 信息，需要在持有启动锁时调用
+///
+/// # 返回值
+/// - 有记录时返回Some(Aria2Info)，没有记录或记录无法解析时返回None
+pub fn read_aria2_info() -> Option<Aria2Info> {
+    let content = std::fs::read_to_string(state_dir().join(INFO_FILE_NAME)).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| log_warn!("解析aria2c信息失败: {}", e))
+        .ok()
+}
+
+/// 记录当前启动的 aria2c 信息，需要在持有启动锁时调用
+///
+/// # 参数
+/// - `info`: aria2c 信息
+pub fn save_aria2_info(info: Aria2Info) {
+    let info_path = state_dir().join(INFO_FILE_NAME);
+    match serde_json::to_string_pretty(&info) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&info_path, content) {
+                log_error!("保存aria2c信息失败: {}", e);
+            }
+        }
+        Err(e) => log_error!("序列化aria2c信息失败: {}", e),
+    }
+
+    if let Ok(mut last_info) = LAST_ARIA2_INFO.lock() {
+        *last_info = Some(info);
+    }
+}
+
+/// aria2c 关闭后清除记录 - 只清除属于指定进程的记录，避免误删其他实例的记录
+///
+/// # 参数
+/// - `pid`: 已关闭的 aria2c 进程ID
+pub fn clear_aria2_info(pid: u32) {
+    if let Ok(mut last_info) = LAST_ARIA2_INFO.lock() {
+        if last_info.as_ref().is_some_and(|info| info.pid == pid) {
+            *last_info = None;
+        }
+    }
+
+    let _lock = match acquire_spawn_lock() {
+        Ok(lock) => lock,
+        Err(e) => {
+            log_warn!("清除aria2c信息时{}", e);
+            return;
+        }
+    };
+
+    if read_aria2_info().is_some_and(|info| info.pid == pid && info.owner_pid == std::process::id())
+    {
+        if let Err(e) = std::fs::remove_file(state_dir().join(INFO_FILE_NAME)) {
+            log_warn!("删除aria2c信息失败: {}", e);
+        }
+    }
+}
+
+/// 读取保存的下载任务与 aria2c 任务的对应关系，接管上次运行时的 aria2c 时使用
+///
+/// # 返回值
+/// - 有记录时返回Some，没有记录或记录无法解析时返回None
+pub fn read_transfers<T: DeserializeOwned>() -> Option<T> {
+    let content = std::fs::read_to_string(state_dir().join(TRANSFERS_FILE_NAME)).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| log_warn!("解析aria2c任务记录失败: {}", e))
+        .ok()
+}
+
+/// 保存下载任务与 aria2c 任务（URL、保存位置、GID）的对应关系，aria2c 被重新启动后据此重新添加任务
+///
+/// # 参数
+/// - `transfers`: 任务ID -> aria2c 任务信息
+pub fn save_transfers<T: Serialize>(transfers: &T) {
+    let path = state_dir().join(TRANSFERS_FILE_NAME);
+    match serde_json::to_string_pretty(transfers) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&path, content) {
+                log_warn!("保存aria2c任务记录失败: {}", e);
+            }
+        }
+        Err(e) => log_error!("序列化aria2c任务记录失败: {}", e),
+    }
+}
@@ -10,8 +10,8 @@ use std::{
     path::PathBuf,
     process::{Child, Command, Stdio},
     sync::{
-        atomic::{AtomicBool, Ordering},
-        mpsc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
     },
     time::Duration,
 };
@@ -22,16 +22,28 @@ use lazy_static::lazy_static;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use tokio::runtime::Runtime;
+use tracing::Instrument;
 use uuid::Uuid;
 
 // 内部模块导入
 use crate::{
-    commands::refresh_download_queue, init::is_app_shutting_down, log_debug, log_error, log_info,
-    log_utils::redirect_process_output, log_warn, utils::get_file_name,
+    aria2_instance::{self, Aria2Info},
+    commands::refresh_download_queue,
+    host_adapter,
+    init::is_app_shutting_down,
+    log_debug, log_error, log_info,
+    log_utils::{redirect_process_output, LogLevel, ProgressVerbosity},
+    log_warn,
+    process_priority::ProcessPriority,
+    tr,
+    utils::{get_file_name, sanitize_file_name},
 };
 
+/// 上次运行时保存的任务在 aria2c 中的信息：(GID, 临时文件名)
+type RestoredTransfer = (Option<String>, Option<String>);
+
 // 全局状态管理
 lazy_static! {
     /// 用于存储运行中的aria2c进程的ID，确保进程跟踪和管理
@@ -43,13 +55,176 @@ lazy_static! {
     /// aria2c RPC管理器单例 - 全局可访问，但只在需要时创建
     pub static ref ARIA2_RPC_MANAGER: Mutex<Option<Aria2RpcManager>> = Mutex::new(None);
 
-    /// 用于存储取消下载请求的任务ID及其原因
-    pub static ref CANCEL_DOWNLOAD_REQUESTS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+    /// 是否已暂停所有下载
+    static ref DOWNLOADS_PAUSED: AtomicBool = AtomicBool::new(false);
+
+    /// 进行中的下载任务在 aria2c 中的信息：任务ID -> aria2c 任务
+    static ref ARIA2_TRANSFERS: Mutex<HashMap<String, Aria2Transfer>> = Mutex::new(HashMap::new());
+
+    /// 上次运行时保存的任务在 aria2c 中的信息：任务ID -> (GID, 临时文件名)
+    static ref RESTORED_TRANSFERS: Mutex<HashMap<String, RestoredTransfer>> =
+        Mutex::new(HashMap::new());
 
     /// aria2c.exe路径常量
     pub static ref ARIA2C_PATH: PathBuf = crate::get_assets_path("bin/aria2c.exe").expect("无法获取aria2c.exe路径");
 }
 
+/// 适配器没有指定 User-Agent 时使用的默认值
+pub(crate) const DEFAULT_USER_AGENT: &str = "pan.baidu.com";
+
+/// aria2c 的启动次数，每次创建新的RPC管理器时加一，用于发现 aria2c 在任务添加后被重新启动
+static ENGINE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 下载任务在 aria2c 中的信息 - aria2c 被重新启动后旧的 GID 不再存在，使用相同的参数重新添加任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Aria2Transfer {
+    /// 下载链接
+    url: String,
+    /// 保存目录
+    dir: String,
+    /// 保存的文件名
+    filename: String,
+    /// 当前的 GID
+    gid: String,
+    /// 添加任务时 aria2c 的启动次数
+    generation: u64,
+}
+
+/// 下载监控持有的任务记录，监控结束（包括提前返回）时自动删除
+struct TransferRegistration {
+    task_id: String,
+}
+
+impl TransferRegistration {
+    fn new(task_id: &str, url: &str, dir: &str, filename: &str, gid: &str) -> Self {
+        if let Ok(mut transfers) = ARIA2_TRANSFERS.lock() {
+            transfers.insert(
+                task_id.to_string(),
+                Aria2Transfer {
+                    url: url.to_string(),
+                    dir: dir.to_string(),
+                    filename: filename.to_string(),
+                    gid: gid.to_string(),
+                    generation: ENGINE_GENERATION.load(Ordering::SeqCst),
+                },
+            );
+            aria2_instance::save_transfers(&*transfers);
+        }
+        TransferRegistration {
+            task_id: task_id.to_string(),
+        }
+    }
+
+    /// 任务被重新添加后记录新的 GID
+    fn update_gid(&self, gid: &str, generation: u64) {
+        if let Ok(mut transfers) = ARIA2_TRANSFERS.lock() {
+            if let Some(transfer) = transfers.get_mut(&self.task_id) {
+                transfer.gid = gid.to_string();
+                transfer.generation = generation;
+            }
+            aria2_instance::save_transfers(&*transfers);
+        }
+    }
+
+    /// 任务换用其他下载地址（例如备用镜像）重新添加后记录新的地址和 GID
+    fn switch_url(&self, url: &str, gid: &str, generation: u64) {
+        if let Ok(mut transfers) = ARIA2_TRANSFERS.lock() {
+            if let Some(transfer) = transfers.get_mut(&self.task_id) {
+                transfer.url = url.to_string();
+                transfer.gid = gid.to_string();
+                transfer.generation = generation;
+            }
+            aria2_instance::save_transfers(&*transfers);
+        }
+    }
+
+    /// aria2c 在任务添加后被重新启动时，使用原来的参数重新添加任务（continue=true，继续已下载的部分）
+    ///
+    /// # 返回值
+    /// - 重新添加成功时返回Ok(Some(新GID))，aria2c 没有被重新启动时返回Ok(None)
+    async fn reattach_if_restarted(&self) -> Result<Option<String>, String> {
+        let generation = ENGINE_GENERATION.load(Ordering::SeqCst);
+        let transfer = ARIA2_TRANSFERS
+            .lock()
+            .ok()
+            .and_then(|transfers| transfers.get(&self.task_id).cloned())
+            .filter(|transfer| transfer.generation != generation);
+        let Some(transfer) = transfer else {
+            return Ok(None);
+        };
+
+        let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+            .and_then(|guard| guard.clone())
+            .ok_or_else(|| "RPC管理器未初始化".to_string())?;
+        let gid = manager
+            .add_download(&transfer.url, &transfer.dir, &transfer.filename)
+            .await?;
+        log_info!(
+            "[{}] aria2c已重新启动，重新添加任务: {} -> {}",
+            self.task_id,
+            transfer.gid,
+            gid
+        );
+        self.update_gid(&gid, generation);
+        Ok(Some(gid))
+    }
+}
+
+/// 获取任务当前在 aria2c 中的 GID 和临时文件名，保存下载队列时记录
+///
+/// # 参数
+/// - `task_id`: 任务ID
+///
+/// # 返回值
+/// - 任务正在 aria2c 中下载时返回 (GID, 临时文件名)
+pub fn transfer_state(task_id: &str) -> Option<(String, String)> {
+    ARIA2_TRANSFERS
+        .lock()
+        .ok()?
+        .get(task_id)
+        .map(|transfer| (transfer.gid.clone(), transfer.filename.clone()))
+}
+
+/// 记录上次运行时任务在 aria2c 中的 GID 和临时文件名，任务重新开始时优先继续同一个下载
+///
+/// # 参数
+/// - `task_id`: 任务ID
+/// - `gid`: 上次的 GID
+/// - `filename`: 上次的临时文件名
+pub fn restore_transfer(task_id: &str, gid: Option<String>, filename: Option<String>) {
+    if gid.is_none() && filename.is_none() {
+        return;
+    }
+    if let Ok(mut restored) = RESTORED_TRANSFERS.lock() {
+        restored.insert(task_id.to_string(), (gid, filename));
+    }
+}
+
+/// 读取上次运行时保存的 aria2c 任务记录 - 接管上次的 aria2c 后，下载队列中没有记录 GID 的任务（应用崩溃时队列可能没有保存）也能继续原来的任务
+fn restore_persisted_transfers() {
+    let Some(transfers) = aria2_instance::read_transfers::<HashMap<String, Aria2Transfer>>() else {
+        return;
+    };
+    if let Ok(mut restored) = RESTORED_TRANSFERS.lock() {
+        for (task_id, transfer) in transfers {
+            restored
+                .entry(task_id)
+                .or_insert((Some(transfer.gid), Some(transfer.filename)));
+        }
+    }
+}
+
+impl Drop for TransferRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut transfers) = ARIA2_TRANSFERS.lock() {
+            if transfers.remove(&self.task_id).is_some() {
+                aria2_instance::save_transfers(&*transfers);
+            }
+        }
+    }
+}
+
 // 下载状态结构体
 #[derive(Debug)]
 struct DownloadStatus {
@@ -61,6 +236,18 @@ struct DownloadStatus {
     download_speed: u64,
 }
 
+/// 下载任务在一个服务器上的连接统计，由 aria2.getServers 返回的各个连接按主机名汇总
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerStats {
+    /// 服务器主机名
+    host: String,
+    /// 连接到该服务器的连接数
+    connections: u32,
+    /// 从该服务器下载的速度（字节/秒）
+    download_speed: u64,
+}
+
 // 辅助函数：尝试在指定时间内获取锁，如果超时则返回None
 // 用于防止在应用关闭时因锁获取失败导致的无限阻塞
 fn try_lock_with_timeout<T>(
@@ -130,6 +317,10 @@ pub struct Aria2RpcManager {
     pub pid: u32,
     /// 标记进程是否被监控
     is_monitored: AtomicBool,
+    /// 启动时间
+    started_at: std::time::Instant,
+    /// 是否是接管的上次运行时的 aria2c（没有进程句柄，关闭时按进程ID终止）
+    adopted: bool,
 }
 
 impl Clone for Aria2RpcManager {
@@ -140,6 +331,8 @@ impl Clone for Aria2RpcManager {
             process: None, // 克隆时不包含进程句柄
             pid: self.pid,
             is_monitored: AtomicBool::new(self.is_monitored.load(Ordering::Relaxed)),
+            started_at: self.started_at,
+            adopted: self.adopted,
         }
     }
 }
@@ -149,22 +342,68 @@ impl Aria2RpcManager {
     pub fn new() -> Result<Self, String> {
         log_info!("创建Aria2 RPC管理器");
 
-        // 查找可用端口
-        let port = find_available_port()?;
-        log_debug!("找到可用端口: {}", port);
+        // 持有启动锁直到信息记录完成，避免多个实例同时启动aria2c
+        let _spawn_lock = aria2_instance::acquire_spawn_lock()?;
+        if let Some(info) = aria2_instance::read_aria2_info() {
+            if info.owner_pid != std::process::id() && is_aria2c_process(info.pid) {
+                if is_process_running(info.owner_pid) {
+                    return Err(format!(
+                        "另一个实例 (PID: {}) 正在管理aria2c (PID: {})",
+                        info.owner_pid, info.pid
+                    ));
+                }
+                match Self::adopt(&info) {
+                    Ok(manager) => return Ok(manager),
+                    Err(e) => {
+                        log_warn!("无法接管上次遗留的aria2c进程 {}: {}", info.pid, e);
+                        log_warn!("终止上次遗留的aria2c进程: {}", info.pid);
+                        kill_process(info.pid);
+                    }
+                }
+            }
+        }
 
         // 生成随机RPC密钥
         let secret = Uuid::new_v4().to_string();
         log_debug!("生成RPC密钥: {}", secret);
 
+        // 重新启动时优先使用上次的端口，端口已被其他进程占用导致 aria2c 无法启动时换用新的可用端口
+        let last_port = aria2_instance::LAST_ARIA2_INFO
+            .lock()
+            .ok()
+            .and_then(|info| info.as_ref().map(|info| info.port));
+        let mut attempt = 0;
+        let (port, process) = loop {
+            let port = match last_port.filter(|_| attempt == 0) {
+                Some(port) => port,
+                None => find_available_port()?,
+            };
+            log_debug!("使用端口: {}", port);
+
+            // 创建新的aria2c RPC服务器实例
+            log_info!("创建新的aria2c RPC服务器实例");
+            match start_aria2c_rpc_server(port, &secret) {
+                Ok(process) => break (port, process),
+                Err(StartError::Exited(e)) if attempt < MAX_PORT_ATTEMPTS => {
+                    log_warn!("{}，端口 {} 可能已被占用，换用新的端口", e, port);
+                    attempt += 1;
+                }
+                Err(StartError::Exited(e) | StartError::Spawn(e)) => return Err(e),
+            }
+        };
+        let pid = process.id();
+
         // 构建RPC URL - 使用localhost而不是localhost，确保连接到IPv4回环地址
+        // 端口变化后各下载任务每次查询时都会从全局RPC管理器获取新的地址
         let url = format!("http://localhost:{}/jsonrpc", port);
         log_info!("RPC服务器URL: {}", url);
 
-        // 创建新的aria2c RPC服务器实例
-        log_info!("创建新的aria2c RPC服务器实例");
-        let process = start_aria2c_rpc_server(port, &secret)?;
-        let pid = process.id();
+        aria2_instance::save_aria2_info(Aria2Info {
+            pid,
+            port,
+            secret: secret.clone(),
+            owner_pid: std::process::id(),
+        });
 
         let manager = Aria2RpcManager {
             url,
@@ -172,11 +411,53 @@ impl Aria2RpcManager {
             process: Some(process),
             pid,
             is_monitored: AtomicBool::new(false),
+            started_at: std::time::Instant::now(),
+            adopted: false,
         };
 
         Ok(manager)
     }
 
+    /// 接管上次运行时遗留的 aria2c - 使用记录的RPC密钥获取版本号，密钥正确才接管，需要在持有启动锁时调用
+    ///
+    /// 接管后记录中的应用进程ID改为当前进程，上次保存的任务 GID 会在任务重新开始时被继续使用
+    fn adopt(info: &Aria2Info) -> Result<Self, String> {
+        let manager = Aria2RpcManager {
+            url: format!("http://localhost:{}/jsonrpc", info.port),
+            secret: info.secret.clone(),
+            process: None,
+            pid: info.pid,
+            is_monitored: AtomicBool::new(false),
+            started_at: std::time::Instant::now(),
+            adopted: true,
+        };
+
+        // 可能在异步任务中被调用，在单独的线程中创建运行时发送请求
+        let probe = manager.clone();
+        let version = std::thread::spawn(move || {
+            Runtime::new()
+                .map_err(|e| format!("创建Tokio运行时失败: {}", e))?
+                .block_on(probe.get_version())
+        })
+        .join()
+        .map_err(|_| "验证RPC密钥的线程异常退出".to_string())??;
+        log_info!(
+            "接管上次遗留的aria2c进程: PID={}, 端口={}, 版本={}",
+            info.pid,
+            info.port,
+            version
+        );
+
+        aria2_instance::save_aria2_info(Aria2Info {
+            owner_pid: std::process::id(),
+            ..info.clone()
+        });
+        RUNNING_ARIA2_PIDS.lock().unwrap().insert(info.pid);
+        apply_priority_to(info.pid);
+        restore_persisted_transfers();
+        Ok(manager)
+    }
+
     /// 关闭RPC服务器
     pub fn shutdown(&mut self) {
         log_info!("关闭Aria2 RPC服务器: PID={}", self.pid);
@@ -184,10 +465,14 @@ impl Aria2RpcManager {
         if let Some(mut process) = self.process.take() {
             let _ = process.kill();
             log_info!("Aria2 RPC服务器已发送终止信号");
+            aria2_instance::clear_aria2_info(self.pid);
+        } else if self.adopted {
+            kill_process(self.pid);
+            aria2_instance::clear_aria2_info(self.pid);
         }
     }
 
-    /// 添加下载任务到RPC服务器（异步版本）
+    /// 添加下载任务到RPC服务器
     pub async fn add_download(
         &self,
         url: &str,
@@ -200,21 +485,36 @@ impl Aria2RpcManager {
         let mut params = Vec::new();
         params.push(serde_json::Value::String(format!("token:{}", self.secret)));
 
-        // URI数组作为第二个参数
+        // URI数组作为第二个参数，分享页链接由对应网站的适配器转换为直链
         params.push(serde_json::Value::Array(vec![serde_json::Value::String(
-            url.to_string(),
+            host_adapter::direct_url(url),
         )]));
 
         // 选项作为第三个参数
-        let options = serde_json::json!({
+        let (user_agent, headers) = host_adapter::request_options(url);
+        let max_connections = crate::download_policy::max_connections(url);
+        let mut options = serde_json::json!({
             "dir": save_path,
             "out": filename,
             "continue": true,
-            "max-connection-per-server": 16,
-            "split": 16,
+            "max-connection-per-server": max_connections,
+            "split": max_connections,
             "console-log-level": "notice",
-            "user-agent": "pan.baidu.com",
+            "user-agent": user_agent.unwrap_or(DEFAULT_USER_AGENT),
         });
+        if let Some(proxy) = crate::doh::download_proxy() {
+            options["all-proxy"] = proxy.into();
+        }
+        // 暂停期间添加的任务也保持暂停，恢复时一起开始
+        if is_downloads_paused() {
+            options["pause"] = "true".into();
+        }
+        if !headers.is_empty() {
+            options["header"] = headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect();
+        }
         params.push(options);
 
         // 构建JSON-RPC请求
@@ -244,17 +544,28 @@ impl Aria2RpcManager {
         }
     }
 
-    /// 添加下载任务到RPC服务器（同步包装器，供非异步环境使用）
-    pub fn add_download_sync(
-        &self,
-        url: &str,
-        save_path: &str,
-        filename: &str,
-    ) -> Result<String, String> {
-        // 创建一个新的Tokio运行时来执行异步操作，确保在任何线程中都能正常工作
-        let rt =
-            tokio::runtime::Runtime::new().map_err(|e| format!("创建Tokio运行时失败: {}", e))?;
-        rt.block_on(self.add_download(url, save_path, filename))
+    /// 通过RPC获取aria2c版本号，可用于确认RPC服务器正常响应
+    pub async fn get_version(&self) -> Result<String, String> {
+        let request = Aria2JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "aria2.getVersion".to_string(),
+            params: vec![serde_json::Value::String(format!("token:{}", self.secret))],
+            id: 1,
+        };
+
+        let response = send_rpc_request_async(self, &request).await?;
+        let response: Aria2JsonRpcResponse<serde_json::Value> =
+            serde_json::from_str(&response).map_err(|e| format!("解析RPC响应失败: {}", e))?;
+
+        match (response.result, response.error) {
+            (Some(result), _) => Ok(result
+                .get("version")
+                .and_then(|v| v.as_str())
+                .unwrap_or("未知")
+                .to_string()),
+            (None, Some(error)) => Err(format!("获取aria2c版本失败: {:?}", error)),
+            (None, None) => Err("获取aria2c版本失败: 未知错误".to_string()),
+        }
     }
 }
 
@@ -274,8 +585,19 @@ fn find_available_port() -> Result<u16, String> {
     Ok(addr.port())
 }
 
+/// aria2c 无法监听端口时换用新端口的最多次数
+const MAX_PORT_ATTEMPTS: u32 = 3;
+
+/// 启动aria2c RPC服务器失败的原因
+enum StartError {
+    /// 无法创建进程
+    Spawn(String),
+    /// 进程启动后立即退出（通常是端口已被占用）
+    Exited(String),
+}
+
 /// 启动aria2c RPC服务器
-fn start_aria2c_rpc_server(port: u16, secret: &str) -> Result<Child, String> {
+fn start_aria2c_rpc_server(port: u16, secret: &str) -> Result<Child, StartError> {
     log_info!("启动aria2c RPC服务器，端口: {}", port);
     log_debug!("aria2c路径: {}", ARIA2C_PATH.display());
 
@@ -289,37 +611,63 @@ fn start_aria2c_rpc_server(port: u16, secret: &str) -> Result<Child, String> {
         .arg(format!("--rpc-secret={}", secret))
         .arg("--rpc-allow-origin-all")
         .arg("--continue=true")
-        .arg("--max-concurrent-downloads=1")
+        .arg(format!(
+            "--max-concurrent-downloads={}",
+            crate::settings::current_settings().max_concurrent_downloads
+        ))
         .arg("--max-connection-per-server=16")
         .arg("--min-split-size=1M")
         .arg("--split=16")
         .arg("--console-log-level=warn") // 不输出INFO级别日志到stdout
+        .arg("--check-certificate=true")
         .stdout(Stdio::piped()) // 捕获stdout输出
         .stderr(Stdio::piped()) // 捕获stderr输出
         .stdin(Stdio::null());
 
+    // 配置了CA证书时只信任该证书，用于在TLS拦截环境中保护下载
+    if let Some(ca_certificate) = crate::settings::current_settings()
+        .ca_certificate
+        .filter(|path| std::path::Path::new(path).is_file())
+    {
+        log_info!("aria2c使用CA证书: {}", ca_certificate);
+        command.arg(format!("--ca-certificate={}", ca_certificate));
+    }
+
     // 在Windows上，隐藏窗口运行
     command.creation_flags(0x08000000);
 
     // 启动进程
     let mut child = command
         .spawn()
-        .map_err(|e| format!("启动aria2c RPC服务器失败: {}", e))?;
-
-    // 等待一小段时间让服务器初始化
-    std::thread::sleep(std::time::Duration::from_millis(200));
+        .map_err(|e| StartError::Spawn(format!("启动aria2c RPC服务器失败: {}", e)))?;
 
     // 获取stdout和stderr流
-    let stdout = child.stdout.take().ok_or("无法获取stdout流")?;
-    let stderr = child.stderr.take().ok_or("无法获取stderr流")?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| StartError::Spawn("无法获取stdout流".to_string()))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| StartError::Spawn("无法获取stderr流".to_string()))?;
+
+    // 重定向aria2c的输出到主程序日志，启动失败时的错误信息也会写入日志
+    let pid = child.id();
+    redirect_process_output(stdout, stderr, format!("aria2c[{}]", pid));
+
+    // 等待一小段时间让服务器初始化，无法监听端口时 aria2c 会立即退出
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    if let Ok(Some(status)) = child.try_wait() {
+        return Err(StartError::Exited(format!(
+            "aria2c RPC服务器启动后立即退出 ({})",
+            status
+        )));
+    }
 
     // 记录进程ID
-    let pid = child.id();
     RUNNING_ARIA2_PIDS.lock().unwrap().insert(pid);
     log_info!("aria2c RPC服务器启动成功，PID: {}", pid);
-
-    // 重定向aria2c的输出到主程序日志
-    redirect_process_output(stdout, stderr, format!("aria2c[{}]", pid));
+    apply_priority_to(pid);
 
     Ok(child)
 }
@@ -436,12 +784,40 @@ async fn send_rpc_request_via_reqwest(
     Err(last_error)
 }
 
+/// 发送 engine-status-changed 事件
+///
+/// # 参数
+/// - `state`: started（启动）、restarted（有进行中的任务时重新启动）、crashed（进程意外退出）或 stopped（关闭）
+/// - `manager`: 相关的RPC管理器
+fn emit_engine_status_changed(state: &str, manager: Option<&Aria2RpcManager>) {
+    let app_handle = crate::init::GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone());
+    if let Some(app_handle) = app_handle {
+        let _ = crate::event_dispatcher::emit(
+            &app_handle,
+            "engine-status-changed",
+            &serde_json::json!({
+                "state": state,
+                "pid": manager.map(|manager| manager.pid),
+                "port": manager
+                    .and_then(|manager| tauri::Url::parse(&manager.url).ok())
+                    .and_then(|url| url.port()),
+                "restarts": ENGINE_GENERATION.load(Ordering::SeqCst).saturating_sub(1),
+            }),
+        );
+    }
+}
+
 /// 重置RPC管理器（如果需要）
 fn reset_rpc_manager_if_needed() {
     match try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000) {
         Some(mut manager) => {
             log_info!("检测到aria2c进程关闭，主动重置全局RPC管理器");
-            *manager = None;
+            if let Some(crashed) = manager.take() {
+                emit_engine_status_changed("crashed", Some(&crashed));
+            }
         }
         None => {
             log_warn!("获取RPC管理器锁超时，无法重置全局RPC管理器");
@@ -569,6 +945,119 @@ async fn get_download_status(gid: &str) -> Result<Option<DownloadStatus>, String
     Ok(None)
 }
 
+/// 获取下载任务在 aria2c 中的完整状态 - 原样返回 aria2.tellStatus 的结果，不做任何处理
+///
+/// # 参数
+/// - `gid`: 下载任务的GID
+///
+/// # 返回值
+/// - 成功时返回状态对象，GID 不存在时返回Ok(None)
+pub async fn download_details(gid: &str) -> Result<Option<serde_json::Value>, String> {
+    let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+        .and_then(|guard| guard.clone())
+        .ok_or_else(|| "RPC管理器未初始化".to_string())?;
+
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "aria2.tellStatus".to_string(),
+        params: vec![
+            serde_json::Value::String(format!("token:{}", manager.secret)),
+            serde_json::Value::String(gid.to_string()),
+        ],
+        id: 1,
+    };
+    let response = send_rpc_request_async(&manager, &request).await?;
+    let response: Aria2JsonRpcResponse<serde_json::Value> =
+        serde_json::from_str(&response).map_err(|e| format!("解析RPC响应失败: {}", e))?;
+
+    if let Some(error) = response.error {
+        let message = error
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("未知错误");
+        if message.contains("is not found") {
+            return Ok(None);
+        }
+        return Err(format!("RPC请求失败: {}", message));
+    }
+    Ok(response.result)
+}
+
+/// 获取下载任务各个连接所在的服务器 - 调用 aria2.getServers，按主机名汇总连接数和速度
+///
+/// 任务没有在下载（等待中、已暂停或已完成）时 aria2c 会返回错误，此时返回空列表
+///
+/// # 参数
+/// - `gid`: 下载任务的GID
+///
+/// # 返回值
+/// - 按下载速度从高到低排列的服务器统计
+async fn get_download_servers(gid: &str) -> Vec<ServerStats> {
+    let Some(manager) =
+        try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000).and_then(|guard| guard.clone())
+    else {
+        return Vec::new();
+    };
+
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "aria2.getServers".to_string(),
+        params: vec![
+            serde_json::Value::String(format!("token:{}", manager.secret)),
+            serde_json::Value::String(gid.to_string()),
+        ],
+        id: 1,
+    };
+    let Some(result) = send_rpc_request_async(&manager, &request)
+        .await
+        .ok()
+        .and_then(|response| {
+            serde_json::from_str::<Aria2JsonRpcResponse<serde_json::Value>>(&response).ok()
+        })
+        .and_then(|response| response.result)
+    else {
+        return Vec::new();
+    };
+
+    // result 为每个文件一项，每项的 servers 为该文件的各个连接
+    let mut stats: Vec<ServerStats> = Vec::new();
+    let connections = result
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|file| file.get("servers").and_then(|v| v.as_array()))
+        .flatten();
+    for connection in connections {
+        let Some(host) = connection
+            .get("currentUri")
+            .and_then(|v| v.as_str())
+            .and_then(|uri| tauri::Url::parse(uri).ok())
+            .and_then(|url| url.host_str().map(str::to_string))
+        else {
+            continue;
+        };
+        let speed = connection
+            .get("downloadSpeed")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        match stats.iter_mut().find(|server| server.host == host) {
+            Some(server) => {
+                server.connections += 1;
+                server.download_speed += speed;
+            }
+            None => stats.push(ServerStats {
+                host,
+                connections: 1,
+                download_speed: speed,
+            }),
+        }
+    }
+    stats.sort_by_key(|stat| std::cmp::Reverse(stat.download_speed));
+    stats
+}
+
 /// 检查进程是否正在运行
 fn is_process_running(pid: u32) -> bool {
     // 检查进程是否存在
@@ -594,6 +1083,42 @@ fn is_process_running(pid: u32) -> bool {
     }
 }
 
+/// 检查进程是否正在运行且为aria2c - 避免进程ID被其他程序复用时误判
+fn is_aria2c_process(pid: u32) -> bool {
+    let mut command = Command::new("tasklist");
+    command.args([
+        "/FI",
+        &format!("PID eq {}", pid),
+        "/FI",
+        "IMAGENAME eq aria2c.exe",
+        "/NH",
+    ]);
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW 标志
+
+    command
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("aria2c.exe"))
+        .unwrap_or(false)
+}
+
+/// 强制终止进程
+fn kill_process(pid: u32) {
+    let result = Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW 标志，隐藏命令行窗口
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => log_info!("成功终止进程: {}", pid),
+        Ok(output) => log_warn!(
+            "终止进程{}失败: {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(e) => log_error!("执行taskkill命令失败: {}", e),
+    }
+}
+
 /// 启动aria2c RPC服务器（按需启动）
 fn start_aria2c_on_demand() -> Result<(), String> {
     log_info!("按需启动aria2c RPC服务器");
@@ -604,110 +1129,635 @@ fn start_aria2c_on_demand() -> Result<(), String> {
 
     if manager.is_none() {
         log_info!("创建新的aria2c RPC管理器实例");
-        let rpc_manager = Aria2RpcManager::new()?;
+        let rpc_manager = Aria2RpcManager::new().inspect_err(|e| {
+            crate::event_log::report_critical(
+                crate::event_log::CriticalEvent::Aria2StartFailed,
+                &format!("aria2c 无法启动: {}", e),
+            );
+        })?;
+        ENGINE_GENERATION.fetch_add(1, Ordering::SeqCst);
+        let interrupted = ARIA2_TRANSFERS
+            .lock()
+            .map(|transfers| !transfers.is_empty())
+            .unwrap_or(false);
+        emit_engine_status_changed(
+            if interrupted { "restarted" } else { "started" },
+            Some(&rpc_manager),
+        );
         *manager = Some(rpc_manager);
         log_info!("aria2c RPC服务器已启动");
     } else {
         log_info!("aria2c RPC服务器已在运行");
     }
 
-    Ok(())
+    Ok(())
+}
+
+/// 关闭aria2c RPC服务器（按需关闭）
+fn shutdown_aria2c_on_demand() {
+    log_info!("按需关闭aria2c RPC服务器");
+
+    let mut manager = match ARIA2_RPC_MANAGER.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            log_warn!("无法获取RPC管理器锁，跳过关闭");
+            return;
+        }
+    };
+
+    if let Some(mut rpc_manager) = manager.take() {
+        log_info!("关闭aria2c RPC服务器");
+        rpc_manager.shutdown();
+        emit_engine_status_changed("stopped", Some(&rpc_manager));
+        log_info!("aria2c RPC服务器已关闭");
+    }
+}
+
+/// 增加活跃下载计数
+fn increment_active_downloads() {
+    let mut count = ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
+    *count += 1;
+    log_info!("活跃下载任务数: {}", *count);
+}
+
+/// 减少活跃下载计数
+fn decrement_active_downloads() {
+    let mut count = ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
+    *count = count.saturating_sub(1);
+    log_info!("活跃下载任务数: {}", *count);
+
+    // 如果没有活跃下载任务，关闭aria2c
+    if *count == 0 {
+        log_info!("没有活跃下载任务，关闭aria2c RPC服务器");
+        shutdown_aria2c_on_demand();
+    }
+}
+
+/// 取消下载任务 - 通过aria2c RPC接口取消指定的下载任务
+///
+/// 此函数使用aria2c的RPC接口发送取消下载请求，
+/// 从aria2c下载队列中移除指定GID的下载任务。
+///
+/// # 参数
+/// - `gid`: 要取消的下载任务的GID
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok
+/// - 失败时返回包含错误信息的Err
+pub async fn cancel_download(gid: &str) -> Result<String, String> {
+    // 获取RPC管理器实例（复制一份，不在等待响应期间持有锁）
+    let manager = ARIA2_RPC_MANAGER
+        .lock()
+        .map_err(|_| "无法获取RPC管理器锁".to_string())?
+        .clone()
+        .ok_or_else(|| "RPC管理器未初始化".to_string())?;
+
+    // 构建取消下载的RPC请求
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "aria2.remove".to_string(),
+        params: vec![
+            format!("token:{}", manager.secret).into(),
+            gid.to_string().into(),
+        ],
+        id: std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    };
+
+    // 发送RPC请求
+    match send_rpc_request_async(&manager, &request).await {
+        Ok(response_str) => {
+            // 尝试解析响应
+            match serde_json::from_str::<serde_json::Value>(&response_str) {
+                Ok(response_json) => {
+                    // 检查响应中是否包含错误
+                    if response_json.get("error").is_some() {
+                        let error_msg = response_json["error"]["message"]
+                            .as_str()
+                            .unwrap_or("未知错误");
+                        Err(error_msg.to_string())
+                    } else {
+                        Ok(format!("下载任务已成功取消: {}", gid))
+                    }
+                }
+                Err(e) => Err(format!("解析RPC响应失败: {}", e)),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 暂停或继续单个下载任务 - 调用 aria2.forcePause / aria2.unpause
+///
+/// # 参数
+/// - `gid`: 下载任务的GID
+/// - `paused`: true 为暂停，false 为继续
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 失败时返回包含错误信息的Err
+async fn set_download_paused(gid: &str, paused: bool) -> Result<(), String> {
+    let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+        .and_then(|guard| guard.clone())
+        .ok_or_else(|| "RPC管理器未初始化".to_string())?;
+
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: if paused {
+            "aria2.forcePause"
+        } else {
+            "aria2.unpause"
+        }
+        .to_string(),
+        params: vec![
+            serde_json::Value::String(format!("token:{}", manager.secret)),
+            gid.to_string().into(),
+        ],
+        id: 1,
+    };
+
+    let response = send_rpc_request_async(&manager, &request).await?;
+    let response: Aria2JsonRpcResponse<String> =
+        serde_json::from_str(&response).map_err(|e| format!("解析RPC响应失败: {}", e))?;
+    match response.error {
+        Some(error) => Err(format!("{:?}", error)),
+        None => Ok(()),
+    }
+}
+
+/// 下载停滞（速度持续为0）时的恢复步骤，按顺序执行，每一步的次数由设置决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StallAction {
+    /// 暂停再继续任务，让 aria2c 重新建立连接
+    Unpause,
+    /// 从备用镜像（没有配置镜像时从原地址）重新添加任务，继续已下载的部分
+    Mirror,
+    /// 判定下载失败
+    Fail,
+}
+
+impl StallAction {
+    /// 第 attempt 次（从0开始）停滞时执行的步骤
+    fn for_attempt(attempt: u32, settings: &crate::settings::Settings) -> Self {
+        if attempt < settings.stall_unpause_attempts {
+            StallAction::Unpause
+        } else if attempt < settings.stall_unpause_attempts + settings.stall_mirror_attempts {
+            StallAction::Mirror
+        } else {
+            StallAction::Fail
+        }
+    }
+
+    /// download-stalled 事件中的步骤名称
+    fn name(self) -> &'static str {
+        match self {
+            StallAction::Unpause => "unpause",
+            StallAction::Mirror => "mirror",
+            StallAction::Fail => "failed",
+        }
+    }
+}
+
+/// 停滞恢复后下载量超过该值才重新从第一个恢复步骤开始，避免恢复后短暂有速度又停滞时反复暂停再继续
+const STALL_RECOVERED_BYTES: u64 = 1024 * 1024;
+
+/// 修改运行中aria2c的最大并发下载数
+///
+/// aria2c未运行时直接返回成功，下次启动时会使用当前设置中的值。
+///
+/// # 参数
+/// - `max_concurrent_downloads`: 新的最大并发下载数
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 失败时返回包含错误信息的Err
+pub async fn set_max_concurrent_downloads(max_concurrent_downloads: u32) -> Result<(), String> {
+    let manager = match try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000) {
+        Some(guard) => match guard.as_ref() {
+            Some(mgr) => mgr.clone(),
+            None => return Ok(()),
+        },
+        None => return Err("获取RPC管理器锁超时".to_string()),
+    };
+
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "aria2.changeGlobalOption".to_string(),
+        params: vec![
+            serde_json::Value::String(format!("token:{}", manager.secret)),
+            serde_json::json!({
+                "max-concurrent-downloads": max_concurrent_downloads.to_string(),
+            }),
+        ],
+        id: 1,
+    };
+
+    let response = send_rpc_request_async(&manager, &request).await?;
+    let response: Aria2JsonRpcResponse<String> =
+        serde_json::from_str(&response).map_err(|e| format!("解析RPC响应失败: {}", e))?;
+
+    if let Some(error) = response.error {
+        log_error!("修改aria2c最大并发下载数失败: {:?}", error);
+        return Err(format!("修改aria2c最大并发下载数失败: {:?}", error));
+    }
+
+    log_info!("aria2c最大并发下载数已修改为: {}", max_concurrent_downloads);
+    Ok(())
+}
+
+/// 高级设置中允许读取和修改的 aria2c 选项，其他选项（下载目录、RPC 等）由应用管理，不允许修改
+pub const ADVANCED_ARIA2_OPTIONS: &[&str] = &[
+    "file-allocation",
+    "timeout",
+    "connect-timeout",
+    "max-tries",
+    "retry-wait",
+    "lowest-speed-limit",
+    "max-overall-download-limit",
+    "max-download-limit",
+    "max-connection-per-server",
+    "split",
+    "min-split-size",
+    "max-file-not-found",
+    "uri-selector",
+    "stream-piece-selector",
+];
+
+/// 调用 aria2c 的 RPC 方法，token 参数自动添加在最前面
+///
+/// # 参数
+/// - `method`: RPC 方法名
+/// - `params`: token 之后的参数
+///
+/// # 返回值
+/// - 成功时返回 result，aria2c 返回错误时返回错误信息
+async fn call_rpc(
+    method: &str,
+    params: Vec<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+        .and_then(|guard| guard.clone())
+        .ok_or_else(|| "RPC管理器未初始化".to_string())?;
+
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: std::iter::once(serde_json::Value::String(format!(
+            "token:{}",
+            manager.secret
+        )))
+        .chain(params)
+        .collect(),
+        id: 1,
+    };
+    let response = send_rpc_request_async(&manager, &request).await?;
+    let response: Aria2JsonRpcResponse<serde_json::Value> =
+        serde_json::from_str(&response).map_err(|e| format!("解析RPC响应失败: {}", e))?;
+
+    if let Some(error) = response.error {
+        let message = error
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("未知错误");
+        return Err(format!("{} 失败: {}", method, message));
+    }
+    Ok(response.result.unwrap_or(serde_json::Value::Null))
+}
+
+/// 获取运行中aria2c的全局选项，只返回 ADVANCED_ARIA2_OPTIONS 中的选项
+///
+/// # 返回值
+/// - 选项名 -> 值
+pub async fn get_global_options() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let options = call_rpc("aria2.getGlobalOption", Vec::new()).await?;
+    Ok(options
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| ADVANCED_ARIA2_OPTIONS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect())
+}
+
+/// 修改运行中aria2c的全局选项 - 调用 aria2.changeGlobalOption，aria2c 重新启动后恢复默认值
+///
+/// # 参数
+/// - `key`: 选项名，必须在 ADVANCED_ARIA2_OPTIONS 中
+/// - `value`: 选项值
+pub async fn set_global_option(key: &str, value: &str) -> Result<(), String> {
+    call_rpc(
+        "aria2.changeGlobalOption",
+        vec![serde_json::json!({ key: value })],
+    )
+    .await?;
+    log_info!("aria2c全局选项已修改: {}={}", key, value);
+    Ok(())
+}
+
+/// 修改单个下载任务的选项 - 调用 aria2.changeOption，只对该任务当前的 GID 生效
+///
+/// # 参数
+/// - `gid`: 下载任务的GID
+/// - `key`: 选项名，必须在 ADVANCED_ARIA2_OPTIONS 中
+/// - `value`: 选项值
+pub async fn change_task_option(gid: &str, key: &str, value: &str) -> Result<(), String> {
+    call_rpc(
+        "aria2.changeOption",
+        vec![gid.to_string().into(), serde_json::json!({ key: value })],
+    )
+    .await?;
+    log_info!("下载任务 {} 的选项已修改: {}={}", gid, key, value);
+    Ok(())
+}
+
+/// aria2c 当前应使用的 CPU 优先级和是否使用低 IO 优先级 - 游戏运行时按配置降为后台优先级
+fn aria2_priority() -> (ProcessPriority, bool) {
+    let settings = crate::settings::current_settings();
+    if settings.aria2_background_while_gaming && crate::game_monitor::is_game_running() {
+        (
+            settings.aria2_priority.min(ProcessPriority::BelowNormal),
+            true,
+        )
+    } else {
+        (settings.aria2_priority, settings.aria2_low_io_priority)
+    }
+}
+
+/// 将当前应使用的优先级应用到aria2c进程
+fn apply_priority_to(pid: u32) {
+    let (priority, low_io) = aria2_priority();
+    match crate::process_priority::set_process_priority(pid, priority, low_io) {
+        Ok(()) => log_debug!(
+            "aria2c[{}] 优先级: {:?}, 低IO优先级: {}",
+            pid,
+            priority,
+            low_io
+        ),
+        Err(e) => log_warn!("{}", e),
+    }
+}
+
+/// 按配置和游戏运行状态调整运行中的aria2c进程的优先级（设置修改或游戏启动/退出时调用）
+pub fn apply_aria2_priority() {
+    let pid = ARIA2_RPC_MANAGER
+        .lock()
+        .ok()
+        .and_then(|manager| manager.as_ref().map(|manager| manager.pid));
+    if let Some(pid) = pid {
+        apply_priority_to(pid);
+    }
+}
+
+/// 是否已暂停所有下载
+pub fn is_downloads_paused() -> bool {
+    DOWNLOADS_PAUSED.load(Ordering::Relaxed)
+}
+
+/// 暂停或恢复所有下载 - 调用 aria2.pauseAll / aria2.unpauseAll，aria2c 未运行时只记录状态
+///
+/// # 参数
+/// - `paused`: true 为暂停，false 为恢复
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 失败时返回包含错误信息的Err
+pub async fn set_downloads_paused(paused: bool) -> Result<(), String> {
+    DOWNLOADS_PAUSED.store(paused, Ordering::Relaxed);
+
+    let manager = match try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000) {
+        Some(guard) => match guard.as_ref() {
+            Some(mgr) => mgr.clone(),
+            None => return Ok(()),
+        },
+        None => return Err("获取RPC管理器锁超时".to_string()),
+    };
+
+    let (method, action) = if paused {
+        ("aria2.pauseAll", "暂停")
+    } else {
+        ("aria2.unpauseAll", "恢复")
+    };
+    let request = Aria2JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: vec![serde_json::Value::String(format!(
+            "token:{}",
+            manager.secret
+        ))],
+        id: 1,
+    };
+
+    let response = send_rpc_request_async(&manager, &request).await?;
+    let response: Aria2JsonRpcResponse<String> =
+        serde_json::from_str(&response).map_err(|e| format!("解析RPC响应失败: {}", e))?;
+
+    if let Some(error) = response.error {
+        log_error!("{}所有下载失败: {:?}", action, error);
+        return Err(format!("{}所有下载失败: {:?}", action, error));
+    }
+
+    log_info!("已{}所有下载", action);
+    Ok(())
+}
+
+/// 重新启动aria2c - 暂停所有任务后关闭RPC服务器并重新启动，进行中的任务由各自的下载监控重新添加（continue=true，继续已下载的部分）
+///
+/// 用于下载卡住时手动修复，不需要重新启动应用。aria2c 没有运行且没有进行中的任务时不做处理。
+///
+/// # 返回值
+/// - 成功时返回需要重新添加的任务数
+/// - 失败时返回包含错误信息的Err
+pub async fn restart_engine() -> Result<usize, String> {
+    let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+        .ok_or_else(|| "获取RPC管理器锁超时".to_string())?
+        .clone();
+    let transfers = ARIA2_TRANSFERS
+        .lock()
+        .map(|transfers| transfers.len())
+        .unwrap_or(0);
+    if manager.is_none() && transfers == 0 {
+        log_info!("aria2c未运行且没有进行中的任务，无需重新启动");
+        return Ok(0);
+    }
+    log_info!("重新启动aria2c，进行中的任务数: {}", transfers);
+
+    // 先暂停所有任务，让 aria2c 写入控制文件，重新添加后可以继续下载
+    if let Some(manager) = &manager {
+        let request = Aria2JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "aria2.forcePauseAll".to_string(),
+            params: vec![serde_json::Value::String(format!(
+                "token:{}",
+                manager.secret
+            ))],
+            id: 1,
+        };
+        if let Err(e) = send_rpc_request_async(manager, &request).await {
+            log_warn!("暂停aria2c任务失败，直接关闭: {}", e);
+        }
+    }
+
+    tokio::task::spawn_blocking(|| {
+        if let Some(mut manager) =
+            try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000).and_then(|mut guard| guard.take())
+        {
+            manager.shutdown();
+        }
+        // 等待进程退出并释放文件
+        std::thread::sleep(Duration::from_millis(500));
+        start_aria2c_on_demand()
+    })
+    .await
+    .map_err(|e| format!("重新启动aria2c失败: {:?}", e))??;
+
+    log_info!("aria2c已重新启动，等待 {} 个任务重新添加", transfers);
+    Ok(transfers)
+}
+
+/// 下载引擎（aria2c）的详细状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineStatus {
+    /// 进程是否正在运行
+    pub running: bool,
+    /// aria2c进程ID
+    pub pid: Option<u32>,
+    /// RPC监听端口
+    pub port: Option<u16>,
+    /// RPC接口是否正常响应
+    pub rpc_reachable: bool,
+    /// aria2c版本
+    pub version: Option<String>,
+    /// 已运行的秒数
+    pub uptime_seconds: Option<u64>,
+    /// 本次运行期间重新启动的次数
+    pub restarts: u64,
+    /// 活跃下载任务数
+    pub active_downloads: u32,
+    /// 正在 aria2c 中下载的任务数
+    pub transfers: usize,
 }
 
-/// 关闭aria2c RPC服务器（按需关闭）
-fn shutdown_aria2c_on_demand() {
-    log_info!("按需关闭aria2c RPC服务器");
+/// 获取下载引擎的详细状态 - 除进程状态外还通过RPC获取版本，用于确认RPC接口正常响应
+pub async fn engine_status() -> EngineStatus {
+    let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000).and_then(|guard| guard.clone());
+    let restarts = ENGINE_GENERATION.load(Ordering::SeqCst).saturating_sub(1);
+    let active_downloads = *ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
+    let transfers = ARIA2_TRANSFERS
+        .lock()
+        .map(|transfers| transfers.len())
+        .unwrap_or(0);
+
+    let Some(manager) = manager else {
+        return EngineStatus {
+            running: false,
+            pid: None,
+            port: None,
+            rpc_reachable: false,
+            version: None,
+            uptime_seconds: None,
+            restarts,
+            active_downloads,
+            transfers,
+        };
+    };
 
-    let mut manager = match ARIA2_RPC_MANAGER.try_lock() {
-        Ok(guard) => guard,
-        Err(_) => {
-            log_warn!("无法获取RPC管理器锁，跳过关闭");
-            return;
-        }
+    let pid = manager.pid;
+    let running = tokio::task::spawn_blocking(move || is_process_running(pid))
+        .await
+        .unwrap_or(false);
+    let version = if running {
+        manager
+            .get_version()
+            .await
+            .inspect_err(|e| log_warn!("aria2c RPC接口没有响应: {}", e))
+            .ok()
+    } else {
+        None
     };
 
-    if let Some(mut rpc_manager) = manager.take() {
-        log_info!("关闭aria2c RPC服务器");
-        rpc_manager.shutdown();
-        log_info!("aria2c RPC服务器已关闭");
+    EngineStatus {
+        running,
+        pid: Some(pid),
+        port: tauri::Url::parse(&manager.url)
+            .ok()
+            .and_then(|url| url.port()),
+        rpc_reachable: version.is_some(),
+        version,
+        uptime_seconds: Some(manager.started_at.elapsed().as_secs()),
+        restarts,
+        active_downloads,
+        transfers,
     }
 }
 
-/// 增加活跃下载计数
-fn increment_active_downloads() {
-    let mut count = ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
-    *count += 1;
-    log_info!("活跃下载任务数: {}", *count);
+/// aria2c RPC服务器状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Aria2Status {
+    /// RPC服务器进程是否正在运行
+    pub running: bool,
+    /// aria2c进程ID
+    pub pid: Option<u32>,
+    /// RPC监听端口
+    pub port: Option<u16>,
+    /// 活跃下载任务数
+    pub active_downloads: u32,
 }
 
-/// 减少活跃下载计数
-fn decrement_active_downloads() {
-    let mut count = ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
-    *count = count.saturating_sub(1);
-    log_info!("活跃下载任务数: {}", *count);
-
-    // 如果没有活跃下载任务，关闭aria2c
-    if *count == 0 {
-        log_info!("没有活跃下载任务，关闭aria2c RPC服务器");
-        shutdown_aria2c_on_demand();
+/// 获取aria2c RPC服务器状态
+pub fn get_aria2_status() -> Aria2Status {
+    let manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000).and_then(|guard| guard.clone());
+    let active_downloads = *ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
+
+    match manager {
+        Some(manager) => Aria2Status {
+            running: is_process_running(manager.pid),
+            pid: Some(manager.pid),
+            port: tauri::Url::parse(&manager.url)
+                .ok()
+                .and_then(|url| url.port()),
+            active_downloads,
+        },
+        None => Aria2Status {
+            running: false,
+            pid: None,
+            port: None,
+            active_downloads,
+        },
     }
 }
 
-/// 取消下载任务 - 通过aria2c RPC接口取消指定的下载任务
-///
-/// 此函数使用aria2c的RPC接口发送取消下载请求，
-/// 从aria2c下载队列中移除指定GID的下载任务。
+/// 重新初始化aria2c RPC管理器 - 关闭当前的RPC服务器，下一次下载时重新启动
 ///
-/// # 参数
-/// - `gid`: 要取消的下载任务的GID
+/// aria2c进程仍在运行且有活跃下载任务时不做处理，避免中断正在进行的下载
 ///
 /// # 返回值
-/// - 成功时返回包含成功信息的Ok
-/// - 失败时返回包含错误信息的Err
-pub async fn cancel_download(gid: &str) -> Result<String, String> {
-    // 获取RPC管理器实例
-    let manager = ARIA2_RPC_MANAGER
-        .lock()
-        .map_err(|_| "无法获取RPC管理器锁".to_string())?;
-    let manager = manager
-        .as_ref()
-        .ok_or_else(|| "RPC管理器未初始化".to_string())?;
-
-    // 构建取消下载的RPC请求
-    let request = Aria2JsonRpcRequest {
-        jsonrpc: "2.0".to_string(),
-        method: "aria2.remove".to_string(),
-        params: vec![
-            format!("token:{}", manager.secret).into(),
-            gid.to_string().into(),
-        ],
-        id: std::time::SystemTime::now()
-            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0),
+/// - 关闭了已有的RPC服务器时返回Ok(true)，没有RPC服务器时返回Ok(false)
+/// - 有活跃下载任务或无法获取锁时返回Err
+pub fn reinitialize_rpc_manager() -> Result<bool, String> {
+    let mut manager = try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+        .ok_or_else(|| "获取RPC管理器锁超时".to_string())?;
+
+    let rpc_manager = match manager.as_mut() {
+        Some(rpc_manager) => rpc_manager,
+        None => return Ok(false),
     };
 
-    // 发送RPC请求
-    match send_rpc_request_async(manager, &request).await {
-        Ok(response_str) => {
-            // 尝试解析响应
-            match serde_json::from_str::<serde_json::Value>(&response_str) {
-                Ok(response_json) => {
-                    // 检查响应中是否包含错误
-                    if response_json.get("error").is_some() {
-                        let error_msg = response_json["error"]["message"]
-                            .as_str()
-                            .unwrap_or("未知错误");
-                        Err(error_msg.to_string())
-                    } else {
-                        Ok(format!("下载任务已成功取消: {}", gid))
-                    }
-                }
-                Err(e) => Err(format!("解析RPC响应失败: {}", e)),
-            }
-        }
-        Err(e) => Err(e),
+    let active_downloads = *ACTIVE_DOWNLOAD_COUNT.lock().unwrap();
+    if active_downloads > 0 && is_process_running(rpc_manager.pid) {
+        return Err(format!(
+            "aria2c正在处理 {} 个下载任务，跳过重新初始化",
+            active_downloads
+        ));
     }
+
+    log_info!("重新初始化aria2c RPC管理器: PID={}", rpc_manager.pid);
+    rpc_manager.shutdown();
+    *manager = None;
+    Ok(true)
 }
 
 /// 清理aria2c资源
@@ -777,31 +1827,28 @@ pub fn cleanup_aria2c_resources() {
     log_info!("等待文件资源完全释放...");
     std::thread::sleep(std::time::Duration::from_millis(500));
 
-    // 清理可能残留的aria2临时文件
-    if let Ok(downloads_dir) = crate::dir_manager::get_global_cache_dir() {
-        if downloads_dir.exists() {
-            log_info!(
-                "检查并清理缓存目录中的临时文件: {}",
-                downloads_dir.to_string_lossy()
-            );
-            match std::fs::read_dir(downloads_dir) {
-                Ok(entries) => {
-                    for entry in entries {
-                        if let Ok(entry) = entry {
-                            let path = entry.path();
-                            log_info!("删除临时文件: {}", path.to_string_lossy());
-                            let _ = std::fs::remove_file(&path);
-                        }
-                    }
-                }
-                Err(e) => log_warn!("读取缓存目录失败: {}", e),
-            }
-        }
-    }
-
     log_info!("aria2c资源清理完成");
 }
 
+/// 生成随机文件名，保留URL中的扩展名（如果有）
+fn random_file_name(url: &str) -> String {
+    let random_name = format!("{}", Uuid::new_v4());
+    let extension = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| {
+            !ext.is_empty() && ext.len() <= 6 && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+
+    match extension {
+        Some(ext) => format!("{}.{}", random_name, ext),
+        None => random_name,
+    }
+}
+
 /// 通过aria2c的RPC接口下载文件
 ///
 /// # 参数
@@ -819,6 +1866,14 @@ pub async fn download_via_aria2(
 ) -> Result<String, String> {
     log_info!("开始通过aria2c RPC下载文件 [{}]: URL={}", task_id, url);
 
+    // aria2c不支持证书公钥固定，下载启用了证书固定的站点前先由应用校验一次
+    crate::cert_pinning::check_endpoint(&host_adapter::direct_url(url))
+        .await
+        .map_err(|e| {
+            log_error!("[{}] 证书校验失败: {}", task_id, e);
+            format!("证书校验失败: {}", e)
+        })?;
+
     // 按需启动aria2c
     if let Err(e) = start_aria2c_on_demand() {
         log_error!("[{}] 启动aria2c失败: {}", task_id, e);
@@ -848,30 +1903,60 @@ pub async fn download_via_aria2(
         downloads_dir.to_string_lossy()
     );
 
-    // 获取文件扩展名（如果有）
-    let extension = match url.split('/').last() {
-        Some(name) => {
-            if let Some(ext) = name.split('.').last() {
-                if ext.len() <= 6 {
-                    Some(format!(".{}", ext))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+    // 优先继续上次运行时同一任务的临时文件，其次继续上次未完成的下载，服务器上的文件已变化时删除临时文件重新下载
+    let (mut restored_gid, restored_filename) = RESTORED_TRANSFERS
+        .lock()
+        .ok()
+        .and_then(|mut restored| restored.remove(task_id))
+        .unwrap_or_default();
+    let mut partial = restored_filename
+        .as_deref()
+        .and_then(|filename| {
+            crate::download_manager::claim_download_file(url, filename, &downloads_dir)
+        })
+        .or_else(|| crate::download_manager::claim_partial_download(url, &downloads_dir));
+    if let Some(filename) = &partial {
+        if !crate::resume_validator::is_unchanged(url).await {
+            log_warn!(
+                "[{}] 服务器上的文件已变化，放弃未完成的下载: {}",
+                task_id,
+                filename
+            );
+            let _ = fs::remove_file(downloads_dir.join(filename));
+            let _ = fs::remove_file(downloads_dir.join(format!("{}.aria2", filename)));
+            crate::download_manager::forget_partial_download(url);
+            partial = None;
         }
-        None => None,
-    };
+    }
+    // 上次的 GID 只有在继续同一个临时文件时才有意义
+    if partial != restored_filename {
+        restored_gid = None;
+    }
 
-    // 生成随机文件名
-    let random_name = format!("{}", Uuid::new_v4());
-    let filename = if let Some(ext) = extension {
-        format!("{}{}", random_name, ext)
-    } else {
-        random_name
+    // 没有可以继续的下载时记录文件的校验值，使用任务的文件名（提取失败时使用随机文件名）
+    let filename = match partial {
+        Some(filename) => {
+            log_info!("[{}] 继续未完成的下载: {}", task_id, filename);
+            filename
+        }
+        None => {
+            // 服务器提供了文件名时替换从URL路径中得到的文件名（很多网站的链接路径是无意义的标识）
+            if let Some(name) = crate::resume_validator::record(url)
+                .await
+                .and_then(|name| sanitize_file_name(&name))
+            {
+                crate::download_manager::update_task_file_name(&app_handle, task_id, &name);
+            }
+            let preferred = crate::download_manager::active_task_file_name(task_id)
+                .or_else(|| get_file_name(url))
+                .and_then(|name| sanitize_file_name(&name))
+                .unwrap_or_else(|| random_file_name(url));
+            let filename =
+                crate::download_manager::reserve_download_filename(url, &downloads_dir, &preferred);
+            log_debug!("[{}] 使用文件名: {}", task_id, filename);
+            filename
+        }
     };
-    log_debug!("[{}] 生成随机文件名: {}", task_id, filename);
 
     // 构建文件完整路径
     let file_path = downloads_dir.join(&filename);
@@ -881,19 +1966,21 @@ pub async fn download_via_aria2(
         file_path.to_string_lossy()
     );
 
-    // 转换为线程安全的路径字符串
+    // 复制监控下载需要的参数，移入下面的异步块
     let url_owned = url.to_string();
     let file_path_clone = file_path.clone();
     let app_handle_for_events = app_handle.clone();
     let task_id_clone = task_id.to_string();
     let filename_clone = filename.clone();
     let downloads_dir_clone = downloads_dir.clone();
+    let cancel_token = crate::download_manager::cancellation_token(task_id);
 
-    // 在后台线程中运行下载，避免阻塞主线程
-    let (tx, rx) = mpsc::channel();
+    // 创建子 span，GID 获取后再记录
+    let span = tracing::info_span!("aria2", gid = tracing::field::Empty);
 
-    std::thread::spawn(move || {
-        log_info!("[{}] 启动后台下载线程", task_id_clone);
+    // 在当前异步任务中添加并监控下载，各个出口统一减少活跃下载计数
+    let result = async move {
+        log_info!("[{}] 开始下载并监控进度", task_id_clone);
 
         // 获取下载目录的路径字符串
         let download_dir_str = downloads_dir_clone
@@ -917,35 +2004,55 @@ pub async fn download_via_aria2(
                     }
                     None => {
                         log_error!("[{}] RPC管理器实例不存在", task_id_clone);
-                        let _ = tx.send(Err("RPC管理器未初始化".to_string()));
-                        decrement_active_downloads();
-                        return;
+                        return Err("RPC管理器未初始化".to_string());
                     }
                 }
             }
             None => {
                 log_error!("[{}] 获取RPC管理器锁超时", task_id_clone);
-                let _ = tx.send(Err("获取RPC管理器锁超时".to_string()));
-                decrement_active_downloads();
-                return;
+                return Err("获取RPC管理器锁超时".to_string());
             }
         };
 
-        // 添加下载任务到RPC服务器
+        // 上次运行时的 aria2c 仍保留着这个任务时直接继续使用原来的 GID，否则添加下载任务到RPC服务器
         log_debug!("[{}] 准备添加下载任务到RPC服务器", task_id_clone);
-        let mut gid =
-            match manager.add_download_sync(&url_owned, &download_dir_str, &filename_clone) {
-                Ok(id) => {
-                    log_info!("[{}] 下载任务添加成功，GID: {}", task_id_clone, id);
-                    id
-                }
-                Err(e) => {
-                    log_error!("[{}] 添加下载任务失败: {}", task_id_clone, e);
-                    let _ = tx.send(Err(e));
-                    decrement_active_downloads();
-                    return;
-                }
-            };
+        let restored_gid = match restored_gid {
+            Some(gid) => matches!(get_download_status(&gid).await, Ok(Some(_))).then_some(gid),
+            None => None,
+        };
+        let added = match restored_gid {
+            Some(gid) => {
+                log_info!(
+                    "[{}] aria2c中仍有上次的任务，继续使用GID: {}",
+                    task_id_clone,
+                    gid
+                );
+                Ok(gid)
+            }
+            None => {
+                manager
+                    .add_download(&url_owned, download_dir_str, &filename_clone)
+                    .await
+            }
+        };
+        let mut gid = match added {
+            Ok(id) => {
+                tracing::Span::current().record("gid", id.as_str());
+                log_info!("[{}] 下载任务添加成功，GID: {}", task_id_clone, id);
+                id
+            }
+            Err(e) => {
+                log_error!("[{}] 添加下载任务失败: {}", task_id_clone, e);
+                return Err(e);
+            }
+        };
+        let transfer = TransferRegistration::new(
+            &task_id_clone,
+            &url_owned,
+            download_dir_str,
+            &filename_clone,
+            &gid,
+        );
         log_debug!("[{}] 下载任务添加完成，开始监控进度", task_id_clone);
 
         // 监控下载进度
@@ -954,124 +2061,152 @@ pub async fn download_via_aria2(
         // 优化的下载进度监控配置
         let progress_interval = Duration::from_millis(800); // 提高监控频率到800ms
 
-        // 获取文件名
-        let display_filename = get_file_name(url_owned.as_str()).unwrap_or("未知文件".to_string());
-
-        // 创建Tokio运行时用于监控下载进度
-        let rt = match Runtime::new() {
-            Ok(rt) => rt,
-            Err(e) => {
-                log_error!("[{}] 创建Tokio运行时失败: {}", task_id_clone, e);
-                let _ = tx.send(Err(format!("创建Tokio运行时失败: {}", e)));
-                decrement_active_downloads();
-                return;
-            }
-        };
+        // 获取文件名，优先使用任务的文件名（可能来自服务器的 Content-Disposition）
+        let display_filename = crate::download_manager::active_task_file_name(&task_id_clone)
+            .or_else(|| get_file_name(url_owned.as_str()))
+            .unwrap_or("未知文件".to_string());
 
         // 监控下载进度，直到完成、失败
         let mut consecutive_failures = 0;
         let max_consecutive_failures = 8; // 增加连续失败次数阈值，避免过早判定失败
         let start_time = std::time::Instant::now(); // 记录下载开始时间
-        let mut last_progress = -1.0; // 记录上次进度，用于检测进度是否真正变化
-        let mut zero_speed_count = 0; // 记录下载速度为0的计次
+        let mut last_progress: f64 = -1.0; // 记录上次进度，用于检测进度是否真正变化
         let mut zero_speed_start_time: Option<std::time::Instant> = None; // 记录下载速度首次为0的时间
-        let max_zero_speed_checks = 10; // 最大计次，每秒1次，达到10次后重试
-        let zero_speed_check_interval = std::time::Duration::from_secs(1); // 计时间隔为1秒
-        let mut retry_count = 0; // 重试次数计数
-        let max_retries = 5; // 最大重试次数，达到5次后判定失败
+        let mut stall_attempts = 0; // 已执行的停滞恢复步骤次数
+        let mut stall_recovered_at = 0; // 上次执行恢复步骤时的已下载字节数
+        let mirror_urls = crate::host_adapter::mirror_urls(&url_owned); // 停滞时依次尝试的备用镜像
+        let mut current_url = url_owned.clone(); // 当前使用的下载地址
+        let mut waiting_for_network = false; // 是否因断网正在等待网络恢复
+        let mut paused = false; // 是否处于暂停状态
 
         loop {
             // 检查应用是否正在关闭，如果是则中断下载
             if is_app_shutting_down() {
                 log_info!("[{}] 检测到应用正在关闭，中断下载任务", task_id_clone);
-                let _ = tx.send(Err("下载被取消：应用程序正在关闭".to_string()));
-                decrement_active_downloads();
-                return;
+                return Err("下载被取消：应用程序正在关闭".to_string());
             }
 
-            // 检查是否有取消下载请求
-            if let Ok(cancel_requests) = CANCEL_DOWNLOAD_REQUESTS.lock() {
-                if let Some(cancel_reason) = cancel_requests.get(&task_id_clone) {
-                    log_info!(
-                        "[{}] 收到取消下载请求，原因: {}",
-                        task_id_clone,
-                        cancel_reason
-                    );
-                    // 从取消请求列表中移除
-                    let reason_clone = cancel_reason.clone();
-                    drop(cancel_requests); // 先释放锁
-                    if let Ok(mut cancel_requests_mut) = CANCEL_DOWNLOAD_REQUESTS.lock() {
-                        cancel_requests_mut.remove(&task_id_clone);
-                    }
+            // 检查下载任务是否已被取消
+            if let Some(cancel_reason) = cancel_token.reason() {
+                let message = finish_canceled_download(
+                    &app_handle,
+                    &task_id_clone,
+                    &gid,
+                    &display_filename,
+                    &cancel_reason,
+                )
+                .await;
+                return Err(message);
+            }
 
-                    // 根据取消原因决定是发送取消事件还是失败事件
-                    if reason_clone == "stalled" {
-                        // 下载停滞视为下载失败
-                        let error_message =
-                            format!("下载停滞，无法继续下载: {}", display_filename.clone());
-                        let _ = app_handle_for_events.emit_to(
-                            "main",
-                            "download-failed",
-                            &serde_json::json!({
-                                "taskId": task_id_clone.clone(),
-                                "filename": display_filename.clone(),
-                                "error": error_message
-                            }),
-                        );
-                    } else {
-                        // 普通取消
-                        let _ = app_handle_for_events.emit_to(
-                            "main",
-                            "download-canceled",
-                            &serde_json::json!({
-                                "taskId": task_id_clone.clone(),
-                                "filename": display_filename.clone()
-                            }),
-                        );
-                    }
+            if cancel_token.sleep_async(progress_interval).await {
+                continue;
+            }
 
-                    let _ = tokio::task::block_in_place(async move || {
-                        refresh_download_queue(app_handle.clone()).await.unwrap();
+            // 网络断开时等待网络恢复，期间不计入失败和停滞次数
+            if !crate::network_monitor::is_online() {
+                if !waiting_for_network {
+                    waiting_for_network = true;
+                    log_warn!("[{}] 网络连接已断开，等待网络恢复", task_id_clone);
+                    let progress_json = serde_json::json!({
+                        "progress": last_progress.max(0.0),
+                        "filename": display_filename.clone(),
+                        "taskId": task_id_clone.clone(),
+                        "waitingForNetwork": true,
+                        "message": tr!("event.download.waiting_network")
                     });
+                    _ = crate::event_dispatcher::emit(
+                        &app_handle_for_events,
+                        "download-progress",
+                        &progress_json,
+                    );
+                }
+                continue;
+            }
+            if waiting_for_network {
+                waiting_for_network = false;
+                consecutive_failures = 0;
+                zero_speed_start_time = None;
+                log_info!("[{}] 网络连接已恢复，继续下载", task_id_clone);
+                _ = crate::event_dispatcher::emit(
+                    &app_handle_for_events,
+                    "download-resumed",
+                    &serde_json::json!({
+                        "taskId": task_id_clone.clone(),
+                        "filename": display_filename.clone(),
+                        "message": tr!("event.download.network_restored")
+                    }),
+                );
+            }
 
-                    // 真正取消下载任务
-                    let gid_clone = gid.clone();
-                    let _ = tokio::task::block_in_place(async move || {
-                        if let Err(e) = cancel_download(&gid_clone).await {
-                            log_error!("取消下载任务失败: {}", e);
-                        }
+            // 暂停期间速度为0是正常的，不计入停滞次数
+            if is_downloads_paused() {
+                if !paused {
+                    paused = true;
+                    log_info!("[{}] 下载已暂停", task_id_clone);
+                    let progress_json = serde_json::json!({
+                        "progress": last_progress.max(0.0),
+                        "filename": display_filename.clone(),
+                        "taskId": task_id_clone.clone(),
+                        "paused": true,
+                        "message": tr!("event.download.paused")
                     });
-
-                    // 根据取消原因返回不同的错误信息
-                    if reason_clone == "stalled" {
-                        let error_message =
-                            format!("下载停滞，无法继续下载: {}", display_filename.clone());
-                        let _ = tx.send(Err(error_message));
-                    } else {
-                        let _ = tx.send(Err("用户取消下载".to_string()));
-                    }
-                    decrement_active_downloads();
-                    return;
+                    _ = crate::event_dispatcher::emit(
+                        &app_handle_for_events,
+                        "download-progress",
+                        &progress_json,
+                    );
                 }
+                continue;
+            }
+            if paused {
+                paused = false;
+                zero_speed_start_time = None;
+                log_info!("[{}] 下载已恢复", task_id_clone);
+                _ = crate::event_dispatcher::emit(
+                    &app_handle_for_events,
+                    "download-resumed",
+                    &serde_json::json!({
+                        "taskId": task_id_clone.clone(),
+                        "filename": display_filename.clone(),
+                        "message": tr!("event.download.resumed")
+                    }),
+                );
             }
 
-            std::thread::sleep(progress_interval);
+            // aria2c 被重新启动后旧的 GID 不再存在，重新添加任务并换用新的 GID
+            match transfer.reattach_if_restarted().await {
+                Ok(Some(new_gid)) => {
+                    tracing::Span::current().record("gid", new_gid.as_str());
+                    gid = new_gid;
+                    consecutive_failures = 0;
+                    zero_speed_start_time = None;
+                }
+                Ok(None) => {}
+                Err(e) => log_warn!("[{}] 重新添加任务失败: {}", task_id_clone, e),
+            }
 
             // 检查下载状态
-            let status_result = rt.block_on(get_download_status(&gid));
+            let status_result = get_download_status(&gid).await;
 
             match status_result {
                 Ok(Some(status)) => {
                     // 重置失败计数
                     consecutive_failures = 0;
 
+                    let settings = crate::settings::current_settings();
+                    let verbosity = settings.progress_verbosity;
+                    let verbose = verbosity == ProgressVerbosity::Full;
+
                     // 发送进度事件
-                    log_info!(
-                        "[{}] 下载进度: {:.1}% - 文件大小: {:.2}MB",
-                        task_id_clone,
-                        status.progress,
-                        status.total_size_mb
-                    );
+                    if verbose {
+                        log_info!(
+                            "[{}] 下载进度: {:.1}% - 文件大小: {:.2}MB",
+                            task_id_clone,
+                            status.progress,
+                            status.total_size_mb
+                        );
+                    }
 
                     // 计算已下载大小（MB）
                     let completed_mb = status.completed_length as f64 / (1024.0 * 1024.0);
@@ -1128,11 +2263,8 @@ pub async fn download_via_aria2(
                         speed_str.clone()
                     };
 
-                    log_debug!("[{}] 检查下载进度，GID: {}", task_id_clone, gid);
-
                     // 从GID中提取前6位作为缓存键
                     let cache_key = gid.chars().take(6).collect::<String>();
-                    log_debug!("[{}] 使用GID前6位作为缓存键: {}", task_id_clone, cache_key);
 
                     // 优化的raw_output格式，增加了更多有用信息
                     let raw_output = {
@@ -1149,7 +2281,16 @@ pub async fn download_via_aria2(
                             elapsed_str
                         )
                     };
-                    log_debug!("[{}] 最终使用的raw_output: {}", task_id_clone, raw_output);
+                    if verbose {
+                        log_debug!("[{}] raw_output: {}", task_id_clone, raw_output);
+                    }
+
+                    // 正在下载时获取各个连接所在的服务器，显示 aria2c 实际从哪些镜像分段下载
+                    let servers = if status.connections > 0 {
+                        get_download_servers(&gid).await
+                    } else {
+                        Vec::new()
+                    };
 
                     // 构建增强的JSON数据，包含更多下载信息
                     let progress_json = serde_json::json!(
@@ -1164,123 +2305,177 @@ pub async fn download_via_aria2(
                             "downloadSpeed": status.download_speed,
                             "avgDownloadSpeed": status.completed_length / (elapsed.max(1)),
                             "connections": status.connections,
+                            "servers": servers,
                             "elapsedTime": elapsed,
                             "eta": if status.download_speed > 0 { status.total_length.saturating_sub(status.completed_length) / status.download_speed } else { 0 }
                         }
                     );
 
-                    // 输出格式化的JSON（带有缩进）
-                    if let Ok(formatted_json) = serde_json::to_string_pretty(&progress_json) {
-                        log_info!("Sending download-progress: {}", formatted_json);
+                    // 只在调试级别输出格式化的JSON（带有缩进），避免每次查询都序列化
+                    if verbose && crate::log_utils::is_level_enabled(LogLevel::Debug) {
+                        if let Ok(formatted_json) = serde_json::to_string_pretty(&progress_json) {
+                            log_debug!("Sending download-progress: {}", formatted_json);
+                        }
                     }
 
-                    // 确保进度确实变化才发送事件
-                    if (status.progress - last_progress).abs() >= 0.1 || status.progress >= 100.0 {
+                    crate::download_manager::record_download_progress(
+                        &task_id_clone,
+                        &progress_json,
+                    );
+
+                    // 进度变化达到 progress_event_min_delta 才发送事件，由下载管理器按间隔合并发送
+                    if (status.progress - last_progress).abs() >= settings.progress_event_min_delta
+                        || status.progress >= 100.0
+                    {
                         last_progress = status.progress;
-                        let emit_result = app_handle_for_events.emit_to(
-                            "main",
-                            "download-progress",
-                            &progress_json,
+                        crate::download_manager::publish_download_progress(
+                            &app_handle_for_events,
+                            &task_id_clone,
+                            progress_json,
                         );
-                        if let Err(e) = emit_result {
-                            log_error!("[{}] 发送下载进度事件失败: {}", task_id_clone, e);
-                        } else {
+                        if verbosity != ProgressVerbosity::Off {
                             log_info!(
-                                "[{}] 成功发送下载进度事件: {:.1}%",
+                                "[{}] 发送下载进度事件: {:.1}%",
                                 task_id_clone,
                                 status.progress
                             );
                         }
                     }
 
-                    // 检查下载速度是否为0
-                    if status.download_speed == 0 {
-                        if zero_speed_start_time.is_none() {
-                            zero_speed_start_time = Some(std::time::Instant::now());
-                            log_warn!("[{}] 检测到下载速度为0，开始每秒计次", task_id_clone);
-                        } else {
-                            let elapsed_zero_speed =
-                                zero_speed_start_time.as_ref().unwrap().elapsed();
-                            if elapsed_zero_speed
-                                >= zero_speed_check_interval * (zero_speed_count + 1)
-                            {
-                                zero_speed_count += 1;
-                                log_warn!(
-                                    "[{}] 下载速度持续为0，已计次 {}/{} 次",
-                                    task_id_clone,
-                                    zero_speed_count,
-                                    max_zero_speed_checks
-                                );
-                            }
-                        }
-
-                        // 如果下载速度为0但进度不是100%，且达到最大计次，需要进行重试
-                        if status.progress < 100.0 && zero_speed_count >= max_zero_speed_checks {
-                            log_warn!(
-                                "[{}] 下载速度持续为0达到最大计次，准备重试下载",
-                                task_id_clone
-                            );
-
-                            retry_count += 1;
+                    // 检查下载速度是否为0，持续达到 stall_timeout_seconds 时按顺序执行恢复步骤
+                    if status.download_speed == 0 && status.progress < 100.0 {
+                        let stalled_since =
+                            *zero_speed_start_time.get_or_insert_with(std::time::Instant::now);
+                        let stalled = stalled_since.elapsed();
+                        if stalled >= Duration::from_secs(settings.stall_timeout_seconds) {
+                            let action = StallAction::for_attempt(stall_attempts, &settings);
+                            stall_attempts += 1;
+                            stall_recovered_at = status.completed_length;
+                            zero_speed_start_time = None;
                             log_warn!(
-                                "[{}] 当前重试次数: {}/{} 次",
+                                "[{}] 下载速度持续为0已 {} 秒，第 {} 次恢复: {}",
                                 task_id_clone,
-                                retry_count,
-                                max_retries
+                                stalled.as_secs(),
+                                stall_attempts,
+                                action.name()
                             );
 
-                            if retry_count <= max_retries {
-                                // 尝试取消当前任务
-                                log_info!("[{}] 取消当前停滞的下载任务", task_id_clone);
-                                let cancel_result = rt.block_on(cancel_download(&gid));
-                                if let Err(e) = cancel_result {
-                                    log_error!("[{}] 取消下载任务失败: {}", task_id_clone, e);
+                            let message = match action {
+                                StallAction::Unpause => {
+                                    if let Err(e) = set_download_paused(&gid, true).await {
+                                        log_warn!("[{}] 暂停停滞的任务失败: {}", task_id_clone, e);
+                                    }
+                                    // forcePause 是异步完成的，等待任务进入暂停状态后再继续
+                                    cancel_token.sleep_async(Duration::from_secs(1)).await;
+                                    if let Err(e) = set_download_paused(&gid, false).await {
+                                        log_warn!("[{}] 继续停滞的任务失败: {}", task_id_clone, e);
+                                    }
+                                    tr!("event.download.stall_unpause", count = stall_attempts)
                                 }
+                                StallAction::Mirror => {
+                                    // 依次尝试各个备用镜像，没有配置镜像时从原地址重新添加
+                                    let mirror_attempt =
+                                        (stall_attempts - settings.stall_unpause_attempts - 1)
+                                            as usize;
+                                    let next_url = if mirror_urls.is_empty() {
+                                        url_owned.clone()
+                                    } else {
+                                        mirror_urls[mirror_attempt % mirror_urls.len()].clone()
+                                    };
+                                    if let Err(e) = cancel_download(&gid).await {
+                                        log_warn!("[{}] 移除停滞的任务失败: {}", task_id_clone, e);
+                                    }
+                                    // 等待 aria2c 释放文件后再用相同的文件名添加（continue=true，继续已下载的部分）
+                                    cancel_token.sleep_async(Duration::from_secs(2)).await;
+                                    let added = match try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+                                        .and_then(|guard| guard.clone())
+                                    {
+                                        Some(current_manager) => {
+                                            current_manager
+                                                .add_download(
+                                                    &next_url,
+                                                    download_dir_str,
+                                                    &filename_clone,
+                                                )
+                                                .await
+                                        }
+                                        None => Err("RPC管理器未初始化".to_string()),
+                                    };
+                                    match added {
+                                        Ok(new_gid) => {
+                                            log_info!(
+                                                "[{}] 已从 {} 重新添加任务，新GID: {}",
+                                                task_id_clone,
+                                                next_url,
+                                                new_gid
+                                            );
+                                            transfer.switch_url(
+                                                &next_url,
+                                                &new_gid,
+                                                ENGINE_GENERATION.load(Ordering::SeqCst),
+                                            );
+                                            tracing::Span::current()
+                                                .record("gid", new_gid.as_str());
+                                            gid = new_gid;
+                                            current_url = next_url;
+                                        }
+                                        Err(e) => {
+                                            log_error!(
+                                                "[{}] 从 {} 重新添加任务失败: {}",
+                                                task_id_clone,
+                                                next_url,
+                                                e
+                                            );
+                                        }
+                                    }
+                                    let host = tauri::Url::parse(&current_url)
+                                        .ok()
+                                        .and_then(|url| url.host_str().map(str::to_string))
+                                        .unwrap_or_default();
+                                    tr!("event.download.stall_mirror", host = host)
+                                }
+                                StallAction::Fail => {
+                                    tr!("event.download.stalled", filename = display_filename)
+                                }
+                            };
 
-                                // 重新开始下载
-                                log_info!("[{}] 重新开始下载任务", task_id_clone);
-
-                                // 取消当前任务并重新下载
-                                // 1. 发送取消事件
-                                let _ = app_handle_for_events.emit_to(
-                                    "main",
-                                    "download-canceled",
-                                    &serde_json::json!({
-                                        "taskId": task_id_clone.clone(),
-                                        "filename": display_filename.clone(),
-                                        "reason": "重新下载（速度为0）"
-                                    }),
-                                );
-
-                                // 2. 等待一段时间后继续监控，让系统有时间处理取消事件
-                                std::thread::sleep(Duration::from_secs(2));
+                            let _ = crate::event_dispatcher::emit(
+                                &app_handle_for_events,
+                                "download-stalled",
+                                &serde_json::json!({
+                                    "taskId": task_id_clone.clone(),
+                                    "filename": display_filename.clone(),
+                                    "action": action.name(),
+                                    "attempt": stall_attempts,
+                                    "stalledSeconds": stalled.as_secs(),
+                                    "url": current_url.clone(),
+                                    "message": message.clone()
+                                }),
+                            );
 
-                                // 2. 重置计数和计时，继续监控
-                                zero_speed_count = 0;
-                                zero_speed_start_time = None;
-                            } else {
-                                // 达到最大重试次数，判定下载失败
-                                log_error!("[{}] 已达到最大重试次数，下载失败", task_id_clone);
-                                let error_message =
-                                    format!("下载停滞，无法继续下载: {}", display_filename.clone());
-                                let _ = app_handle_for_events.emit_to(
-                                    "main",
+                            if action == StallAction::Fail {
+                                log_error!("[{}] 停滞恢复步骤已用完，下载失败", task_id_clone);
+                                let _ = crate::event_dispatcher::emit(
+                                    &app_handle_for_events,
                                     "download-failed",
                                     &serde_json::json!({
                                         "taskId": task_id_clone.clone(),
                                         "filename": display_filename.clone(),
-                                        "error": error_message
+                                        "error": message
                                     }),
                                 );
-                                let _ = tx.send(Err(error_message));
-                                decrement_active_downloads();
-                                return;
+                                return Err(message);
                             }
+                            continue;
                         }
                     } else {
-                        // 下载速度不为0，重置计数和计时
-                        zero_speed_count = 0;
+                        // 下载速度不为0，重置计时；恢复后确实继续下载了才重新从第一个恢复步骤开始
                         zero_speed_start_time = None;
+                        if status.completed_length
+                            >= stall_recovered_at.saturating_add(STALL_RECOVERED_BYTES)
+                        {
+                            stall_attempts = 0;
+                        }
                     }
 
                     if status.progress >= 100.0 {
@@ -1290,8 +2485,8 @@ pub async fn download_via_aria2(
                         // 多次确认下载状态，确保真的完成
                         let mut confirmed_complete = false;
                         for _ in 0..3 {
-                            std::thread::sleep(Duration::from_secs(1));
-                            let final_status = rt.block_on(get_download_status(&gid));
+                            cancel_token.sleep_async(Duration::from_secs(1)).await;
+                            let final_status = get_download_status(&gid).await;
                             if let Ok(Some(final_stat)) = final_status {
                                 if final_stat.progress >= 100.0 {
                                     log_info!(
@@ -1324,9 +2519,7 @@ pub async fn download_via_aria2(
                     // 先尝试重新获取RPC管理器，可能连接失效
                     if let Err(e) = start_aria2c_on_demand() {
                         log_error!("[{}] 重新获取RPC管理器失败: {}", task_id_clone, e);
-                        let _ = tx.send(Err(e));
-                        decrement_active_downloads();
-                        return;
+                        return Err(e);
                     };
 
                     // 检查文件是否存在且不为空
@@ -1339,14 +2532,14 @@ pub async fn download_via_aria2(
 
                             // 增加额外的等待时间，确保文件下载完全
                             log_info!("[{}] 增加额外等待时间以确保下载完全完成", task_id_clone);
-                            std::thread::sleep(Duration::from_secs(5));
+                            cancel_token.sleep_async(Duration::from_secs(5)).await;
 
                             // 多次检查文件大小是否有变化，确保下载真的完成
                             let mut size_stable = true;
                             let initial_size = metadata.len();
 
                             for i in 0..3 {
-                                std::thread::sleep(Duration::from_secs(2));
+                                cancel_token.sleep_async(Duration::from_secs(2)).await;
                                 if let Ok(new_metadata) = fs::metadata(&file_path_clone) {
                                     if new_metadata.len() != initial_size {
                                         log_warn!(
@@ -1377,10 +2570,10 @@ pub async fn download_via_aria2(
                                     "progress": 99.0,
                                     "filename": display_filename.clone(),
                                     "taskId": task_id_clone.clone(),
-                                    "message": "下载中，任务状态查询暂时不可用"
+                                    "message": tr!("event.download.status_unavailable")
                                 });
-                                _ = app_handle_for_events.emit_to(
-                                    "main",
+                                _ = crate::event_dispatcher::emit(
+                                    &app_handle_for_events,
                                     "download-progress",
                                     &progress_json,
                                 );
@@ -1388,18 +2581,14 @@ pub async fn download_via_aria2(
                             }
                         } else {
                             log_error!("[{}] 任务文件为空，下载可能失败", task_id_clone);
-                            let _ = tx.send(Err("下载失败：任务文件为空".to_string()));
-                            decrement_active_downloads();
-                            return;
+                            return Err("下载失败：任务文件为空".to_string());
                         }
                     } else {
                         log_error!("[{}] 无法访问任务文件，下载可能失败", task_id_clone);
                         // 重试访问文件
-                        std::thread::sleep(Duration::from_secs(2));
+                        cancel_token.sleep_async(Duration::from_secs(2)).await;
                         if let Err(_) = fs::metadata(&file_path_clone) {
-                            let _ = tx.send(Err("下载失败：无法访问任务文件".to_string()));
-                            decrement_active_downloads();
-                            return;
+                            return Err("下载失败：无法访问任务文件".to_string());
                         }
                     }
                 }
@@ -1426,30 +2615,33 @@ pub async fn download_via_aria2(
                                 task_id_clone,
                                 manager_err
                             );
-                        } else if let Some(manager_guard) =
+                        } else if let Some(current_manager) =
                             try_lock_with_timeout(&ARIA2_RPC_MANAGER, 1000)
+                                .and_then(|guard| guard.clone())
                         {
-                            if let Some(current_manager) = manager_guard.as_ref() {
-                                log_info!("[{}] 尝试使用新的RPC管理器重新添加任务", task_id_clone);
-
-                                // 使用原始URL重新添加任务
-                                if let Ok(new_gid) = current_manager.add_download_sync(
-                                    &url_owned,
-                                    &download_dir_str,
-                                    &filename_clone,
-                                ) {
-                                    log_info!(
-                                        "[{}] 任务重新添加成功，新GID: {}",
-                                        task_id_clone,
-                                        new_gid
-                                    );
-                                    // 更新GID，继续监控新的任务
-                                    gid = new_gid;
-                                    consecutive_failures = 0;
-                                    continue;
-                                } else {
-                                    log_error!("[{}] 任务重新添加失败", task_id_clone);
-                                }
+                            log_info!("[{}] 尝试使用新的RPC管理器重新添加任务", task_id_clone);
+
+                            // 使用原始URL重新添加任务
+                            if let Ok(new_gid) = current_manager
+                                .add_download(&current_url, download_dir_str, &filename_clone)
+                                .await
+                            {
+                                log_info!(
+                                    "[{}] 任务重新添加成功，新GID: {}",
+                                    task_id_clone,
+                                    new_gid
+                                );
+                                // 更新GID，继续监控新的任务
+                                transfer.update_gid(
+                                    &new_gid,
+                                    ENGINE_GENERATION.load(Ordering::SeqCst),
+                                );
+                                tracing::Span::current().record("gid", new_gid.as_str());
+                                gid = new_gid;
+                                consecutive_failures = 0;
+                                continue;
+                            } else {
+                                log_error!("[{}] 任务重新添加失败", task_id_clone);
                             }
                         }
                     }
@@ -1487,16 +2679,17 @@ pub async fn download_via_aria2(
                         "progress": last_progress.max(0.0),
                         "filename": display_filename.clone(),
                         "taskId": task_id_clone.clone(),
-                        "message": format!("正在重试... ({}次重试)", consecutive_failures)
+                        "message": tr!("event.download.retrying", count = consecutive_failures)
                     });
-                    _ = app_handle_for_events.emit_to("main", "download-progress", &progress_json);
+                    _ = crate::event_dispatcher::emit(
+                        &app_handle_for_events,
+                        "download-progress",
+                        &progress_json,
+                    );
 
                     // 定期更新下载队列状态，确保前端能正确显示任务栏
                     if consecutive_failures % 3 == 0 {
-                        let _ = {
-                            let app_handle: &AppHandle = &app_handle_for_events;
-                            let _ = refresh_download_queue(app_handle.clone());
-                        };
+                        let _ = refresh_download_queue(app_handle_for_events.clone()).await;
                     }
 
                     // 如果连续失败次数过多，认为下载失败
@@ -1533,23 +2726,19 @@ pub async fn download_via_aria2(
                                     // 只在文件特别小（0字节）时才判定失败
                                     if metadata.len() == 0 {
                                         log_error!("[{}] 文件大小为0，确认下载失败", task_id_clone);
-                                        let _ = tx.send(Err(
+                                        return Err(
                                             "下载失败：获取状态失败且文件大小为0".to_string()
-                                        ));
-                                        decrement_active_downloads();
-                                        return;
+                                        );
                                     }
                                 }
                             }
                         }
 
                         log_error!("[{}] 文件也不存在，确认下载失败", task_id_clone);
-                        let _ = tx.send(Err(format!(
+                        return Err(format!(
                             "下载失败：连续获取下载状态失败，最后错误：{}",
                             e
-                        )));
-                        decrement_active_downloads();
-                        return;
+                        ));
                     }
 
                     // 继续尝试
@@ -1578,14 +2767,14 @@ pub async fn download_via_aria2(
             let is_file_valid = check_file_magic_number(&file_path_clone);
 
             if is_file_valid {
-                // 发送下载完成事件到main窗口，表示文件已下载完成
-                let emit_result = app_handle_for_events.emit_to(
-                    "main",
+                // 发送下载完成事件，表示文件已下载完成
+                let emit_result = crate::event_dispatcher::emit(
+                    &app_handle_for_events,
                     "download-complete",
                     &serde_json::json!({
                         "taskId": task_id_clone.clone(),
                         "success": true,
-                        "message": "下载完成，正在准备解压",
+                        "message": tr!("event.download.complete"),
                         "filename": display_filename,
                         "fileSize": final_file_size
                     }),
@@ -1599,33 +2788,29 @@ pub async fn download_via_aria2(
                     "[{}] 下载完成但文件魔数检查失败，可能是无效文件",
                     task_id_clone
                 );
-                let _ = app_handle_for_events.emit_to(
-                    "main",
+                let _ = crate::event_dispatcher::emit(
+                    &app_handle_for_events,
                     "download-failed",
                     &serde_json::json!({
                         "taskId": task_id_clone.clone(),
                         "filename": display_filename,
-                        "error": "下载完成但文件魔数检查失败，可能是无效文件"
+                        "error": tr!("event.download.invalid_magic")
                     }),
                 );
-                let _ = tx.send(Err("下载完成但文件魔数检查失败，可能是无效文件".to_string()));
-                decrement_active_downloads();
-                return;
+                return Err(tr!("event.download.invalid_magic"));
             }
         } else {
             log_error!("[{}] 下载完成但文件大小为0，发送失败事件", task_id_clone);
-            let _ = app_handle_for_events.emit_to(
-                "main",
+            let _ = crate::event_dispatcher::emit(
+                &app_handle_for_events,
                 "download-failed",
                 &serde_json::json!({
                     "taskId": task_id_clone.clone(),
                     "filename": display_filename,
-                    "error": "下载完成但文件大小为0"
+                    "error": tr!("event.download.empty_file")
                 }),
             );
-            let _ = tx.send(Err("下载完成但文件大小为0".to_string()));
-            decrement_active_downloads();
-            return;
+            return Err(tr!("event.download.empty_file"));
         }
 
         // 等待aria2c完全释放文件 - 检查是否存在临时的aria2文件
@@ -1646,8 +2831,9 @@ pub async fn download_via_aria2(
         // 等待aria2临时文件消失或文件大小稳定
         while (aria2_file_path.exists() || consecutive_stable_size < 10)
             && wait_count < max_wait_seconds * 2
+            && !cancel_token.is_cancelled()
         {
-            std::thread::sleep(check_interval);
+            cancel_token.sleep_async(check_interval).await;
             wait_count += 1;
 
             // 检查文件大小是否稳定
@@ -1694,7 +2880,7 @@ pub async fn download_via_aria2(
             let mut size_stable = true;
 
             for _i in 0..3 {
-                std::thread::sleep(Duration::from_secs(1));
+                cancel_token.sleep_async(Duration::from_secs(1)).await;
                 if let Ok(new_metadata) = fs::metadata(&file_path_clone) {
                     if new_metadata.len() != initial_size {
                         log_warn!(
@@ -1717,32 +2903,94 @@ pub async fn download_via_aria2(
             } else {
                 log_warn!("[{}] 文件大小仍在变化，可能下载尚未完全完成", task_id_clone);
                 // 再次等待额外时间
-                std::thread::sleep(Duration::from_secs(5));
+                cancel_token.sleep_async(Duration::from_secs(5)).await;
             }
         } else {
             log_info!("[{}] aria2临时文件已消失，文件已完全释放", task_id_clone);
         }
 
-        // 减少活跃下载计数，如果为0则关闭aria2c
-        decrement_active_downloads();
-
-        // 发送成功结果
-        let _ = tx.send(Ok(file_path_clone.to_string_lossy().to_string()));
+        // 等待释放文件期间被取消
+        if let Some(cancel_reason) = cancel_token.reason() {
+            let message = finish_canceled_download(
+                &app_handle,
+                &task_id_clone,
+                &gid,
+                &display_filename,
+                &cancel_reason,
+            )
+            .await;
+            return Err(message);
+        }
 
-        log_debug!("[{}] 下载线程结束", task_id_clone);
-    });
+        log_debug!("[{}] 下载监控结束", task_id_clone);
 
-    // 等待下载完成并返回结果
-    log_debug!("[{}] 等待下载线程完成...", task_id);
-    let result = rx.recv().map_err(|_| {
-        log_error!("[{}] 接收下载结果失败", task_id);
-        decrement_active_downloads();
-        "接收下载结果失败".to_string()
-    })?;
+        // 返回成功结果
+        Ok(file_path_clone.to_string_lossy().to_string())
+    }
+    .instrument(span)
+    .await;
 
+    // 减少活跃下载计数，如果为0则关闭aria2c
+    decrement_active_downloads();
     result
 }
 
+/// 结束被取消的下载 - 发送取消事件（下载停滞时为失败事件）并从aria2c中移除任务
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+/// - `task_id`: 下载任务ID
+/// - `gid`: aria2c 任务GID
+/// - `filename`: 显示的文件名
+/// - `reason`: 取消原因
+///
+/// # 返回值
+/// - 下载结果的错误信息
+async fn finish_canceled_download(
+    app_handle: &AppHandle,
+    task_id: &str,
+    gid: &str,
+    filename: &str,
+    reason: &str,
+) -> String {
+    log_info!("[{}] 收到取消下载请求，原因: {}", task_id, reason);
+
+    // 根据取消原因决定是发送取消事件还是失败事件
+    let message = if reason == "stalled" {
+        // 下载停滞视为下载失败
+        let error_message = format!("下载停滞，无法继续下载: {}", filename);
+        let _ = crate::event_dispatcher::emit(
+            app_handle,
+            "download-failed",
+            &serde_json::json!({
+                "taskId": task_id,
+                "filename": filename,
+                "error": error_message
+            }),
+        );
+        error_message
+    } else {
+        // 普通取消
+        let _ = crate::event_dispatcher::emit(
+            app_handle,
+            "download-canceled",
+            &serde_json::json!({
+                "taskId": task_id,
+                "filename": filename
+            }),
+        );
+        "用户取消下载".to_string()
+    };
+
+    let _ = refresh_download_queue(app_handle.clone()).await;
+
+    // 真正取消下载任务
+    if let Err(e) = cancel_download(gid).await {
+        log_error!("取消下载任务失败: {}", e);
+    }
+    message
+}
+
 /// 检查文件的魔数，判断文件是否有效
 /// 考虑到文件可能被aria2锁定，会进行多次尝试读取
 fn check_file_magic_number(file_path: &PathBuf) -> bool {
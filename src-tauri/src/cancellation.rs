@@ -0,0 +1,84 @@
+// cancellation.rs 模块 - 任务取消令牌：随任务保存在队列中，下载监控、解压和等待 .aria2 文件的循环定期检查，
+// 取消后正在等待的循环会被立即唤醒
+
+// 标准库导入
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+/// 取消令牌 - 克隆的令牌共享同一个取消状态
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<(Mutex<Option<String>>, Condvar)>,
+}
+
+impl CancellationToken {
+    /// 创建未取消的令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取消任务并唤醒所有正在等待的循环，已取消时保留第一次的原因
+    ///
+    /// # 参数
+    /// - `reason`: 取消原因（例如 normal、stalled）
+    ///
+    /// # 返回值
+    /// - 本次调用取消了任务时返回true，已经取消过时返回false
+    pub fn cancel(&self, reason: &str) -> bool {
+        let (reason_slot, condvar) = &*self.inner;
+        let mut current = reason_slot.lock().unwrap();
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(reason.to_string());
+        condvar.notify_all();
+        true
+    }
+
+    /// 是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    /// 取消原因，没有取消时返回None
+    pub fn reason(&self) -> Option<String> {
+        self.inner.0.lock().unwrap().clone()
+    }
+
+    /// 等待指定时间，期间被取消时立即返回
+    ///
+    /// # 返回值
+    /// - 已取消时返回true
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (reason_slot, condvar) = &*self.inner;
+        let deadline = Instant::now() + duration;
+        let mut current = reason_slot.lock().unwrap();
+        while current.is_none() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            current = condvar.wait_timeout(current, deadline - now).unwrap().0;
+        }
+        current.is_some()
+    }
+
+    /// 异步等待指定时间，期间被取消时在一个检查间隔内返回
+    ///
+    /// # 返回值
+    /// - 已取消时返回true
+    pub async fn sleep_async(&self, duration: Duration) -> bool {
+        const CHECK_INTERVAL: Duration = Duration::from_millis(200);
+        let deadline = tokio::time::Instant::now() + duration;
+        while !self.is_cancelled() {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            tokio::time::sleep(CHECK_INTERVAL.min(deadline - now)).await;
+        }
+        true
+    }
+}
@@ -0,0 +1,422 @@
+// catalog.rs 模块 - 地图目录客户端：直接调用 maps.nyase.ru 的目录接口（AList 的 /api/fs/list、/api/fs/search、/api/fs/get），
+// 提供分类、搜索、分页和地图详情，不依赖内嵌的网站页面
+//
+// 接口响应按请求内容缓存在下载缓存目录的 catalog 子目录中，缓存未过期时直接使用；
+// 请求失败（例如断网）时使用已过期的缓存。
+
+// 标准库导入
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+// 第三方库导入
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+// 内部模块导入
+use crate::{
+    dir_manager::get_global_cache_dir,
+    error::{AppError, AppResult},
+    log_debug, log_info, log_warn, tr,
+};
+
+/// 目录网站地址
+pub const CATALOG_BASE_URL: &str = "https://maps.nyase.ru";
+
+/// 缓存目录名（位于下载缓存目录下）
+const CATALOG_CACHE_DIR: &str = "catalog";
+
+/// 列表和搜索结果的缓存时间
+const LIST_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 地图详情的缓存时间（包含有时效的下载签名，比列表短）
+const DETAILS_CACHE_TTL: Duration = Duration::from_secs(2 * 60);
+
+/// 请求超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 每页条目数的上限
+const MAX_PER_PAGE: u32 = 200;
+
+/// 目录接口的响应
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    code: i64,
+    #[serde(default)]
+    message: String,
+    data: Option<T>,
+}
+
+/// 目录接口返回的文件或目录
+#[derive(Debug, Clone, Deserialize)]
+struct ApiObject {
+    name: String,
+    /// 搜索结果中的父目录
+    #[serde(default)]
+    parent: Option<String>,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    is_dir: bool,
+    #[serde(default)]
+    modified: Option<String>,
+    #[serde(default)]
+    sign: Option<String>,
+}
+
+/// /api/fs/list 和 /api/fs/search 的结果
+#[derive(Debug, Deserialize)]
+struct ApiList {
+    #[serde(default)]
+    content: Option<Vec<ApiObject>>,
+    #[serde(default)]
+    total: u64,
+}
+
+/// /api/fs/get 的结果
+#[derive(Debug, Deserialize)]
+struct ApiDetails {
+    #[serde(flatten)]
+    object: ApiObject,
+    #[serde(default)]
+    readme: Option<String>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    hash_info: Option<BTreeMap<String, String>>,
+}
+
+/// 目录中的地图或分类
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogEntry {
+    pub name: String,
+    /// 在目录网站中的完整路径
+    pub path: String,
+    pub is_dir: bool,
+    /// 文件大小（字节）
+    pub size: u64,
+    pub modified: Option<String>,
+    /// 可以直接传给 install 的下载链接（分类为None）
+    pub download_url: Option<String>,
+}
+
+/// 一页结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogPage {
+    pub entries: Vec<CatalogEntry>,
+    /// 符合条件的总数
+    pub total: u64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// 地图详情
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapDetails {
+    #[serde(flatten)]
+    pub entry: CatalogEntry,
+    /// 说明（目录网站中的 README）
+    pub description: Option<String>,
+    /// 存储来源
+    pub provider: Option<String>,
+    /// 校验和：算法（如 sha1、md5） -> 值
+    pub checksums: BTreeMap<String, String>,
+}
+
+/// 规范化目录路径：以 "/" 开头，不以 "/" 结尾，不包含 ".."
+fn normalize_path(path: &str) -> AppResult<String> {
+    let parts = path
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+    if parts.iter().any(|part| *part == "." || *part == "..") {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_catalog_path",
+            path = path
+        )));
+    }
+    Ok(format!("/{}", parts.join("/")))
+}
+
+/// 拼接父目录和名称
+fn join_path(parent: &str, name: &str) -> String {
+    format!("{}/{}", parent.trim_end_matches('/'), name)
+}
+
+/// 地图文件的下载链接
+fn download_url(path: &str, sign: Option<&str>) -> String {
+    let encoded = path
+        .split('/')
+        .map(|part| urlencoding::encode(part).into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    match sign.filter(|sign| !sign.is_empty()) {
+        Some(sign) => format!("{}/d{}?sign={}", CATALOG_BASE_URL, encoded, sign),
+        None => format!("{}/d{}", CATALOG_BASE_URL, encoded),
+    }
+}
+
+/// 把接口返回的对象转换为目录条目
+fn to_entry(object: ApiObject, parent: &str) -> CatalogEntry {
+    let path = join_path(object.parent.as_deref().unwrap_or(parent), &object.name);
+    CatalogEntry {
+        download_url: (!object.is_dir).then(|| download_url(&path, object.sign.as_deref())),
+        name: object.name,
+        path,
+        is_dir: object.is_dir,
+        size: object.size,
+        modified: object.modified,
+    }
+}
+
+/// 请求的缓存文件路径
+fn cache_path(endpoint: &str, body: &Value) -> Result<PathBuf, String> {
+    let dir = get_global_cache_dir()?.join(CATALOG_CACHE_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("创建目录缓存失败: {}", e))?;
+    let key = Sha256::digest(format!("{}\n{}", endpoint, body).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    Ok(dir.join(format!("{}.json", key)))
+}
+
+/// 读取缓存，`ttl` 为None时不检查是否过期
+fn read_cache<T: DeserializeOwned>(path: &Path, ttl: Option<Duration>) -> Option<T> {
+    let metadata = fs::metadata(path).ok()?;
+    if let Some(ttl) = ttl {
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+        if age > ttl {
+            return None;
+        }
+    }
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// 发送请求并返回 data
+async fn request(endpoint: &str, body: &Value) -> Result<Value, String> {
+    let client = crate::cert_pinning::client_builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+    let response = client
+        .post(format!("{}{}", CATALOG_BASE_URL, endpoint))
+        .json(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    crate::cert_pinning::verify_response(&response)?;
+
+    let response: ApiResponse<Value> = response.json().await.map_err(|e| e.to_string())?;
+    if response.code != 200 {
+        return Err(format!(
+            "目录接口返回错误 ({}): {}",
+            response.code, response.message
+        ));
+    }
+    Ok(response.data.unwrap_or(Value::Null))
+}
+
+/// 调用目录接口，优先使用未过期的缓存，请求失败时使用过期的缓存
+async fn cached_request<T: DeserializeOwned>(
+    endpoint: &str,
+    body: Value,
+    ttl: Duration,
+) -> AppResult<T> {
+    let cache = cache_path(endpoint, &body)
+        .map_err(|e| {
+            log_warn!("无法使用目录缓存: {}", e);
+        })
+        .ok();
+    if let Some(cached) = cache.as_ref().and_then(|path| read_cache(path, Some(ttl))) {
+        log_debug!("使用缓存的目录结果: {} {}", endpoint, body);
+        return Ok(cached);
+    }
+
+    match request(endpoint, &body).await {
+        Ok(data) => {
+            if let Some(path) = &cache {
+                if let Err(e) = fs::write(path, data.to_string()) {
+                    log_warn!("保存目录缓存失败: {}", e);
+                }
+            }
+            serde_json::from_value(data).map_err(AppError::from)
+        }
+        Err(e) => {
+            if let Some(stale) = cache.as_ref().and_then(|path| read_cache(path, None)) {
+                log_warn!("请求目录接口失败，使用过期的缓存: {}", e);
+                return Ok(stale);
+            }
+            Err(AppError::Internal(tr!(
+                "error.catalog_request_failed",
+                error = e
+            )))
+        }
+    }
+}
+
+/// 列出目录
+async fn list(path: &str, page: u32, per_page: u32) -> AppResult<CatalogPage> {
+    let list: ApiList = cached_request(
+        "/api/fs/list",
+        json!({
+            "path": path,
+            "password": "",
+            "page": page,
+            "per_page": per_page,
+            "refresh": false,
+        }),
+        LIST_CACHE_TTL,
+    )
+    .await?;
+    Ok(CatalogPage {
+        entries: list
+            .content
+            .unwrap_or_default()
+            .into_iter()
+            .map(|object| to_entry(object, path))
+            .collect(),
+        total: list.total,
+        page,
+        per_page,
+    })
+}
+
+/// 获取地图分类（目录网站根目录下的目录）
+#[tauri::command(async)]
+pub async fn get_catalog_categories() -> AppResult<Vec<CatalogEntry>> {
+    let page = list("/", 1, MAX_PER_PAGE).await?;
+    Ok(page
+        .entries
+        .into_iter()
+        .filter(|entry| entry.is_dir)
+        .collect())
+}
+
+/// 搜索地图目录 - 关键词为空时列出分类中的内容
+///
+/// # 参数
+/// - `keywords`: 搜索关键词
+/// - `category`: 分类路径，为空时搜索整个目录
+/// - `page`: 页码（从 1 开始）
+/// - `per_page`: 每页条目数
+///
+/// # 返回值
+/// - 一页结果
+#[tauri::command(async)]
+pub async fn search_catalog(
+    keywords: Option<String>,
+    category: Option<String>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> AppResult<CatalogPage> {
+    let parent = normalize_path(category.as_deref().unwrap_or("/"))?;
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(50).clamp(1, MAX_PER_PAGE);
+    let keywords = keywords.unwrap_or_default().trim().to_string();
+
+    if keywords.is_empty() {
+        return list(&parent, page, per_page).await;
+    }
+
+    log_info!(
+        "搜索地图目录: {} (分类: {}, 第 {} 页)",
+        keywords,
+        parent,
+        page
+    );
+    let result: ApiList = cached_request(
+        "/api/fs/search",
+        json!({
+            "parent": parent,
+            "keywords": keywords,
+            "scope": 0,
+            "page": page,
+            "per_page": per_page,
+            "password": "",
+        }),
+        LIST_CACHE_TTL,
+    )
+    .await?;
+    Ok(CatalogPage {
+        entries: result
+            .content
+            .unwrap_or_default()
+            .into_iter()
+            .map(|object| to_entry(object, &parent))
+            .collect(),
+        total: result.total,
+        page,
+        per_page,
+    })
+}
+
+/// 获取地图详情
+///
+/// # 参数
+/// - `path`: 地图在目录网站中的路径
+pub async fn map_details(path: &str) -> AppResult<MapDetails> {
+    let path = normalize_path(path)?;
+    let details: ApiDetails = cached_request(
+        "/api/fs/get",
+        json!({ "path": path, "password": "" }),
+        DETAILS_CACHE_TTL,
+    )
+    .await?;
+
+    let parent = path
+        .rsplit_once('/')
+        .map(|(parent, _)| parent)
+        .unwrap_or("");
+    let mut entry = to_entry(details.object, parent);
+    entry.path = path;
+    Ok(MapDetails {
+        entry,
+        description: details.readme.filter(|readme| !readme.trim().is_empty()),
+        provider: details.provider,
+        checksums: details.hash_info.unwrap_or_default(),
+    })
+}
+
+/// 从目录网站的下载或预览链接（/d/...、/p/...）中取出地图在目录中的路径
+///
+/// # 参数
+/// - `url`: 链接
+///
+/// # 返回值
+/// - 不是目录网站的链接时返回None
+pub fn catalog_path(url: &str) -> Option<String> {
+    let parsed = tauri::Url::parse(url).ok()?;
+    let base = tauri::Url::parse(CATALOG_BASE_URL).ok()?;
+    if parsed.host_str() != base.host_str() {
+        return None;
+    }
+    let path = parsed
+        .path()
+        .strip_prefix("/d/")
+        .or_else(|| parsed.path().strip_prefix("/p/"))?;
+    let decoded = urlencoding::decode(path).ok()?;
+    normalize_path(&decoded).ok()
+}
+
+/// 获取地图详情命令
+///
+/// # 参数
+/// - `path`: 地图在目录网站中的路径
+///
+/// # 返回值
+/// - 地图详情，包括下载链接、说明和校验和
+#[tauri::command(async)]
+pub async fn get_map_details(path: String) -> AppResult<MapDetails> {
+    map_details(&path).await
+}
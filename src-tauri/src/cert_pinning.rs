@@ -0,0 +1,125 @@
+// cert_pinning.rs 模块 - 第一方站点（maps.nyase.ru、op.nyase.ru）的证书公钥固定
+//
+// 固定的公钥通过 config.json 的 certificate_pins 配置（格式为 "sha256/<Base64>"，即证书 SubjectPublicKeyInfo
+// 的 SHA-256），为空时不启用。启用后应用自身的 HTTP 请求会校验服务器证书的公钥；aria2c 不支持公钥固定，
+// 下载前先由应用请求一次同一地址完成校验，并可通过 ca_certificate 配置限制 aria2c 信任的 CA。
+
+// 第三方库导入
+use base64::Engine;
+use reqwest::{tls::TlsInfo, ClientBuilder, Response};
+use sha2::{Digest, Sha256};
+use tauri::Url;
+
+// 内部模块导入
+use crate::{log_debug, log_error};
+
+/// 启用证书固定的第一方站点（包括其子域名）
+const FIRST_PARTY_HOSTS: &[&str] = &["maps.nyase.ru", "op.nyase.ru"];
+
+/// 公钥固定值的前缀
+const PIN_PREFIX: &str = "sha256/";
+
+/// 获取链接需要匹配的公钥固定值，没有启用证书固定时返回None
+fn pins_for(url: &Url) -> Option<Vec<String>> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let is_first_party = FIRST_PARTY_HOSTS
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)));
+    if !is_first_party {
+        return None;
+    }
+
+    let pins = crate::settings::current_settings()
+        .certificate_pins
+        .iter()
+        .map(|pin| pin.trim().trim_start_matches(PIN_PREFIX).to_string())
+        .filter(|pin| !pin.is_empty())
+        .collect::<Vec<_>>();
+    (!pins.is_empty()).then_some(pins)
+}
+
+/// 是否需要对该链接进行证书固定校验
+pub fn is_pinned(url: &str) -> bool {
+    Url::parse(url).ok().as_ref().and_then(pins_for).is_some()
+}
+
+/// 计算证书公钥的固定值（SubjectPublicKeyInfo 的 SHA-256，Base64 编码）
+fn public_key_pin(certificate_der: &[u8]) -> Result<String, String> {
+    let (_, certificate) = x509_parser::parse_x509_certificate(certificate_der)
+        .map_err(|e| format!("解析服务器证书失败: {}", e))?;
+    let digest = Sha256::digest(certificate.public_key().raw);
+    Ok(base64::engine::general_purpose::STANDARD.encode(digest))
+}
+
+/// 创建可以进行证书固定校验的 HTTP 客户端构建器（保留 TLS 连接信息）
+pub fn client_builder() -> ClientBuilder {
+    reqwest::Client::builder().tls_info(true)
+}
+
+/// 校验响应的服务器证书公钥 - 没有启用证书固定的站点直接通过
+///
+/// 响应必须来自 client_builder 创建的客户端，否则无法获取证书信息
+///
+/// # 参数
+/// - `response`: HTTP 响应
+///
+/// # 返回值
+/// - 证书公钥与配置匹配时返回Ok(())，否则返回包含错误信息的Err
+pub fn verify_response(response: &Response) -> Result<(), String> {
+    let url = response.url();
+    let Some(pins) = pins_for(url) else {
+        return Ok(());
+    };
+    let host = url.host_str().unwrap_or_default();
+
+    if url.scheme() != "https" {
+        return Err(format!("{} 已启用证书固定，必须使用HTTPS连接", host));
+    }
+
+    let certificate = response
+        .extensions()
+        .get::<TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .ok_or_else(|| format!("无法获取 {} 的服务器证书", host))?;
+    let pin = public_key_pin(certificate)?;
+
+    if pins.contains(&pin) {
+        log_debug!("{} 证书公钥校验通过", host);
+        Ok(())
+    } else {
+        log_error!(
+            "{} 证书公钥不匹配: {}{}，连接可能被拦截",
+            host,
+            PIN_PREFIX,
+            pin
+        );
+        Err(format!(
+            "{} 的服务器证书与固定的公钥不匹配，连接可能被代理或安全软件拦截",
+            host
+        ))
+    }
+}
+
+/// 请求一次链接并校验服务器证书公钥，用于 aria2c 下载前的检查
+///
+/// # 参数
+/// - `url`: 要检查的链接
+///
+/// # 返回值
+/// - 校验通过或该站点没有启用证书固定时返回Ok(())
+pub async fn check_endpoint(url: &str) -> Result<(), String> {
+    if !is_pinned(url) {
+        return Ok(());
+    }
+
+    let client = client_builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .map_err(|e| format!("连接服务器失败: {}", e))?;
+    verify_response(&response)
+}
@@ -0,0 +1,95 @@
+// cli_output.rs 模块 - 自动化模式：使用 --json 启动时，将下载、解压和更新事件以 NDJSON（每行一个 JSON 对象）输出到标准输出
+//
+// 每行格式为 {"event": 事件名, "payload": 事件内容, "timestamp": RFC 3339 时间}，payload 与前端收到的 Tauri 事件内容一致。
+// 该模式下日志改为输出到标准错误，标准输出只包含 NDJSON 行。
+// 注意：应用为单实例运行，已有实例时新进程会把参数转发给已有实例后退出，事件只会出现在第一个实例的输出中。
+
+// 标准库导入
+use std::io::Write;
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use serde_json::Value;
+use tauri::{AppHandle, Listener};
+
+/// 启用 NDJSON 输出的命令行参数
+pub const JSON_OUTPUT_FLAG: &str = "--json";
+
+/// 以 NDJSON 输出的事件
+const AUTOMATION_EVENTS: &[&str] = &[
+    "download-task-add",
+    "download-task-start",
+    "download-filename-resolved",
+    "download-progress",
+    "download-complete",
+    "download-failed",
+    "download-retrying",
+    "download-cancel-requested",
+    "download-canceled",
+    "download-resumed",
+    "download-queue-update",
+    "download-queue-paused",
+    "network-offline",
+    "network-online",
+    "engine-status-changed",
+    "game-started",
+    "game-stopped",
+    "extract-start",
+    "extract-complete",
+    "extract-retrying",
+    "extract-redownload",
+    "extract-queue-update",
+    "map-conflict",
+    "new-map-available",
+    "update-available",
+    "update-progress",
+    "update-downloaded",
+    "deep-link-open",
+];
+
+lazy_static! {
+    /// 是否以 --json 参数启动
+    static ref JSON_OUTPUT_ENABLED: bool = std::env::args().any(|arg| arg == JSON_OUTPUT_FLAG);
+}
+
+/// 是否启用了 NDJSON 输出
+pub fn is_json_output_enabled() -> bool {
+    *JSON_OUTPUT_ENABLED
+}
+
+/// 向标准输出写入一行 NDJSON，写入失败（例如管道已关闭）时忽略
+pub fn write_json_line(event: &str, payload: Value) {
+    let line = serde_json::json!({
+        "event": event,
+        "payload": payload,
+        "timestamp": chrono::Local::now().to_rfc3339(),
+    });
+
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", line);
+    let _ = stdout.flush();
+}
+
+/// 注册事件监听，将自动化相关的事件输出为 NDJSON - 未使用 --json 启动时不做任何事
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn install(app_handle: &AppHandle) {
+    if !is_json_output_enabled() {
+        return;
+    }
+
+    for &event in AUTOMATION_EVENTS {
+        app_handle.listen_any(event, move |e| {
+            // 事件内容是序列化后的 JSON，无法解析时按字符串输出
+            let payload = serde_json::from_str(e.payload())
+                .unwrap_or_else(|_| Value::String(e.payload().to_string()));
+            write_json_line(event, payload);
+        });
+    }
+
+    write_json_line(
+        "ready",
+        serde_json::json!({ "version": app_handle.package_info().version.to_string() }),
+    );
+}
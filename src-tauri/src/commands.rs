@@ -6,7 +6,7 @@ use std::hash::{Hash, Hasher};
 
 // 第三方库导入
 use serde_json;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_dialog::MessageDialogKind;
 use uuid::Uuid;
 
@@ -14,16 +14,24 @@ use uuid::Uuid;
 use crate::{
     dialog_manager::show_dialog,
     dir_manager::DIR_MANAGER,
-    download_manager::{process_download, process_download_queue, DownloadTask, DOWNLOAD_QUEUE},
+    download_manager::{
+        process_download, process_download_queue, DownloadTask, QueuedDownload, DOWNLOAD_QUEUE,
+    },
+    error::{AppError, AppResult},
     handle_deep_link, log_debug, log_error, log_info, log_warn,
+    queue_manager::{TaskPriority, TaskState},
+    tr,
     utils::get_file_name,
 };
 
 // 显示窗口
-fn show_window(window: &tauri::WebviewWindow, window_name: &str) -> Result<(), String> {
+fn show_window(window: &tauri::WebviewWindow, window_name: &str) -> AppResult<()> {
     if let Err(e) = window.show() {
         log_error!("显示{}窗口失败: {:?}", window_name, e);
-        return Err(format!("显示窗口失败: {:?}", e));
+        return Err(AppError::Window(tr!(
+            "error.window_show_failed",
+            error = format!("{:?}", e)
+        )));
     }
     Ok(())
 }
@@ -45,7 +53,33 @@ fn reset_window_state(window: &tauri::WebviewWindow, window_name: &str) {
     }
 }
 
+// 将窗口区域限制在显示器范围内（物理像素），窗口比显示器大时缩小到显示器大小
+fn clamp_to_monitor(
+    position: tauri::PhysicalPosition<i32>,
+    size: tauri::PhysicalSize<u32>,
+    monitor: &tauri::Monitor,
+) -> (tauri::PhysicalPosition<i32>, tauri::PhysicalSize<u32>) {
+    let monitor_pos = monitor.position();
+    let monitor_size = monitor.size();
+
+    let width = size.width.min(monitor_size.width);
+    let height = size.height.min(monitor_size.height);
+    let max_x = monitor_pos.x + (monitor_size.width - width) as i32;
+    let max_y = monitor_pos.y + (monitor_size.height - height) as i32;
+
+    (
+        tauri::PhysicalPosition {
+            x: position.x.clamp(monitor_pos.x, max_x),
+            y: position.y.clamp(monitor_pos.y, max_y),
+        },
+        tauri::PhysicalSize { width, height },
+    )
+}
+
 // 继承主窗口位置和大小
+//
+// 位置和大小限制在主窗口所在的显示器内；先把窗口移动到该显示器，
+// 再按该显示器的缩放比例以逻辑像素设置大小，避免不同 DPI 的显示器之间出现尺寸错乱
 fn inherit_window_position_and_size_from_main(
     window: &tauri::WebviewWindow,
     app_handle: &AppHandle,
@@ -57,24 +91,38 @@ fn inherit_window_position_and_size_from_main(
             main_window.inner_size(),
             main_window.is_maximized(),
         ) {
-            // 继承主窗口位置
-            if let Err(e) =
-                window.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
-                    x: main_pos.x,
-                    y: main_pos.y,
-                }))
-            {
+            let monitor = main_window
+                .current_monitor()
+                .ok()
+                .flatten()
+                .or_else(|| main_window.primary_monitor().ok().flatten());
+            let (position, size, scale_factor) = match &monitor {
+                Some(monitor) => {
+                    let (position, size) = clamp_to_monitor(main_pos, main_size, monitor);
+                    (position, size, monitor.scale_factor())
+                }
+                None => (
+                    main_pos,
+                    main_size,
+                    main_window.scale_factor().unwrap_or(1.0),
+                ),
+            };
+
+            // 先移动到目标显示器，使窗口采用该显示器的缩放比例
+            if let Err(e) = window.set_position(tauri::Position::Physical(position)) {
                 log_error!("设置{}窗口位置失败: {:?}", window_name, e);
             }
 
-            // 继承主窗口大小
-            if let Err(e) = window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
-                width: main_size.width,
-                height: main_size.height,
-            })) {
+            // 继承主窗口大小（按目标显示器的缩放比例转换为逻辑像素）
+            if let Err(e) = window.set_size(tauri::Size::Logical(size.to_logical(scale_factor))) {
                 log_error!("设置{}窗口大小失败: {:?}", window_name, e);
             }
 
+            // 调整大小后再次设置位置，确保窗口左上角与主窗口对齐
+            if let Err(e) = window.set_position(tauri::Position::Physical(position)) {
+                log_error!("设置{}窗口位置失败: {:?}", window_name, e);
+            }
+
             // 继承主窗口最大化状态
             if is_maximized {
                 if let Err(e) = window.maximize() {
@@ -94,7 +142,7 @@ fn inherit_window_position_and_size_from_main(
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn open_filemanager_window(app_handle: AppHandle) -> Result<(), String> {
+pub fn open_filemanager_window(app_handle: AppHandle) -> AppResult<()> {
     log_info!("接收到打开文件管理器窗口请求");
 
     match app_handle.get_webview_window("filemanager") {
@@ -102,7 +150,10 @@ pub fn open_filemanager_window(app_handle: AppHandle) -> Result<(), String> {
             show_window(&window, "文件管理器")?;
             focus_window(&window, "文件管理器");
             reset_window_state(&window, "文件管理器");
-            inherit_window_position_and_size_from_main(&window, &app_handle, "文件管理器");
+            // 优先恢复上次关闭时的位置和大小，没有记录时继承主窗口位置
+            if !crate::window_state::restore_window_geometry(&window) {
+                inherit_window_position_and_size_from_main(&window, &app_handle, "文件管理器");
+            }
 
             if let Some(window) = app_handle.get_webview_window("filemanager") {
                 window.reload().unwrap();
@@ -113,7 +164,9 @@ pub fn open_filemanager_window(app_handle: AppHandle) -> Result<(), String> {
         }
         None => {
             log_error!("未找到文件管理器窗口");
-            Err("未找到文件管理器窗口配置".to_string())
+            Err(AppError::NotFound(tr!(
+                "error.filemanager_window_not_found"
+            )))
         }
     }
 }
@@ -124,7 +177,7 @@ pub fn open_filemanager_window(app_handle: AppHandle) -> Result<(), String> {
 /// - 成功时返回包含分组文件信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
+pub fn get_maps(app_handle: AppHandle) -> AppResult<serde_json::Value> {
     log_info!("接收到获取maps文件列表请求");
 
     // 尝试从配置文件读取 nmd_data 目录
@@ -134,7 +187,10 @@ pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
     let (maps_dir, addons_dir) = {
         let mut manager = DIR_MANAGER.lock().map_err(|e| {
             log_error!("无法锁定目录管理器: {:?}", e);
-            format!("无法锁定目录管理器: {:?}", e)
+            AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            ))
         })?;
 
         // 初始化目录管理器（如果需要）
@@ -148,16 +204,18 @@ pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
                 log_warn!("未配置 nmd_data 目录，弹窗要求配置");
                 show_dialog(
                     &app_handle,
-                    "请先配置数据存储目录。\n\n在文件管理器窗口中点击\"修改目录\"按钮进行配置。",
+                    &tr!("dialog.data_dir_missing.message"),
                     MessageDialogKind::Warning,
-                    "未配置数据目录",
+                    &tr!("dialog.data_dir_missing.title"),
                 );
-                return Err("未配置数据存储目录，请先配置".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.data_dir_not_configured"
+                )));
             };
 
             *manager = Some(dir_manager.map_err(|e| {
                 log_error!("目录管理器初始化失败: {}", e);
-                e
+                AppError::Config(e)
             })?);
         }
 
@@ -169,7 +227,9 @@ pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
             Some(p) => p.to_owned(),
             None => {
                 log_warn!("未配置 addons_dir");
-                return Err("未配置 addons_dir".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.addons_dir_not_configured"
+                )));
             }
         };
 
@@ -209,7 +269,7 @@ pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
                                                     if file_path.is_file() {
                                                         // 只处理vpk文件
                                                         if let Some(ext) = file_path.extension() {
-                                                            if ext != "vpk" {
+                                                            if !ext.eq_ignore_ascii_case("vpk") {
                                                                 continue;
                                                             }
                                                         } else {
@@ -330,7 +390,10 @@ pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
         }
         Err(e) => {
             log_error!("读取maps目录失败: {:?}", e);
-            return Err(format!("读取目录失败: {:?}", e));
+            return Err(AppError::FileOperation(tr!(
+                "error.read_dir_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -347,7 +410,7 @@ pub fn get_maps(app_handle: AppHandle) -> Result<serde_json::Value, String> {
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn open_serverlist_window(app_handle: AppHandle) -> Result<(), String> {
+pub fn open_serverlist_window(app_handle: AppHandle) -> AppResult<()> {
     log_info!("接收到打开服务器列表窗口请求");
 
     match app_handle.get_webview_window("serverlist") {
@@ -355,14 +418,49 @@ pub fn open_serverlist_window(app_handle: AppHandle) -> Result<(), String> {
             show_window(&window, "服务器列表")?;
             focus_window(&window, "服务器列表");
             reset_window_state(&window, "服务器列表");
-            inherit_window_position_and_size_from_main(&window, &app_handle, "服务器列表");
+            // 优先恢复上次关闭时的位置和大小，没有记录时继承主窗口位置
+            if !crate::window_state::restore_window_geometry(&window) {
+                inherit_window_position_and_size_from_main(&window, &app_handle, "服务器列表");
+            }
 
             log_info!("服务器列表窗口已成功打开");
             Ok(())
         }
         None => {
             log_error!("未找到服务器列表窗口");
-            Err("未找到服务器列表窗口配置".to_string())
+            Err(AppError::NotFound(tr!("error.serverlist_window_not_found")))
+        }
+    }
+}
+
+/// 打开下载列表窗口
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄，用于获取窗口实例
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok
+/// - 失败时返回包含错误信息的Err
+#[tauri::command]
+pub fn open_downloads_window(app_handle: AppHandle) -> AppResult<()> {
+    log_info!("接收到打开下载列表窗口请求");
+
+    match app_handle.get_webview_window(crate::downloads_window::DOWNLOADS_WINDOW) {
+        Some(window) => {
+            show_window(&window, "下载列表")?;
+            focus_window(&window, "下载列表");
+            reset_window_state(&window, "下载列表");
+            // 优先恢复上次关闭时的位置和大小，没有记录时继承主窗口位置
+            if !crate::window_state::restore_window_geometry(&window) {
+                inherit_window_position_and_size_from_main(&window, &app_handle, "下载列表");
+            }
+
+            log_info!("下载列表窗口已成功打开");
+            Ok(())
+        }
+        None => {
+            log_error!("未找到下载列表窗口");
+            Err(AppError::NotFound(tr!("error.downloads_window_not_found")))
         }
     }
 }
@@ -377,20 +475,25 @@ pub fn open_serverlist_window(app_handle: AppHandle) -> Result<(), String> {
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn delete_map_file(group_name: String, file_name: String) -> Result<String, String> {
+pub async fn delete_map_file(group_name: String, file_name: String) -> AppResult<String> {
     log_info!("接收到删除文件请求: 组={}, 文件={}", group_name, file_name);
 
     // 获取 maps 目录
     let maps_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager.as_ref().unwrap().maps_dir()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -400,22 +503,32 @@ pub fn delete_map_file(group_name: String, file_name: String) -> Result<String,
     // 检查文件是否存在
     if !file_path.exists() {
         log_error!("文件不存在: {}", file_path.display());
-        return Err(format!("文件不存在: {}", file_name));
+        return Err(AppError::NotFound(tr!(
+            "error.file_not_found",
+            name = file_name
+        )));
     }
 
     // 检查是否为文件
     if !file_path.is_file() {
         log_error!("指定的路径不是文件: {}", file_path.display());
-        return Err(format!("指定的路径不是文件: {}", file_name));
+        return Err(AppError::InvalidInput(tr!(
+            "error.not_a_file",
+            name = file_name
+        )));
     }
 
-    // 删除文件
-    if let Err(e) = std::fs::remove_file(&file_path) {
-        log_error!("删除文件失败: {}, 错误: {:?}", file_path.display(), e);
-        return Err(format!("删除文件失败: {:?}", e));
+    // 删除文件（默认移动到回收站，永久删除时权限不足会通过 helper 提权删除）
+    if let Err(e) = crate::symlink_manager::delete_path(&file_path).await {
+        log_error!("删除文件失败: {}, 错误: {}", file_path.display(), e);
+        return Err(AppError::FileOperation(tr!(
+            "error.delete_file_failed",
+            error = e
+        )));
     }
 
     log_info!("文件已成功删除: {}", file_path.display());
+    crate::dir_manager::clear_dir_size_cache();
 
     // 检查并删除空文件夹
     let group_dir = file_path.parent();
@@ -444,20 +557,25 @@ pub fn delete_map_file(group_name: String, file_name: String) -> Result<String,
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn delete_group(group_name: String) -> Result<String, String> {
+pub async fn delete_group(group_name: String) -> AppResult<String> {
     log_info!("接收到删除分组请求: 组={}", group_name);
 
     // 获取 maps 目录
     let maps_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager.as_ref().unwrap().maps_dir()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -467,44 +585,68 @@ pub fn delete_group(group_name: String) -> Result<String, String> {
     // 检查目录是否存在
     if !group_dir.exists() {
         log_error!("分组目录不存在: {}", group_dir.display());
-        return Err(format!("分组目录不存在: {}", group_name));
+        return Err(AppError::NotFound(tr!(
+            "error.group_not_found",
+            name = group_name
+        )));
     }
 
     // 检查是否为目录
     if !group_dir.is_dir() {
         log_error!("指定的路径不是目录: {}", group_dir.display());
-        return Err(format!("指定的路径不是目录: {}", group_name));
+        return Err(AppError::InvalidInput(tr!(
+            "error.not_a_directory",
+            name = group_name
+        )));
     }
 
-    // 删除目录及其内容
-    if let Err(e) = std::fs::remove_dir_all(&group_dir) {
-        log_error!("删除分组失败: {}, 错误: {:?}", group_dir.display(), e);
-        return Err(format!("删除分组失败: {:?}", e));
+    // 删除目录及其内容（默认移动到回收站，永久删除时权限不足会通过 helper 提权删除）
+    if let Err(e) = crate::symlink_manager::delete_path(&group_dir).await {
+        log_error!("删除分组失败: {}, 错误: {}", group_dir.display(), e);
+        return Err(AppError::FileOperation(tr!(
+            "error.delete_group_failed",
+            error = e
+        )));
     }
 
     log_info!("分组已成功删除: {}", group_dir.display());
+    crate::dir_manager::clear_dir_size_cache();
     Ok(format!("分组 {} 已成功删除", group_name))
 }
 
 /// 下载函数 - 将地图下载任务添加到下载队列
 ///
+/// 任务加入队列后立即返回，不等待下载完成；下载进度和结果通过 download-progress、
+/// download-complete、download-failed 等事件通知。
+///
 /// # 参数
 /// - `url`: 要下载的文件URL
 /// - `path`: 下载完成后保存的文件路径
+/// - `max_attempts`: 下载失败后最多尝试的次数（可选，未指定时使用配置的 max_download_attempts）
 /// - `app_handle`: Tauri应用句柄，用于发送事件通知
 ///
 /// # 返回值
-/// - 成功时返回包含成功信息的Ok
+/// - 成功时返回加入队列的任务信息
 /// - 失败时返回包含错误信息的Err
 #[tauri::command(async)]
 pub async fn install(
     url: &str,
     savepath: &str,
     saveonly: bool,
+    max_attempts: Option<u32>,
     app_handle: AppHandle,
-) -> Result<String, String> {
+) -> AppResult<QueuedDownload> {
     log_info!("接收到下载请求: URL={}, Path={}", url, savepath);
 
+    // 队列中等待的任务已达到上限时直接拒绝，不再解析链接
+    let capacity = crate::download_manager::check_queue_capacity(&DOWNLOAD_QUEUE.lock().unwrap());
+    if let Err(full) = capacity {
+        return Err(crate::download_manager::reject_queue_full(
+            &app_handle,
+            full,
+        ));
+    }
+
     // 检查并初始化目录管理器（仅在需要时锁定）
     {
         log_debug!("检查目录管理器状态...");
@@ -513,11 +655,14 @@ pub async fn install(
             log_error!("无法锁定目录管理器: {:?}", e);
             show_dialog(
                 &app_handle_clone,
-                &format!("无法锁定目录管理器: {:?}", e),
+                &tr!("error.lock_dir_manager_failed", error = format!("{:?}", e)),
                 MessageDialogKind::Error,
-                "错误",
+                &tr!("dialog.error.title"),
             );
-            format!("无法锁定目录管理器: {:?}", e)
+            AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            ))
         })?;
 
         // 如果目录管理器尚未初始化，则进行初始化
@@ -538,43 +683,61 @@ pub async fn install(
                 log_warn!("未配置 nmd_data 目录，弹窗要求配置");
                 show_dialog(
                     &app_handle.clone(),
-                    "请先配置数据存储目录。\n\n在文件管理器窗口中点击\"修改\"按钮进行配置。",
+                    &tr!("dialog.data_dir_missing.message"),
                     MessageDialogKind::Warning,
-                    "未配置数据目录",
+                    &tr!("dialog.data_dir_missing.title"),
                 );
-                return Err("未配置数据存储目录，请先配置".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.data_dir_not_configured"
+                )));
             };
 
             *manager = Some(dir_manager.map_err(|e| {
                 log_error!("目录管理器初始化失败: {}", e);
                 show_dialog(
                     &app_handle.clone(),
-                    &format!("目录管理器初始化失败: {}", e),
+                    &tr!("dialog.dir_manager_init_failed", error = e),
                     MessageDialogKind::Error,
-                    "错误",
+                    &tr!("dialog.error.title"),
                 );
-                e
+                AppError::Config(e)
             })?);
             log_info!("目录管理器初始化成功");
         }
     }
 
+    // 跟随短链接和重定向，队列中保存并显示最终的下载地址；网盘分享链接转换为直链，文件名从服务器响应中获取
+    let resolved_url = crate::url_resolver::resolve_url(url).await;
+    let resolved = crate::host_adapter::resolve_share_link(&resolved_url).await;
+    let url = resolved.url.as_str();
+
+    // 按下载策略检查网站和文件大小，不符合时不加入队列
+    if let Err(rejection) = crate::download_policy::check(url).await {
+        log_warn!("下载请求被下载策略拒绝: {}, 原因: {}", url, rejection);
+        crate::telemetry::record("install.policy_denied");
+        return Err(AppError::PolicyDenied(rejection));
+    }
+
     // 生成唯一的任务ID
     let task_id = Uuid::new_v4().to_string();
     log_info!("生成任务ID: {}", task_id);
 
-    // 尝试从URL中提取文件名
-    let filename = get_file_name(url).unwrap_or_else(|| {
-        log_error!("无法从URL中提取文件名: {}", url);
-        // 显示错误对话框
-        show_dialog(
-            &app_handle,
-            &format!("无法从URL中提取文件名: {}", url),
-            MessageDialogKind::Error,
-            "错误",
-        );
-        "unknown".to_string()
-    });
+    // 尝试从服务器响应或URL中提取文件名
+    let filename = resolved
+        .file_name
+        .clone()
+        .or_else(|| get_file_name(url))
+        .unwrap_or_else(|| {
+            log_error!("无法从URL中提取文件名: {}", url);
+            // 显示错误对话框
+            show_dialog(
+                &app_handle,
+                &tr!("dialog.filename_from_url_failed", url = url),
+                MessageDialogKind::Error,
+                &tr!("dialog.error.title"),
+            );
+            "unknown".to_string()
+        });
 
     // 创建下载任务
     let task = DownloadTask {
@@ -583,6 +746,8 @@ pub async fn install(
         savepath: Some(savepath.to_string()),
         saveonly: saveonly,
         filename: Some(filename.clone()),
+        attempt: 0,
+        max_attempts,
     };
     log_info!("创建下载任务: ID={}, URL={}", task_id, url);
 
@@ -590,6 +755,14 @@ pub async fn install(
     log_debug!("尝试锁定下载队列并添加任务...");
     {
         let mut queue = (&*DOWNLOAD_QUEUE).lock().unwrap();
+        // 解析链接期间其他请求可能已经填满了队列
+        if let Err(full) = crate::download_manager::check_queue_capacity(&queue) {
+            drop(queue);
+            return Err(crate::download_manager::reject_queue_full(
+                &app_handle,
+                full,
+            ));
+        }
         queue.add_task(task.id.clone(), task);
         log_info!(
             "任务已添加到下载队列，当前队列长度: {}",
@@ -617,11 +790,17 @@ pub async fn install(
 
     // 获取当前队列信息
     log_debug!("获取当前队列信息...");
-    let total_tasks = {
+    let (position, total_tasks) = {
         let queue = (&*DOWNLOAD_QUEUE).lock().unwrap();
         let size = queue.waiting_tasks.len();
         let active = queue.active_tasks.len();
         let total = size + active;
+        // 队列处理可能已经开始下载该任务，此时位置为0
+        let position = queue
+            .waiting_tasks
+            .iter()
+            .position(|id| *id == task_id)
+            .map_or(0, |index| index + 1);
 
         log_debug!(
             "当前队列中有 {} 个等待任务，{} 个活跃任务，总共 {} 个任务",
@@ -629,13 +808,13 @@ pub async fn install(
             active,
             total
         );
-        total
+        (position, total)
     };
 
     // 发送任务添加事件通知
     log_debug!("发送download-task-add事件...");
-    let _ = app_handle.emit_to(
-        "main",
+    let _ = crate::event_dispatcher::emit(
+        &app_handle,
         "download-task-add",
         &serde_json::json!({
             "taskId": task_id,
@@ -643,17 +822,22 @@ pub async fn install(
             "filename": filename
         }),
     );
+    crate::downloads_window::publish_task_detail(&app_handle, &task_id);
+    crate::telemetry::record("install.queued");
 
-    // 返回成功消息
+    // 返回任务信息
     log_info!(
         "下载请求处理完成: 任务ID={}, 总任务数={}",
         task_id,
         total_tasks
     );
-    Ok(format!(
-        "任务已添加到下载队列，当前总任务数: {}",
-        total_tasks
-    ))
+    Ok(QueuedDownload {
+        task_id,
+        url: url.to_string(),
+        filename,
+        position,
+        total_tasks,
+    })
 }
 
 /// 取消下载任务 - 从下载队列中移除指定的下载任务
@@ -674,7 +858,7 @@ pub async fn cancel_download(
     task_id: &str,
     app_handle: AppHandle,
     reason: Option<&str>,
-) -> Result<String, String> {
+) -> AppResult<String> {
     // 处理取消下载原因，如果没有提供则默认为普通取消
     let cancel_reason = reason.unwrap_or("normal");
     log_info!(
@@ -687,13 +871,14 @@ pub async fn cancel_download(
     let mut queue = (&*DOWNLOAD_QUEUE).lock().unwrap();
 
     // 查找并移除队列中的任务
-    let original_len = queue.waiting_tasks.len();
-    queue.waiting_tasks.retain(|task| task != task_id);
-
-    if original_len != queue.waiting_tasks.len() {
+    if queue.remove_pending_task(task_id).is_some() {
         log_info!("任务 {} 已从等待队列中移除", task_id);
     }
 
+    if crate::download_manager::cancel_pending_retries(Some(task_id)) > 0 {
+        log_info!("任务 {} 已取消重试", task_id);
+    }
+
     // 检查任务是否在活跃任务中
     let task_in_active = queue.active_tasks.iter().any(|task| task == task_id);
     if task_in_active {
@@ -704,21 +889,18 @@ pub async fn cancel_download(
             queue.active_tasks.remove(index);
         }
 
-        // 将任务ID和取消原因添加到取消下载请求列表
-        if let Ok(mut cancel_requests) = crate::aria2c::CANCEL_DOWNLOAD_REQUESTS.lock() {
-            cancel_requests.insert(task_id.to_string(), cancel_reason.to_string());
+        // 取消任务的取消令牌，下载监控在一秒内结束下载
+        if queue.cancel_task(task_id, cancel_reason) {
             log_info!(
-                "已将任务ID {} 添加到取消下载请求列表，取消原因: {}",
+                "已取消下载任务 {} 的取消令牌，取消原因: {}",
                 task_id,
                 cancel_reason
             );
-        } else {
-            log_error!("无法锁定取消下载请求列表");
         }
 
         // 发送取消下载事件给前端，包含任务ID
-        let _ = app_handle.emit_to(
-            "main",
+        let _ = crate::event_dispatcher::emit(
+            &app_handle,
             "download-cancel-requested",
             &serde_json::json!({ "taskId": task_id }),
         );
@@ -740,40 +922,37 @@ pub async fn cancel_download(
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command(async)]
-pub async fn refresh_download_queue(app_handle: AppHandle) -> Result<String, String> {
+pub async fn refresh_download_queue(app_handle: AppHandle) -> AppResult<String> {
     log_info!("接收到刷新下载队列请求");
 
     // 发送队列更新事件通知
     let (total_tasks, active_tasks, waiting_tasks) = {
         let queue = (&*DOWNLOAD_QUEUE).lock().unwrap();
-        let active = queue
-            .active_tasks
-            .iter()
-            .filter_map(|task_id| {
-                queue.tasks.get(task_id).map(|task| {
+        // 构建任务列表（转换为可序列化的格式）
+        let list = |state| {
+            queue
+                .tasks_in(state)
+                .map(|(_, task)| {
                     serde_json::json!({"id": task.id, "url": task.url, "filename": task.filename})
                 })
-            })
-            .collect::<Vec<_>>();
-
-        // 构建等待任务列表（转换为可序列化的格式）
-        let tasks = queue
-            .waiting_tasks
-            .iter()
-            .filter_map(|task_id| {
-                queue.tasks.get(task_id).map(|task| {
-                    serde_json::json!({"id": task.id, "url": task.url, "filename": task.filename})
-                })
-            })
-            .collect::<Vec<_>>();
+                .collect::<Vec<_>>()
+        };
+        let active = list(TaskState::Active);
+        // 等待中的任务附带队列中的位置（从1开始）和预计多少秒后开始（无法估算时为null）
+        let mut tasks = list(TaskState::Waiting);
+        let estimates = crate::download_manager::estimate_start_times(&queue);
+        for (index, (task, start)) in tasks.iter_mut().zip(estimates).enumerate() {
+            task["position"] = (index + 1).into();
+            task["estimatedStart"] = start.into();
+        }
 
         let total = active.len() + tasks.len();
 
         (total, active, tasks)
     };
 
-    let _ = app_handle.emit_to(
-        "main",
+    let _ = crate::event_dispatcher::emit(
+        &app_handle,
         "download-queue-update",
         &serde_json::json!({
             "queue": {"waiting_tasks": waiting_tasks,
@@ -795,9 +974,45 @@ pub async fn refresh_download_queue(app_handle: AppHandle) -> Result<String, Str
     ))
 }
 
+/// 获取下载队列的完整快照 - 一次返回等待中、正在下载（包含GID、进度和速度）和最近结束的任务
+///
+/// refresh_download_queue 只通过事件推送队列变化，新打开的窗口可以先调用此命令渲染完整状态，再监听增量事件。
+///
+/// # 返回值
+/// - 成功时返回队列状态
+#[tauri::command]
+pub fn get_queue_state() -> AppResult<crate::download_manager::QueueState> {
+    Ok(crate::download_manager::get_queue_state())
+}
+
+/// 获取正在下载的任务在 aria2c 中的完整状态 - 原样返回 aria2.tellStatus 的结果（分块位图、连接、文件列表、错误代码等），
+/// 供高级详情面板使用，后端不做任何处理
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+///
+/// # 返回值
+/// - 成功时返回 { taskId, gid, status }
+/// - 任务没有在 aria2c 中下载时返回NotFound
+#[tauri::command(async)]
+pub async fn get_download_details(task_id: String) -> AppResult<serde_json::Value> {
+    let not_downloading = || AppError::NotFound(tr!("error.task_not_downloading", id = &task_id));
+    let (gid, _) = crate::aria2c::transfer_state(&task_id).ok_or_else(not_downloading)?;
+    let status = crate::aria2c::download_details(&gid)
+        .await
+        .map_err(AppError::Download)?
+        .ok_or_else(not_downloading)?;
+
+    Ok(serde_json::json!({
+        "taskId": task_id,
+        "gid": gid,
+        "status": status,
+    }))
+}
+
 /// 取消所有排队任务但保留当前正在下载的任务
 #[tauri::command(async)]
-pub async fn cancel_all_downloads(app_handle: AppHandle) -> Result<String, String> {
+pub async fn cancel_all_downloads(app_handle: AppHandle) -> AppResult<String> {
     log_info!("接收到取消所有排队任务请求");
 
     let queue_tasks_count;
@@ -806,11 +1021,9 @@ pub async fn cancel_all_downloads(app_handle: AppHandle) -> Result<String, Strin
     {
         let mut queue = (&*DOWNLOAD_QUEUE).lock().unwrap();
 
-        // 记录等待队列中的任务数量
-        queue_tasks_count = queue.waiting_tasks.len();
-
-        // 清空等待队列
-        queue.waiting_tasks.clear();
+        // 清空等待队列，记录等待队列和等待重试的任务数量
+        queue_tasks_count =
+            queue.clear_pending_tasks() + crate::download_manager::cancel_pending_retries(None);
 
         // 获取活跃任务数量
         let active_tasks_count = queue.active_tasks.len();
@@ -834,14 +1047,190 @@ pub async fn cancel_all_downloads(app_handle: AppHandle) -> Result<String, Strin
     ))
 }
 
+/// 暂停所有下载 - 正在下载的任务保持在队列中，恢复后从断点继续
+#[tauri::command(async)]
+pub async fn pause_all_downloads(app_handle: AppHandle) -> AppResult<String> {
+    log_info!("接收到暂停所有下载请求");
+
+    crate::shell_integration::set_downloads_paused(&app_handle, true)
+        .await
+        .map_err(AppError::Download)?;
+    Ok("已暂停所有下载".into())
+}
+
+/// 恢复所有已暂停的下载
+#[tauri::command(async)]
+pub async fn resume_all_downloads(app_handle: AppHandle) -> AppResult<String> {
+    log_info!("接收到恢复所有下载请求");
+
+    crate::shell_integration::set_downloads_paused(&app_handle, false)
+        .await
+        .map_err(AppError::Download)?;
+    Ok("已恢复所有下载".into())
+}
+
+/// 修改下载任务的优先级 - 等待中的任务按新的优先级重新排队，正在下载的任务不受影响
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+/// - `priority`: 新的优先级（low / normal / high）
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok
+/// - 任务不在队列中时返回AppError
+#[tauri::command(async)]
+pub async fn set_download_priority(
+    task_id: &str,
+    priority: TaskPriority,
+    app_handle: AppHandle,
+) -> AppResult<String> {
+    log_info!(
+        "接收到修改下载优先级请求: 任务ID={}, 优先级={:?}",
+        task_id,
+        priority
+    );
+
+    if !DOWNLOAD_QUEUE
+        .lock()
+        .unwrap()
+        .set_priority(task_id, priority)
+    {
+        return Err(AppError::InvalidInput(format!(
+            "下载任务不在队列中: {}",
+            task_id
+        )));
+    }
+    let _ = refresh_download_queue(app_handle).await;
+    Ok(format!("已修改下载任务优先级: {}", task_id))
+}
+
+/// 暂停等待中的下载任务 - 任务保留在队列中，恢复前不会开始下载
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok
+/// - 任务不在等待中时返回AppError
+#[tauri::command(async)]
+pub async fn pause_download(task_id: &str, app_handle: AppHandle) -> AppResult<String> {
+    log_info!("接收到暂停下载任务请求: 任务ID={}", task_id);
+
+    if !DOWNLOAD_QUEUE.lock().unwrap().pause_task(task_id) {
+        return Err(AppError::InvalidInput(format!(
+            "下载任务不在等待中: {}",
+            task_id
+        )));
+    }
+    let _ = refresh_download_queue(app_handle).await;
+    Ok(format!("已暂停下载任务: {}", task_id))
+}
+
+/// 恢复已暂停的下载任务 - 任务按优先级回到等待队列
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok
+/// - 任务没有暂停时返回AppError
+#[tauri::command(async)]
+pub async fn resume_download(task_id: &str, app_handle: AppHandle) -> AppResult<String> {
+    log_info!("接收到恢复下载任务请求: 任务ID={}", task_id);
+
+    if !DOWNLOAD_QUEUE.lock().unwrap().requeue_task(task_id) {
+        return Err(AppError::InvalidInput(format!(
+            "下载任务没有暂停: {}",
+            task_id
+        )));
+    }
+    let _ = refresh_download_queue(app_handle).await;
+    Ok(format!("已恢复下载任务: {}", task_id))
+}
+
+/// 重新启动下载引擎命令 - 下载卡住时关闭并重新启动aria2c，进行中的任务重新添加后从断点继续
+///
+/// # 返回值
+/// - 成功时返回重新添加的任务数
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn restart_download_engine() -> AppResult<usize> {
+    log_info!("接收到重新启动下载引擎请求");
+
+    crate::aria2c::restart_engine()
+        .await
+        .map_err(AppError::Download)
+}
+
+/// 获取下载引擎状态命令
+///
+/// # 返回值
+/// - 进程ID、端口、RPC接口是否正常响应、版本和已运行时间
+#[tauri::command(async)]
+pub async fn get_engine_status() -> AppResult<crate::aria2c::EngineStatus> {
+    Ok(crate::aria2c::engine_status().await)
+}
+
+/// 检查高级设置中修改的 aria2c 选项是否允许修改
+fn check_aria2_option(key: &str) -> AppResult<()> {
+    if crate::aria2c::ADVANCED_ARIA2_OPTIONS.contains(&key) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(tr!(
+            "error.aria2_option_not_allowed",
+            key = key
+        )))
+    }
+}
+
+/// 获取下载引擎的高级选项命令 - 返回运行中aria2c允许修改的全局选项
+///
+/// # 返回值
+/// - 选项名 -> 值
+#[tauri::command(async)]
+pub async fn get_global_options() -> AppResult<serde_json::Map<String, serde_json::Value>> {
+    crate::aria2c::get_global_options()
+        .await
+        .map_err(AppError::Download)
+}
+
+/// 修改下载引擎的高级选项命令 - 修改运行中aria2c的全局选项，aria2c 重新启动后恢复默认值
+///
+/// # 参数
+/// - `key`: 选项名，只允许 ADVANCED_ARIA2_OPTIONS 中的选项
+/// - `value`: 选项值
+#[tauri::command(async)]
+pub async fn set_global_option(key: String, value: String) -> AppResult<()> {
+    check_aria2_option(&key)?;
+    crate::aria2c::set_global_option(&key, &value)
+        .await
+        .map_err(AppError::Download)
+}
+
+/// 修改正在下载的任务的aria2c选项命令
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+/// - `key`: 选项名，只允许 ADVANCED_ARIA2_OPTIONS 中的选项
+/// - `value`: 选项值
+#[tauri::command(async)]
+pub async fn change_task_option(task_id: String, key: String, value: String) -> AppResult<()> {
+    check_aria2_option(&key)?;
+    let (gid, _) = crate::aria2c::transfer_state(&task_id)
+        .ok_or_else(|| AppError::NotFound(tr!("error.task_not_downloading", id = &task_id)))?;
+    crate::aria2c::change_task_option(&gid, &key, &value)
+        .await
+        .map_err(AppError::Download)
+}
+
 /// 前端加载完成通知命令
 ///
 /// 由前端调用，通知后端下载拦截器已成功加载完成
 #[tauri::command]
-pub fn frontend_loaded() -> Result<String, String> {
+pub fn frontend_loaded() -> AppResult<String> {
     log_info!("接收到前端加载完成通知");
 
-    process_download().map_err(|e| e.to_string())?;
+    process_download().map_err(|e| AppError::Download(e.to_string()))?;
 
     Ok("前端加载完成通知已收到".into())
 }
@@ -851,15 +1240,19 @@ pub fn deep_link_ready(handle: AppHandle) {
     static ONCE: std::sync::Once = std::sync::Once::new();
     ONCE.call_once(|| {
         let args = std::env::args().collect::<Vec<_>>();
+        let cwd = std::env::current_dir().unwrap_or_default();
+        crate::handle_local_files(handle.clone(), &args, &cwd);
+        crate::shell_integration::handle_jump_list_args(&handle, &args);
         handle_deep_link(handle.clone(), args);
     });
 }
 
 #[tauri::command]
-pub fn get_file_symlinks(dir_path: String) -> Result<serde_json::Value, String> {
+pub fn get_file_symlinks(dir_path: String) -> AppResult<serde_json::Value> {
     log_info!("接收到获取文件符号链接请求: {}", dir_path);
 
-    let symlinks = crate::symlink_manager::get_all_file_symlinks_in_dir(&dir_path)?;
+    let symlinks = crate::symlink_manager::get_all_file_symlinks_in_dir(&dir_path)
+        .map_err(AppError::Symlink)?;
 
     Ok(serde_json::json!(symlinks))
 }
@@ -869,7 +1262,7 @@ pub async fn create_file_symlink(
     target_path: String,
     link_dir: String,
     link_name: String,
-) -> Result<String, String> {
+) -> AppResult<String> {
     log_info!(
         "接收到创建文件符号链接请求: 目标={}, 链接目录={}, 链接名称={}",
         target_path,
@@ -877,31 +1270,38 @@ pub async fn create_file_symlink(
         link_name
     );
 
-    crate::symlink_manager::create_file_symlink(&target_path, &link_dir, &link_name).await
+    crate::symlink_manager::create_file_symlink(&target_path, &link_dir, &link_name)
+        .await
+        .map_err(AppError::Symlink)
 }
 
 #[tauri::command]
-pub fn delete_file_symlink(link_path: String) -> Result<String, String> {
+pub fn delete_file_symlink(link_path: String) -> AppResult<String> {
     log_info!("接收到删除文件符号链接请求: {}", link_path);
 
-    crate::symlink_manager::delete_file_symlink(&link_path)
+    crate::symlink_manager::delete_file_symlink(&link_path).map_err(AppError::Symlink)
 }
 
 #[tauri::command]
-pub async fn mount_file(group_name: String, file_name: String) -> Result<String, String> {
+pub async fn mount_file(group_name: String, file_name: String) -> AppResult<String> {
     log_info!("接收到挂载文件请求: 组={}, 文件={}", group_name, file_name);
 
     // 获取 maps 目录
     let maps_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager.as_ref().unwrap().maps_dir()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -909,23 +1309,34 @@ pub async fn mount_file(group_name: String, file_name: String) -> Result<String,
     let source_path = maps_dir.join(&group_name).join(&file_name);
 
     if !source_path.exists() {
-        return Err(format!("源文件不存在: {}", source_path.display()));
+        return Err(AppError::NotFound(tr!(
+            "error.source_file_not_found",
+            path = source_path.display()
+        )));
     }
 
     // 检查是否为vpk文件
     if let Some(ext) = source_path.extension() {
-        if ext != "vpk" {
-            return Err(format!("只能挂载vpk文件: {}", file_name));
+        if !ext.eq_ignore_ascii_case("vpk") {
+            return Err(AppError::InvalidInput(tr!(
+                "error.only_vpk_mountable",
+                name = file_name
+            )));
         }
     } else {
-        return Err(format!("只能挂载vpk文件: {}", file_name));
+        return Err(AppError::InvalidInput(tr!(
+            "error.only_vpk_mountable",
+            name = file_name
+        )));
     }
 
     // 获取 addons_dir
     let addons_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager
                 .as_ref()
@@ -933,13 +1344,16 @@ pub async fn mount_file(group_name: String, file_name: String) -> Result<String,
                 .addons_dir()
                 .ok_or_else(|| {
                     log_error!("无法获取 addons_dir");
-                    "无法获取 addons_dir".to_string()
+                    AppError::NotConfigured(tr!("error.addons_dir_not_configured"))
                 })?
                 .to_path_buf()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -956,7 +1370,8 @@ pub async fn mount_file(group_name: String, file_name: String) -> Result<String,
         addons_dir.to_str().unwrap_or(""),
         &link_name,
     )
-    .await?;
+    .await
+    .map_err(AppError::Symlink)?;
 
     log_info!("文件挂载成功: {} -> {}", relative_path, link_name);
     Ok(format!("文件挂载成功: {}", link_name))
@@ -967,14 +1382,16 @@ pub fn unmount_file(
     group_name: String,
     file_name: String,
     _app_handle: AppHandle,
-) -> Result<String, String> {
+) -> AppResult<String> {
     log_info!("接收到卸载文件请求: 组={}, 文件={}", group_name, file_name);
 
     // 获取 addons_dir
     let addons_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager
                 .as_ref()
@@ -982,13 +1399,16 @@ pub fn unmount_file(
                 .addons_dir()
                 .ok_or_else(|| {
                     log_error!("无法获取 addons_dir");
-                    "无法获取 addons_dir".to_string()
+                    AppError::NotConfigured(tr!("error.addons_dir_not_configured"))
                 })?
                 .to_path_buf()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1003,27 +1423,33 @@ pub fn unmount_file(
     let link_path = addons_dir.join(&link_name);
 
     // 删除符号链接
-    crate::symlink_manager::delete_file_symlink(link_path.to_str().unwrap_or(""))?;
+    crate::symlink_manager::delete_file_symlink(link_path.to_str().unwrap_or(""))
+        .map_err(AppError::Symlink)?;
 
     log_info!("文件卸载成功: {}", link_name);
     Ok(format!("文件卸载成功: {}", link_name))
 }
 
 #[tauri::command]
-pub async fn mount_group(group_name: String) -> Result<String, String> {
+pub async fn mount_group(group_name: String) -> AppResult<String> {
     log_info!("接收到挂载组请求: 组={}", group_name);
 
     // 获取 maps 目录
     let maps_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager.as_ref().unwrap().maps_dir()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1031,14 +1457,19 @@ pub async fn mount_group(group_name: String) -> Result<String, String> {
     let group_dir = maps_dir.join(&group_name);
 
     if !group_dir.exists() {
-        return Err(format!("组目录不存在: {}", group_dir.display()));
+        return Err(AppError::NotFound(tr!(
+            "error.group_not_found",
+            name = group_dir.display()
+        )));
     }
 
     // 获取 addons_dir
     let addons_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager
                 .as_ref()
@@ -1046,13 +1477,16 @@ pub async fn mount_group(group_name: String) -> Result<String, String> {
                 .addons_dir()
                 .ok_or_else(|| {
                     log_error!("无法获取 addons_dir");
-                    "无法获取 addons_dir".to_string()
+                    AppError::NotConfigured(tr!("error.addons_dir_not_configured"))
                 })?
                 .to_path_buf()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1061,7 +1495,10 @@ pub async fn mount_group(group_name: String) -> Result<String, String> {
         Ok(entries) => entries,
         Err(e) => {
             log_error!("无法读取组目录: {:?}, 错误: {:?}", group_dir, e);
-            return Err(format!("无法读取组目录: {:?}", e));
+            return Err(AppError::FileOperation(tr!(
+                "error.read_group_dir_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1084,7 +1521,7 @@ pub async fn mount_group(group_name: String) -> Result<String, String> {
 
         // 只处理vpk文件
         if let Some(ext) = file_path.extension() {
-            if ext != "vpk" {
+            if !ext.eq_ignore_ascii_case("vpk") {
                 continue;
             }
         } else {
@@ -1117,7 +1554,11 @@ pub async fn mount_group(group_name: String) -> Result<String, String> {
             Ok(_) => mounted_count += 1,
             Err(e) => {
                 log_error!("挂载文件 {} 失败: {:?}", file_name, e);
-                return Err(format!("组挂载失败: 挂载文件 {} 失败: {:?}", file_name, e));
+                return Err(AppError::Symlink(tr!(
+                    "error.mount_group_failed",
+                    name = file_name,
+                    error = format!("{:?}", e)
+                )));
             }
         }
     }
@@ -1127,20 +1568,25 @@ pub async fn mount_group(group_name: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn unmount_group(group_name: String) -> Result<String, String> {
+pub fn unmount_group(group_name: String) -> AppResult<String> {
     log_info!("接收到卸载组请求: 组={}", group_name);
 
     // 获取 maps 目录
     let maps_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager.as_ref().unwrap().maps_dir()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1151,7 +1597,9 @@ pub fn unmount_group(group_name: String) -> Result<String, String> {
     let addons_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager
                 .as_ref()
@@ -1159,13 +1607,16 @@ pub fn unmount_group(group_name: String) -> Result<String, String> {
                 .addons_dir()
                 .ok_or_else(|| {
                     log_error!("无法获取 addons_dir");
-                    "无法获取 addons_dir".to_string()
+                    AppError::NotConfigured(tr!("error.addons_dir_not_configured"))
                 })?
                 .to_path_buf()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1174,7 +1625,10 @@ pub fn unmount_group(group_name: String) -> Result<String, String> {
         Ok(entries) => entries,
         Err(e) => {
             log_error!("无法读取组目录: {:?}, 错误: {:?}", group_dir, e);
-            return Err(format!("无法读取组目录: {:?}", e));
+            return Err(AppError::FileOperation(tr!(
+                "error.read_group_dir_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
@@ -1241,14 +1695,16 @@ pub fn unmount_group(group_name: String) -> Result<String, String> {
 /// - 成功时返回包含清理结果的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn cleanup_invalid_links() -> Result<String, String> {
+pub fn cleanup_invalid_links() -> AppResult<String> {
     log_info!("接收到清理无效链接请求");
 
     // 获取 addons_dir
     let addons_dir = match DIR_MANAGER.lock() {
         Ok(manager) => {
             if manager.is_none() {
-                return Err("目录管理器未初始化".to_string());
+                return Err(AppError::NotConfigured(tr!(
+                    "error.dir_manager_not_initialized"
+                )));
             }
             manager
                 .as_ref()
@@ -1256,20 +1712,24 @@ pub fn cleanup_invalid_links() -> Result<String, String> {
                 .addons_dir()
                 .ok_or_else(|| {
                     log_error!("无法获取 addons_dir");
-                    "无法获取 addons_dir".to_string()
+                    AppError::NotConfigured(tr!("error.addons_dir_not_configured"))
                 })?
                 .to_path_buf()
         }
         Err(e) => {
             log_error!("无法锁定目录管理器: {:?}", e);
-            return Err(format!("无法锁定目录管理器: {:?}", e));
+            return Err(AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            )));
         }
     };
 
     let addons_dir_str = addons_dir.to_string_lossy().to_string();
 
     // 扫描addons目录中的符号链接
-    let symlinks = crate::symlink_manager::get_all_file_symlinks_in_dir(&addons_dir_str)?;
+    let symlinks = crate::symlink_manager::get_all_file_symlinks_in_dir(&addons_dir_str)
+        .map_err(AppError::Symlink)?;
 
     let mut cleanup_count = 0;
     let mut error_count = 0;
@@ -1300,7 +1760,7 @@ pub fn cleanup_invalid_links() -> Result<String, String> {
 
         // 检查目标文件是否为vpk文件
         if let Some(ext) = target_path.extension() {
-            if ext != "vpk" {
+            if !ext.eq_ignore_ascii_case("vpk") {
                 // 目标文件不是vpk文件，视为无效链接
                 log_info!(
                     "清理无效链接（非vpk文件）: {} -> {}",
@@ -1363,7 +1823,7 @@ pub async fn open_server_window(
     name: String,
     icon: String,
     app_handle: AppHandle,
-) -> Result<String, String> {
+) -> AppResult<String> {
     log_info!("接收到打开服务器窗口请求: URL={}, Name={}", url, name);
 
     let window_label = format!(
@@ -1414,7 +1874,7 @@ pub async fn open_server_window(
         .get_webview_window("serverlist")
         .ok_or_else(|| {
             log_error!("未找到服务器列表窗口");
-            "未找到服务器列表窗口".to_string()
+            AppError::NotFound(tr!("error.serverlist_window_not_found"))
         })?;
 
     let position_result = parent_window.outer_position();
@@ -1426,7 +1886,7 @@ pub async fn open_server_window(
         &window_label,
         tauri::WebviewUrl::External(url.parse().map_err(|e| {
             log_error!("无效的URL: {:?}", e);
-            format!("无效的URL: {:?}", e)
+            AppError::InvalidInput(tr!("error.invalid_url", error = format!("{:?}", e)))
         })?),
     )
     .title(format!("{} {}", name, icon_placeholder))
@@ -1435,7 +1895,10 @@ pub async fn open_server_window(
     .build()
     .map_err(|e| {
         log_error!("创建WebviewWindowBuilder失败: {:?}", e);
-        format!("创建窗口失败: {:?}", e)
+        AppError::Window(tr!(
+            "error.create_window_failed",
+            error = format!("{:?}", e)
+        ))
     })?;
 
     if let (Ok(pos), Ok(size), Ok(is_maximized)) =
@@ -1465,7 +1928,7 @@ pub async fn open_server_window(
 
     parent_window.hide().map_err(|e| {
         log_error!("隐藏父窗口失败: {:?}", e);
-        format!("隐藏父窗口失败: {:?}", e)
+        AppError::Window(tr!("error.hide_window_failed", error = format!("{:?}", e)))
     })?;
 
     log_info!("服务器窗口已成功打开: {}", window_label);
@@ -1486,19 +1949,25 @@ pub async fn open_server_window(
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command]
-pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<String, String> {
+pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> AppResult<String> {
     log_info!("接收到拖拽文件解压请求: {}", file_path);
 
     // 验证文件是否存在
     let path = std::path::Path::new(&file_path);
     if !path.exists() {
         log_error!("文件不存在: {}", file_path);
-        return Err(format!("文件不存在: {}", file_path));
+        return Err(AppError::NotFound(tr!(
+            "error.file_not_found",
+            name = file_path
+        )));
     }
 
     if !path.is_file() {
         log_error!("指定的路径不是文件: {}", file_path);
-        return Err(format!("指定的路径不是文件: {}", file_path));
+        return Err(AppError::InvalidInput(tr!(
+            "error.not_a_file",
+            name = file_path
+        )));
     }
 
     // 提取文件名（不带扩展名）作为压缩包名称，去除首尾空格以处理 `xxx .zip` 这类情况
@@ -1526,6 +1995,7 @@ pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<
         app_handle: app_handle.clone(),
         download_task_id: format!("drag-drop-{}", task_id),
         is_dragged_file: true,
+        source_task: None,
     };
 
     // 添加任务到解压队列
@@ -1535,7 +2005,10 @@ pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<
             .lock()
             .map_err(|e| {
                 log_error!("无法获取解压队列锁: {:?}", e);
-                format!("无法获取解压队列锁: {:?}", e)
+                AppError::Lock(tr!(
+                    "error.lock_extract_queue_failed",
+                    error = format!("{:?}", e)
+                ))
             })?;
         queue.add_task(task_id.clone(), extract_task);
         log_info!(
@@ -1554,7 +2027,10 @@ pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<
                 .lock()
                 .map_err(|e| {
                     log_error!("无法获取解压队列锁: {:?}", e);
-                    format!("无法获取解压队列锁: {:?}", e)
+                    AppError::Lock(tr!(
+                        "error.lock_extract_queue_failed",
+                        error = format!("{:?}", e)
+                    ))
                 })?;
 
             let active = queue
@@ -1582,8 +2058,8 @@ pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<
             (total, active, tasks)
         };
 
-        let _ = app_handle.emit_to(
-            "main",
+        let _ = crate::event_dispatcher::emit(
+            &app_handle,
             "extract-queue-update",
             &serde_json::json!({
                 "queue": {"waiting_tasks": waiting_tasks,
@@ -1600,7 +2076,10 @@ pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<
             .lock()
             .map_err(|e| {
                 log_error!("无法获取解压队列锁: {:?}", e);
-                format!("无法获取解压队列锁: {:?}", e)
+                AppError::Lock(tr!(
+                    "error.lock_extract_queue_failed",
+                    error = format!("{:?}", e)
+                ))
             })?;
         !queue.processing_started
     };
@@ -1625,7 +2104,7 @@ pub fn extract_dropped_file(file_path: String, app_handle: AppHandle) -> Result<
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command(async)]
-pub async fn refresh_extract_queue(app_handle: AppHandle) -> Result<String, String> {
+pub async fn refresh_extract_queue(app_handle: AppHandle) -> AppResult<String> {
     log_info!("接收到刷新解压队列请求");
 
     // 发送队列更新事件通知
@@ -1635,37 +2114,31 @@ pub async fn refresh_extract_queue(app_handle: AppHandle) -> Result<String, Stri
             .lock()
             .map_err(|e| {
                 log_error!("无法获取解压队列锁: {:?}", e);
-                format!("无法获取解压队列锁: {:?}", e)
+                AppError::Lock(tr!(
+                    "error.lock_extract_queue_failed",
+                    error = format!("{:?}", e)
+                ))
             })?;
 
-        let active = queue
-            .active_tasks
-            .iter()
-            .filter_map(|task_id| {
-                queue.tasks.get(task_id).map(|task| {
-                    serde_json::json!({"id": task.id, "file_path": task.file_path, "archive_name": task.archive_name})
-                })
-            })
-            .collect::<Vec<_>>();
-
-        // 构建等待任务列表（转换为可序列化的格式）
-        let tasks = queue
-            .waiting_tasks
-            .iter()
-            .filter_map(|task_id| {
-                queue.tasks.get(task_id).map(|task| {
+        // 构建任务列表（转换为可序列化的格式）
+        let list = |state| {
+            queue
+                .tasks_in(state)
+                .map(|(_, task)| {
                     serde_json::json!({"id": task.id, "file_path": task.file_path, "archive_name": task.archive_name})
                 })
-            })
-            .collect::<Vec<_>>();
+                .collect::<Vec<_>>()
+        };
+        let active = list(TaskState::Active);
+        let tasks = list(TaskState::Waiting);
 
         let total = active.len() + tasks.len();
 
         (total, active, tasks)
     };
 
-    let _ = app_handle.emit_to(
-        "main",
+    let _ = crate::event_dispatcher::emit(
+        &app_handle,
         "extract-queue-update",
         &serde_json::json!({
             "queue": {"waiting_tasks": waiting_tasks,
@@ -1689,7 +2162,7 @@ pub async fn refresh_extract_queue(app_handle: AppHandle) -> Result<String, Stri
 
 /// 取消解压任务
 ///
-/// 此函数用于取消指定的解压任务，如果任务正在解压中则无法取消。
+/// 此函数用于取消指定的解压任务，正在解压的任务会结束7zG.exe进程并保留压缩包。
 ///
 /// # 参数
 /// - `task_id`: 要取消的解压任务ID
@@ -1699,7 +2172,7 @@ pub async fn refresh_extract_queue(app_handle: AppHandle) -> Result<String, Stri
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command(async)]
-pub async fn cancel_extract(task_id: &str, _app_handle: AppHandle) -> Result<String, String> {
+pub async fn cancel_extract(task_id: &str, _app_handle: AppHandle) -> AppResult<String> {
     log_info!("接收到取消解压任务请求: 任务ID={}", task_id);
 
     // 检查并处理等待队列中的任务
@@ -1708,27 +2181,30 @@ pub async fn cancel_extract(task_id: &str, _app_handle: AppHandle) -> Result<Str
         .lock()
         .map_err(|e| {
             log_error!("无法获取解压队列锁: {:?}", e);
-            format!("无法获取解压队列锁: {:?}", e)
+            AppError::Lock(tr!(
+                "error.lock_extract_queue_failed",
+                error = format!("{:?}", e)
+            ))
         })?;
 
     // 查找并移除队列中的任务
-    let original_len = queue.waiting_tasks.len();
-    queue.waiting_tasks.retain(|task| task != task_id);
-
-    if original_len != queue.waiting_tasks.len() {
+    if queue.remove_pending_task(task_id).is_some() {
         log_info!("解压任务 {} 已从等待队列中移除", task_id);
-        // 从任务映射中移除
-        queue.tasks.remove(task_id);
         Ok(format!("已成功取消解压任务: {}", task_id))
     } else {
         // 检查任务是否在活跃任务中
         let task_in_active = queue.active_tasks.iter().any(|task| task == task_id);
         if task_in_active {
-            log_warn!("解压任务 {} 正在解压中，无法取消", task_id);
-            Err(format!("任务正在解压中，无法取消: {}", task_id))
+            // 取消任务的取消令牌，解压任务在一秒内结束7zG.exe进程
+            queue.cancel_task(task_id, "normal");
+            log_info!("解压任务 {} 正在解压中，已请求取消", task_id);
+            Ok(format!("已请求取消解压任务: {}", task_id))
         } else {
             log_warn!("解压任务 {} 不存在", task_id);
-            Err(format!("任务不存在: {}", task_id))
+            Err(AppError::NotFound(tr!(
+                "error.task_not_found",
+                id = task_id
+            )))
         }
     }
 }
@@ -1742,7 +2218,7 @@ pub async fn cancel_extract(task_id: &str, _app_handle: AppHandle) -> Result<Str
 /// - 成功时返回包含成功信息的Ok
 /// - 失败时返回包含错误信息的Err
 #[tauri::command(async)]
-pub async fn cancel_all_extracts(app_handle: AppHandle) -> Result<String, String> {
+pub async fn cancel_all_extracts(app_handle: AppHandle) -> AppResult<String> {
     log_info!("接收到取消所有排队解压任务请求");
 
     let queue_tasks_count;
@@ -1754,22 +2230,14 @@ pub async fn cancel_all_extracts(app_handle: AppHandle) -> Result<String, String
             .lock()
             .map_err(|e| {
                 log_error!("无法获取解压队列锁: {:?}", e);
-                format!("无法获取解压队列锁: {:?}", e)
+                AppError::Lock(tr!(
+                    "error.lock_extract_queue_failed",
+                    error = format!("{:?}", e)
+                ))
             })?;
 
-        // 记录等待队列中的任务数量
-        queue_tasks_count = queue.waiting_tasks.len();
-
-        // 收集所有等待任务的ID
-        let task_ids: Vec<String> = queue.waiting_tasks.iter().cloned().collect();
-
         // 清空等待队列
-        queue.waiting_tasks.clear();
-
-        // 从任务映射中移除等待任务
-        for task_id in task_ids {
-            queue.tasks.remove(&task_id);
-        }
+        queue_tasks_count = queue.clear_pending_tasks();
 
         // 获取活跃任务数量
         let active_tasks_count = queue.active_tasks.len();
@@ -1786,3 +2254,425 @@ pub async fn cancel_all_extracts(app_handle: AppHandle) -> Result<String, String
 
     Ok(format!("已成功取消 {} 个排队解压任务", queue_tasks_count))
 }
+
+/// 获取下载前的地图信息命令 - 解析最终下载地址、文件名和文件大小，目录网站中的地图同时返回说明和校验和
+///
+/// 供确认下载前显示即将下载的内容，不会添加下载任务
+///
+/// # 参数
+/// - `url`: 下载链接
+///
+/// # 返回值
+/// - 成功时返回包含 url、finalUrl、fileName、size、catalog 的JSON对象
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn get_remote_map_info(url: String) -> AppResult<serde_json::Value> {
+    log_info!("接收到获取地图信息请求: {}", url);
+    if tauri::Url::parse(&url).is_err() {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_url",
+            error = &url
+        )));
+    }
+
+    let resolved_url = crate::url_resolver::resolve_url(&url).await;
+    let resolved = crate::host_adapter::resolve_share_link(&resolved_url).await;
+    let file_name = resolved
+        .file_name
+        .clone()
+        .or_else(|| get_file_name(&resolved.url));
+    let size = crate::download_policy::fetch_file_size(&resolved.url)
+        .await
+        .unwrap_or_else(|e| {
+            log_warn!("获取文件大小失败: {}, 错误: {}", resolved.url, e);
+            None
+        });
+
+    // 目录网站中的地图查询详情，查询失败时仍返回已解析的信息
+    let catalog = match crate::catalog::catalog_path(&url) {
+        Some(path) => match crate::catalog::map_details(&path).await {
+            Ok(details) => Some(details),
+            Err(e) => {
+                log_warn!("查询地图目录失败: {}, 错误: {}", path, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    Ok(serde_json::json!({
+        "url": url,
+        "finalUrl": resolved.url,
+        "fileName": file_name,
+        "size": size.or_else(|| catalog.as_ref().map(|details| details.entry.size)),
+        "catalog": catalog,
+    }))
+}
+
+/// 获取应用信息命令 - 返回版本、数据目录、临时目录、addons 目录以及 aria2c 和 helper 的运行状态
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 成功时返回包含应用信息的JSON对象
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn get_app_info(app_handle: AppHandle) -> AppResult<serde_json::Value> {
+    log_info!("接收到获取应用信息请求");
+
+    let (data_dir, cache_dir, maps_dir, addons_dir) = {
+        let manager = DIR_MANAGER.lock().map_err(|e| {
+            log_error!("无法获取目录管理器锁: {:?}", e);
+            AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            ))
+        })?;
+        match manager.as_ref() {
+            Some(dm) => (
+                Some(dm.data_dir()),
+                Some(dm.cache_dir()),
+                Some(dm.maps_dir()),
+                dm.addons_dir().cloned(),
+            ),
+            None => (None, None, None, None),
+        }
+    };
+
+    // 注册表查询和进程检查较慢，放到阻塞线程中执行
+    let (detected_addons_dir, aria2c) = tokio::task::spawn_blocking(|| {
+        (
+            crate::dir_manager::get_l4d2_addons_dir().ok(),
+            crate::aria2c::get_aria2_status(),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("{:?}", e)))?;
+
+    Ok(serde_json::json!({
+        "version": app_handle.package_info().version.to_string(),
+        "dataDir": data_dir,
+        "cacheDir": cache_dir,
+        "mapsDir": maps_dir,
+        "tempDir": std::env::temp_dir(),
+        "addonsDir": addons_dir,
+        "detectedAddonsDir": detected_addons_dir,
+        "aria2c": aria2c,
+        "helper": crate::symlink_manager::get_helper_status(),
+    }))
+}
+
+/// 生成注入脚本使用的运行时配置 - 应用版本、界面语言、数据目录和功能开关
+///
+/// 注入脚本由 asset 协议提供时会替换其中的占位符，脚本运行期间也可以调用 get_injection_config 重新获取
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub(crate) fn injection_config(app_handle: &AppHandle) -> serde_json::Value {
+    let data_dir = DIR_MANAGER
+        .lock()
+        .ok()
+        .and_then(|manager| manager.as_ref().map(|dm| dm.data_dir()));
+    let settings = crate::settings::current_settings();
+    let plugins = crate::plugins::list_plugins()
+        .map(|plugins| plugins.len())
+        .unwrap_or(0);
+
+    serde_json::json!({
+        "version": app_handle.package_info().version.to_string(),
+        "locale": crate::i18n::current_language(),
+        "dataDir": data_dir,
+        "features": {
+            "debug": cfg!(debug_assertions),
+            "archiveCache": settings.archive_cache_enabled,
+            "downloadPolicy": !settings.allowed_hosts.is_empty()
+                || !settings.denied_hosts.is_empty()
+                || settings.max_download_size_mb > 0,
+            "plugins": plugins > 0,
+            "quickInstall": !settings.quick_install_shortcut.is_empty(),
+        },
+    })
+}
+
+/// 获取注入脚本的运行时配置命令
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 包含版本、语言、数据目录和功能开关的JSON对象
+#[tauri::command]
+pub fn get_injection_config(app_handle: AppHandle) -> AppResult<serde_json::Value> {
+    Ok(injection_config(&app_handle))
+}
+
+/// 获取文件管理器目录列表命令 - 列出应用管理目录下的文件和子目录
+///
+/// 目录大小递归计算并缓存，在阻塞线程中执行以避免界面卡顿
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `dir`: 要列出的目录（可选，默认为数据目录）
+///
+/// # 返回值
+/// - 成功时返回目录项列表（名称、路径、是否为目录/符号链接、大小、修改时间）
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn get_managed_entries(
+    app_handle: AppHandle,
+    dir: Option<String>,
+) -> AppResult<Vec<crate::dir_manager::ManagedEntry>> {
+    log_info!("接收到获取目录列表请求: {:?}", dir);
+
+    let dir = match dir {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => DIR_MANAGER
+            .lock()
+            .map_err(|e| {
+                AppError::Lock(tr!(
+                    "error.lock_dir_manager_failed",
+                    error = format!("{:?}", e)
+                ))
+            })?
+            .as_ref()
+            .map(|dm| dm.data_dir())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .ok_or_else(|| AppError::NotConfigured(tr!("error.data_dir_not_configured")))?,
+    };
+    let dir = crate::explorer::validate_managed_path(&app_handle, &dir)?;
+
+    tokio::task::spawn_blocking(move || crate::dir_manager::list_managed_entries(&dir))
+        .await
+        .map_err(|e| AppError::Internal(format!("{:?}", e)))?
+        .map_err(|e| {
+            log_error!("获取目录列表失败: {}", e);
+            AppError::FileOperation(tr!("error.read_dir_failed", error = e))
+        })
+}
+
+/// 获取数据目录各部分（地图、下载缓存、压缩包缓存）和 addons 目录的占用空间，以及所在磁盘的剩余空间
+///
+/// # 返回值
+/// - 成功时返回占用空间统计，未配置数据目录时各项为0
+#[tauri::command(async)]
+pub async fn get_disk_usage() -> AppResult<crate::dir_manager::DiskUsage> {
+    tokio::task::spawn_blocking(crate::dir_manager::get_disk_usage)
+        .await
+        .map_err(|e| AppError::Internal(format!("{:?}", e)))
+}
+
+/// 计算地图文件的挂载链接名（使用相对路径：组/文件 的哈希值）
+pub(crate) fn map_link_name(group_name: &str, file_name: &str) -> String {
+    let relative_path = format!("{}/{}", group_name, file_name);
+    let mut hasher = DefaultHasher::new();
+    relative_path.hash(&mut hasher);
+    format!("nmd_link_{:016x}.vpk", hasher.finish())
+}
+
+/// 校验文件名或分组名 - 不能为空，不能包含路径分隔符或 Windows 不允许的字符
+fn validate_entry_name(name: &str) -> AppResult<()> {
+    let invalid = name.trim().is_empty()
+        || name == "."
+        || name == ".."
+        || name.ends_with(['.', ' '])
+        || name
+            .chars()
+            .any(|c| c.is_control() || "<>:\"/\\|?*".contains(c));
+    if invalid {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_entry_name",
+            name = name
+        )));
+    }
+    Ok(())
+}
+
+/// 获取 maps 目录和 addons 目录
+fn get_maps_and_addons_dir() -> AppResult<(std::path::PathBuf, Option<std::path::PathBuf>)> {
+    let manager = DIR_MANAGER.lock().map_err(|e| {
+        log_error!("无法锁定目录管理器: {:?}", e);
+        AppError::Lock(tr!(
+            "error.lock_dir_manager_failed",
+            error = format!("{:?}", e)
+        ))
+    })?;
+    let dir_manager = manager
+        .as_ref()
+        .ok_or_else(|| AppError::NotConfigured(tr!("error.dir_manager_not_initialized")))?;
+    Ok((dir_manager.maps_dir(), dir_manager.addons_dir().cloned()))
+}
+
+/// 移动或重命名地图文件，并更新已挂载的符号链接
+///
+/// 原文件已挂载时，先为新位置创建链接，再删除旧链接；新链接创建失败时不影响文件本身的移动
+async fn relocate_map_file(
+    maps_dir: &std::path::Path,
+    addons_dir: Option<&std::path::Path>,
+    (group_name, file_name): (&str, &str),
+    (new_group_name, new_file_name): (&str, &str),
+) -> AppResult<String> {
+    let source_path = maps_dir.join(group_name).join(file_name);
+    if !source_path.is_file() {
+        return Err(AppError::NotFound(tr!(
+            "error.file_not_found",
+            name = file_name
+        )));
+    }
+
+    let dest_dir = maps_dir.join(new_group_name);
+    let dest_path = dest_dir.join(new_file_name);
+    // 只改变大小写时允许覆盖自身（Windows 文件系统不区分大小写）
+    let same_file = crate::utils::same_entry_name(group_name, new_group_name)
+        && crate::utils::same_entry_name(file_name, new_file_name);
+    if dest_path.exists() && !same_file {
+        return Err(AppError::InvalidInput(tr!(
+            "error.entry_already_exists",
+            name = format!("{}/{}", new_group_name, new_file_name)
+        )));
+    }
+
+    std::fs::create_dir_all(&dest_dir).map_err(|e| {
+        AppError::FileOperation(tr!("error.create_dir_failed", error = format!("{:?}", e)))
+    })?;
+    std::fs::rename(&source_path, &dest_path).map_err(|e| {
+        log_error!(
+            "移动文件失败: {} -> {}, 错误: {:?}",
+            source_path.display(),
+            dest_path.display(),
+            e
+        );
+        AppError::FileOperation(tr!("error.move_file_failed", error = format!("{:?}", e)))
+    })?;
+    log_info!(
+        "文件已移动: {} -> {}",
+        source_path.display(),
+        dest_path.display()
+    );
+    crate::dir_manager::clear_dir_size_cache();
+
+    // 原分组为空时删除该分组目录
+    if group_name != new_group_name {
+        if let Some(dir) = source_path.parent() {
+            if std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none()) {
+                if let Err(e) = std::fs::remove_dir(dir) {
+                    log_warn!("删除空文件夹失败: {}, 错误: {:?}", dir.display(), e);
+                }
+            }
+        }
+    }
+
+    // 更新挂载链接
+    if let Some(addons_dir) = addons_dir {
+        let old_link = addons_dir.join(map_link_name(group_name, file_name));
+        if old_link.is_symlink() {
+            let new_link_name = map_link_name(new_group_name, new_file_name);
+            crate::symlink_manager::create_file_symlink(
+                &dest_path.to_string_lossy(),
+                &addons_dir.to_string_lossy(),
+                &new_link_name,
+            )
+            .await
+            .map_err(|e| {
+                log_error!("为移动后的文件创建挂载链接失败: {}", e);
+                AppError::Symlink(tr!("error.relink_failed", error = e))
+            })?;
+            if old_link != addons_dir.join(&new_link_name) {
+                if let Err(e) =
+                    crate::symlink_manager::delete_file_symlink(&old_link.to_string_lossy())
+                {
+                    log_warn!("删除旧挂载链接失败: {}", e);
+                }
+            }
+            log_info!("挂载链接已更新: {}", new_link_name);
+        }
+    }
+
+    Ok(dest_path.to_string_lossy().to_string())
+}
+
+/// 重命名地图文件（在 /maps 目录下），已挂载的文件会同步更新挂载链接
+///
+/// # 参数
+/// - `group_name`: 文件所在的组名（子文件夹名）
+/// - `file_name`: 原文件名
+/// - `new_name`: 新文件名（必须保留原扩展名）
+///
+/// # 返回值
+/// - 成功时返回重命名后的文件路径
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn rename_map_file(
+    group_name: String,
+    file_name: String,
+    new_name: String,
+) -> AppResult<String> {
+    log_info!(
+        "接收到重命名文件请求: 组={}, 文件={}, 新文件名={}",
+        group_name,
+        file_name,
+        new_name
+    );
+
+    validate_entry_name(&group_name)?;
+    validate_entry_name(&file_name)?;
+    validate_entry_name(&new_name)?;
+
+    let extension = |name: &str| {
+        std::path::Path::new(name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+    };
+    if extension(&file_name) != extension(&new_name) {
+        return Err(AppError::InvalidInput(tr!(
+            "error.extension_changed",
+            name = new_name
+        )));
+    }
+
+    let (maps_dir, addons_dir) = get_maps_and_addons_dir()?;
+    relocate_map_file(
+        &maps_dir,
+        addons_dir.as_deref(),
+        (&group_name, &file_name),
+        (&group_name, &new_name),
+    )
+    .await
+}
+
+/// 移动地图文件到另一个分组（在 /maps 目录下），目标分组不存在时自动创建，已挂载的文件会同步更新挂载链接
+///
+/// # 参数
+/// - `group_name`: 文件所在的组名（子文件夹名）
+/// - `file_name`: 要移动的文件名
+/// - `dest_group`: 目标组名
+///
+/// # 返回值
+/// - 成功时返回移动后的文件路径
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn move_map_file(
+    group_name: String,
+    file_name: String,
+    dest_group: String,
+) -> AppResult<String> {
+    log_info!(
+        "接收到移动文件请求: 组={}, 文件={}, 目标组={}",
+        group_name,
+        file_name,
+        dest_group
+    );
+
+    validate_entry_name(&group_name)?;
+    validate_entry_name(&file_name)?;
+    validate_entry_name(&dest_group)?;
+
+    let (maps_dir, addons_dir) = get_maps_and_addons_dir()?;
+    relocate_map_file(
+        &maps_dir,
+        addons_dir.as_deref(),
+        (&group_name, &file_name),
+        (&dest_group, &file_name),
+    )
+    .await
+}
@@ -1,7 +1,7 @@
 // config_manager.rs 模块 - 处理用户配置的读写操作
 
 use serde_json::{json, Value};
-use std::fs;
+use std::{fs, path::PathBuf};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_dialog::MessageDialogKind;
 
@@ -11,6 +11,26 @@ use crate::init::update_window_title;
 // 导入对话框函数
 use crate::dialog_manager::show_blocking_dialog;
 
+// 导入统一错误类型
+use crate::error::{AppError, AppResult};
+
+/// 获取配置文件的完整路径
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄，用于获取应用配置目录
+/// - `config_name`: 配置文件名（不包含路径）
+///
+/// # 返回值
+/// - 成功时返回配置文件路径
+/// - 失败时返回包含错误信息的Err(String)
+pub fn get_config_path(app_handle: &AppHandle, config_name: &str) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join(config_name))
+        .map_err(|e| format!("无法获取应用配置目录: {:?}", e))
+}
+
 /// 读取用户配置
 ///
 /// # 参数
@@ -19,14 +39,14 @@ use crate::dialog_manager::show_blocking_dialog;
 ///
 /// # 返回值
 /// - 成功时返回包含配置内容的Ok(Value)
-/// - 失败时返回包含错误信息的Err(String)
+/// - 失败时返回AppError
 #[tauri::command]
-pub fn read_config(app_handle: AppHandle, config_name: &str) -> Result<Value, String> {
+pub fn read_config(app_handle: AppHandle, config_name: &str) -> AppResult<Value> {
     // 获取应用配置目录
     let config_dir = match app_handle.path().app_config_dir() {
         Ok(path) => path,
         Err(e) => {
-            return Err(format!("无法获取应用配置目录: {:?}", e));
+            return Err(AppError::Config(format!("无法获取应用配置目录: {:?}", e)));
         }
     };
 
@@ -43,7 +63,7 @@ pub fn read_config(app_handle: AppHandle, config_name: &str) -> Result<Value, St
     let content = match fs::read_to_string(&config_path) {
         Ok(content) => content,
         Err(e) => {
-            return Err(format!("无法读取配置文件: {:?}", e));
+            return Err(AppError::Config(format!("无法读取配置文件: {:?}", e)));
         }
     };
 
@@ -51,7 +71,7 @@ pub fn read_config(app_handle: AppHandle, config_name: &str) -> Result<Value, St
     let config: Value = match serde_json::from_str(&content) {
         Ok(config) => config,
         Err(e) => {
-            return Err(format!("无法解析配置文件: {:?}", e));
+            return Err(AppError::Config(format!("无法解析配置文件: {:?}", e)));
         }
     };
 
@@ -67,18 +87,21 @@ pub fn read_config(app_handle: AppHandle, config_name: &str) -> Result<Value, St
 ///
 /// # 返回值
 /// - 成功时返回包含成功信息的Ok(String)
-/// - 失败时返回包含错误信息的Err(String)
+/// - 失败时返回AppError
 #[tauri::command]
-pub fn write_config(
-    app_handle: AppHandle,
-    config_name: &str,
-    config: Value,
-) -> Result<String, String> {
+pub fn write_config(app_handle: AppHandle, config_name: &str, config: Value) -> AppResult<String> {
+    // config.json 写入前进行格式校验和版本迁移
+    let config = if config_name == "config.json" {
+        crate::settings::validate_config(config).map_err(AppError::InvalidInput)?
+    } else {
+        config
+    };
+
     // 获取应用配置目录
     let config_dir = match app_handle.path().app_config_dir() {
         Ok(path) => path,
         Err(e) => {
-            return Err(format!("无法获取应用配置目录: {:?}", e));
+            return Err(AppError::Config(format!("无法获取应用配置目录: {:?}", e)));
         }
     };
 
@@ -87,7 +110,7 @@ pub fn write_config(
         match fs::create_dir_all(&config_dir) {
             Ok(_) => {}
             Err(e) => {
-                return Err(format!("无法创建配置目录: {:?}", e));
+                return Err(AppError::Config(format!("无法创建配置目录: {:?}", e)));
             }
         }
     }
@@ -99,7 +122,7 @@ pub fn write_config(
     let content = match serde_json::to_string_pretty(&config) {
         Ok(content) => content,
         Err(e) => {
-            return Err(format!("无法序列化配置: {:?}", e));
+            return Err(AppError::Config(format!("无法序列化配置: {:?}", e)));
         }
     };
 
@@ -108,51 +131,27 @@ pub fn write_config(
         Ok(_) => {
             // 如果是config.json，且包含nmd_data，更新窗口标题并初始化资源
             if config_name == "config.json" {
+                crate::settings::record_own_config_write(&app_handle);
                 if let Some(nmd_data) = config.get("nmd_data") {
                     if let Some(data_dir) = nmd_data.as_str() {
-                        // 更新窗口标题
-                        update_window_title(&app_handle, data_dir);
-
-                        // 获取当前的 addons_dir（如果已设置）
-                        let current_addons_dir = {
-                            let guard = crate::dir_manager::DIR_MANAGER.lock().unwrap();
-                            guard.as_ref().and_then(|dm| dm.addons_dir().cloned())
-                        };
-
-                        // 重新初始化目录管理器
-                        let mut dir_manager =
-                            match crate::dir_manager::DirManager::with_nmd_data_dir(
-                                std::path::PathBuf::from(data_dir),
-                            ) {
-                                Ok(dm) => dm,
-                                Err(e) => {
-                                    crate::log_error!("重新初始化目录管理器失败: {}", e);
-                                    let error_msg = format!("初始化目录管理器失败: {}\n\n请检查目录路径是否正确，或选择其他目录。", e);
-                                    show_blocking_dialog(
-                                        &app_handle,
-                                        &error_msg,
-                                        "初始化失败",
-                                        MessageDialogKind::Error,
-                                    );
-                                    panic!("{}", error_msg);
-                                }
-                            };
-
-                        // 如果之前有设置 addons_dir，重新设置回去
-                        if let Some(addons_dir) = current_addons_dir {
-                            dir_manager.set_addons_dir(addons_dir);
+                        if let Err(e) = reload_data_dir(&app_handle, data_dir) {
+                            let error_msg = crate::tr!("dialog.reload_data_dir_failed", error = e);
+                            show_blocking_dialog(
+                                &app_handle,
+                                &error_msg,
+                                &crate::tr!("dialog.init_failed.title"),
+                                MessageDialogKind::Error,
+                            );
+                            panic!("{}", error_msg);
                         }
-
-                        // 更新全局目录管理器
-                        *crate::dir_manager::DIR_MANAGER.lock().unwrap() = Some(dir_manager);
                     }
                 } else {
-                    let error_msg = "未配置数据目录，无法初始化资源";
+                    let error_msg = crate::tr!("dialog.data_dir_required");
                     crate::log_error!("{}", error_msg);
                     show_blocking_dialog(
                         &app_handle,
                         &error_msg,
-                        "初始化失败",
+                        &crate::tr!("dialog.init_failed.title"),
                         MessageDialogKind::Error,
                     );
                     panic!("{}", error_msg);
@@ -160,10 +159,104 @@ pub fn write_config(
             }
             Ok(format!("配置已成功写入: {:?}", config_path))
         }
-        Err(e) => Err(format!("无法写入配置文件: {:?}", e)),
+        Err(e) => Err(AppError::Config(format!("无法写入配置文件: {:?}", e))),
     }
 }
 
+/// 切换数据存储目录 - 更新窗口标题并重新初始化目录管理器，保留已设置的 addons 目录
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `data_dir`: 新的数据存储目录
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 失败时返回包含错误信息的Err(String)
+pub fn reload_data_dir(app_handle: &AppHandle, data_dir: &str) -> Result<(), String> {
+    // 更新窗口标题
+    update_window_title(app_handle, data_dir);
+
+    // 获取当前的 addons_dir 和自定义下载目录（如果已设置）
+    let (current_addons_dir, current_download_dir) = {
+        let guard = crate::dir_manager::DIR_MANAGER.lock().unwrap();
+        (
+            guard.as_ref().and_then(|dm| dm.addons_dir().cloned()),
+            guard.as_ref().and_then(|dm| dm.download_dir().cloned()),
+        )
+    };
+
+    // 重新初始化目录管理器
+    let mut dir_manager =
+        crate::dir_manager::DirManager::with_nmd_data_dir(std::path::PathBuf::from(data_dir))
+            .map_err(|e| {
+                crate::log_error!("重新初始化目录管理器失败: {}", e);
+                e
+            })?;
+
+    // 如果之前有设置 addons_dir，重新设置回去
+    if let Some(addons_dir) = current_addons_dir {
+        dir_manager.set_addons_dir(addons_dir);
+    }
+    dir_manager.set_download_dir(current_download_dir);
+
+    // 更新全局目录管理器
+    *crate::dir_manager::DIR_MANAGER.lock().unwrap() = Some(dir_manager);
+
+    // 日志目录跟随数据目录切换
+    if let Some(logs_dir) = crate::init::get_logs_dir(app_handle) {
+        if let Err(e) = crate::log_utils::init_file_logger(logs_dir) {
+            crate::log_warn!("切换日志目录失败: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// 合并写入用户配置 - 仅覆盖补丁中包含的顶层键，不触发目录管理器重新初始化
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄，用于获取应用数据目录
+/// - `config_name`: 配置文件名（不包含路径）
+/// - `patch`: 要合并的配置内容（JSON对象）
+///
+/// # 返回值
+/// - 成功时返回合并后的完整配置
+/// - 失败时返回包含错误信息的Err(String)
+pub fn merge_config(
+    app_handle: &AppHandle,
+    config_name: &str,
+    patch: Value,
+) -> Result<Value, String> {
+    let mut config = read_config(app_handle.clone(), config_name)?;
+
+    let patch = match patch {
+        Value::Object(map) => map,
+        _ => return Err("配置补丁必须是JSON对象".to_string()),
+    };
+
+    match config.as_object_mut() {
+        Some(object) => object.extend(patch),
+        None => config = Value::Object(patch),
+    }
+
+    let config_path = get_config_path(app_handle, config_name)?;
+
+    // 确保配置目录存在
+    if let Some(config_dir) = config_path.parent() {
+        fs::create_dir_all(config_dir).map_err(|e| format!("无法创建配置目录: {:?}", e))?;
+    }
+
+    let content =
+        serde_json::to_string_pretty(&config).map_err(|e| format!("无法序列化配置: {:?}", e))?;
+
+    fs::write(&config_path, content).map_err(|e| format!("无法写入配置文件: {:?}", e))?;
+    if config_name == "config.json" {
+        crate::settings::record_own_config_write(app_handle);
+    }
+
+    Ok(config)
+}
+
 /// 删除用户配置
 ///
 /// # 参数
@@ -172,14 +265,14 @@ pub fn write_config(
 ///
 /// # 返回值
 /// - 成功时返回包含成功信息的Ok(String)
-/// - 失败时返回包含错误信息的Err(String)
+/// - 失败时返回AppError
 #[tauri::command]
-pub fn delete_config(app_handle: AppHandle, config_name: &str) -> Result<String, String> {
+pub fn delete_config(app_handle: AppHandle, config_name: &str) -> AppResult<String> {
     // 获取应用配置目录
     let config_dir = match app_handle.path().app_config_dir() {
         Ok(path) => path,
         Err(e) => {
-            return Err(format!("无法获取应用配置目录: {:?}", e));
+            return Err(AppError::Config(format!("无法获取应用配置目录: {:?}", e)));
         }
     };
 
@@ -194,7 +287,7 @@ pub fn delete_config(app_handle: AppHandle, config_name: &str) -> Result<String,
     // 删除文件
     match fs::remove_file(&config_path) {
         Ok(_) => Ok(format!("配置已成功删除: {:?}", config_path)),
-        Err(e) => Err(format!("无法删除配置文件: {:?}", e)),
+        Err(e) => Err(AppError::Config(format!("无法删除配置文件: {:?}", e))),
     }
 }
 
@@ -205,9 +298,9 @@ pub fn delete_config(app_handle: AppHandle, config_name: &str) -> Result<String,
 ///
 /// # 返回值
 /// - 成功时返回包含数据存储目录的Ok(Option<String>)
-/// - 失败时返回包含错误信息的Err(String)
+/// - 失败时返回AppError
 #[tauri::command]
-pub fn get_data_dir(app_handle: AppHandle) -> Result<Option<String>, String> {
+pub fn get_data_dir(app_handle: AppHandle) -> AppResult<Option<String>> {
     // 读取配置文件
     let config = read_config(app_handle, "config.json")?;
 
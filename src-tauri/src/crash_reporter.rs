@@ -0,0 +1,307 @@
+// crash_reporter.rs 模块 - 捕获 panic 和未处理的 Windows 异常，将崩溃报告写入数据目录
+
+// 标准库导入
+use std::{
+    backtrace::Backtrace,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// 第三方库导入
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+// 内部模块导入
+use crate::{
+    error::{AppError, AppResult},
+    init::GLOBAL_APP_HANDLE,
+    log_error, log_info,
+    log_utils::recent_log_lines,
+    tr,
+};
+
+/// 当前应用版本
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 崩溃报告中附带的最近日志行数
+const CRASH_REPORT_LOG_LINES: usize = 200;
+
+/// 标记是否已经写入过原生异常转储，避免嵌套异常时重复写入
+static MINIDUMP_WRITTEN: AtomicBool = AtomicBool::new(false);
+
+/// 崩溃报告信息，返回给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportInfo {
+    /// 报告文件名
+    pub name: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 生成时间（Unix 时间戳，秒）
+    pub created: u64,
+}
+
+/// 获取崩溃报告目录 - 优先使用 nmd_data/crash_reports，未配置数据目录时使用应用数据目录
+///
+/// 使用 try_lock/try_read 获取全局状态，避免在崩溃处理过程中死锁
+fn crash_reports_dir() -> Option<PathBuf> {
+    let data_dir = crate::dir_manager::DIR_MANAGER
+        .try_lock()
+        .ok()
+        .and_then(|manager| manager.as_ref().map(|dm| dm.data_dir()))
+        .filter(|dir| !dir.as_os_str().is_empty());
+
+    let base_dir = match data_dir {
+        Some(dir) => dir,
+        None => GLOBAL_APP_HANDLE
+            .try_read()
+            .ok()
+            .and_then(|guard| {
+                guard
+                    .as_ref()
+                    .and_then(|handle| handle.path().app_local_data_dir().ok())
+            })
+            .unwrap_or_else(std::env::temp_dir),
+    };
+
+    Some(base_dir.join("crash_reports"))
+}
+
+/// 生成崩溃报告文件名前缀
+fn report_file_stem() -> String {
+    format!(
+        "crash-{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        std::process::id()
+    )
+}
+
+/// 写入崩溃报告文本
+///
+/// # 参数
+/// - `stem`: 报告文件名前缀
+/// - `kind`: 崩溃类型描述
+/// - `details`: 崩溃详情（panic 信息或异常代码）
+/// - `stack`: 调用栈
+fn write_report(stem: &str, kind: &str, details: &str, stack: &str) -> Option<PathBuf> {
+    let dir = crash_reports_dir()?;
+    fs::create_dir_all(&dir).ok()?;
+
+    let logs = recent_log_lines(CRASH_REPORT_LOG_LINES);
+    let report = format!(
+        "Nyaser Maps Downloader 崩溃报告\n\
+         版本: {}\n\
+         时间: {}\n\
+         系统: {} {}\n\
+         线程: {}\n\
+         类型: {}\n\n\
+         == 详情 ==\n{}\n\n\
+         == 调用栈 ==\n{}\n\n\
+         == 最近 {} 行日志 ==\n{}\n",
+        APP_VERSION,
+        chrono::Local::now().to_rfc3339(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::thread::current().name().unwrap_or("<unnamed>"),
+        kind,
+        details,
+        stack,
+        logs.len(),
+        logs.join("\n")
+    );
+
+    let path = dir.join(format!("{}.txt", stem));
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
+
+/// 安装崩溃处理程序 - panic 钩子和 Windows 未处理异常过滤器
+///
+/// 应在应用启动时尽早调用
+pub fn install_crash_handlers() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let message = match panic_info.payload().downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match panic_info.payload().downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "<非字符串 panic 信息>".to_string(),
+            },
+        };
+        let location = panic_info
+            .location()
+            .map(|location| {
+                format!(
+                    "{}:{}:{}",
+                    location.file(),
+                    location.line(),
+                    location.column()
+                )
+            })
+            .unwrap_or_else(|| "<未知位置>".to_string());
+        let details = format!("{}\n位置: {}", message, location);
+        let stack = Backtrace::force_capture().to_string();
+
+        match write_report(&report_file_stem(), "panic", &details, &stack) {
+            Some(path) => log_error!("程序发生 panic，崩溃报告已写入: {}", path.display()),
+            None => log_error!("程序发生 panic，写入崩溃报告失败"),
+        }
+
+        default_hook(panic_info);
+    }));
+
+    #[cfg(target_os = "windows")]
+    unsafe {
+        windows_sys::Win32::System::Diagnostics::Debug::SetUnhandledExceptionFilter(Some(
+            unhandled_exception_filter,
+        ));
+    }
+
+    log_info!("崩溃处理程序已安装");
+}
+
+/// Windows 未处理异常过滤器 - 写入 minidump 和崩溃报告，然后交给系统默认处理
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn unhandled_exception_filter(
+    exception_info: *const windows_sys::Win32::System::Diagnostics::Debug::EXCEPTION_POINTERS,
+) -> i32 {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::Diagnostics::Debug::{
+        MiniDumpWithThreadInfo, MiniDumpWriteDump, MINIDUMP_EXCEPTION_INFORMATION,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+    };
+
+    // EXCEPTION_CONTINUE_SEARCH
+    const CONTINUE_SEARCH: i32 = 0;
+
+    if MINIDUMP_WRITTEN.swap(true, Ordering::SeqCst) {
+        return CONTINUE_SEARCH;
+    }
+
+    let stem = report_file_stem();
+    let exception_code = if exception_info.is_null() || (*exception_info).ExceptionRecord.is_null()
+    {
+        0
+    } else {
+        (*(*exception_info).ExceptionRecord).ExceptionCode
+    };
+
+    if let Some(dir) = crash_reports_dir() {
+        if fs::create_dir_all(&dir).is_ok() {
+            if let Ok(file) = fs::File::create(dir.join(format!("{}.dmp", stem))) {
+                let exception_param = MINIDUMP_EXCEPTION_INFORMATION {
+                    ThreadId: GetCurrentThreadId(),
+                    ExceptionPointers: exception_info as *mut _,
+                    ClientPointers: 0,
+                };
+                MiniDumpWriteDump(
+                    GetCurrentProcess(),
+                    GetCurrentProcessId(),
+                    file.as_raw_handle() as _,
+                    MiniDumpWithThreadInfo,
+                    &exception_param,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                );
+            }
+        }
+    }
+
+    let details = format!("未处理的异常，异常代码: 0x{:08X}", exception_code as u32);
+    let stack = Backtrace::force_capture().to_string();
+    let _ = write_report(&stem, "native exception", &details, &stack);
+
+    CONTINUE_SEARCH
+}
+
+/// 获取崩溃报告列表
+///
+/// # 返回值
+/// - 成功时返回崩溃报告列表（按时间倒序）
+/// - 失败时返回AppError
+#[tauri::command]
+pub fn get_crash_reports() -> AppResult<Vec<CrashReportInfo>> {
+    let dir = match crash_reports_dir() {
+        Some(dir) if dir.exists() => dir,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut reports: Vec<CrashReportInfo> = fs::read_dir(&dir)
+        .map_err(|e| {
+            AppError::FileOperation(tr!(
+                "error.read_crash_reports_dir_failed",
+                error = format!("{:?}", e)
+            ))
+        })?
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let created = metadata
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            Some(CrashReportInfo {
+                name: entry.file_name().to_string_lossy().to_string(),
+                size: metadata.len(),
+                created,
+            })
+        })
+        .collect();
+
+    reports.sort_by_key(|report| std::cmp::Reverse(report.created));
+    Ok(reports)
+}
+
+/// 提交崩溃报告 - 由用户主动调用，上传到设置中配置的崩溃报告地址
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `name`: 崩溃报告文件名
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn submit_crash_report(app_handle: AppHandle, name: String) -> AppResult<String> {
+    let endpoint = crate::settings::load_settings(&app_handle)
+        .crash_report_endpoint
+        .ok_or_else(|| {
+            AppError::NotConfigured(tr!("error.crash_report_endpoint_not_configured"))
+        })?;
+
+    // 只允许提交崩溃报告目录中的文件
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_crash_report_name"
+        )));
+    }
+    let path = crash_reports_dir()
+        .ok_or_else(|| AppError::NotFound(tr!("error.crash_reports_dir_unavailable")))?
+        .join(&name);
+    let content = fs::read(&path).map_err(|e| {
+        AppError::NotFound(tr!(
+            "error.read_crash_report_failed",
+            error = format!("{:?}", e)
+        ))
+    })?;
+
+    log_info!("提交崩溃报告: {} -> {}", name, endpoint);
+
+    reqwest::Client::new()
+        .post(&endpoint)
+        .header("Content-Type", "application/octet-stream")
+        .header("X-Report-Name", &name)
+        .header("X-App-Version", APP_VERSION)
+        .body(content)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(AppError::Network)?;
+
+    Ok(format!("崩溃报告已提交: {}", name))
+}
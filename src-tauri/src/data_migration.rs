@@ -0,0 +1,408 @@
+// data_migration.rs 模块 - 迁移数据目录：把已解压的地图移动到新的数据目录，更新 addons 中指向旧位置的挂载链接，
+// 最后写入配置并切换目录管理器
+//
+// 迁移是全有或全无的：任一文件移动或链接更新失败、或用户在进度对话框中取消时，已移动的文件会被移回原位置，
+// 配置保持不变。下载缓存、日志等其他数据不迁移。
+
+// 标准库导入
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// 第三方库导入
+use serde::Serialize;
+use tauri::AppHandle;
+
+// 内部模块导入
+use crate::{
+    cancellation::CancellationToken,
+    dialog_manager::{ProgressDialog, ProgressReporter},
+    dir_manager::DIR_MANAGER,
+    download_manager::DOWNLOAD_QUEUE,
+    error::{AppError, AppResult},
+    extract_manager::EXTRACT_MANAGER,
+    log_error, log_info, log_warn, tr,
+};
+
+/// 数据目录迁移结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationSummary {
+    /// 原数据目录
+    pub from: String,
+    /// 新数据目录
+    pub to: String,
+    /// 移动的地图文件数量
+    pub moved_files: usize,
+    /// 移动的总字节数
+    pub moved_bytes: u64,
+    /// 更新的挂载链接数量
+    pub relinked: usize,
+}
+
+/// 待迁移的地图文件
+struct MapEntry {
+    /// 相对于 maps 目录的路径（组/文件）
+    relative: PathBuf,
+    /// 指向该文件的挂载链接名
+    link_names: Vec<String>,
+    /// 文件大小
+    size: u64,
+}
+
+/// 递归列出 maps 目录下的所有文件（相对路径）
+fn list_map_files(
+    maps_dir: &Path,
+    dir: &Path,
+    files: &mut Vec<(PathBuf, u64)>,
+) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("无法读取目录 {}: {:?}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            list_map_files(maps_dir, &path, files)?;
+        } else if metadata.is_file() {
+            if let Ok(relative) = path.strip_prefix(maps_dir) {
+                files.push((relative.to_path_buf(), metadata.len()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 获取 addons 目录中指向 maps 目录的挂载链接：相对路径 -> 链接名
+fn list_map_links(maps_dir: &Path, addons_dir: &Path) -> HashMap<PathBuf, Vec<String>> {
+    let mut links: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    let Ok(canonical_maps) = fs::canonicalize(maps_dir) else {
+        return links;
+    };
+    let Ok(entries) = fs::read_dir(addons_dir) else {
+        return links;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_symlink() {
+            continue;
+        }
+        // 目标不存在的链接无法判断属于哪个文件，保持不变
+        let Some(target) = fs::read_link(&path)
+            .ok()
+            .and_then(|target| fs::canonicalize(target).ok())
+        else {
+            continue;
+        };
+        if let (Ok(relative), Some(name)) = (target.strip_prefix(&canonical_maps), path.file_name())
+        {
+            links
+                .entry(relative.to_path_buf())
+                .or_default()
+                .push(name.to_string_lossy().to_string());
+        }
+    }
+    links
+}
+
+/// 移动一个地图文件并把指向它的挂载链接改为指向新位置
+///
+/// 跨磁盘时复制文件可能需要较长时间，在阻塞线程中执行
+async fn move_entry<F: FnMut(u64, u64) + Send + 'static>(
+    (from_maps, to_maps): (&Path, &Path),
+    entry: &MapEntry,
+    addons_dir: Option<&Path>,
+    token: &CancellationToken,
+    on_progress: F,
+) -> Result<(), String> {
+    let source = from_maps.join(&entry.relative);
+    let dest = to_maps.join(&entry.relative);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {:?}", e))?;
+    }
+    let (source_path, dest_path, move_token) = (source.clone(), dest.clone(), token.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::file_mover::move_path(&source_path, &dest_path, &move_token, on_progress)
+    })
+    .await
+    .map_err(|e| format!("移动文件的任务异常结束: {}", e))??;
+
+    if let Some(addons_dir) = addons_dir {
+        for link_name in &entry.link_names {
+            // 目标已被移动，旧链接已失效，直接删除后重新创建
+            let link_path = addons_dir.join(link_name);
+            fs::remove_file(&link_path).map_err(|e| format!("删除旧挂载链接失败: {:?}", e))?;
+            crate::symlink_manager::create_file_symlink(
+                &dest.to_string_lossy(),
+                &addons_dir.to_string_lossy(),
+                link_name,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// 删除 maps 目录下迁移后留下的空文件夹（保留 maps 目录本身）
+fn remove_empty_dirs(dir: &Path, keep: bool) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && !path.is_symlink() {
+                remove_empty_dirs(&path, false);
+            }
+        }
+    }
+    if !keep && fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none()) {
+        if let Err(e) = fs::remove_dir(dir) {
+            log_warn!("删除空文件夹失败: {}, 错误: {:?}", dir.display(), e);
+        }
+    }
+}
+
+/// 把已移动的文件移回原位置
+async fn roll_back(
+    moved: &[&MapEntry],
+    (old_maps, new_maps): (&Path, &Path),
+    addons_dir: Option<&Path>,
+    reporter: &ProgressReporter,
+) {
+    let total = moved.len() as u64;
+    for (index, entry) in moved.iter().rev().enumerate() {
+        let name = entry.relative.to_string_lossy();
+        reporter.report(
+            index as u64,
+            total,
+            &tr!("dialog.migrate_data_dir.rolling_back", name = name),
+        );
+        // 回滚不响应取消，保证文件都回到原位置
+        let result = move_entry(
+            (new_maps, old_maps),
+            entry,
+            addons_dir,
+            &CancellationToken::new(),
+            |_, _| {},
+        )
+        .await;
+        if let Err(e) = result {
+            log_error!("回滚迁移失败: {}, 错误: {}", name, e);
+        }
+    }
+    remove_empty_dirs(new_maps, true);
+}
+
+/// 迁移数据目录 - 移动所有已解压的地图到新目录，更新挂载链接，并把配置中的 nmd_data 切换到新目录
+///
+/// 下载或解压任务进行中时不能迁移。迁移期间显示进度对话框，用户可以取消，取消或失败时会恢复原状。
+///
+/// # 参数
+/// - `new_path`: 新的数据目录
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 成功时返回迁移结果
+/// - 失败或被取消时返回AppError，已移动的文件会被移回原位置
+#[tauri::command(async)]
+pub async fn migrate_data_dir(
+    new_path: String,
+    app_handle: AppHandle,
+) -> AppResult<MigrationSummary> {
+    log_info!("接收到迁移数据目录请求: {}", new_path);
+
+    let (old_data_dir, old_maps, addons_dir) = {
+        let manager = DIR_MANAGER.lock().map_err(|e| {
+            AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            ))
+        })?;
+        let dir_manager = manager
+            .as_ref()
+            .ok_or_else(|| AppError::NotConfigured(tr!("error.dir_manager_not_initialized")))?;
+        (
+            dir_manager.data_dir(),
+            dir_manager.maps_dir(),
+            dir_manager.addons_dir().cloned(),
+        )
+    };
+
+    let new_data_dir = PathBuf::from(new_path.trim());
+    if !new_data_dir.is_absolute() {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_path",
+            path = new_path
+        )));
+    }
+    fs::create_dir_all(&new_data_dir).map_err(|e| {
+        AppError::FileOperation(tr!("error.create_dir_failed", error = format!("{:?}", e)))
+    })?;
+    let (Ok(canonical_old), Ok(canonical_new)) = (
+        fs::canonicalize(&old_data_dir),
+        fs::canonicalize(&new_data_dir),
+    ) else {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_path",
+            path = new_path
+        )));
+    };
+    if canonical_old == canonical_new {
+        return Err(AppError::InvalidInput(tr!("error.migrate_same_dir")));
+    }
+    if canonical_new.starts_with(&canonical_old) || canonical_old.starts_with(&canonical_new) {
+        return Err(AppError::InvalidInput(tr!("error.migrate_nested_dir")));
+    }
+
+    // 迁移期间不允许有正在进行的下载和解压，避免任务写入旧目录
+    let downloads_busy = !DOWNLOAD_QUEUE.lock().unwrap().active_tasks.is_empty();
+    let extracts_busy = !EXTRACT_MANAGER
+        .queue
+        .lock()
+        .unwrap()
+        .active_tasks
+        .is_empty();
+    if downloads_busy || extracts_busy {
+        return Err(AppError::Busy(tr!("error.migrate_busy")));
+    }
+
+    let new_maps = new_data_dir.join("maps");
+    fs::create_dir_all(&new_maps).map_err(|e| {
+        AppError::FileOperation(tr!("error.create_dir_failed", error = format!("{:?}", e)))
+    })?;
+
+    let mut files = Vec::new();
+    list_map_files(&old_maps, &old_maps, &mut files).map_err(AppError::FileOperation)?;
+    let mut links = addons_dir
+        .as_deref()
+        .map(|addons_dir| list_map_links(&old_maps, addons_dir))
+        .unwrap_or_default();
+    let entries: Vec<MapEntry> = files
+        .into_iter()
+        .map(|(relative, size)| MapEntry {
+            link_names: links.remove(&relative).unwrap_or_default(),
+            relative,
+            size,
+        })
+        .collect();
+
+    // 新目录中已有同名文件时不开始迁移
+    if let Some(conflict) = entries
+        .iter()
+        .find(|entry| new_maps.join(&entry.relative).exists())
+    {
+        return Err(AppError::InvalidInput(tr!(
+            "error.entry_already_exists",
+            name = conflict.relative.to_string_lossy()
+        )));
+    }
+
+    let dialog = ProgressDialog::open(&app_handle, &tr!("dialog.migrate_data_dir.title"), true)
+        .map_err(AppError::Window)?;
+    let reporter = dialog.reporter();
+    let token = dialog.cancellation_token();
+    let total = entries.len() as u64;
+    log_info!(
+        "开始迁移数据目录: {} -> {}, 共 {} 个文件",
+        old_data_dir.display(),
+        new_data_dir.display(),
+        total
+    );
+
+    let mut moved: Vec<&MapEntry> = Vec::new();
+    let mut failure = None;
+    for (index, entry) in entries.iter().enumerate() {
+        if reporter.is_cancelled() {
+            failure = Some(AppError::Cancelled(tr!("error.migrate_cancelled")));
+            break;
+        }
+        let name = entry.relative.to_string_lossy();
+        reporter.report(index as u64, total, &name);
+
+        // 跨磁盘复制大文件时在说明中显示该文件的复制进度
+        let file_reporter = reporter.clone();
+        let file_name = name.to_string();
+        let mut last_percent = 0;
+        let on_progress = move |copied: u64, size: u64| {
+            let percent = (copied * 100).checked_div(size).unwrap_or(100);
+            if percent != last_percent && percent < 100 {
+                last_percent = percent;
+                file_reporter.report(
+                    index as u64,
+                    total,
+                    &format!("{} ({}%)", file_name, percent),
+                );
+            }
+        };
+
+        let result = move_entry(
+            (&old_maps, &new_maps),
+            entry,
+            addons_dir.as_deref(),
+            &token,
+            on_progress,
+        )
+        .await;
+        match result {
+            Ok(()) => moved.push(entry),
+            Err(_) if token.is_cancelled() => {
+                failure = Some(AppError::Cancelled(tr!("error.migrate_cancelled")));
+                break;
+            }
+            Err(e) => {
+                log_error!("迁移文件失败: {}, 错误: {}", name, e);
+                failure = Some(AppError::FileOperation(tr!(
+                    "error.migrate_file_failed",
+                    name = name,
+                    error = e
+                )));
+                break;
+            }
+        }
+    }
+
+    if let Some(error) = failure {
+        log_warn!("数据目录迁移中止，正在恢复 {} 个已移动的文件", moved.len());
+        roll_back(
+            &moved,
+            (&old_maps, &new_maps),
+            addons_dir.as_deref(),
+            &reporter,
+        )
+        .await;
+        return Err(error);
+    }
+
+    reporter.report(total, total, &tr!("dialog.migrate_data_dir.switching"));
+    remove_empty_dirs(&old_maps, true);
+
+    let new_data_dir_str = new_data_dir.to_string_lossy().to_string();
+    crate::config_manager::merge_config(
+        &app_handle,
+        "config.json",
+        serde_json::json!({ "nmd_data": new_data_dir_str }),
+    )
+    .map_err(AppError::Config)?;
+    crate::config_manager::reload_data_dir(&app_handle, &new_data_dir_str)
+        .map_err(AppError::Config)?;
+    crate::dir_manager::clear_dir_size_cache();
+
+    let summary = MigrationSummary {
+        from: old_data_dir.to_string_lossy().to_string(),
+        to: new_data_dir_str,
+        moved_files: entries.len(),
+        moved_bytes: entries.iter().map(|entry| entry.size).sum(),
+        relinked: entries.iter().map(|entry| entry.link_names.len()).sum(),
+    };
+    log_info!(
+        "数据目录迁移完成: {} -> {}, 文件 {} 个, 共 {} 字节, 更新挂载链接 {} 个",
+        summary.from,
+        summary.to,
+        summary.moved_files,
+        summary.moved_bytes,
+        summary.relinked
+    );
+    Ok(summary)
+}
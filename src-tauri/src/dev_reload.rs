@@ -0,0 +1,99 @@
+// dev_reload.rs 模块 - 调试版本中直接使用前端源码目录（src）提供 asset 协议的文件，
+// 文件修改后向所有窗口发送 assets-reload 事件，修改注入脚本和窗口页面时不需要重新运行 minify 或重新编译后端
+//
+// 发布版本不包含源码目录，这里的函数都只在 debug_assertions 下生效。
+
+// 标准库导入
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+// 第三方库导入
+use tauri::AppHandle;
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_debug, log_info};
+
+/// 检查源码修改的间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 前端源码目录（与 minify.js 的 srcDir 一致）
+fn source_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("src")
+}
+
+/// 获取 asset 协议请求对应的源码文件，发布版本或文件不存在时返回None
+///
+/// # 参数
+/// - `path`: 请求路径（相对于 assets 目录）
+pub fn source_asset_path(path: &str) -> Option<PathBuf> {
+    if !cfg!(debug_assertions) || path.split(['/', '\\']).any(|part| part == "..") {
+        return None;
+    }
+    Some(source_dir().join(path)).filter(|path| path.is_file())
+}
+
+/// 递归获取目录下所有文件的修改时间，按路径排序
+fn snapshot(dir: &Path, files: &mut Vec<(PathBuf, SystemTime)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            snapshot(&path, files);
+        } else if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+            files.push((path, modified));
+        }
+    }
+}
+
+/// 源码目录的当前状态
+fn source_snapshot() -> Vec<(PathBuf, SystemTime)> {
+    let mut files = Vec::new();
+    snapshot(&source_dir(), &mut files);
+    files.sort();
+    files
+}
+
+/// 启动源码监视线程（仅调试版本），文件被修改、添加或删除时发送 assets-reload 事件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn start_source_watcher(app_handle: AppHandle) {
+    if !cfg!(debug_assertions) || !source_dir().is_dir() {
+        return;
+    }
+    log_info!("调试模式：直接使用前端源码目录 {}", source_dir().display());
+
+    thread::spawn(move || {
+        let mut last = source_snapshot();
+        while !is_app_shutting_down() {
+            thread::sleep(WATCH_INTERVAL);
+
+            let current = source_snapshot();
+            if current == last {
+                continue;
+            }
+            let mut changed = current
+                .iter()
+                .filter(|file| !last.contains(file))
+                .chain(last.iter().filter(|file| !current.contains(file)))
+                .filter_map(|(path, _)| path.strip_prefix(source_dir()).ok())
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+                .collect::<Vec<_>>();
+            changed.sort();
+            changed.dedup();
+            last = current;
+
+            log_debug!("前端源码已修改: {:?}", changed);
+            let _ = crate::event_dispatcher::emit(
+                &app_handle,
+                "assets-reload",
+                &serde_json::json!({ "files": changed }),
+            );
+        }
+    });
+}
@@ -1,7 +1,59 @@
 // dialog_manager.rs 模块 - 处理各种对话框
 
-use tauri::{AppHandle, Runtime};
-use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, MessageDialogBuilder, MessageDialogKind};
+use std::{collections::HashMap, sync::Mutex};
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+use tauri_plugin_dialog::{
+    DialogExt, FileDialogBuilder, MessageDialogBuilder, MessageDialogButtons, MessageDialogKind,
+    MessageDialogResult,
+};
+use tokio::sync::oneshot;
+
+use crate::{
+    cancellation::CancellationToken,
+    error::{AppError, AppResult},
+    log_error, log_info,
+};
+
+/// 进度对话框窗口标签前缀
+const PROGRESS_WINDOW_PREFIX: &str = "progress-";
+
+/// 进度对话框的进度更新事件
+const PROGRESS_UPDATE_EVENT: &str = "progress-dialog-update";
+
+lazy_static! {
+    /// 对话框队列 - 同一时间只显示一个需要等待结果的对话框，其余按请求顺序排队（tokio::sync::Mutex 按先后顺序唤醒）
+    static ref DIALOG_QUEUE: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+
+    /// 正在显示的进度对话框：对话框ID -> 状态，窗口加载完成后据此显示最新进度
+    static ref PROGRESS_DIALOGS: Mutex<HashMap<String, ProgressEntry>> = Mutex::new(HashMap::new());
+}
+
+/// 进度对话框状态（发送给进度窗口）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressState {
+    /// 对话框标题
+    pub title: String,
+    /// 当前步骤说明（例如正在处理的文件）
+    pub message: String,
+    /// 已完成数量
+    pub current: u64,
+    /// 总数量，未知时为0
+    pub total: u64,
+    /// 是否允许取消
+    pub cancellable: bool,
+    /// 是否已请求取消
+    pub cancelled: bool,
+}
+
+/// 进度对话框记录
+struct ProgressEntry {
+    state: ProgressState,
+    token: CancellationToken,
+}
 
 /// 显示目录选择对话框
 /// 
@@ -10,35 +62,26 @@ use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, MessageDialogBuilder, Me
 /// 
 /// # 返回值
 /// - 成功时返回用户选择的目录路径
-/// - 失败时返回包含错误信息的Err(String)
+/// - 用户取消时返回AppError::Cancelled
 #[tauri::command(async)]
-pub async fn show_directory_dialog<R: Runtime>(app_handle: AppHandle<R>) -> Result<String, String> {
-    use std::sync::{Arc, Mutex};
-    use tokio::sync::oneshot;
+pub async fn show_directory_dialog<R: Runtime>(app_handle: AppHandle<R>) -> AppResult<String> {
+    // 排队等待前面的对话框关闭
+    let _turn = DIALOG_QUEUE.lock().await;
     
-    // 创建一个oneshot通道用于传递结果
+    // 创建一个oneshot通道用于传递结果，回调只会被调用一次
     let (tx, rx) = oneshot::channel();
-    let tx = Arc::new(Mutex::new(Some(tx)));
     
-    let dialog = app_handle.dialog().clone();
-    
-    // 在后台线程中运行对话框，避免阻塞UI
-    std::thread::spawn(move || {
-        FileDialogBuilder::new(dialog)
-            .set_title("选择数据存储目录")
-            .pick_folder(move |path| {
-                // 使用Mutex和Option确保只发送一次结果
-                if let Ok(mut guard) = tx.lock() {
-                    if let Some(tx) = guard.take() {
-                        // 忽略发送错误，因为接收端可能已关闭
-                        let _ = tx.send(path);
-                    }
-                }
-            });
-    });
+    FileDialogBuilder::new(app_handle.dialog().clone())
+        .set_title("选择数据存储目录")
+        .pick_folder(move |path| {
+            // 忽略发送错误，因为接收端可能已关闭
+            let _ = tx.send(path);
+        });
     
     // 异步等待结果
-    let result = rx.await.map_err(|_| "接收结果失败".to_string())?;
+    let result = rx
+        .await
+        .map_err(|_| AppError::Internal("接收结果失败".to_string()))?;
     
     match result {
         Some(path) => {
@@ -47,7 +90,9 @@ pub async fn show_directory_dialog<R: Runtime>(app_handle: AppHandle<R>) -> Resu
         },
         None => {
             // 用户取消了选择
-            Err("用户取消了目录选择".to_string())
+            Err(AppError::Cancelled(crate::tr!(
+                "error.directory_selection_cancelled"
+            )))
         }
     }
 }
@@ -93,28 +138,281 @@ pub fn show_blocking_dialog<R: Runtime>(app_handle: &AppHandle<R>, message: &str
     rx.recv().unwrap();
 }
 
-/// 显示确认对话框
+/// 选择对话框中用户点击的按钮
+///
+/// 按位置对应按钮：自定义按钮时第一个按钮为Yes，三个按钮时第二个为No，最后一个（以及直接关闭对话框）为Cancel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogChoice {
+    /// 确定 / 是 / 第一个自定义按钮
+    Yes,
+    /// 否 / 三个按钮时的第二个自定义按钮
+    No,
+    /// 取消 / 最后一个自定义按钮 / 关闭对话框
+    Cancel,
+}
+
+impl DialogChoice {
+    /// 把对话框插件返回的结果转换为按钮位置
+    fn from_result(result: MessageDialogResult, buttons: &MessageDialogButtons) -> Self {
+        match result {
+            MessageDialogResult::Yes | MessageDialogResult::Ok => Self::Yes,
+            MessageDialogResult::No => Self::No,
+            MessageDialogResult::Custom(label) => match buttons {
+                MessageDialogButtons::OkCustom(yes)
+                | MessageDialogButtons::OkCancelCustom(yes, _)
+                | MessageDialogButtons::YesNoCancelCustom(yes, _, _)
+                    if label == *yes =>
+                {
+                    Self::Yes
+                }
+                MessageDialogButtons::YesNoCancelCustom(_, no, _) if label == *no => Self::No,
+                _ => Self::Cancel,
+            },
+            _ => Self::Cancel,
+        }
+    }
+}
+
+/// 显示选择对话框 - 支持 是/否/取消 和自定义按钮文字（例如 覆盖/保留两者/跳过）
+/// 
+/// 对话框排队显示，等待期间不阻塞异步运行时
 /// 
 /// # 参数
 /// - `app_handle`: Tauri应用句柄
 /// - `message`: 对话框消息
 /// - `title`: 对话框标题
+/// - `buttons`: 按钮组合，例如 MessageDialogButtons::YesNoCancelCustom(覆盖, 保留两者, 跳过)
 /// 
 /// # 返回值
-/// - 成功时返回用户的选择结果
-pub fn show_confirm_dialog<R: Runtime>(app_handle: &AppHandle<R>, message: &str, title: &str) -> bool {
-    // 在Tauri 2.0中，MessageDialogBuilder没有show_blocking方法，
-    // 我们使用show方法并等待结果
-    use std::sync::mpsc;
+/// - 用户点击的按钮
+pub async fn show_choice_dialog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    message: &str,
+    title: &str,
+    buttons: MessageDialogButtons,
+) -> DialogChoice {
+    // 排队等待前面的对话框关闭
+    let _turn = DIALOG_QUEUE.lock().await;
     
-    let (tx, rx) = mpsc::channel();
+    let (tx, rx) = oneshot::channel();
     
     MessageDialogBuilder::new(app_handle.dialog().clone(), title, message)
         .kind(MessageDialogKind::Info)
-        .show(move |result| {
-            tx.send(result).unwrap();
+        .buttons(buttons.clone())
+        .show_with_result(move |result| {
+            let _ = tx.send(result);
         });
     
-    // 等待并返回结果
-    rx.recv().unwrap()
+    // 异步等待并返回结果，对话框异常关闭时视为取消
+    rx.await
+        .map(|result| DialogChoice::from_result(result, &buttons))
+        .unwrap_or(DialogChoice::Cancel)
+}
+
+/// 显示确认对话框
+/// 
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `message`: 对话框消息
+/// - `title`: 对话框标题
+/// 
+/// # 返回值
+/// - 成功时返回用户的选择结果
+pub async fn show_confirm_dialog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    message: &str,
+    title: &str,
+) -> bool {
+    show_choice_dialog(app_handle, message, title, MessageDialogButtons::OkCancel).await
+        == DialogChoice::Yes
+}
+
+/// 进度对话框 - 长时间的维护操作（迁移数据目录、批量导入等）显示进度并允许用户取消
+/// 
+/// 后台任务通过 reporter() 获取的 ProgressReporter 报告进度，通过 cancellation_token() 检查是否被取消；
+/// 对话框被 drop 时关闭进度窗口
+pub struct ProgressDialog {
+    id: String,
+    window: WebviewWindow,
+    token: CancellationToken,
+}
+
+/// 进度报告器 - 可以克隆并发送到工作线程，向对应的进度对话框报告进度
+#[derive(Clone)]
+pub struct ProgressReporter {
+    id: String,
+    app_handle: AppHandle,
+    token: CancellationToken,
+}
+
+impl ProgressDialog {
+    /// 创建并显示进度对话框窗口
+    /// 
+    /// # 参数
+    /// - `app_handle`: Tauri应用句柄
+    /// - `title`: 对话框标题
+    /// - `cancellable`: 是否显示取消按钮并允许关闭窗口取消操作
+    /// 
+    /// # 返回值
+    /// - 成功时返回进度对话框，窗口创建失败时返回错误信息
+    pub fn open(app_handle: &AppHandle, title: &str, cancellable: bool) -> Result<Self, String> {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let label = format!("{}{}", PROGRESS_WINDOW_PREFIX, id);
+        let token = CancellationToken::new();
+
+        PROGRESS_DIALOGS.lock().unwrap().insert(
+            id.clone(),
+            ProgressEntry {
+                state: ProgressState {
+                    title: title.to_string(),
+                    cancellable,
+                    ..Default::default()
+                },
+                token: token.clone(),
+            },
+        );
+
+        let url = format!("http://asset.localhost/progress/main.html?id={}", id);
+        let window = tauri::WebviewWindowBuilder::new(
+            app_handle,
+            &label,
+            tauri::WebviewUrl::External(url.parse().map_err(|e| format!("无效的URL: {:?}", e))?),
+        )
+        .title(title)
+        .inner_size(420.0, 170.0)
+        .resizable(false)
+        .minimizable(false)
+        .maximizable(false)
+        .always_on_top(true)
+        .center()
+        .build()
+        .map_err(|e| {
+            PROGRESS_DIALOGS.lock().unwrap().remove(&id);
+            format!("创建进度窗口失败: {:?}", e)
+        })?;
+
+        // 关闭窗口视为取消，不允许取消时阻止关闭
+        let close_id = id.clone();
+        window.on_window_event(move |event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if !cancel_progress(&close_id) {
+                    api.prevent_close();
+                }
+            }
+        });
+
+        log_info!("已打开进度对话框: {} ({})", title, id);
+        Ok(Self { id, window, token })
+    }
+
+    /// 获取进度报告器
+    pub fn reporter(&self) -> ProgressReporter {
+        ProgressReporter {
+            id: self.id.clone(),
+            app_handle: self.window.app_handle().clone(),
+            token: self.token.clone(),
+        }
+    }
+
+    /// 获取取消令牌，用户点击取消或关闭窗口后被取消
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl Drop for ProgressDialog {
+    fn drop(&mut self) {
+        PROGRESS_DIALOGS.lock().unwrap().remove(&self.id);
+        if let Err(e) = self.window.destroy() {
+            log_error!("关闭进度窗口失败: {:?}", e);
+        }
+    }
+}
+
+impl ProgressReporter {
+    /// 报告进度
+    /// 
+    /// # 参数
+    /// - `current`: 已完成数量
+    /// - `total`: 总数量，未知时传0
+    /// - `message`: 当前步骤说明
+    pub fn report(&self, current: u64, total: u64, message: &str) {
+        let state = {
+            let mut dialogs = PROGRESS_DIALOGS.lock().unwrap();
+            let Some(entry) = dialogs.get_mut(&self.id) else {
+                return;
+            };
+            entry.state.current = current;
+            entry.state.total = total;
+            entry.state.message = message.to_string();
+            entry.state.clone()
+        };
+        let label = format!("{}{}", PROGRESS_WINDOW_PREFIX, self.id);
+        if let Err(e) = self
+            .app_handle
+            .emit_to(label.as_str(), PROGRESS_UPDATE_EVENT, &state)
+        {
+            log_error!("发送进度更新事件失败: {}", e);
+        }
+    }
+
+    /// 是否已被用户取消
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+/// 请求取消进度对话框对应的操作
+/// 
+/// # 返回值
+/// - 对话框允许取消（或已不存在）时返回true
+fn cancel_progress(id: &str) -> bool {
+    let mut dialogs = PROGRESS_DIALOGS.lock().unwrap();
+    let Some(entry) = dialogs.get_mut(id) else {
+        return true;
+    };
+    if !entry.state.cancellable {
+        return false;
+    }
+    entry.state.cancelled = true;
+    entry.token.cancel("normal");
+    log_info!("用户取消了进度对话框对应的操作: {}", id);
+    true
+}
+
+/// 获取进度对话框的当前状态，进度窗口加载完成后调用
+/// 
+/// # 参数
+/// - `id`: 对话框ID
+/// 
+/// # 返回值
+/// - 成功时返回当前状态
+/// - 对话框已关闭时返回AppError::NotFound
+#[tauri::command]
+pub fn get_progress_dialog_state(id: String) -> AppResult<ProgressState> {
+    PROGRESS_DIALOGS
+        .lock()
+        .unwrap()
+        .get(&id)
+        .map(|entry| entry.state.clone())
+        .ok_or_else(|| AppError::NotFound(crate::tr!("error.progress_dialog_not_found")))
+}
+
+/// 取消进度对话框对应的操作，进度窗口的取消按钮调用
+/// 
+/// # 参数
+/// - `id`: 对话框ID
+/// 
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 对话框不允许取消时返回AppError::InvalidInput
+#[tauri::command]
+pub fn cancel_progress_dialog(id: String) -> AppResult<()> {
+    if cancel_progress(&id) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(crate::tr!(
+            "error.progress_dialog_not_cancellable"
+        )))
+    }
 }
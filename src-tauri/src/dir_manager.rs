@@ -2,43 +2,63 @@
 
 // 标准库导入
 use std::{
+    collections::{HashMap, HashSet},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
 };
 
 // 第三方库导入
 extern crate lazy_static;
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::Serialize;
 use winreg::{enums::*, RegKey};
 
 // 内部模块导入
 use crate::{log_error, log_info, log_warn};
 
+/// 目录大小缓存项：(目录修改时间, 计算时间, 大小)
+type DirSizeEntry = (Option<SystemTime>, Instant, u64);
+
 // 全局目录管理器实例，使用 Arc<Mutex<>> 确保线程安全
 lazy_static! {
     pub static ref DIR_MANAGER: Arc<Mutex<Option<DirManager>>> = Arc::new(Mutex::new(None));
+
+    /// 目录大小缓存：目录路径 -> 缓存项
+    static ref DIR_SIZE_CACHE: Mutex<HashMap<PathBuf, DirSizeEntry>> = Mutex::new(HashMap::new());
 }
 
-// 目录管理器，负责创建和管理临时目录和 L4D2 addons 目录
-// 实现 Drop trait，在程序退出时自动清理临时目录
+// 目录管理器，负责管理数据目录、下载缓存目录和 L4D2 addons 目录
+//
+// 下载缓存目录固定为 nmd_data/.cache（或自定义下载目录下的 nmd_downloads 子目录），应用退出时不清空，
+// 未完成的下载可以在重启后继续，过期文件由 clean_download_cache 按保留天数清理
 pub struct DirManager {
     addons_dir: Option<PathBuf>,
+    data_dir: PathBuf,
     cache_dir: PathBuf,
+    download_dir: Option<PathBuf>,
     maps_dir: PathBuf,
 }
 
+/// 自定义下载目录所在磁盘的最低剩余空间要求（1 GiB）
+pub const MIN_DOWNLOAD_DIR_FREE_SPACE: u64 = 1024 * 1024 * 1024;
+
+/// 自定义下载目录中存放下载文件的子目录 - 自定义目录不一定专用于本应用，
+/// 清理缓存时只处理这个子目录，不会删除用户自己的文件
+pub const DOWNLOAD_SUBDIR: &str = "nmd_downloads";
+
 impl DirManager {
     /// 创建一个新的目录管理器实例
     ///
-    /// 返回包含临时目录的 DirManager 实例
-    ///
-    /// 临时目录以 "nmd_" 开头并附加随机 UUID
+    /// 返回尚未设置数据目录的 DirManager 实例
     pub fn new() -> Result<Self, String> {
         Ok(Self {
             addons_dir: None,
+            data_dir: PathBuf::new(),
             cache_dir: PathBuf::new(),
+            download_dir: None,
             maps_dir: PathBuf::new(),
         })
     }
@@ -65,7 +85,9 @@ impl DirManager {
 
         Ok(Self {
             addons_dir: None,
+            data_dir: nmd_data_dir,
             cache_dir,
+            download_dir: None,
             maps_dir,
         })
     }
@@ -80,9 +102,27 @@ impl DirManager {
         self.addons_dir.as_ref()
     }
 
-    /// 获取缓存目录路径
+    /// 获取 nmd_data 数据目录路径
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir.to_path_buf()
+    }
+
+    /// 设置自定义下载目录，为 None 时使用 nmd_data/.cache
+    pub fn set_download_dir(&mut self, download_dir: Option<PathBuf>) {
+        self.download_dir = download_dir;
+    }
+
+    /// 获取自定义下载目录（如果已设置）
+    pub fn download_dir(&self) -> Option<&PathBuf> {
+        self.download_dir.as_ref()
+    }
+
+    /// 获取缓存目录路径（下载文件存放位置），设置了自定义下载目录时返回其中的 nmd_downloads 子目录
     pub fn cache_dir(&self) -> PathBuf {
-        self.cache_dir.to_path_buf()
+        self.download_dir
+            .as_ref()
+            .map(|dir| dir.join(DOWNLOAD_SUBDIR))
+            .unwrap_or_else(|| self.cache_dir.to_path_buf())
     }
 
     /// 获取 maps 目录路径
@@ -108,6 +148,362 @@ pub fn get_global_cache_dir() -> Result<PathBuf, String> {
     Ok(manager.as_ref().unwrap().cache_dir().to_path_buf())
 }
 
+/// 获取指定路径所在磁盘的剩余可用空间（字节）
+pub fn get_free_space(path: &Path) -> Result<u64, String> {
+    get_disk_space(path).map(|(free, _)| free)
+}
+
+/// 获取指定路径所在磁盘的空间（字节）
+///
+/// # 返回值
+/// - 成功时返回（剩余可用空间, 总空间）
+pub fn get_disk_space(path: &Path) -> Result<(u64, u64), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDiskFreeSpaceExW;
+    use winapi::um::winnt::ULARGE_INTEGER;
+
+    let wide_path: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut free_bytes: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let mut total_bytes: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        GetDiskFreeSpaceExW(
+            wide_path.as_ptr(),
+            &mut free_bytes,
+            &mut total_bytes,
+            std::ptr::null_mut(),
+        )
+    };
+
+    if result == 0 {
+        return Err(format!(
+            "无法获取磁盘剩余空间: {:?}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    Ok(unsafe { (*free_bytes.QuadPart(), *total_bytes.QuadPart()) })
+}
+
+/// 获取指定路径所在卷的根目录（例如 C:\），路径不存在时使用最近的已存在的上级目录
+pub fn get_volume_root(path: &Path) -> Result<PathBuf, String> {
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use winapi::um::fileapi::GetVolumePathNameW;
+
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .ok_or_else(|| format!("路径不存在: {}", path.display()))?;
+    let wide_path: Vec<u16> = existing
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut volume_path = [0u16; 261];
+    let result = unsafe {
+        GetVolumePathNameW(
+            wide_path.as_ptr(),
+            volume_path.as_mut_ptr(),
+            volume_path.len() as u32,
+        )
+    };
+    if result == 0 {
+        return Err(format!(
+            "无法获取路径所在的卷: {:?}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    let length = volume_path
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(volume_path.len());
+    Ok(PathBuf::from(std::ffi::OsString::from_wide(
+        &volume_path[..length],
+    )))
+}
+
+/// 获取指定路径所在卷的序列号，路径不存在时使用最近的已存在的上级目录
+pub fn get_volume_serial(path: &Path) -> Result<u32, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetVolumeInformationW;
+
+    let wide_root: Vec<u16> = get_volume_root(path)?
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut serial = 0u32;
+    let result = unsafe {
+        GetVolumeInformationW(
+            wide_root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            &mut serial,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result == 0 {
+        return Err(format!(
+            "无法获取卷信息: {:?}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    Ok(serial)
+}
+
+/// 两个路径是否位于同一个卷（比较卷序列号），无法判断时返回false
+pub fn is_same_volume(a: &Path, b: &Path) -> bool {
+    match (get_volume_serial(a), get_volume_serial(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        (Err(e), _) | (_, Err(e)) => {
+            log_warn!("比较卷序列号失败: {}", e);
+            false
+        }
+    }
+}
+
+/// 校验下载目录 - 确保目录（及其中的 nmd_downloads 子目录）可创建、可写入且所在磁盘有足够的剩余空间
+///
+/// # 参数
+/// - `download_dir`: 要校验的下载目录
+///
+/// # 返回值
+/// - 校验通过时返回Ok(())
+/// - 失败时返回包含错误信息的Err
+pub fn validate_download_dir(download_dir: &Path) -> Result<(), String> {
+    if !download_dir.is_absolute() {
+        return Err(format!(
+            "下载目录必须是绝对路径: {}",
+            download_dir.display()
+        ));
+    }
+
+    // 下载文件存放在其中的 nmd_downloads 子目录
+    let download_dir = download_dir.join(DOWNLOAD_SUBDIR);
+    fs::create_dir_all(&download_dir).map_err(|e| format!("无法创建下载目录: {:?}", e))?;
+
+    // 写入测试文件以确认写入权限
+    let probe_path = download_dir.join(format!(".nmd_write_test_{}", std::process::id()));
+    fs::write(&probe_path, b"nmd").map_err(|e| format!("下载目录不可写入: {:?}", e))?;
+    let _ = fs::remove_file(&probe_path);
+
+    let free_space = get_free_space(&download_dir)?;
+    if free_space < MIN_DOWNLOAD_DIR_FREE_SPACE {
+        return Err(format!(
+            "下载目录所在磁盘剩余空间不足: {:.2} MB（至少需要 {} MB）",
+            free_space as f64 / (1024.0 * 1024.0),
+            MIN_DOWNLOAD_DIR_FREE_SPACE / (1024 * 1024)
+        ));
+    }
+
+    Ok(())
+}
+
+/// 应用自定义下载目录配置到全局目录管理器
+///
+/// 目录校验失败时记录警告并回退到 nmd_data/.cache
+///
+/// # 参数
+/// - `download_dir`: 配置中的下载目录，为 None 时使用默认目录
+pub fn apply_download_dir(download_dir: Option<&str>) {
+    let download_dir = download_dir
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+        .and_then(|dir| match validate_download_dir(&dir) {
+            Ok(_) => Some(dir),
+            Err(e) => {
+                log_warn!("自定义下载目录不可用，使用默认目录: {}", e);
+                None
+            }
+        });
+
+    if let Ok(mut manager) = DIR_MANAGER.lock() {
+        if let Some(dir_manager) = manager.as_mut() {
+            match download_dir {
+                Some(ref dir) => log_info!("使用自定义下载目录: {}", dir.display()),
+                None => log_info!("使用默认下载目录: {}", dir_manager.cache_dir.display()),
+            }
+            dir_manager.set_download_dir(download_dir);
+        }
+    }
+}
+
+/// 按保留天数清理下载缓存目录 - 删除修改时间早于保留期限的文件
+///
+/// 只处理缓存目录下的文件，不进入子目录；未完成的下载（及其 .aria2 控制文件）始终保留
+///
+/// # 参数
+/// - `retention_days`: 保留天数
+/// - `keep`: 需要保留的文件名
+///
+/// # 返回值
+/// - 删除的文件数
+pub fn clean_download_cache(retention_days: u32, keep: &HashSet<String>) -> usize {
+    let cache_dir = match get_global_cache_dir() {
+        Ok(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => return 0,
+    };
+    let entries = match fs::read_dir(&cache_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log_warn!("读取缓存目录失败: {}", e);
+            return 0;
+        }
+    };
+
+    let retention = Duration::from_secs(retention_days as u64 * 24 * 60 * 60);
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        if keep.contains(&*entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let expired = entry
+            .metadata()
+            .ok()
+            .filter(|metadata| metadata.is_file())
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= retention);
+        if !expired {
+            continue;
+        }
+
+        match fs::remove_file(entry.path()) {
+            Ok(_) => {
+                log_info!("删除过期的缓存文件: {}", entry.path().display());
+                removed += 1;
+            }
+            Err(e) => log_warn!("删除缓存文件失败: {}, 错误: {}", entry.path().display(), e),
+        }
+    }
+
+    removed
+}
+
+/// 系统临时目录中的残留项超过这个时间未修改才清理，避免删除其他实例正在使用的文件
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// 本应用在系统临时目录中创建的文件和目录的前缀（旧版本的下载目录、自检目录、诊断包暂存目录和回滚安装包）
+const TEMP_ENTRY_PREFIXES: &[&str] = &["nmd_", ".nmd-", "nmd-support-", "nmd-rollback-"];
+
+/// 把残留的临时下载目录中未完成的下载（带 .aria2 控制文件）移动到下载缓存目录，之后重新下载同一文件时可以继续
+///
+/// # 返回值
+/// - 移动的文件数
+fn adopt_partial_downloads(dir: &Path, cache_dir: &Path) -> usize {
+    let token = crate::cancellation::CancellationToken::new();
+    let mut adopted = 0;
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let control_name = format!("{}.aria2", name);
+        let control_path = dir.join(&control_name);
+        if !path.is_file() || !control_path.is_file() {
+            continue;
+        }
+        // 缓存目录中已有同名文件时不覆盖
+        if cache_dir.join(&name).exists() || cache_dir.join(&control_name).exists() {
+            continue;
+        }
+        let moved = crate::file_mover::move_path(&path, &cache_dir.join(&name), &token, |_, _| {})
+            .and_then(|_| {
+                crate::file_mover::move_path(
+                    &control_path,
+                    &cache_dir.join(&control_name),
+                    &token,
+                    |_, _| {},
+                )
+            });
+        match moved {
+            Ok(_) => {
+                log_info!("已将未完成的下载移动到下载缓存目录: {}", name);
+                adopted += 1;
+            }
+            Err(e) => log_warn!("移动未完成的下载失败: {}, 错误: {}", path.display(), e),
+        }
+    }
+    adopted
+}
+
+/// 清理系统临时目录中残留的临时文件和目录 - 应用崩溃或被强制结束时，这些临时文件不会被删除
+///
+/// 旧版本 nmd_<uuid> 下载目录中未完成的下载先移动到下载缓存目录，再删除目录
+///
+/// # 返回值
+/// - 删除的文件和目录数
+pub fn clean_stale_temp_entries() -> usize {
+    let temp_dir = std::env::temp_dir();
+    let cache_dir = get_global_cache_dir()
+        .ok()
+        .filter(|dir| !dir.as_os_str().is_empty());
+    let entries = match fs::read_dir(&temp_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log_warn!("读取系统临时目录失败: {}", e);
+            return 0;
+        }
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !TEMP_ENTRY_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let stale = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= STALE_TEMP_AGE);
+        if !stale {
+            continue;
+        }
+
+        let path = entry.path();
+        // nmd_ 前缀只用于旧版本的下载目录，名称必须是 nmd_<uuid>，避免误删其他程序的文件
+        if let Some(id) = name.strip_prefix("nmd_") {
+            if !metadata.is_dir() || uuid::Uuid::parse_str(id).is_err() {
+                continue;
+            }
+            if let Some(ref cache_dir) = cache_dir {
+                adopt_partial_downloads(&path, cache_dir);
+            }
+        }
+
+        let result = if metadata.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match result {
+            Ok(_) => {
+                log_info!("已删除残留的临时文件: {}", path.display());
+                removed += 1;
+            }
+            Err(e) => log_warn!("删除残留的临时文件失败: {}, 错误: {}", path.display(), e),
+        }
+    }
+
+    removed
+}
+
 /// 设置全局 L4D2 addons 目录
 ///
 /// 如果全局目录管理器尚未初始化，则会自动初始化
@@ -299,3 +695,231 @@ pub fn get_l4d2_addons_dir() -> Result<String, String> {
     log_error!("{}", error_msg);
     Err(error_msg)
 }
+
+// ========== 文件管理器列表 ==========
+
+/// 目录大小缓存的有效期，目录本身的修改时间变化时也会重新计算
+const DIR_SIZE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 文件管理器中显示的目录项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedEntry {
+    /// 文件或目录名
+    pub name: String,
+    /// 完整路径
+    pub path: String,
+    /// 是否为目录（符号链接按其指向的目标判断）
+    pub is_dir: bool,
+    /// 是否为符号链接
+    pub is_symlink: bool,
+    /// 符号链接指向的路径
+    pub target: Option<String>,
+    /// 符号链接指向的目标是否存在
+    pub target_exists: bool,
+    /// 大小（字节），目录为递归计算的总大小
+    pub size: u64,
+    /// 修改时间（RFC 3339 格式）
+    pub modified: Option<String>,
+}
+
+/// 递归计算目录大小，不跟随符号链接，避免重复计算和循环引用
+fn compute_dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_symlink() => 0,
+            Ok(file_type) if file_type.is_dir() => get_dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// 获取目录大小 - 优先使用缓存，缓存过期或目录修改时间变化时重新计算
+pub fn get_dir_size(dir: &Path) -> u64 {
+    let modified = fs::metadata(dir).and_then(|meta| meta.modified()).ok();
+
+    if let Ok(cache) = DIR_SIZE_CACHE.lock() {
+        if let Some((cached_modified, computed_at, size)) = cache.get(dir) {
+            if *cached_modified == modified && computed_at.elapsed() < DIR_SIZE_CACHE_TTL {
+                return *size;
+            }
+        }
+    }
+
+    let size = compute_dir_size(dir);
+    if let Ok(mut cache) = DIR_SIZE_CACHE.lock() {
+        cache.insert(dir.to_path_buf(), (modified, Instant::now(), size));
+    }
+    size
+}
+
+/// 清除目录大小缓存（删除、移动文件后调用，确保下次列表重新计算）
+pub fn clear_dir_size_cache() {
+    if let Ok(mut cache) = DIR_SIZE_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// 列出目录下的文件和子目录，包含递归计算的目录大小、修改时间和符号链接信息
+///
+/// # 参数
+/// - `dir`: 要列出的目录
+///
+/// # 返回值
+/// - 成功时返回目录项列表，目录在前，按名称排序
+/// - 失败时返回包含错误信息的Err
+pub fn list_managed_entries(dir: &Path) -> Result<Vec<ManagedEntry>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("读取目录失败: {:?}", e))?;
+
+    let mut result: Vec<ManagedEntry> = entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            let link_meta = fs::symlink_metadata(&path).ok();
+            let is_symlink = link_meta
+                .as_ref()
+                .is_some_and(|meta| meta.file_type().is_symlink());
+            let target = is_symlink
+                .then(|| fs::read_link(&path).ok())
+                .flatten()
+                .map(|target| target.to_string_lossy().to_string());
+
+            // 符号链接使用目标的元数据，目标不存在时退回链接本身
+            let target_meta = fs::metadata(&path).ok();
+            let target_exists = target_meta.is_some();
+            let meta = target_meta.or(link_meta);
+            let is_dir = meta.as_ref().is_some_and(|meta| meta.is_dir());
+
+            let size = match &meta {
+                Some(_) if is_dir && !is_symlink => get_dir_size(&path),
+                Some(meta) if !is_dir => meta.len(),
+                _ => 0,
+            };
+            let modified = meta
+                .and_then(|meta| meta.modified().ok())
+                .map(|time| chrono::DateTime::<chrono::Local>::from(time).to_rfc3339());
+
+            ManagedEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                is_dir,
+                is_symlink,
+                target,
+                target_exists,
+                size,
+                modified,
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(result)
+}
+
+// ========== 占用空间统计 ==========
+
+/// 磁盘卷的空间信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeUsage {
+    /// 卷的根目录（例如 C:\）
+    pub root: String,
+    /// 剩余可用空间（字节）
+    pub free: u64,
+    /// 总空间（字节）
+    pub total: u64,
+}
+
+/// 受管理目录的占用空间（字节）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    /// 数据目录，未配置时为None
+    pub data_dir: Option<String>,
+    /// 已解压的地图（maps 目录）
+    pub maps: u64,
+    /// 下载缓存（不含压缩包缓存）
+    pub download_cache: u64,
+    /// 压缩包缓存
+    pub archive_cache: u64,
+    /// 数据目录中的其他文件（日志、崩溃报告等）
+    pub other: u64,
+    /// addons 目录，未设置时为None
+    pub addons_dir: Option<String>,
+    /// addons 目录中的文件（不含挂载链接指向的地图）
+    pub addons: u64,
+    /// 上述目录所在的磁盘
+    pub volumes: Vec<VolumeUsage>,
+}
+
+/// 统计数据目录各部分和 addons 目录的占用空间，以及所在磁盘的剩余空间
+///
+/// 目录大小使用 get_dir_size 的缓存，只重新计算修改过或缓存已过期的目录
+pub fn get_disk_usage() -> DiskUsage {
+    let (data_dir, maps_dir, cache_dir, addons_dir) = match DIR_MANAGER.lock() {
+        Ok(manager) => match manager.as_ref() {
+            Some(dm) if !dm.data_dir.as_os_str().is_empty() => (
+                dm.data_dir(),
+                dm.maps_dir(),
+                dm.cache_dir(),
+                dm.addons_dir().cloned(),
+            ),
+            _ => return DiskUsage::default(),
+        },
+        Err(e) => {
+            log_error!("无法锁定目录管理器: {:?}", e);
+            return DiskUsage::default();
+        }
+    };
+
+    let maps = get_dir_size(&maps_dir);
+    let archive_cache = get_dir_size(&cache_dir.join(crate::archive_cache::ARCHIVE_CACHE_DIR));
+    let cache_total = get_dir_size(&cache_dir);
+    // 使用默认下载目录时，下载缓存位于数据目录中
+    let cache_in_data_dir = if cache_dir.starts_with(&data_dir) {
+        cache_total
+    } else {
+        0
+    };
+    let other = get_dir_size(&data_dir)
+        .saturating_sub(maps)
+        .saturating_sub(cache_in_data_dir);
+    let addons = addons_dir.as_deref().map(get_dir_size).unwrap_or(0);
+
+    let mut volumes: Vec<VolumeUsage> = Vec::new();
+    for dir in [Some(&data_dir), Some(&cache_dir), addons_dir.as_ref()]
+        .into_iter()
+        .flatten()
+    {
+        let Ok(root) = get_volume_root(dir) else {
+            continue;
+        };
+        let root = root.to_string_lossy().to_string();
+        if volumes.iter().any(|volume| volume.root == root) {
+            continue;
+        }
+        match get_disk_space(Path::new(&root)) {
+            Ok((free, total)) => volumes.push(VolumeUsage { root, free, total }),
+            Err(e) => log_warn!("获取磁盘空间失败: {}, 错误: {}", root, e),
+        }
+    }
+
+    DiskUsage {
+        data_dir: Some(data_dir.to_string_lossy().to_string()),
+        maps,
+        download_cache: cache_total.saturating_sub(archive_cache),
+        archive_cache,
+        other,
+        addons_dir: addons_dir.map(|dir| dir.to_string_lossy().to_string()),
+        addons,
+        volumes,
+    }
+}
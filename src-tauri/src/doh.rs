@@ -0,0 +1,329 @@
+// doh.rs 模块 - 可选的 DNS-over-HTTPS 解析，帮助运营商 DNS 被污染的用户连接地图下载站点
+//
+// aria2c 不能直接使用外部解析结果，因此在本地启动一个只监听 127.0.0.1 的 HTTP 代理：aria2c 通过 all-proxy 连接代理，
+// 代理使用 DoH 解析目标域名后建立 TCP 隧道。HTTPS 下载仍然由 aria2c 与服务器直接完成 TLS 握手（包括 SNI 和证书校验）。
+// DoH 服务器地址通过 config.json 的 doh_server 配置（JSON API 格式，例如 https://1.1.1.1/dns-query），未配置时不启用。
+
+// 标准库导入
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+// 内部模块导入
+use crate::{log_debug, log_error, log_info, log_warn};
+
+/// 请求头的最大长度
+const MAX_REQUEST_HEADER_SIZE: usize = 16 * 1024;
+
+/// DoH 请求超时时间
+const DOH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 解析结果缓存时间的范围（秒）
+const MIN_CACHE_TTL: u64 = 60;
+const MAX_CACHE_TTL: u64 = 3600;
+
+lazy_static! {
+    /// 本地代理监听的端口，启动后不再变化
+    static ref PROXY_PORT: Mutex<Option<u16>> = Mutex::new(None);
+
+    /// 解析结果缓存：域名 -> (地址列表, 过期时间)
+    static ref DNS_CACHE: Mutex<HashMap<String, (Vec<IpAddr>, Instant)>> = Mutex::new(HashMap::new());
+}
+
+/// DoH JSON API 响应
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+/// DoH JSON API 响应中的一条记录
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    #[serde(rename = "TTL", default)]
+    ttl: u64,
+    data: String,
+}
+
+/// 获取配置的 DoH 服务器地址，未配置时返回None
+fn doh_server() -> Option<String> {
+    crate::settings::current_settings()
+        .doh_server
+        .filter(|server| !server.trim().is_empty())
+}
+
+/// 通过 DoH 查询一种记录类型
+async fn query(
+    client: &reqwest::Client,
+    server: &str,
+    host: &str,
+    record_type: u16,
+) -> Result<(Vec<IpAddr>, u64), String> {
+    let response: DohResponse = client
+        .get(server)
+        .query(&[("name", host), ("type", &record_type.to_string())])
+        .header(reqwest::header::ACCEPT, "application/dns-json")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("DoH请求失败: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析DoH响应失败: {}", e))?;
+
+    if response.status != 0 {
+        return Err(format!("DoH解析失败，状态码: {}", response.status));
+    }
+
+    let records = response
+        .answer
+        .iter()
+        .filter(|answer| answer.record_type == record_type);
+    let ttl = records.clone().map(|answer| answer.ttl).min().unwrap_or(0);
+    let addresses = records
+        .filter_map(|answer| answer.data.parse::<IpAddr>().ok())
+        .collect();
+    Ok((addresses, ttl))
+}
+
+/// 使用 DoH 解析域名，优先使用 IPv4 地址，结果按 TTL 缓存
+///
+/// # 参数
+/// - `host`: 要解析的域名
+///
+/// # 返回值
+/// - 解析到的地址列表
+pub async fn resolve(host: &str) -> Result<Vec<IpAddr>, String> {
+    if let Ok(ip) = host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        return Ok(vec![ip]);
+    }
+
+    let host = host.to_ascii_lowercase();
+    if let Some((addresses, expires)) = DNS_CACHE.lock().unwrap().get(&host) {
+        if *expires > Instant::now() {
+            return Ok(addresses.clone());
+        }
+    }
+
+    let server = doh_server().ok_or_else(|| "未配置DoH服务器".to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(DOH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+    // A 记录，没有结果时再查询 AAAA 记录
+    let (mut addresses, mut ttl) = query(&client, &server, &host, 1).await?;
+    if addresses.is_empty() {
+        (addresses, ttl) = query(&client, &server, &host, 28).await?;
+    }
+    if addresses.is_empty() {
+        return Err(format!("DoH没有返回 {} 的地址", host));
+    }
+
+    log_debug!("DoH解析 {} -> {:?}", host, addresses);
+    let expires = Instant::now() + Duration::from_secs(ttl.clamp(MIN_CACHE_TTL, MAX_CACHE_TTL));
+    DNS_CACHE
+        .lock()
+        .unwrap()
+        .insert(host, (addresses.clone(), expires));
+    Ok(addresses)
+}
+
+/// 将 "host:port" 拆分为域名和端口
+fn split_host_port(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    // IPv6 地址格式为 [::1]:443
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, port) = rest.split_once(']')?;
+        let port = match port.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None => default_port,
+        };
+        return Some((host.to_string(), port));
+    }
+
+    match authority.rsplit_once(':') {
+        Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+        None => Some((authority.to_string(), default_port)),
+    }
+}
+
+/// 读取代理请求头（到空行为止）
+async fn read_request_header(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+    let mut header = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !header.windows(4).any(|window| window == b"\r\n\r\n") {
+        if header.len() > MAX_REQUEST_HEADER_SIZE {
+            return Err("代理请求头过长".to_string());
+        }
+        let read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取代理请求失败: {}", e))?;
+        if read == 0 {
+            return Err("连接已关闭".to_string());
+        }
+        header.extend_from_slice(&buffer[..read]);
+    }
+    Ok(header)
+}
+
+/// 用 DoH 解析的地址连接目标服务器
+async fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    let mut last_error = String::new();
+    for address in resolve(host).await? {
+        match TcpStream::connect(SocketAddr::new(address, port)).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("连接 {} 失败: {}", address, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// 处理一个代理连接 - CONNECT 请求建立隧道，其他请求（HTTP 下载）改写为普通请求后转发
+async fn handle_connection(mut client: TcpStream) -> Result<(), String> {
+    let header = read_request_header(&mut client).await?;
+    let header_end = header
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .unwrap_or(header.len())
+        + 4;
+    let header_text = String::from_utf8_lossy(&header[..header_end]).to_string();
+    let request_line = header_text.lines().next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (method, target, version) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) => (method, target, version),
+        _ => return Err(format!("无效的代理请求: {}", request_line)),
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) =
+            split_host_port(target, 443).ok_or_else(|| format!("无效的目标地址: {}", target))?;
+        let mut server = match connect(&host, port).await {
+            Ok(server) => server,
+            Err(e) => {
+                let _ = client.write_all(b"HTTP/1.1 502 Bad Gateway\r\n\r\n").await;
+                return Err(e);
+            }
+        };
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await
+            .map_err(|e| e.to_string())?;
+        // 客户端可能在请求头之后立即发送了 TLS 握手数据
+        server
+            .write_all(&header[header_end..])
+            .await
+            .map_err(|e| e.to_string())?;
+        let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+        return Ok(());
+    }
+
+    // 普通 HTTP 请求使用绝对地址，改写为路径形式后转发给目标服务器
+    let url = tauri::Url::parse(target).map_err(|e| format!("无效的请求地址 {}: {}", target, e))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("请求地址缺少主机名: {}", target))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    // 每个连接只转发一个请求，避免同一连接上的后续请求被发到错误的服务器
+    let mut rewritten = format!("{} {} {}\r\n", method, path, version);
+    for line in header_text.lines().skip(1).filter(|line| !line.is_empty()) {
+        let name = line.split(':').next().unwrap_or_default().trim();
+        if !name.eq_ignore_ascii_case("proxy-connection")
+            && !name.eq_ignore_ascii_case("connection")
+        {
+            rewritten.push_str(line);
+            rewritten.push_str("\r\n");
+        }
+    }
+    rewritten.push_str("Connection: close\r\n\r\n");
+
+    let mut server = connect(host, port).await?;
+    server
+        .write_all(rewritten.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    server
+        .write_all(&header[header_end..])
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+    Ok(())
+}
+
+/// 启动本地代理（只启动一次）
+fn ensure_proxy() -> Result<u16, String> {
+    let mut port_guard = PROXY_PORT.lock().unwrap();
+    if let Some(port) = *port_guard {
+        return Ok(port);
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| format!("启动DoH代理失败: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("启动DoH代理失败: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("启动DoH代理失败: {}", e))?
+        .port();
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log_error!("启动DoH代理失败: {}", e);
+                return;
+            }
+        };
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = handle_connection(stream).await {
+                            log_warn!("DoH代理连接失败: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log_warn!("DoH代理接受连接失败: {}", e),
+            }
+        }
+    });
+
+    log_info!("DoH代理已启动: 127.0.0.1:{}", port);
+    *port_guard = Some(port);
+    Ok(port)
+}
+
+/// 获取下载时 aria2c 使用的代理地址 - 配置了 DoH 服务器时返回本地代理地址
+///
+/// # 返回值
+/// - 启用 DoH 时返回 Some("http://127.0.0.1:端口")，未启用或代理启动失败时返回None
+pub fn download_proxy() -> Option<String> {
+    doh_server()?;
+    match ensure_proxy() {
+        Ok(port) => Some(format!("http://127.0.0.1:{}", port)),
+        Err(e) => {
+            log_error!("{}，下载将使用系统DNS", e);
+            None
+        }
+    }
+}
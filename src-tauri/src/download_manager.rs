@@ -2,25 +2,35 @@
 
 // 标准库导入
 use std::{
-    fs,
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    collections::{HashMap, HashSet, VecDeque},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 // 第三方库导入
+use serde::Serialize;
 use serde_json;
-use tauri::{AppHandle, Emitter, Manager};
-use tauri_plugin_dialog::MessageDialogKind;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind};
+use tracing::Instrument;
 
 // 内部模块导入
 use crate::{
     aria2c::download_via_aria2,
+    cancellation::CancellationToken,
     commands::refresh_download_queue,
-    dialog_manager::{show_confirm_dialog, show_dialog},
+    dialog_manager::{show_choice_dialog, show_confirm_dialog, show_dialog},
     extract_manager::{start_extract_queue_manager, ExtractTask},
     init::is_app_shutting_down,
     log_debug, log_error, log_info, log_warn,
-    queue_manager::{process_queue, TaskQueue},
+    queue_manager::{
+        process_queue, JsonFileStore, QueueEntry, QueueStore, TaskPriority, TaskQueue, TaskState,
+    },
+    tr,
     utils::get_file_name,
 };
 
@@ -37,18 +47,475 @@ pub struct DownloadTask {
     pub savepath: Option<String>,
     /// 是否仅保存文件，不进行解压（默认false）
     pub saveonly: bool,
+    /// 已经失败的次数
+    #[serde(default)]
+    pub attempt: u32,
+    /// 最多尝试的次数（包括第一次），未指定时使用配置的 max_download_attempts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+}
+
+impl DownloadTask {
+    /// 任务最多尝试的次数
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+            .unwrap_or_else(|| crate::settings::current_settings().max_download_attempts)
+            .clamp(1, crate::settings::MAX_DOWNLOAD_ATTEMPTS_LIMIT)
+    }
+}
+
+/// 下载队列的任务分配条件 - 任务所在网站的进行中下载数达到 host_limits 的限制时暂缓开始
+fn has_host_capacity(task: &DownloadTask, active: &[&DownloadTask]) -> bool {
+    let active_urls = active
+        .iter()
+        .map(|task| task.url.as_str())
+        .collect::<Vec<_>>();
+    crate::download_policy::has_host_capacity(&task.url, &active_urls)
 }
 
 // 创建全局下载队列实例 - 使用lazy_static实现延迟初始化
 lazy_static::lazy_static! {
     pub static ref DOWNLOAD_QUEUE: Arc<Mutex<TaskQueue<DownloadTask>>> =
-        Arc::new(Mutex::new(TaskQueue::new(1)));
+        Arc::new(Mutex::new(TaskQueue::new(1).with_dispatch_filter(has_host_capacity)));
 
     // 添加全局HashMap来跟踪完整的活跃任务信息
     pub static ref ACTIVE_DOWNLOAD_TASKS: Arc<Mutex<std::collections::HashMap<String, DownloadTask>>> =
         Arc::new(Mutex::new(std::collections::HashMap::new()));
+
+    /// 活跃任务最近一次的下载进度（与 download-progress 事件内容相同）
+    static ref DOWNLOAD_PROGRESS: Mutex<std::collections::HashMap<String, serde_json::Value>> =
+        Mutex::new(std::collections::HashMap::new());
+
+    /// 等待合并发送的 download-progress 事件：任务ID -> 最新的事件内容
+    static ref PENDING_PROGRESS_EVENTS: Mutex<HashMap<String, serde_json::Value>> =
+        Mutex::new(HashMap::new());
+
+    /// 保护未完成下载记录文件的读写
+    static ref PARTIAL_DOWNLOADS_LOCK: Mutex<()> = Mutex::new(());
+
+    /// 正在被下载任务使用的临时文件名
+    static ref CLAIMED_PARTIAL_DOWNLOADS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+
+    /// 下载失败后正在等待重试的任务
+    static ref RETRY_PENDING_TASKS: Mutex<HashMap<String, DownloadTask>> = Mutex::new(HashMap::new());
+
+    /// 最近结束的下载任务，最新的在前
+    static ref FINISHED_DOWNLOADS: Mutex<VecDeque<FinishedDownload>> = Mutex::new(VecDeque::new());
+}
+
+/// 未完成下载记录文件名（URL到缓存目录中临时文件名的映射），与下载队列文件位于同一目录
+const PARTIAL_DOWNLOADS_FILE: &str = "partial_downloads.json";
+
+/// 保留的最近结束任务数
+const MAX_FINISHED_DOWNLOADS: usize = 20;
+
+/// 已结束的下载任务
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishedDownload {
+    /// 任务唯一标识符
+    pub id: String,
+    /// 下载的文件URL
+    pub url: String,
+    /// 文件名
+    pub filename: Option<String>,
+    /// 是否成功
+    pub success: bool,
+    /// 成功时为保存路径，失败时为错误信息
+    pub message: String,
+    /// 结束时间（Unix 时间戳，秒）
+    pub finished_at: i64,
+}
+
+/// 下载队列已满时拒绝添加任务的原因，序列化后作为错误详情返回给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueFull {
+    /// 等待任务数上限（max_waiting_downloads）
+    pub limit: u32,
+    /// 当前等待中的任务数
+    pub waiting: usize,
+    /// 本地化的说明
+    pub message: String,
+}
+
+impl fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 队列已满的提示对话框是否正在显示，一批链接被拒绝时只显示一个对话框
+static QUEUE_FULL_DIALOG_OPEN: AtomicBool = AtomicBool::new(false);
+
+/// 检查下载队列中等待的任务数是否已达到 max_waiting_downloads
+///
+/// # 参数
+/// - `queue`: 已锁定的下载队列
+///
+/// # 返回值
+/// - 还可以添加任务时返回Ok(())，已满时返回拒绝原因
+pub fn check_queue_capacity(queue: &TaskQueue<DownloadTask>) -> Result<(), QueueFull> {
+    let limit = crate::settings::current_settings().max_waiting_downloads;
+    let waiting = queue.waiting_tasks.len();
+    if limit == 0 || waiting < limit as usize {
+        return Ok(());
+    }
+    Err(QueueFull {
+        limit,
+        waiting,
+        message: tr!("error.queue_full", limit = limit),
+    })
+}
+
+/// 拒绝添加任务 - 提示用户队列已满（同一时间只显示一个对话框），返回给前端的错误
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+/// - `full`: 拒绝原因
+pub fn reject_queue_full(app_handle: &AppHandle, full: QueueFull) -> crate::error::AppError {
+    log_warn!(
+        "下载队列已满（{}/{}），拒绝添加任务",
+        full.waiting,
+        full.limit
+    );
+    crate::telemetry::record("install.queue_full");
+
+    if !QUEUE_FULL_DIALOG_OPEN.swap(true, Ordering::SeqCst) {
+        let app_handle = app_handle.clone();
+        let message = full.message.clone();
+        tauri::async_runtime::spawn(async move {
+            show_choice_dialog(
+                &app_handle,
+                &message,
+                &tr!("dialog.queue_full.title"),
+                MessageDialogButtons::Ok,
+            )
+            .await;
+            QUEUE_FULL_DIALOG_OPEN.store(false, Ordering::SeqCst);
+        });
+    }
+    crate::error::AppError::QueueFull(full)
+}
+
+/// install 命令的结果 - 任务加入下载队列后立即返回
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedDownload {
+    /// 下载任务ID，之后的事件和命令都使用这个ID
+    pub task_id: String,
+    /// 最终的下载地址（已跟随短链接和重定向）
+    pub url: String,
+    /// 文件名
+    pub filename: String,
+    /// 在等待队列中的位置（从1开始），已经开始下载时为0
+    pub position: usize,
+    /// 等待中和正在下载的任务总数
+    pub total_tasks: usize,
+}
+
+/// 下载队列完整状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueState {
+    /// 等待中的任务，按开始的顺序排列
+    pub waiting: Vec<serde_json::Value>,
+    /// 已暂停的任务
+    pub paused: Vec<serde_json::Value>,
+    /// 正在下载的任务，包含最近一次的GID、进度和速度
+    pub active: Vec<serde_json::Value>,
+    /// 下载失败后等待重试的任务
+    pub retrying: Vec<serde_json::Value>,
+    /// 最近结束的任务，最新的在前
+    pub finished: Vec<FinishedDownload>,
+}
+
+/// 记录活跃任务的最新下载进度，供 get_queue_state 使用
+///
+/// # 参数
+/// - `task_id`: 任务ID
+/// - `progress`: download-progress 事件内容
+pub fn record_download_progress(task_id: &str, progress: &serde_json::Value) {
+    if let Ok(mut progress_map) = DOWNLOAD_PROGRESS.lock() {
+        progress_map.insert(task_id.to_string(), progress.clone());
+    }
+}
+
+/// 发送下载进度事件 - 同一间隔内的多次更新合并为每个任务最新的一次，
+/// 按 progress_event_interval_ms 统一发送；进度达到100%时立即发送
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `task_id`: 任务ID
+/// - `payload`: download-progress 事件内容
+pub fn publish_download_progress(
+    app_handle: &AppHandle,
+    task_id: &str,
+    payload: serde_json::Value,
+) {
+    let mut pending = PENDING_PROGRESS_EVENTS.lock().unwrap();
+    if payload["progress"]
+        .as_f64()
+        .is_some_and(|progress| progress >= 100.0)
+    {
+        pending.remove(task_id);
+        let _ = crate::event_dispatcher::emit(app_handle, "download-progress", &payload);
+        drop(pending);
+        crate::downloads_window::publish_task_detail(app_handle, task_id);
+        return;
+    }
+    pending.insert(task_id.to_string(), payload);
+    drop(pending);
+
+    start_progress_flusher(app_handle);
+}
+
+/// 启动合并发送下载进度事件的线程（只启动一次）
+fn start_progress_flusher(app_handle: &AppHandle) {
+    static STARTED: std::sync::Once = std::sync::Once::new();
+    STARTED.call_once(|| {
+        let app_handle = app_handle.clone();
+        std::thread::spawn(move || {
+            while !is_app_shutting_down() {
+                std::thread::sleep(std::time::Duration::from_millis(
+                    crate::settings::current_settings().progress_event_interval_ms,
+                ));
+
+                // 发送期间持有锁，避免已结束任务的进度在完成事件之后才发送
+                let mut pending = PENDING_PROGRESS_EVENTS.lock().unwrap();
+                let mut task_ids = Vec::with_capacity(pending.len());
+                for (task_id, payload) in pending.drain() {
+                    if let Err(e) =
+                        crate::event_dispatcher::emit(&app_handle, "download-progress", &payload)
+                    {
+                        log_error!("[{}] 发送下载进度事件失败: {}", task_id, e);
+                    }
+                    task_ids.push(task_id);
+                }
+                drop(pending);
+
+                for task_id in task_ids {
+                    crate::downloads_window::publish_task_detail(&app_handle, &task_id);
+                }
+            }
+        });
+    });
+}
+
+/// 记录结束的下载任务
+fn record_finished_download(task: &DownloadTask, success: bool, message: &str) {
+    if let Ok(mut finished) = FINISHED_DOWNLOADS.lock() {
+        finished.push_front(FinishedDownload {
+            id: task.id.clone(),
+            url: task.url.clone(),
+            filename: task.filename.clone(),
+            success,
+            message: message.to_string(),
+            finished_at: chrono::Utc::now().timestamp(),
+        });
+        finished.truncate(MAX_FINISHED_DOWNLOADS);
+    }
+}
+
+/// 估算等待中任务的开始时间
+///
+/// 按最大并发数模拟下载槽位：每个槽位在进行中任务的剩余时间（eta）后空出，等待中的任务按顺序占用最早空出的槽位，
+/// 每个等待中任务的下载时间按进行中任务的平均总时间（已用时间 + 剩余时间）估算。
+/// 进行中的任务还没有速度时无法估算，对应的任务返回None。
+///
+/// # 参数
+/// - `queue`: 已锁定的下载队列
+///
+/// # 返回值
+/// - 与等待中任务顺序对应的预计开始时间（距现在的秒数）
+pub fn estimate_start_times(queue: &TaskQueue<DownloadTask>) -> Vec<Option<u64>> {
+    let progress_map = DOWNLOAD_PROGRESS
+        .lock()
+        .map(|map| map.clone())
+        .unwrap_or_default();
+
+    let mut remaining = Vec::new();
+    let mut durations = Vec::new();
+    for (_, task) in queue.tasks_in(TaskState::Active) {
+        let progress = progress_map.get(&task.id);
+        let field = |key: &str| progress.and_then(|p| p.get(key)).and_then(|v| v.as_u64());
+        if field("downloadSpeed").unwrap_or(0) == 0 {
+            return vec![None; queue.waiting_tasks.len()];
+        }
+        let eta = field("eta").unwrap_or(0);
+        remaining.push(eta);
+        durations.push(field("elapsedTime").unwrap_or(0) + eta);
+    }
+    let average =
+        (!durations.is_empty()).then(|| durations.iter().sum::<u64>() / durations.len() as u64);
+
+    // 并发数被调低时，超出的进行中任务完成前不会空出槽位
+    let slot_count = (queue.max_concurrent_tasks as usize).max(1);
+    remaining.sort_unstable();
+    let excess = remaining.len().saturating_sub(slot_count);
+    let mut slots: Vec<Option<u64>> = remaining.into_iter().skip(excess).map(Some).collect();
+    slots.resize(slot_count, Some(0));
+
+    queue
+        .tasks_in(TaskState::Waiting)
+        .map(|_| {
+            let (index, start) = slots
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, start)| start.unwrap_or(u64::MAX))
+                .map(|(index, start)| (index, *start))?;
+            slots[index] = start.zip(average).map(|(start, duration)| start + duration);
+            start
+        })
+        .collect()
+}
+
+/// 获取下载队列的完整状态 - 包括等待中、正在下载和最近结束的任务
+pub fn get_queue_state() -> QueueState {
+    let progress_map = DOWNLOAD_PROGRESS
+        .lock()
+        .map(|map| map.clone())
+        .unwrap_or_default();
+
+    let queue = DOWNLOAD_QUEUE.lock().unwrap();
+    let pending = |state| {
+        queue
+            .tasks_in(state)
+            .map(|(id, task)| {
+                serde_json::json!({
+                    "id": task.id,
+                    "url": task.url,
+                    "filename": task.filename,
+                    "priority": queue.priority(id),
+                })
+            })
+            .collect()
+    };
+    let mut waiting: Vec<serde_json::Value> = pending(TaskState::Waiting);
+    for (index, (task, start)) in waiting
+        .iter_mut()
+        .zip(estimate_start_times(&queue))
+        .enumerate()
+    {
+        task["position"] = (index + 1).into();
+        task["estimatedStart"] = start.into();
+    }
+    let paused = pending(TaskState::Paused);
+    let active = queue
+        .tasks_in(TaskState::Active)
+        .map(|(_, task)| {
+            let progress = progress_map.get(&task.id);
+            let field = |key: &str| {
+                progress
+                    .and_then(|p| p.get(key))
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null)
+            };
+            serde_json::json!({
+                "id": task.id,
+                "url": task.url,
+                "filename": task.filename,
+                "gid": field("gid"),
+                "progress": field("progress"),
+                "totalSize": field("totalSize"),
+                "completedSize": field("completedSize"),
+                "downloadSpeed": field("downloadSpeed"),
+                "eta": field("eta"),
+            })
+        })
+        .collect();
+    drop(queue);
+
+    let retrying = RETRY_PENDING_TASKS
+        .lock()
+        .map(|pending| {
+            pending
+                .values()
+                .map(|task| {
+                    serde_json::json!({
+                        "id": task.id,
+                        "url": task.url,
+                        "filename": task.filename,
+                        "attempt": task.attempt,
+                        "maxAttempts": task.max_attempts(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let finished = FINISHED_DOWNLOADS
+        .lock()
+        .map(|finished| finished.iter().cloned().collect())
+        .unwrap_or_default();
+
+    QueueState {
+        waiting,
+        paused,
+        active,
+        retrying,
+        finished,
+    }
 }
 
+/// 获取单个任务的完整信息，供下载列表窗口使用
+///
+/// 依次查找正在下载、等待重试、等待中（或已暂停）和最近结束的任务，每次只持有一个锁。
+///
+/// # 参数
+/// - `task_id`: 任务ID
+///
+/// # 返回值
+/// - 包含任务状态（active / retrying / waiting / paused / finished）、任务参数、最新进度和结束信息的JSON，
+///   找不到任务时返回None
+pub fn task_detail(task_id: &str) -> Option<serde_json::Value> {
+    let progress = DOWNLOAD_PROGRESS
+        .lock()
+        .ok()
+        .and_then(|map| map.get(task_id).cloned())
+        .unwrap_or(serde_json::Value::Null);
+
+    let active = ACTIVE_DOWNLOAD_TASKS
+        .lock()
+        .ok()
+        .and_then(|tasks| tasks.get(task_id).cloned());
+    let retrying = || RETRY_PENDING_TASKS.lock().unwrap().get(task_id).cloned();
+    let waiting = || {
+        let queue = DOWNLOAD_QUEUE.lock().unwrap();
+        let state = match queue.task_state(task_id) {
+            Some(TaskState::Paused) => "paused",
+            _ => "waiting",
+        };
+        queue.find_task(task_id).cloned().map(|task| (state, task))
+    };
+    let (state, task) = match active {
+        Some(task) => ("active", Some(task)),
+        None => match retrying() {
+            Some(task) => ("retrying", Some(task)),
+            None => match waiting() {
+                Some((state, task)) => (state, Some(task)),
+                None => ("finished", None),
+            },
+        },
+    };
+
+    let finished = FINISHED_DOWNLOADS
+        .lock()
+        .ok()
+        .and_then(|finished| finished.iter().find(|f| f.id == task_id).cloned());
+    if task.is_none() && finished.is_none() {
+        return None;
+    }
+
+    Some(serde_json::json!({
+        "taskId": task_id,
+        "state": state,
+        "task": task,
+        "maxAttempts": task.as_ref().map(|task| task.max_attempts()),
+        "progress": progress,
+        "finished": finished,
+    }))
+}
 /// 处理下载队列中的任务 - 持续监控队列并启动下载任务
 ///
 /// 此函数会持续运行，定期检查队列并根据最大并发任务数启动新的下载任务，支持多文件同时下载。
@@ -81,46 +548,54 @@ pub async fn process_download_queue(app_handle: AppHandle) {
 
         send_download_start_event(&app_clone, &task);
 
-        tauri::async_runtime::spawn(async move {
-            let result = download_and_extract(
-                &task_clone.url,
-                savepath.as_deref(),
-                saveonly,
-                app_clone.clone(),
-                &task_clone.id,
-            )
-            .await;
-
-            let message = match &result {
-                Ok(dir_path) => format!("{}", dir_path),
-                Err(e) => e.to_string(),
-            };
-
-            if result.is_ok() {
-                log_info!(
-                    "下载任务 [{}] 完成: {}, 解压将在后台进行",
-                    task_id,
-                    filename
-                );
-            } else {
-                log_error!(
-                    "下载任务 [{}] 失败: {}, 错误: {}",
-                    task_id,
-                    filename,
-                    message
-                );
-            }
-
-            if result.is_ok() {
-                send_download_complete_event(&app_clone, &task_clone, saveonly, &message);
-            } else {
-                send_download_failed_event(&app_clone, &task_clone, &message);
+        tauri::async_runtime::spawn(
+            async move {
+                let result = download_and_extract(
+                    &task_clone.url,
+                    savepath.as_deref(),
+                    saveonly,
+                    app_clone.clone(),
+                    &task_clone.id,
+                )
+                .await;
+
+                let message = match &result {
+                    Ok(dir_path) => dir_path.clone(),
+                    Err(e) => e.to_string(),
+                };
+
+                if result.is_ok() {
+                    log_info!(
+                        "下载任务 [{}] 完成: {}, 解压将在后台进行",
+                        task_id,
+                        filename
+                    );
+                } else {
+                    log_error!(
+                        "下载任务 [{}] 失败: {}, 错误: {}",
+                        task_id,
+                        filename,
+                        message
+                    );
+                }
+
+                remove_task_from_active_tasks(&task_id);
+
+                if result.is_ok() {
+                    record_finished_download(&task_clone, true, &message);
+                    send_download_complete_event(&app_clone, &task_clone, saveonly, &message);
+                } else if !schedule_download_retry(&app_clone, &task_clone, &message) {
+                    record_finished_download(&task_clone, false, &message);
+                    send_download_failed_event(&app_clone, &task_clone, &message);
+                }
+                if message != "用户取消下载" {
+                    crate::notifications::on_task_finished();
+                }
+
+                refresh_download_queue(app_clone.clone()).await.unwrap();
             }
-
-            remove_task_from_active_tasks(&task_id);
-
-            refresh_download_queue(app_clone.clone()).await.unwrap();
-        });
+            .instrument(tracing::info_span!("download", task_id = %task.id)),
+        );
     };
 
     // 获取任务ID的函数
@@ -153,6 +628,72 @@ fn add_task_to_active_tasks(task_id: &str, task: &DownloadTask) {
     );
 }
 
+/// 获取下载任务的取消令牌，任务已不在队列中时返回新的令牌
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+pub fn cancellation_token(task_id: &str) -> CancellationToken {
+    DOWNLOAD_QUEUE
+        .lock()
+        .ok()
+        .and_then(|queue| queue.cancellation_token(task_id))
+        .unwrap_or_default()
+}
+
+/// 获取活跃下载任务添加时确定的文件名（来自服务器响应或URL），没有有效文件名时返回None
+///
+/// # 参数
+/// - `task_id`: 下载任务ID
+pub fn active_task_file_name(task_id: &str) -> Option<String> {
+    ACTIVE_DOWNLOAD_TASKS
+        .lock()
+        .ok()?
+        .get(task_id)
+        .and_then(|task| task.filename.clone())
+        .filter(|filename| !filename.is_empty() && filename != "unknown")
+}
+
+/// 使用服务器提供的文件名更新任务，之后的事件和解压目录都使用新的文件名，并发送 download-filename-resolved 事件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `task_id`: 下载任务ID
+/// - `filename`: 新的文件名
+pub fn update_task_file_name(app_handle: &AppHandle, task_id: &str, filename: &str) {
+    let previous = {
+        let mut active_tasks = ACTIVE_DOWNLOAD_TASKS.lock().unwrap();
+        let Some(task) = active_tasks.get_mut(task_id) else {
+            return;
+        };
+        if task.filename.as_deref() == Some(filename) {
+            return;
+        }
+        task.filename.replace(filename.to_string())
+    };
+    if let Some(task) = DOWNLOAD_QUEUE.lock().unwrap().tasks.get_mut(task_id) {
+        task.filename = Some(filename.to_string());
+    }
+    log_info!(
+        "下载任务 [{}] 使用服务器提供的文件名: {} -> {}",
+        task_id,
+        previous.as_deref().unwrap_or("未知文件"),
+        filename
+    );
+
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
+        "download-filename-resolved",
+        &serde_json::json!(
+            {
+                "taskId": task_id,
+                "filename": filename,
+                "previousFilename": previous
+            }
+        ),
+    );
+    crate::downloads_window::publish_task_detail(app_handle, task_id);
+}
+
 // 从活跃集合移除任务
 fn remove_task_from_active_tasks(task_id: &str) {
     let mut queue = (&*DOWNLOAD_QUEUE).lock().unwrap();
@@ -173,12 +714,17 @@ fn remove_task_from_active_tasks(task_id: &str) {
         task_id,
         active_tasks.len()
     );
+
+    if let Ok(mut progress_map) = DOWNLOAD_PROGRESS.lock() {
+        progress_map.remove(task_id);
+    }
+    PENDING_PROGRESS_EVENTS.lock().unwrap().remove(task_id);
 }
 
 // 发送下载开始事件
 fn send_download_start_event(app_handle: &AppHandle, task: &DownloadTask) {
-    let _ = app_handle.emit_to(
-        "main",
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
         "download-task-start",
         &serde_json::json!(
             {
@@ -188,6 +734,7 @@ fn send_download_start_event(app_handle: &AppHandle, task: &DownloadTask) {
             }
         ),
     );
+    crate::downloads_window::publish_task_detail(app_handle, &task.id);
 }
 
 // 发送下载完成事件
@@ -197,8 +744,9 @@ fn send_download_complete_event(
     saveonly: bool,
     message: &str,
 ) {
-    let _ = app_handle.emit_to(
-        "main",
+    crate::telemetry::record("download.complete");
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
         "download-complete",
         &serde_json::json!(
             {
@@ -210,21 +758,155 @@ fn send_download_complete_event(
             }
         ),
     );
+    crate::downloads_window::publish_task_detail(app_handle, &task.id);
 }
 
 // 发送下载失败事件
 fn send_download_failed_event(app_handle: &AppHandle, task: &DownloadTask, message: &str) {
-    let _ = app_handle.emit_to(
-        "main",
+    crate::telemetry::record("download.failed");
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
         "download-failed",
         &serde_json::json!(
             {
                 "taskId": task.id,
                 "filename": task.filename.clone().unwrap_or("未知文件".to_string()),
-                "error": message
+                "error": message,
+                "attempt": task.attempt + 1,
+                "maxAttempts": task.max_attempts()
+            }
+        ),
+    );
+    crate::downloads_window::publish_task_detail(app_handle, &task.id);
+}
+
+/// 下载失败后是否还会重试 - 用户取消和应用关闭导致的失败不重试
+fn will_retry(task: &DownloadTask, error: &str) -> bool {
+    error != "用户取消下载" && !is_app_shutting_down() && task.attempt + 1 < task.max_attempts()
+}
+
+/// 第 attempt 次失败后的等待时间（指数退避）
+fn retry_delay(attempt: u32) -> std::time::Duration {
+    let settings = crate::settings::current_settings();
+    let delay = settings
+        .retry_backoff_seconds
+        .saturating_mul(1u64 << attempt.min(16))
+        .min(settings.max_retry_backoff_seconds);
+    std::time::Duration::from_secs(delay)
+}
+
+/// 下载失败后安排重试 - 等待退避时间后将任务重新加入下载队列，并发送 download-retrying 事件
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+/// - `task`: 失败的下载任务
+/// - `error`: 失败原因
+///
+/// # 返回值
+/// - 已安排重试时返回true，不再重试时返回false
+fn schedule_download_retry(app_handle: &AppHandle, task: &DownloadTask, error: &str) -> bool {
+    if !will_retry(task, error) {
+        return false;
+    }
+
+    let delay = retry_delay(task.attempt);
+    let mut retry_task = task.clone();
+    retry_task.attempt += 1;
+    let max_attempts = retry_task.max_attempts();
+    log_warn!(
+        "下载任务 [{}] 第 {}/{} 次尝试失败，{} 秒后重试",
+        task.id,
+        retry_task.attempt,
+        max_attempts,
+        delay.as_secs()
+    );
+
+    RETRY_PENDING_TASKS
+        .lock()
+        .unwrap()
+        .insert(task.id.clone(), retry_task.clone());
+
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
+        "download-retrying",
+        &serde_json::json!(
+            {
+                "taskId": task.id,
+                "filename": task.filename.clone().unwrap_or("未知文件".to_string()),
+                "error": error,
+                "attempt": retry_task.attempt + 1,
+                "maxAttempts": max_attempts,
+                "delay": delay.as_secs()
             }
         ),
     );
+    crate::downloads_window::publish_task_detail(app_handle, &task.id);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+
+        // 等待期间任务可能已被取消
+        let Some(task) = RETRY_PENDING_TASKS.lock().unwrap().remove(&retry_task.id) else {
+            return;
+        };
+        if is_app_shutting_down() {
+            return;
+        }
+
+        log_info!(
+            "下载任务 [{}] 开始第 {}/{} 次尝试",
+            task.id,
+            task.attempt + 1,
+            task.max_attempts()
+        );
+        requeue_download(&app_handle, task);
+        let _ = refresh_download_queue(app_handle).await;
+    });
+    true
+}
+
+/// 将任务重新加入下载队列，队列处理未运行时启动处理
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+/// - `task`: 要重新下载的任务
+pub fn requeue_download(app_handle: &AppHandle, task: DownloadTask) {
+    let should_start_processing = {
+        let mut queue = DOWNLOAD_QUEUE.lock().unwrap();
+        queue.add_task(task.id.clone(), task);
+        !queue.processing_started
+    };
+    if should_start_processing {
+        let app_handle_clone = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            process_download_queue(app_handle_clone).await;
+        });
+    }
+}
+
+/// 取消等待重试的下载任务
+///
+/// # 参数
+/// - `task_id`: 任务ID，为None时取消所有等待重试的任务
+///
+/// # 返回值
+/// - 被取消的任务数
+pub fn cancel_pending_retries(task_id: Option<&str>) -> usize {
+    let mut pending = RETRY_PENDING_TASKS.lock().unwrap();
+    match task_id {
+        Some(task_id) => pending.remove(task_id).map_or(0, |_| 1),
+        None => {
+            let count = pending.len();
+            pending.clear();
+            count
+        }
+    }
+}
+
+/// 是否有等待重试的下载任务
+pub fn has_pending_retries() -> bool {
+    !RETRY_PENDING_TASKS.lock().unwrap().is_empty()
 }
 
 /// 处理下载队列中的任务 - 持续监控队列并启动下载任务
@@ -260,126 +942,316 @@ pub fn get_download_queue_file_path() -> Result<PathBuf, String> {
     Ok(app_data_dir.join("download_queue.json"))
 }
 
-/// 保存下载队列到文件
-///
-/// 此函数将当前下载队列中的活跃任务和等待任务保存到文件，以便应用重启后能够恢复
-pub fn save_download_queue() -> Result<(), String> {
-    log_info!("开始保存下载队列...");
+/// 读取URL到临时文件名的映射
+fn read_partial_downloads() -> HashMap<String, String> {
+    get_download_queue_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path.with_file_name(PARTIAL_DOWNLOADS_FILE)).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
 
-    // 获取队列配置文件路径
-    let queue_file_path = get_download_queue_file_path()?;
+/// 写入URL到临时文件名的映射，映射为空时删除文件
+fn write_partial_downloads(partials: &HashMap<String, String>) {
+    let path = match get_download_queue_file_path() {
+        Ok(path) => path.with_file_name(PARTIAL_DOWNLOADS_FILE),
+        Err(e) => {
+            log_warn!("无法保存未完成下载记录: {}", e);
+            return;
+        }
+    };
 
-    // 获取下载队列的等待任务
-    let waiting_tasks = {
-        let queue = DOWNLOAD_QUEUE
-            .lock()
-            .map_err(|e| format!("无法获取下载队列锁: {:?}", e))?;
-        // 从tasks HashMap中获取完整的任务对象
-        queue
-            .waiting_tasks
-            .iter()
-            .filter_map(|task_id| queue.tasks.get(task_id).cloned())
-            .collect::<Vec<_>>()
+    let result = if partials.is_empty() {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    } else {
+        serde_json::to_string_pretty(partials)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()))
     };
+    if let Err(e) = result {
+        log_warn!("保存未完成下载记录失败: {}", e);
+    }
+}
 
-    // 获取完整的活跃任务信息
-    let active_tasks = {
-        let tasks = ACTIVE_DOWNLOAD_TASKS
-            .lock()
-            .map_err(|e| format!("无法获取活跃下载任务锁: {:?}", e))?;
-        tasks.values().cloned().collect::<Vec<_>>()
+/// 查找URL上次下载使用的临时文件名，用于继续未完成的下载
+///
+/// 只有缓存目录中仍存在该文件或其 .aria2 控制文件、且没有被其他正在进行的任务使用时才返回
+///
+/// # 参数
+/// - `url`: 下载URL
+/// - `downloads_dir`: 下载缓存目录
+///
+/// # 返回值
+/// - 可以继续使用的临时文件名
+pub fn claim_partial_download(url: &str, downloads_dir: &Path) -> Option<String> {
+    let _guard = PARTIAL_DOWNLOADS_LOCK.lock().ok()?;
+    let filename = read_partial_downloads().remove(url)?;
+
+    let exists = downloads_dir.join(&filename).exists()
+        || downloads_dir.join(format!("{}.aria2", filename)).exists();
+    let mut claimed = CLAIMED_PARTIAL_DOWNLOADS.lock().ok()?;
+    if exists && claimed.insert(filename.clone()) {
+        Some(filename)
+    } else {
+        None
+    }
+}
+
+/// 使用指定的临时文件名继续下载（应用重启前同一任务使用的文件）并记录
+///
+/// # 参数
+/// - `url`: 下载URL
+/// - `filename`: 临时文件名
+/// - `downloads_dir`: 下载缓存目录
+///
+/// # 返回值
+/// - 文件或其 .aria2 控制文件仍存在、且没有被其他正在进行的任务使用时返回文件名
+pub fn claim_download_file(url: &str, filename: &str, downloads_dir: &Path) -> Option<String> {
+    let _guard = PARTIAL_DOWNLOADS_LOCK.lock().ok()?;
+    let exists = downloads_dir.join(filename).exists()
+        || downloads_dir.join(format!("{}.aria2", filename)).exists();
+    let mut claimed = CLAIMED_PARTIAL_DOWNLOADS.lock().ok()?;
+    if !exists || !claimed.insert(filename.to_string()) {
+        return None;
+    }
+
+    let mut partials = read_partial_downloads();
+    partials.insert(url.to_string(), filename.to_string());
+    write_partial_downloads(&partials);
+    Some(filename.to_string())
+}
+
+/// 为下载分配缓存目录中的文件名并记录，应用重启后重新排队的任务可以继续下载同一个文件
+///
+/// 已存在同名文件（或 .aria2 控制文件）、或文件名已被其他任务使用时，在扩展名前追加 " (n)" 去重
+///
+/// # 参数
+/// - `url`: 下载URL
+/// - `downloads_dir`: 下载缓存目录
+/// - `preferred`: 期望使用的文件名
+///
+/// # 返回值
+/// - 实际使用的文件名
+pub fn reserve_download_filename(url: &str, downloads_dir: &Path, preferred: &str) -> String {
+    let _guard = PARTIAL_DOWNLOADS_LOCK.lock();
+    let mut partials = read_partial_downloads();
+    let mut claimed = CLAIMED_PARTIAL_DOWNLOADS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let preferred_path = Path::new(preferred);
+    let stem = preferred_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| preferred.to_string());
+    let extension = preferred_path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    // 缓存目录在不区分大小写的文件系统上，只有大小写不同的文件名也视为已被使用
+    let is_taken = |name: &str| {
+        claimed
+            .iter()
+            .any(|filename| crate::utils::same_entry_name(filename, name))
+            || partials
+                .values()
+                .any(|filename| crate::utils::same_entry_name(filename, name))
+            || downloads_dir.join(name).exists()
+            || downloads_dir.join(format!("{}.aria2", name)).exists()
     };
+    let filename = (1..)
+        .map(|n| match n {
+            1 => preferred.to_string(),
+            n => format!("{} ({}){}", stem, n, extension),
+        })
+        .find(|name| !is_taken(name))
+        .unwrap_or_else(|| preferred.to_string());
 
-    // 创建一个包含所有任务的统一数组，active任务放在前面
-    let mut tasks = Vec::new();
-    tasks.extend(active_tasks.clone()); // 先添加活跃任务
-    tasks.extend(waiting_tasks); // 再添加等待任务
+    partials.insert(url.to_string(), filename.clone());
+    write_partial_downloads(&partials);
+    claimed.insert(filename.clone());
+    filename
+}
 
-    // 创建一个只包含tasks字段的结构体
-    #[derive(serde::Serialize)]
-    struct SavedQueue {
-        tasks: Vec<DownloadTask>,
+/// 下载结束（完成、失败或取消）后删除URL的临时文件名记录和校验值记录
+///
+/// # 参数
+/// - `url`: 下载URL
+pub fn forget_partial_download(url: &str) {
+    if let Ok(_guard) = PARTIAL_DOWNLOADS_LOCK.lock() {
+        let mut partials = read_partial_downloads();
+        if let Some(filename) = partials.remove(url) {
+            write_partial_downloads(&partials);
+            if let Ok(mut claimed) = CLAIMED_PARTIAL_DOWNLOADS.lock() {
+                claimed.remove(&filename);
+            }
+        }
     }
+    crate::resume_validator::forget(url);
+}
 
-    let saved_queue = SavedQueue { tasks };
+/// 获取所有需要保留的未完成下载文件名（包括 .aria2 控制文件），按保留天数清理缓存目录时会跳过这些文件
+pub fn partial_download_files() -> HashSet<String> {
+    let _guard = PARTIAL_DOWNLOADS_LOCK.lock();
+    read_partial_downloads()
+        .into_values()
+        .flat_map(|filename| [format!("{}.aria2", filename), filename])
+        .collect()
+}
 
-    // 如果没有任务，则不创建配置文件
-    if saved_queue.tasks.is_empty() {
-        // 如果配置文件存在，则删除它
-        if queue_file_path.exists() {
-            if let Err(e) = fs::remove_file(&queue_file_path) {
-                log_warn!("无法删除空的下载队列配置文件: {:?}", e);
+/// 删除不再属于任何排队任务的未完成下载记录及其文件
+fn prune_partial_downloads(tasks: &[DownloadTask]) {
+    let _guard = PARTIAL_DOWNLOADS_LOCK.lock();
+    let mut partials = read_partial_downloads();
+    let downloads_dir = crate::dir_manager::get_global_cache_dir().ok();
+
+    partials.retain(|url, filename| {
+        let keep = tasks.iter().any(|task| &task.url == url);
+        if !keep {
+            log_info!("删除不再需要的未完成下载: {}", filename);
+            if let Some(dir) = &downloads_dir {
+                let _ = fs::remove_file(dir.join(&*filename));
+                let _ = fs::remove_file(dir.join(format!("{}.aria2", filename)));
             }
         }
+        keep
+    });
+    write_partial_downloads(&partials);
+    crate::resume_validator::retain(|url| partials.contains_key(url));
+}
+
+/// 保存的下载任务 - 正在下载的任务同时记录 aria2c 中的 GID 和临时文件名，应用重启后继续同一个下载
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedTask {
+    #[serde(flatten)]
+    task: DownloadTask,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    temp_filename: Option<String>,
+}
+
+/// 保存下载队列到文件
+///
+/// 此函数将当前下载队列中的活跃任务、等待任务和暂停的任务（包括优先级）保存到文件，以便应用重启后能够恢复
+pub fn save_download_queue() -> Result<(), String> {
+    log_info!("开始保存下载队列...");
+
+    // 获取队列配置文件路径
+    let store = JsonFileStore {
+        path: get_download_queue_file_path()?,
+    };
+
+    // 队列中的任务，活跃任务在前
+    let mut entries = DOWNLOAD_QUEUE
+        .lock()
+        .map_err(|e| format!("无法获取下载队列锁: {:?}", e))?
+        .entries();
+
+    // 等待重试的任务保存为等待中，重启后重新下载
+    entries.extend(
+        RETRY_PENDING_TASKS
+            .lock()
+            .map_err(|e| format!("无法获取重试任务锁: {:?}", e))?
+            .values()
+            .cloned()
+            .map(|task| QueueEntry {
+                task,
+                state: TaskState::Waiting,
+                priority: TaskPriority::Normal,
+            }),
+    );
+
+    let active_count = entries
+        .iter()
+        .filter(|entry| entry.state == TaskState::Active)
+        .count();
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            entry.map(|task| {
+                let (gid, temp_filename) = crate::aria2c::transfer_state(&task.id).unzip();
+                SavedTask {
+                    task,
+                    gid,
+                    temp_filename,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // 没有任务时删除配置文件
+    store.save(&entries)?;
+    if entries.is_empty() {
         log_info!("下载队列为空，无需保存");
         return Ok(());
     }
 
-    // 将队列数据序列化为JSON
-    let json_data = serde_json::to_string_pretty(&saved_queue)
-        .map_err(|e| format!("无法序列化下载队列: {:?}", e))?;
-
-    // 写入文件
-    fs::write(&queue_file_path, json_data)
-        .map_err(|e| format!("无法写入下载队列配置文件: {:?}", e))?;
-
     log_info!(
         "下载队列已成功保存到: {}, 总任务数: {}, 活跃任务数: {}",
-        queue_file_path.to_string_lossy(),
-        saved_queue.tasks.len(),
-        active_tasks.len()
+        store.path.to_string_lossy(),
+        entries.len(),
+        active_count
     );
     Ok(())
 }
 
 /// 从文件加载下载队列
 ///
-/// 此函数在应用启动时调用，尝试从文件恢复之前的下载队列
+/// 此函数在应用启动时调用，尝试从文件恢复之前的下载队列，上次正在下载的任务重新排队
 pub fn load_download_queue() -> Result<(), String> {
     log_info!("开始加载下载队列...");
 
-    // 获取队列配置文件路径
-    let queue_file_path = get_download_queue_file_path()?;
-
-    // 检查配置文件是否存在
-    if !queue_file_path.exists() {
-        log_info!("下载队列配置文件不存在，无需加载");
-        return Ok(());
-    }
-
-    // 读取配置文件
-    let json_data = fs::read_to_string(&queue_file_path)
-        .map_err(|e| format!("无法读取下载队列配置文件: {:?}", e))?;
+    // 读取配置文件，文件不存在时为空
+    let store = JsonFileStore {
+        path: get_download_queue_file_path()?,
+    };
+    let entries: Vec<QueueEntry<SavedTask>> = store.load()?;
 
-    // 定义队列结构 - 只包含一个tasks字段
-    #[derive(serde::Deserialize)]
-    struct SavedQueue {
-        tasks: Vec<DownloadTask>,
-    }
+    log_info!("成功加载下载队列: 总任务数={}", entries.len());
 
-    // 反序列化为新格式，不支持旧格式
-    let saved_queue: SavedQueue =
-        serde_json::from_str(&json_data).map_err(|e| format!("无法反序列化下载队列: {:?}", e))?;
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            entry.map(|saved| {
+                crate::aria2c::restore_transfer(&saved.task.id, saved.gid, saved.temp_filename);
+                saved.task
+            })
+        })
+        .collect::<Vec<_>>();
 
-    log_info!("成功加载下载队列: 总任务数={}", saved_queue.tasks.len());
+    // 只保留仍在队列中的任务的未完成下载
+    let tasks = entries
+        .iter()
+        .map(|entry| entry.task.clone())
+        .collect::<Vec<_>>();
+    prune_partial_downloads(&tasks);
 
     // 将任务添加到下载队列
-    if !saved_queue.tasks.is_empty() {
+    if !entries.is_empty() {
         let mut queue = DOWNLOAD_QUEUE
             .lock()
             .map_err(|e| format!("无法获取下载队列锁: {:?}", e))?;
 
-        // 添加所有任务
-        for task in saved_queue.tasks {
-            queue.add_task(task.id.clone(), task);
-        }
+        // 添加所有任务，保留优先级和暂停状态
+        queue.restore(
+            entries
+                .into_iter()
+                .map(|entry| (entry.task.id.clone(), entry))
+                .collect(),
+        );
 
         let loaded_tasks_count = queue.waiting_tasks.len();
 
         log_info!(
-            "成功加载下载队列: 等待任务数={}, 总计需要重新下载的任务数={}",
+            "成功加载下载队列: 等待任务数={}, 暂停任务数={}",
             loaded_tasks_count,
-            loaded_tasks_count
+            queue.paused_tasks.len()
         );
     } else {
         log_info!("加载的下载队列为空");
@@ -404,50 +1276,67 @@ pub fn process_download() -> Result<(), String> {
     .clone();
 
     tauri::async_runtime::spawn(async move {
-        let mut queue = DOWNLOAD_QUEUE.lock().unwrap();
-        let has_pending_tasks = { queue.waiting_tasks.is_empty() } == false;
-        if has_pending_tasks {
-            let tasklist = queue
+        // 上次没有正常退出时改为询问是否恢复会话：重新打开窗口并继续下载队列
+        let session = crate::session::take_unclean_session();
+
+        // 只在生成对话框内容时持有队列锁，等待用户选择期间不阻塞队列
+        let tasks = {
+            let queue = DOWNLOAD_QUEUE.lock().unwrap();
+            queue
                 .waiting_tasks
                 .iter()
                 .filter_map(|id| queue.find_task(id))
-                .collect::<Vec<_>>();
-            let should_continue = show_confirm_dialog(
-                &app_handle,
-                tasklist
-                    .iter()
-                    .map(|task| {
-                        format!(
-                            "[{}] {} -> {}",
-                            task.saveonly.then_some("只存").unwrap_or("安装"),
-                            task.filename.as_deref().unwrap_or("未知文件"),
-                            task.savepath
-                                .as_ref()
-                                .filter(|p| !p.is_empty())
-                                .map(|p| p.as_str())
-                                .unwrap_or("不存")
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n")
-                    .as_str(),
-                "要继续上次未完成的任务吗?",
-            );
-            let tasks = tasklist
-                .into_iter()
-                .map(|task| (task.id.clone(), task.clone()))
-                .collect();
-            if should_continue {
-                log_info!("用户选择继续上次未完成的下载任务");
-                queue.replace_tasks(tasks);
-                tauri::async_runtime::spawn(async move {
-                    log_debug!("下载队列处理线程已创建，准备开始处理队列");
-                    refresh_download_queue(app_handle.clone()).await.ok();
-                    process_download_queue(app_handle).await;
-                });
-            } else {
-                queue.clear_tasks();
+                .map(|task| {
+                    format!(
+                        "[{}] {} -> {}",
+                        if task.saveonly {
+                            tr!("dialog.resume_tasks.save_only")
+                        } else {
+                            tr!("dialog.resume_tasks.install")
+                        },
+                        task.filename
+                            .clone()
+                            .unwrap_or_else(|| tr!("common.unknown_file")),
+                        task.savepath
+                            .clone()
+                            .filter(|p| !p.is_empty())
+                            .unwrap_or_else(|| tr!("dialog.resume_tasks.not_saved"))
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let (message, title) = match &session {
+            Some(session) if !tasks.is_empty() || !session.open_windows.is_empty() => {
+                let mut lines = vec![tr!("dialog.restore_session.message")];
+                if !session.open_windows.is_empty() {
+                    lines.push(tr!(
+                        "dialog.restore_session.windows",
+                        windows = session.open_windows.join(", ")
+                    ));
+                }
+                lines.extend(tasks.iter().cloned());
+                (lines.join("\n"), tr!("dialog.restore_session.title"))
+            }
+            _ if !tasks.is_empty() => (tasks.join("\n"), tr!("dialog.resume_tasks.title")),
+            _ => return,
+        };
+
+        let should_continue = show_confirm_dialog(&app_handle, &message, &title).await;
+        if should_continue {
+            if let Some(session) = &session {
+                log_info!("用户选择恢复上次意外退出前的会话");
+                crate::session::restore_windows(&app_handle, session);
+            }
+            if tasks.is_empty() {
+                return;
             }
+            log_info!("用户选择继续上次未完成的下载任务");
+            log_debug!("下载队列处理线程已创建，准备开始处理队列");
+            refresh_download_queue(app_handle.clone()).await.ok();
+            process_download_queue(app_handle).await;
+        } else {
+            DOWNLOAD_QUEUE.lock().unwrap().clear_tasks();
         }
     });
     Ok(())
@@ -475,19 +1364,45 @@ pub async fn download_and_extract(
 ) -> Result<String, String> {
     log_info!("开始下载文件 [{}]: URL={}", task_id, url);
 
+    // 之前下载过的压缩包仍在缓存中时直接解压，不重新下载
+    let cached = crate::archive_cache::lookup(url);
+    let from_cache = cached.is_some();
+
     // 下载文件（异步等待）
-    log_info!("[{}] 开始调用download_via_aria2函数进行下载", task_id);
-    let file_path = match download_via_aria2(url, app_handle.clone(), task_id).await {
+    let result = match cached {
+        Some(path) => Ok(path.to_string_lossy().to_string()),
+        None => {
+            log_info!("[{}] 开始调用download_via_aria2函数进行下载", task_id);
+            download_via_aria2(url, app_handle.clone(), task_id).await
+        }
+    };
+
+    // 应用退出导致的失败保留临时文件，重启后重新排队的任务会继续下载
+    if result.is_ok() || !is_app_shutting_down() {
+        forget_partial_download(url);
+    }
+
+    let file_path = match result {
         Ok(path) => {
             log_info!("文件下载成功 [{}]: 保存路径={}", task_id, path);
             path
         }
         Err(err) => {
             log_error!("文件下载失败 [{}]: 错误={}", task_id, err);
-            // 检查是否是用户取消下载
-            if err != "用户取消下载" {
+            // 用户取消下载和还会自动重试的失败不显示对话框
+            let retrying = ACTIVE_DOWNLOAD_TASKS
+                .lock()
+                .ok()
+                .and_then(|tasks| tasks.get(task_id).map(|task| will_retry(task, &err)))
+                .unwrap_or(false);
+            if err != "用户取消下载" && !retrying {
                 // 只对真正的错误显示对话框
-                show_dialog(&app_handle, &err, MessageDialogKind::Error, "下载失败");
+                show_dialog(
+                    &app_handle,
+                    &err,
+                    MessageDialogKind::Error,
+                    &tr!("dialog.download_failed.title"),
+                );
             }
             return Err(err);
         }
@@ -500,6 +1415,26 @@ pub async fn download_and_extract(
 
     if let Some(savepath) = savepath {
         if !savepath.is_empty() {
+            // 只保存且下载目录与保存位置在同一个卷时直接重命名，避免复制大文件
+            let moved = saveonly
+                && !from_cache
+                && crate::dir_manager::is_same_volume(
+                    std::path::Path::new(&file_path),
+                    std::path::Path::new(savepath),
+                )
+                && fs::rename(&file_path, savepath)
+                    .map_err(|e| log_warn!("[{}] 重命名文件失败，改为复制: {:?}", task_id, e))
+                    .is_ok();
+            if moved {
+                log_info!(
+                    "文件已移动到保存位置 [{}]: 源路径={}, 保存路径={}",
+                    task_id,
+                    file_path,
+                    savepath
+                );
+                return Ok(savepath.to_string());
+            }
+
             log_info!(
                 "开始复制文件 [{}]: 源路径={}, 目标路径={}",
                 task_id,
@@ -514,15 +1449,21 @@ pub async fn download_and_extract(
                 saveonly
             );
             if saveonly {
-                fs::remove_file(&file_path).map_err(|e| format!("删除临时文件失败: {:?}", e))?;
+                if !from_cache {
+                    fs::remove_file(&file_path)
+                        .map_err(|e| format!("删除临时文件失败: {:?}", e))?;
+                }
                 return Ok(savepath.to_string());
             }
         }
     }
 
     // 创建解压任务并添加到解压队列
-    // 从URL中提取文件名，然后提取压缩包名称（不含扩展名）
-    let archive_name = get_file_name(url)
+    // 使用任务的文件名（没有时从URL中提取），然后提取压缩包名称（不含扩展名）
+    // 文件名可能来自服务器响应，先处理为可以在 Windows 上使用的形式，与下载文件名的处理一致
+    let archive_name = active_task_file_name(task_id)
+        .or_else(|| get_file_name(url))
+        .and_then(|filename| crate::utils::sanitize_file_name(&filename))
         .and_then(|filename| {
             std::path::Path::new(&filename)
                 .file_stem()
@@ -538,6 +1479,10 @@ pub async fn download_and_extract(
         app_handle: app_handle.clone(),
         download_task_id: task_id.to_string(),
         is_dragged_file: false,
+        source_task: ACTIVE_DOWNLOAD_TASKS
+            .lock()
+            .ok()
+            .and_then(|tasks| tasks.get(task_id).cloned()),
     };
 
     let extract_task_id = extract_task.id.clone();
@@ -587,7 +1532,7 @@ pub async fn download_and_extract(
             .lock()
             .unwrap();
         if !queue.waiting_tasks.is_empty() && queue.active_tasks.is_empty() {
-            log_warn!("解压队列有任务但无活跃任务，这由QueueManager自动处理");
+            log_warn!("解压队列有任务但无活跃任务，处理循环停止时由队列看门狗重新启动");
         }
     };
 
@@ -0,0 +1,256 @@
+// download_policy.rs 模块 - 下载策略：添加下载任务前按配置的网站白名单/黑名单和文件大小上限检查，
+// 不符合策略的链接不会加入队列，返回包含规则和原因的 PolicyRejection
+//
+// 适用于网吧等需要限制下载来源和大小的场景。策略来自 config.json 的 allowed_hosts、denied_hosts
+// 和 max_download_size_mb，均未配置时不做任何检查。
+//
+// host_limits 按网站限制同时进行的下载数和每个下载的连接数，由下载队列分配任务和添加 aria2c 任务时使用。
+
+// 标准库导入
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+// 第三方库导入
+use reqwest::header;
+use serde::Serialize;
+use tauri::Url;
+
+// 内部模块导入
+use crate::{host_adapter, log_debug, log_warn, settings::HostLimit, tr};
+
+/// aria2c 每个服务器的最大连接数上限
+const MAX_CONNECTIONS_PER_SERVER: u32 = 16;
+
+/// 获取文件大小的请求超时时间
+const SIZE_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 被策略拒绝的规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRule {
+    /// 网站在黑名单中
+    HostDenied,
+    /// 配置了白名单且网站不在白名单中
+    HostNotAllowed,
+    /// 文件超过大小上限
+    FileTooLarge,
+}
+
+/// 下载请求被策略拒绝的原因，序列化后作为错误详情返回给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRejection {
+    /// 拒绝的规则
+    pub rule: PolicyRule,
+    /// 下载链接
+    pub url: String,
+    /// 链接的主机名
+    pub host: Option<String>,
+    /// 匹配的黑名单项或白名单（host_denied / host_not_allowed）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub matched: Vec<String>,
+    /// 文件大小（字节，file_too_large）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// 文件大小上限（MB，file_too_large）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_mb: Option<u64>,
+    /// 本地化的说明
+    pub message: String,
+}
+
+impl fmt::Display for PolicyRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 主机名是否匹配配置中的域名（包括其子域名），配置项可以带前导的 "."
+fn matches_host(url: &Url, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+    !domain.is_empty() && host_adapter::is_host(url, &domain)
+}
+
+/// 检查链接的网站是否符合白名单和黑名单
+fn check_host(
+    url: &Url,
+    allowed_hosts: &[String],
+    denied_hosts: &[String],
+) -> Result<(), Box<PolicyRejection>> {
+    let host = url.host_str().unwrap_or_default().to_string();
+    let rejection = |rule, matched: Vec<String>, message| {
+        Box::new(PolicyRejection {
+            rule,
+            url: url.to_string(),
+            host: Some(host.clone()),
+            matched,
+            size: None,
+            limit_mb: None,
+            message,
+        })
+    };
+
+    let denied = denied_hosts
+        .iter()
+        .filter(|domain| matches_host(url, domain))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !denied.is_empty() {
+        return Err(rejection(
+            PolicyRule::HostDenied,
+            denied,
+            tr!("error.policy_host_denied", host = &host),
+        ));
+    }
+
+    if !allowed_hosts.is_empty() && !allowed_hosts.iter().any(|domain| matches_host(url, domain)) {
+        return Err(rejection(
+            PolicyRule::HostNotAllowed,
+            allowed_hosts.to_vec(),
+            tr!("error.policy_host_not_allowed", host = &host),
+        ));
+    }
+    Ok(())
+}
+
+/// 请求文件的第一个字节获取文件大小，优先使用 Content-Range 中的总大小
+pub(crate) async fn fetch_file_size(url: &str) -> Result<Option<u64>, String> {
+    let (user_agent, headers) = host_adapter::request_options(url);
+    let client = crate::cert_pinning::client_builder()
+        .timeout(SIZE_REQUEST_TIMEOUT)
+        .user_agent(user_agent.unwrap_or(crate::aria2c::DEFAULT_USER_AGENT))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+    let mut request = client.get(url).header(header::RANGE, "bytes=0-0");
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    crate::cert_pinning::verify_response(&response)?;
+
+    let total_from_range = response
+        .headers()
+        .get(header::CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit('/').next())
+        .and_then(|total| total.trim().parse::<u64>().ok());
+    // 服务器不支持范围请求时返回完整文件，Content-Length 即文件大小
+    let length = (response.status() == reqwest::StatusCode::OK)
+        .then(|| response.content_length())
+        .flatten();
+    Ok(total_from_range.or(length))
+}
+
+/// 添加下载任务前检查链接是否符合下载策略
+///
+/// 无法获取文件大小时不做大小检查，下载可以继续。
+///
+/// # 参数
+/// - `url`: 解析后的下载链接
+///
+/// # 返回值
+/// - 符合策略时返回Ok，否则返回拒绝原因
+pub async fn check(url: &str) -> Result<(), Box<PolicyRejection>> {
+    let settings = crate::settings::current_settings();
+    if settings.allowed_hosts.is_empty()
+        && settings.denied_hosts.is_empty()
+        && settings.max_download_size_mb == 0
+    {
+        return Ok(());
+    }
+
+    let parsed = Url::parse(url).map_err(|e| {
+        Box::new(PolicyRejection {
+            rule: PolicyRule::HostNotAllowed,
+            url: url.to_string(),
+            host: None,
+            matched: Vec::new(),
+            size: None,
+            limit_mb: None,
+            message: tr!("error.invalid_url", error = e),
+        })
+    })?;
+    check_host(&parsed, &settings.allowed_hosts, &settings.denied_hosts)?;
+
+    let limit_mb = settings.max_download_size_mb;
+    if limit_mb == 0 {
+        return Ok(());
+    }
+    let size = match fetch_file_size(url).await {
+        Ok(Some(size)) => size,
+        Ok(None) => {
+            log_debug!("服务器未返回文件大小，跳过大小检查: {}", url);
+            return Ok(());
+        }
+        Err(e) => {
+            log_warn!("获取文件大小失败，跳过大小检查: {}, 错误: {}", url, e);
+            return Ok(());
+        }
+    };
+    if size > limit_mb.saturating_mul(1024 * 1024) {
+        return Err(Box::new(PolicyRejection {
+            rule: PolicyRule::FileTooLarge,
+            url: url.to_string(),
+            host: parsed.host_str().map(str::to_string),
+            matched: Vec::new(),
+            size: Some(size),
+            limit_mb: Some(limit_mb),
+            message: tr!(
+                "error.policy_file_too_large",
+                size = format!("{:.1}", size as f64 / 1024.0 / 1024.0),
+                limit = limit_mb
+            ),
+        }));
+    }
+    Ok(())
+}
+
+/// 查找链接所在网站的限制，多个域名匹配时使用最长（最具体）的一个
+///
+/// # 返回值
+/// - (配置中的域名, 限制)，没有配置时返回None
+fn host_limit(url: &str, limits: &BTreeMap<String, HostLimit>) -> Option<(String, HostLimit)> {
+    let parsed = Url::parse(url).ok()?;
+    limits
+        .iter()
+        .filter(|(domain, _)| matches_host(&parsed, domain))
+        .max_by_key(|(domain, _)| domain.trim().trim_start_matches('.').len())
+        .map(|(domain, limit)| (domain.clone(), *limit))
+}
+
+/// 下载队列是否可以开始该任务 - 任务所在网站的进行中下载数达到 host_limits 的 max_downloads 时暂缓，
+/// 队列先开始其他网站的任务
+///
+/// # 参数
+/// - `url`: 等待中任务的下载链接
+/// - `active_urls`: 进行中任务的下载链接
+pub fn has_host_capacity(url: &str, active_urls: &[&str]) -> bool {
+    let settings = crate::settings::current_settings();
+    let Some((domain, limit)) = host_limit(url, &settings.host_limits) else {
+        return true;
+    };
+    if limit.max_downloads == 0 {
+        return true;
+    }
+    let active = active_urls
+        .iter()
+        .filter(|active_url| {
+            host_limit(active_url, &settings.host_limits).is_some_and(|(d, _)| d == domain)
+        })
+        .count();
+    active < limit.max_downloads as usize
+}
+
+/// 下载该链接时每个服务器的最大连接数，按 host_limits 的 max_connections 限制
+///
+/// # 参数
+/// - `url`: 下载链接
+pub fn max_connections(url: &str) -> u32 {
+    let settings = crate::settings::current_settings();
+    match host_limit(url, &settings.host_limits) {
+        Some((_, limit)) if limit.max_connections > 0 => {
+            limit.max_connections.min(MAX_CONNECTIONS_PER_SERVER)
+        }
+        _ => MAX_CONNECTIONS_PER_SERVER,
+    }
+}
@@ -0,0 +1,44 @@
+// downloads_window.rs 模块 - 下载列表窗口：向该窗口发送包含完整任务信息的 download-task-detail 事件
+//
+// 主窗口只需要处理轻量的队列事件，每个任务的完整信息（任务参数、最新进度、重试和结束状态）
+// 只发送到下载列表窗口，窗口隐藏时不发送。
+
+// 第三方库导入
+use tauri::{AppHandle, Emitter, Manager};
+
+// 内部模块导入
+use crate::log_error;
+
+/// 下载列表窗口标签
+pub const DOWNLOADS_WINDOW: &str = "downloads";
+
+/// 向下载列表窗口发送任务详情事件
+///
+/// 调用方可能持有下载队列的锁，任务详情在后台任务中读取和发送。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `task_id`: 任务ID
+pub fn publish_task_detail(app_handle: &AppHandle, task_id: &str) {
+    let visible = app_handle
+        .get_webview_window(DOWNLOADS_WINDOW)
+        .is_some_and(|window| window.is_visible().unwrap_or(false));
+    if !visible {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    let task_id = task_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        // 任务已被取消或移出记录时发送 removed 状态，窗口据此移除任务
+        let detail = crate::download_manager::task_detail(&task_id).unwrap_or_else(|| {
+            serde_json::json!({
+                "taskId": task_id,
+                "state": "removed",
+            })
+        });
+        if let Err(e) = app_handle.emit_to(DOWNLOADS_WINDOW, "download-task-detail", &detail) {
+            log_error!("[{}] 发送任务详情事件失败: {}", task_id, e);
+        }
+    });
+}
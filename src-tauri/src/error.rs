@@ -0,0 +1,142 @@
+// error.rs 模块 - 定义应用统一的错误类型，命令返回给前端时序列化为 { code, message, details }
+
+// 第三方库导入
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+/// 应用错误类型 - 每个变体对应一个稳定的错误代码，供前端判断错误类别和本地化
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    /// 尚未配置必要的设置（例如数据目录、addons 目录）
+    #[error("{0}")]
+    NotConfigured(String),
+    /// 请求的文件、目录、窗口或任务不存在
+    #[error("{0}")]
+    NotFound(String),
+    /// 参数不合法
+    #[error("{0}")]
+    InvalidInput(String),
+    /// 目标正忙，当前无法执行操作
+    #[error("{0}")]
+    Busy(String),
+    /// 用户取消了操作
+    #[error("{0}")]
+    Cancelled(String),
+    /// 无法获取全局状态锁
+    #[error("{0}")]
+    Lock(String),
+    /// 文件或目录操作失败
+    #[error("{0}")]
+    FileOperation(String),
+    /// 符号链接（挂载/卸载）操作失败
+    #[error("{0}")]
+    Symlink(String),
+    /// 窗口操作失败
+    #[error("{0}")]
+    Window(String),
+    /// 配置读写或校验失败
+    #[error("{0}")]
+    Config(String),
+    /// 下载失败
+    #[error("{0}")]
+    Download(String),
+    /// 解压失败
+    #[error("{0}")]
+    Extract(String),
+    /// 更新检查、下载或安装失败
+    #[error("{0}")]
+    Update(String),
+    /// 下载请求不符合下载策略，详情为序列化的拒绝原因
+    #[error("{0}")]
+    PolicyDenied(Box<crate::download_policy::PolicyRejection>),
+    /// 下载队列中等待的任务数已达到上限，详情为序列化的队列状态
+    #[error("{0}")]
+    QueueFull(crate::download_manager::QueueFull),
+    /// 网络请求失败
+    #[error("网络请求失败: {0}")]
+    Network(#[from] reqwest::Error),
+    /// 文件读写失败
+    #[error("文件读写失败: {0}")]
+    Io(#[from] std::io::Error),
+    /// JSON 序列化或解析失败
+    #[error("JSON 解析失败: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Tauri 内部错误
+    #[error("Tauri 错误: {0}")]
+    Tauri(#[from] tauri::Error),
+    /// 其他未分类错误（由尚未迁移的 String 错误转换而来）
+    #[error("{0}")]
+    Internal(String),
+}
+
+/// 应用统一的返回值类型
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// 获取稳定的错误代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotConfigured(_) => "NOT_CONFIGURED",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::Busy(_) => "BUSY",
+            AppError::Cancelled(_) => "CANCELLED",
+            AppError::Lock(_) => "LOCK_FAILED",
+            AppError::FileOperation(_) => "FILE_OPERATION_FAILED",
+            AppError::Symlink(_) => "SYMLINK_FAILED",
+            AppError::Window(_) => "WINDOW_FAILED",
+            AppError::Config(_) => "CONFIG_FAILED",
+            AppError::Download(_) => "DOWNLOAD_FAILED",
+            AppError::Extract(_) => "EXTRACT_FAILED",
+            AppError::Update(_) => "UPDATE_FAILED",
+            AppError::PolicyDenied(_) => "POLICY_DENIED",
+            AppError::QueueFull(_) => "QUEUE_FULL",
+            AppError::Network(_) => "NETWORK_FAILED",
+            AppError::Io(_) => "IO_ERROR",
+            AppError::Json(_) => "INVALID_JSON",
+            AppError::Tauri(_) => "TAURI_ERROR",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// 获取错误详情 - 包装的底层错误返回其调试信息，策略拒绝返回拒绝原因的 JSON，队列已满返回队列状态的 JSON，其余返回 None
+    pub fn details(&self) -> Option<String> {
+        match self {
+            AppError::PolicyDenied(rejection) => serde_json::to_string(rejection).ok(),
+            AppError::QueueFull(full) => serde_json::to_string(full).ok(),
+            AppError::Network(e) => Some(format!("{:?}", e)),
+            AppError::Io(e) => Some(format!("{:?}", e)),
+            AppError::Json(e) => Some(format!("{:?}", e)),
+            AppError::Tauri(e) => Some(format!("{:?}", e)),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+// 允许尚未迁移、仍返回 Result<_, String> 的内部函数通过 `?` 调用返回 AppError 的函数
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,48 @@
+// event_dispatcher.rs 模块 - 统一发送后端事件：下载、解压和队列等状态事件广播到所有窗口，其他事件只发送到主窗口
+//
+// 文件管理器和服务器列表窗口需要知道有新文件被安装或下载已完成，后端发送事件时统一调用 emit，
+// 由这里决定事件发送到哪些窗口，新增窗口或调整路由时不需要修改各个发送事件的模块。
+
+// 第三方库导入
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// 按前缀匹配、需要广播到所有窗口的事件
+const BROADCAST_EVENT_PREFIXES: &[&str] = &["download-", "extract-", "network-", "game-"];
+
+/// 需要广播到所有窗口的其他事件
+const BROADCAST_EVENTS: &[&str] = &[
+    "settings-changed",
+    "map-conflict",
+    "assets-reload",
+    "engine-status-changed",
+];
+
+/// 是否将事件广播到所有窗口
+fn is_broadcast_event(event: &str) -> bool {
+    BROADCAST_EVENTS.contains(&event)
+        || BROADCAST_EVENT_PREFIXES
+            .iter()
+            .any(|prefix| event.starts_with(prefix))
+}
+
+/// 发送事件 - 状态事件广播到所有窗口，其他事件只发送到主窗口
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `event`: 事件名称
+/// - `payload`: 事件内容
+///
+/// # 返回值
+/// - 发送失败时返回Err
+pub fn emit<S: Serialize + Clone>(
+    app_handle: &AppHandle,
+    event: &str,
+    payload: S,
+) -> tauri::Result<()> {
+    if is_broadcast_event(event) {
+        app_handle.emit(event, payload)
+    } else {
+        app_handle.emit_to("main", event, payload)
+    }
+}
@@ -0,0 +1,113 @@
+// event_log.rs 模块 - 将严重错误写入 Windows 应用程序日志，应用日志被清除后管理员仍可以在事件查看器中排查问题
+//
+// 事件源需要在注册表 HKLM\SYSTEM\CurrentControlSet\Services\EventLog\Application 下注册，写入注册表需要管理员权限，
+// 应用以管理员身份运行或 helper 提权后会自动注册。未注册时事件仍会写入应用程序日志，只是事件查看器无法显示格式化的描述。
+
+// 标准库导入
+use std::{ffi::OsStr, os::windows::ffi::OsStrExt};
+
+// 第三方库导入
+use windows_sys::Win32::System::EventLog::{
+    DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+};
+use winreg::{
+    enums::{RegType, HKEY_LOCAL_MACHINE},
+    RegKey, RegValue,
+};
+
+// 内部模块导入
+use crate::{log_debug, log_warn};
+
+/// 事件源名称
+pub const EVENT_SOURCE: &str = "Nyaser Maps Downloader";
+
+/// 事件源的注册表路径
+const EVENT_SOURCE_KEY: &str =
+    r"SYSTEM\CurrentControlSet\Services\EventLog\Application\Nyaser Maps Downloader";
+
+/// 只输出 %1 的通用消息文件（.NET Framework 自带）
+const EVENT_MESSAGE_FILE: &str =
+    r"%SystemRoot%\Microsoft.NET\Framework64\v4.0.30319\EventLogMessages.dll";
+
+/// 写入事件日志的严重错误类型，值为事件ID
+#[derive(Debug, Clone, Copy)]
+pub enum CriticalEvent {
+    /// aria2c 无法启动
+    Aria2StartFailed = 1001,
+    /// 解压失败，且解压前已删除了原有的地图目录
+    ExtractTargetLost = 1002,
+    /// helper 提权被拒绝
+    ElevationDenied = 1003,
+}
+
+/// 在注册表中注册事件源（需要管理员权限，已注册时直接返回）
+///
+/// # 返回值
+/// - 注册成功或已注册时返回Ok(())
+pub fn register_event_source() -> Result<(), String> {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    if hklm.open_subkey(EVENT_SOURCE_KEY).is_ok() {
+        return Ok(());
+    }
+
+    let (key, _) = hklm
+        .create_subkey(EVENT_SOURCE_KEY)
+        .map_err(|e| format!("注册事件源失败: {}", e))?;
+    // 路径中包含环境变量，需要使用 REG_EXPAND_SZ 类型
+    let message_file = RegValue {
+        vtype: RegType::REG_EXPAND_SZ,
+        bytes: OsStr::new(EVENT_MESSAGE_FILE)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .flat_map(|c| c.to_le_bytes())
+            .collect(),
+    };
+    key.set_raw_value("EventMessageFile", &message_file)
+        .and_then(|_| key.set_value("TypesSupported", &7u32))
+        .map_err(|e| format!("注册事件源失败: {}", e))?;
+    Ok(())
+}
+
+/// 将严重错误写入 Windows 应用程序日志，写入失败时只记录警告
+///
+/// # 参数
+/// - `event`: 错误类型
+/// - `message`: 错误描述
+pub fn report_critical(event: CriticalEvent, message: &str) {
+    let source = OsStr::new(EVENT_SOURCE)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<_>>();
+    let text = OsStr::new(&format!("{}\r\n\r\n{:?}", message, event))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<_>>();
+    let strings = [text.as_ptr()];
+
+    let reported = unsafe {
+        let handle = RegisterEventSourceW(std::ptr::null(), source.as_ptr());
+        if handle.is_null() {
+            false
+        } else {
+            let result = ReportEventW(
+                handle,
+                EVENTLOG_ERROR_TYPE,
+                0,
+                event as u32,
+                std::ptr::null_mut(),
+                strings.len() as u16,
+                0,
+                strings.as_ptr(),
+                std::ptr::null(),
+            );
+            DeregisterEventSource(handle);
+            result != 0
+        }
+    };
+
+    if reported {
+        log_debug!("已写入Windows事件日志: {:?}", event);
+    } else {
+        log_warn!("写入Windows事件日志失败: {:?}", event);
+    }
+}
@@ -0,0 +1,275 @@
+// explorer.rs 模块 - 在 Windows 资源管理器中打开目录或选中文件，只允许访问应用管理的目录
+
+// 标准库导入
+use std::{
+    ffi::OsStr,
+    os::windows::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+// 第三方库导入
+use tauri::AppHandle;
+use windows_sys::Win32::{
+    System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED},
+    UI::{
+        Shell::{ILCreateFromPathW, ILFree, SHOpenFolderAndSelectItems, ShellExecuteW},
+        WindowsAndMessaging::SW_SHOWNORMAL,
+    },
+};
+
+// 内部模块导入
+use crate::{
+    dir_manager::DIR_MANAGER,
+    error::{AppError, AppResult},
+    log_error, log_info, tr,
+};
+
+/// 将字符串转换为以 0 结尾的宽字符串
+fn to_wide(value: &OsStr) -> Vec<u16> {
+    value.encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// 去掉 canonicalize 生成的 `\\?\` 前缀，资源管理器不能正确处理这种路径
+fn strip_verbatim_prefix(path: &Path) -> PathBuf {
+    let path_str = path.to_string_lossy();
+    if let Some(rest) = path_str.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", rest))
+    } else if let Some(rest) = path_str.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// 获取允许在资源管理器中打开的根目录（addons 目录、数据目录、下载目录和日志目录）
+fn get_allowed_roots(app_handle: &AppHandle) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Ok(manager) = DIR_MANAGER.lock() {
+        if let Some(dir_manager) = manager.as_ref() {
+            if let Some(addons_dir) = dir_manager.addons_dir() {
+                roots.push(addons_dir.clone());
+            }
+            let data_dir = dir_manager.data_dir();
+            if !data_dir.as_os_str().is_empty() {
+                roots.push(data_dir);
+            }
+            roots.push(dir_manager.cache_dir());
+        }
+    }
+    if let Some(logs_dir) = crate::init::get_logs_dir(app_handle) {
+        roots.push(logs_dir);
+    }
+    roots
+        .into_iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect()
+}
+
+/// 校验路径是否存在且位于允许的根目录下
+///
+/// 只解析父目录，addons 目录中的符号链接仍按链接本身的位置校验，不跟随到链接目标
+///
+/// # 返回值
+/// - 校验通过时返回规范化后的路径
+/// - 路径不存在时返回AppError::NotFound，不在允许的目录下时返回AppError::InvalidInput
+pub fn validate_managed_path(app_handle: &AppHandle, path: &Path) -> AppResult<PathBuf> {
+    let not_found = || AppError::NotFound(tr!("error.path_not_found", path = path.display()));
+
+    if path.symlink_metadata().is_err() {
+        return Err(not_found());
+    }
+
+    let resolved = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            parent.canonicalize().map_err(|_| not_found())?.join(name)
+        }
+        _ => path.canonicalize().map_err(|_| not_found())?,
+    };
+
+    if get_allowed_roots(app_handle)
+        .iter()
+        .any(|root| resolved.starts_with(root))
+    {
+        Ok(resolved)
+    } else {
+        log_error!("拒绝打开应用管理目录以外的路径: {}", path.display());
+        Err(AppError::InvalidInput(tr!(
+            "error.path_not_allowed",
+            path = path.display()
+        )))
+    }
+}
+
+/// 在资源管理器中打开目录
+fn open_folder(path: &Path) -> Result<(), String> {
+    let operation = to_wide(OsStr::new("open"));
+    let file = to_wide(path.as_os_str());
+
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            operation.as_ptr(),
+            file.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+
+    // ShellExecuteW 返回值大于 32 表示成功
+    if result as usize > 32 {
+        Ok(())
+    } else {
+        Err(format!("ShellExecuteW 失败，错误码: {:?}", result))
+    }
+}
+
+/// 在资源管理器中打开父目录并选中指定的文件或目录
+fn select_in_folder(path: &Path) -> Result<(), String> {
+    let wide_path = to_wide(path.as_os_str());
+
+    unsafe {
+        // SHOpenFolderAndSelectItems 要求当前线程已初始化 COM
+        let com_initialized =
+            CoInitializeEx(std::ptr::null(), COINIT_APARTMENTTHREADED as u32) >= 0;
+
+        let pidl = ILCreateFromPathW(wide_path.as_ptr());
+        let result = if pidl.is_null() {
+            Err("ILCreateFromPathW 无法解析路径".to_string())
+        } else {
+            let hr = SHOpenFolderAndSelectItems(pidl, 0, std::ptr::null(), 0);
+            ILFree(pidl);
+            if hr >= 0 {
+                Ok(())
+            } else {
+                Err(format!(
+                    "SHOpenFolderAndSelectItems 失败，HRESULT: {:#x}",
+                    hr
+                ))
+            }
+        };
+
+        if com_initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+/// 在资源管理器中显示路径 - 目录直接打开，文件（或 select 为 true 时）在父目录中选中
+///
+/// # 参数
+/// - `path`: 已经过校验的路径
+/// - `select`: 是否在父目录中选中该路径
+pub fn show_in_explorer(path: &Path, select: bool) -> AppResult<()> {
+    let path = strip_verbatim_prefix(path);
+    let select = select || !path.is_dir();
+    log_info!(
+        "在资源管理器中{}: {}",
+        if select { "选中" } else { "打开" },
+        path.display()
+    );
+
+    let result = if select {
+        select_in_folder(&path)
+    } else {
+        open_folder(&path)
+    };
+
+    result.map_err(|e| {
+        log_error!("打开资源管理器失败: {}", e);
+        AppError::FileOperation(tr!("error.open_explorer_failed", error = e))
+    })
+}
+
+/// 在资源管理器中打开目录或选中文件
+///
+/// 只允许打开 addons 目录、数据目录、下载目录和日志目录下的路径
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `path`: 要打开的目录或要选中的文件
+/// - `select`: 是否在父目录中选中该路径（可选，文件总是被选中）
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn open_path_in_explorer(
+    app_handle: AppHandle,
+    path: String,
+    select: Option<bool>,
+) -> AppResult<()> {
+    log_info!("接收到在资源管理器中打开路径请求: {}", path);
+
+    let path = validate_managed_path(&app_handle, Path::new(&path))?;
+    let select = select.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || show_in_explorer(&path, select))
+        .await
+        .map_err(|e| AppError::Internal(format!("{:?}", e)))?
+}
+
+/// 在资源管理器中显示已安装的地图 - 打开 maps 目录并选中地图所在的文件夹
+///
+/// `map_name` 可以是 maps 目录下的分组名，也可以是 addons 目录中的挂载链接名
+/// （例如 nmd_link_xxx.vpk），链接会被解析为其指向的地图文件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `map_name`: 分组名或挂载链接名
+///
+/// # 返回值
+/// - 成功时返回被选中的路径
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn reveal_map(app_handle: AppHandle, map_name: String) -> AppResult<String> {
+    log_info!("接收到在资源管理器中显示地图请求: {}", map_name);
+
+    if map_name.is_empty() || map_name == ".." || map_name.contains(['/', '\\', ':']) {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_map_name",
+            name = map_name
+        )));
+    }
+
+    let (maps_dir, addons_dir) = {
+        let manager = DIR_MANAGER.lock().map_err(|e| {
+            AppError::Lock(tr!(
+                "error.lock_dir_manager_failed",
+                error = format!("{:?}", e)
+            ))
+        })?;
+        let dir_manager = manager
+            .as_ref()
+            .ok_or_else(|| AppError::NotConfigured(tr!("error.data_dir_not_configured")))?;
+        (dir_manager.maps_dir(), dir_manager.addons_dir().cloned())
+    };
+
+    let group_dir = maps_dir.join(&map_name);
+    let target = if group_dir.is_dir() {
+        group_dir
+    } else {
+        let link_path = addons_dir
+            .map(|dir| dir.join(&map_name))
+            .filter(|path| path.is_symlink())
+            .ok_or_else(|| AppError::NotFound(tr!("error.map_not_found", name = map_name)))?;
+        let link_target = std::fs::read_link(&link_path).map_err(|e| {
+            AppError::Symlink(tr!("error.read_link_failed", error = format!("{:?}", e)))
+        })?;
+        // 相对链接以链接所在目录为基准
+        match link_path.parent() {
+            Some(parent) if link_target.is_relative() => parent.join(link_target),
+            _ => link_target,
+        }
+    };
+
+    let target = validate_managed_path(&app_handle, &target)?;
+    let revealed = strip_verbatim_prefix(&target).to_string_lossy().to_string();
+
+    tokio::task::spawn_blocking(move || show_in_explorer(&target, true))
+        .await
+        .map_err(|e| AppError::Internal(format!("{:?}", e)))??;
+
+    Ok(revealed)
+}
@@ -4,15 +4,23 @@
 use std::{fs, path::PathBuf};
 
 // 第三方库导入
+use serde::{Deserialize, Serialize};
 use serde_json;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use tauri_plugin_dialog::MessageDialogKind;
+use tracing::Instrument;
 
 // 内部模块导入
 use crate::{
-    dialog_manager::show_dialog, download_manager::DOWNLOAD_QUEUE, init::is_app_shutting_down,
-    log_debug, log_error, log_info, log_utils::redirect_process_output, log_warn,
-    queue_manager::QueueManager,
+    cancellation::CancellationToken,
+    dialog_manager::show_dialog,
+    download_manager::DOWNLOAD_QUEUE,
+    init::is_app_shutting_down,
+    log_debug, log_error, log_info,
+    log_utils::redirect_process_output,
+    log_warn,
+    queue_manager::{QueueManager, TaskState},
+    tr,
 };
 
 /// 解压任务结构体 - 表示一个文件解压任务
@@ -30,6 +38,35 @@ pub struct ExtractTask {
     pub download_task_id: String,
     /// 是否为拖拽文件（拖拽文件解压后不删除）
     pub is_dragged_file: bool,
+    /// 产生该文件的下载任务（拖拽文件为None），压缩包损坏时用于重新下载
+    pub source_task: Option<crate::download_manager::DownloadTask>,
+}
+
+/// 解压重试等待时间的增长方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryStrategy {
+    /// 每次等待相同的时间
+    Fixed,
+    /// 等待时间随重试次数线性增长
+    #[default]
+    Linear,
+    /// 等待时间每次翻倍
+    Exponential,
+}
+
+impl RetryStrategy {
+    /// 第 retry 次重试（从1开始）前的等待时间
+    fn delay(self, base_seconds: u64, retry: u32) -> std::time::Duration {
+        let seconds = match self {
+            RetryStrategy::Fixed => base_seconds,
+            RetryStrategy::Linear => base_seconds.saturating_mul(retry as u64),
+            RetryStrategy::Exponential => {
+                base_seconds.saturating_mul(1u64 << retry.saturating_sub(1).min(16))
+            }
+        };
+        std::time::Duration::from_secs(seconds)
+    }
 }
 
 // 创建全局解压队列管理器实例和7z资源路径常量
@@ -69,6 +106,7 @@ fn wait_for_aria2_file(
     extract_task_id: String,
     download_task_id: String,
     task: &ExtractTask,
+    cancel_token: &CancellationToken,
 ) {
     if !aria2_file_path.exists() {
         log_debug!(
@@ -93,7 +131,7 @@ fn wait_for_aria2_file(
     let extract_task_id_clone = extract_task_id.clone();
     let download_task_id_clone = download_task_id.clone();
 
-    while aria2_file_path.exists() {
+    while aria2_file_path.exists() && !cancel_token.is_cancelled() {
         wait_count += 1;
 
         let elapsed = start_time.elapsed().as_secs();
@@ -115,17 +153,24 @@ fn wait_for_aria2_file(
                 log_info!("找到下载任务 [{}]，开始继续下载", download_task_id_clone);
 
                 let task_file_path_clone = task.file_path.clone();
-                std::thread::spawn(move || {
-                    let result = {
-                        if let Ok(mut manager_guard) = crate::aria2c::ARIA2_RPC_MANAGER.lock() {
-                            if let Some(manager) = &mut *manager_guard {
-                                let path_buf = PathBuf::from(&aria2_file_path_str);
-                                let filename = path_buf
-                                    .file_stem()
-                                    .and_then(|os_str| os_str.to_str())
-                                    .unwrap_or("未知文件");
-
-                                manager.add_download_sync(
+                tauri::async_runtime::spawn(async move {
+                    // 复制RPC管理器后立即释放锁，避免等待RPC响应时持有锁
+                    let manager = match crate::aria2c::ARIA2_RPC_MANAGER.lock() {
+                        Ok(manager_guard) => manager_guard
+                            .clone()
+                            .ok_or_else(|| "ARIA2 RPC管理器未初始化".to_string()),
+                        Err(_) => Err("无法获取ARIA2 RPC管理器锁".to_string()),
+                    };
+                    let result = match manager {
+                        Ok(manager) => {
+                            let path_buf = PathBuf::from(&aria2_file_path_str);
+                            let filename = path_buf
+                                .file_stem()
+                                .and_then(|os_str| os_str.to_str())
+                                .unwrap_or("未知文件");
+
+                            manager
+                                .add_download(
                                     &download_task.url,
                                     &std::path::Path::new(&aria2_file_path_str)
                                         .parent()
@@ -133,12 +178,9 @@ fn wait_for_aria2_file(
                                         .unwrap_or("\\".to_string()),
                                     filename,
                                 )
-                            } else {
-                                Err("ARIA2 RPC管理器未初始化".to_string())
-                            }
-                        } else {
-                            Err("无法获取ARIA2 RPC管理器锁".to_string())
+                                .await
                         }
+                        Err(e) => Err(e),
                     };
 
                     match result {
@@ -149,8 +191,8 @@ fn wait_for_aria2_file(
                                 download_task_id_clone,
                                 gid
                             );
-                            let _ = app_handle_clone.emit_to(
-                                "main",
+                            let _ = crate::event_dispatcher::emit(
+                                &app_handle_clone,
                                 "download-resumed",
                                 &serde_json::json!(
                                     {
@@ -159,7 +201,7 @@ fn wait_for_aria2_file(
                                             .file_name()
                                             .and_then(|os_str| os_str.to_str())
                                             .unwrap_or("未知文件"),
-                                        "message": "成功继续下载任务"
+                                        "message": tr!("event.download.resumed")
                                     }
                                 ),
                             );
@@ -195,7 +237,7 @@ fn wait_for_aria2_file(
             );
         }
 
-        std::thread::sleep(std::time::Duration::from_millis(500));
+        cancel_token.sleep(std::time::Duration::from_millis(500));
     }
 }
 
@@ -212,8 +254,8 @@ fn send_extract_start_event(task: &ExtractTask, filename: &str) {
         Err(_) => "未知".to_string(),
     };
 
-    let _ = task.app_handle.emit_to(
-        "main",
+    let _ = crate::event_dispatcher::emit(
+        &task.app_handle,
         "extract-start",
         &serde_json::json!(
             {
@@ -225,17 +267,24 @@ fn send_extract_start_event(task: &ExtractTask, filename: &str) {
     );
 }
 
-// 发送解压完成事件
-fn send_extract_complete_event(task: &ExtractTask, success: bool, message: &str, filename: &str) {
-    let _ = task.app_handle.emit_to(
-        "main",
+// 发送解压完成事件，invalid_vpks 为解压出的无效 VPK 文件
+fn send_extract_complete_event(
+    task: &ExtractTask,
+    success: bool,
+    message: &str,
+    filename: &str,
+    invalid_vpks: &[crate::vpk_validator::InvalidVpk],
+) {
+    let _ = crate::event_dispatcher::emit(
+        &task.app_handle,
         "extract-complete",
         &serde_json::json!(
             {
                 "taskId": task.download_task_id,
                 "success": success,
                 "message": message,
-                "filename": filename
+                "filename": filename,
+                "invalidVpks": invalid_vpks
             }
         ),
     );
@@ -251,25 +300,16 @@ fn send_extract_queue_update_event(app_handle: &AppHandle) {
 
         match queue {
             Ok(queue) => {
-                let active = queue
-                    .active_tasks
-                    .iter()
-                    .filter_map(|task_id| {
-                        queue.tasks.get(task_id).map(|task| {
+                let list = |state| {
+                    queue
+                        .tasks_in(state)
+                        .map(|(_, task)| {
                             serde_json::json!({"id": task.id, "file_path": task.file_path, "archive_name": task.archive_name})
                         })
-                    })
-                    .collect::<Vec<_>>();
-
-                let tasks = queue
-                    .waiting_tasks
-                    .iter()
-                    .filter_map(|task_id| {
-                        queue.tasks.get(task_id).map(|task| {
-                            serde_json::json!({"id": task.id, "file_path": task.file_path, "archive_name": task.archive_name})
-                        })
-                    })
-                    .collect::<Vec<_>>();
+                        .collect::<Vec<_>>()
+                };
+                let active = list(TaskState::Active);
+                let tasks = list(TaskState::Waiting);
 
                 let total = active.len() + tasks.len();
 
@@ -279,8 +319,8 @@ fn send_extract_queue_update_event(app_handle: &AppHandle) {
         }
     };
 
-    let _ = app_handle.emit_to(
-        "main",
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
         "extract-queue-update",
         &serde_json::json!({
             "queue": {"waiting_tasks": waiting_tasks,
@@ -300,6 +340,24 @@ fn cleanup_temp_file(task: &ExtractTask, extract_task_id: &str, success: bool) {
                 extract_task_id,
                 task.file_path
             );
+        } else if crate::archive_cache::is_cached_file(std::path::Path::new(&task.file_path)) {
+            log_debug!(
+                "解压任务 [{}]: 使用缓存的压缩包解压完成，保留缓存文件: {}",
+                extract_task_id,
+                task.file_path
+            );
+        } else if let Some(source_task) = task
+            .source_task
+            .as_ref()
+            .filter(|_| crate::archive_cache::is_enabled())
+        {
+            // 加入缓存失败时按普通临时文件处理
+            if let Err(e) =
+                crate::archive_cache::store(&source_task.url, std::path::Path::new(&task.file_path))
+            {
+                log_warn!("解压任务 [{}]: 压缩包加入缓存失败: {}", extract_task_id, e);
+                let _ = fs::remove_file(&task.file_path);
+            }
         } else {
             // 下载的临时文件，解压后删除
             if let Err(e) = fs::remove_file(&task.file_path) {
@@ -326,36 +384,141 @@ fn cleanup_temp_file(task: &ExtractTask, extract_task_id: &str, success: bool) {
     }
 }
 
-// 重试解压
+// 删除损坏的压缩包并重新下载，返回是否已安排重新下载（拖拽文件或已达到最多尝试次数时不重新下载）
+fn redownload_source(
+    task: &ExtractTask,
+    extract_task_id: &str,
+    filename: &str,
+    error: &str,
+) -> bool {
+    let Some(source_task) = &task.source_task else {
+        return false;
+    };
+    if source_task.attempt + 1 >= source_task.max_attempts() {
+        return false;
+    }
+
+    let mut download_task = source_task.clone();
+    download_task.attempt += 1;
+    log_warn!(
+        "解压任务 [{}]: {}，重新下载任务 [{}]（第 {}/{} 次尝试）",
+        extract_task_id,
+        error,
+        download_task.id,
+        download_task.attempt + 1,
+        download_task.max_attempts()
+    );
+    if let Err(e) = fs::remove_file(&task.file_path) {
+        log_warn!("解压任务 [{}]: 无法删除损坏的文件: {}", extract_task_id, e);
+    }
+
+    let _ = crate::event_dispatcher::emit(
+        &task.app_handle,
+        "extract-redownload",
+        &serde_json::json!(
+            {
+                "taskId": task.download_task_id,
+                "filename": filename,
+                "error": error,
+                "attempt": download_task.attempt + 1,
+                "maxAttempts": download_task.max_attempts()
+            }
+        ),
+    );
+    crate::download_manager::requeue_download(&task.app_handle, download_task);
+    true
+}
+
+// 压缩包损坏时重新下载，返回是否已安排重新下载
+fn redownload_corrupt_archive(task: &ExtractTask, extract_task_id: &str, filename: &str) -> bool {
+    if !crate::settings::current_settings().redownload_corrupt_archives {
+        return false;
+    }
+    // 文件能够正常列出内容时不是压缩包损坏，仍然重试解压
+    let Err(error) = validate_archieve(&task.file_path) else {
+        return false;
+    };
+    redownload_source(task, extract_task_id, filename, &error)
+}
+
+// 解压出的 VPK 文件无效时删除解压目录并重新下载，返回是否已安排重新下载
+fn redownload_invalid_vpks(
+    task: &ExtractTask,
+    extract_task_id: &str,
+    filename: &str,
+    invalid_vpks: &[crate::vpk_validator::InvalidVpk],
+) -> bool {
+    if invalid_vpks.is_empty() || !crate::settings::current_settings().redownload_invalid_vpks {
+        return false;
+    }
+    let error = tr!("event.extract.invalid_vpks", count = invalid_vpks.len());
+    if !redownload_source(task, extract_task_id, filename, &error) {
+        return false;
+    }
+    let target_dir = MAPS_DIR.join(&task.archive_name);
+    if let Err(e) = fs::remove_dir_all(&target_dir) {
+        log_warn!("解压任务 [{}]: 无法删除解压目录: {}", extract_task_id, e);
+    }
+    true
+}
+
+// 重试解压，返回最终结果和重试次数
 async fn retry_extract(
     task: &ExtractTask,
     extract_task_id: &str,
+    filename: &str,
     initial_result: Result<String, String>,
-) -> Result<String, String> {
-    const MAX_RETRY_COUNT: u32 = 3;
+    cancel_token: &CancellationToken,
+) -> (Result<String, String>, u32) {
+    let settings = crate::settings::current_settings();
+    let max_retry_count = settings.extract_max_retries;
     let mut retry_count = 0;
     let mut final_result = initial_result;
 
-    while final_result.is_err() && retry_count < MAX_RETRY_COUNT {
+    while retry_count < max_retry_count && !cancel_token.is_cancelled() {
+        let Err(error) = &final_result else {
+            break;
+        };
         retry_count += 1;
+        let delay = settings
+            .extract_retry_strategy
+            .delay(settings.extract_retry_delay_seconds, retry_count);
         log_warn!(
-            "解压任务 [{}] 失败，开始第 {} 次重试: {}",
+            "解压任务 [{}] 失败，{} 秒后开始第 {}/{} 次重试: {}",
             extract_task_id,
+            delay.as_secs(),
             retry_count,
-            final_result.unwrap_err()
+            max_retry_count,
+            error
+        );
+        let _ = crate::event_dispatcher::emit(
+            &task.app_handle,
+            "extract-retrying",
+            &serde_json::json!(
+                {
+                    "taskId": task.download_task_id,
+                    "filename": filename,
+                    "error": error,
+                    "attempt": retry_count,
+                    "maxAttempts": max_retry_count,
+                    "delay": delay.as_secs()
+                }
+            ),
         );
 
-        tokio::time::sleep(tokio::time::Duration::from_secs(2 * retry_count as u64)).await;
+        if cancel_token.sleep_async(delay).await {
+            break;
+        }
 
         log_debug!(
             "解压任务 [{}] 第 {} 次重试中...",
             extract_task_id,
             retry_count
         );
-        final_result = extract_with_7zip(&task.file_path, &task.archive_name).await;
+        final_result = extract_with_7zip(&task.file_path, &task.archive_name, cancel_token).await;
     }
 
-    final_result
+    (final_result, retry_count)
 }
 
 // 构建返回消息
@@ -367,14 +530,22 @@ fn build_result_message(
     match result {
         Ok(msg) => {
             if retry_count > 0 {
-                format!("{} (重试了{}次)", msg, retry_count)
+                tr!(
+                    "event.extract.succeeded_after_retries",
+                    message = msg,
+                    count = retry_count
+                )
             } else {
                 msg.clone()
             }
         }
         Err(e) => {
             if retry_count >= max_retry_count {
-                format!("解压失败（已尝试{}次）: {}", max_retry_count, e)
+                tr!(
+                    "event.extract.failed_after_retries",
+                    count = max_retry_count,
+                    error = e
+                )
             } else {
                 e.to_string()
             }
@@ -382,8 +553,35 @@ fn build_result_message(
     }
 }
 
+// 结束被取消的解压任务，保留压缩包
+fn finish_canceled_extract(task: &ExtractTask, extract_task_id: &str, filename: &str) {
+    log_info!("解压任务 [{}] 已取消: {}", extract_task_id, task.file_path);
+    send_extract_complete_event(task, false, &tr!("event.extract.canceled"), filename, &[]);
+    send_extract_queue_update_event(&task.app_handle);
+}
+
 // 处理解压任务
-async fn process_extract_task(task: ExtractTask, extract_task_id: &str, download_task_id: &str) {
+async fn process_extract_task(
+    mut task: ExtractTask,
+    extract_task_id: &str,
+    download_task_id: &str,
+    cancel_token: CancellationToken,
+) {
+    // 已安装只有大小写不同的同名地图时沿用其目录名，覆盖原有安装，挂载链接名也保持不变
+    if let Some(existing) =
+        crate::utils::find_existing_entry(MAPS_DIR.as_path(), &task.archive_name)
+    {
+        if existing != task.archive_name {
+            log_info!(
+                "解压任务 [{}]: 使用已安装的同名目录 {}（压缩包名称: {}）",
+                extract_task_id,
+                existing,
+                task.archive_name
+            );
+            task.archive_name = existing;
+        }
+    }
+
     let filename = get_filename_from_path(&task.file_path);
     let aria2_file_path = build_aria2_file_path(&task.file_path);
 
@@ -392,7 +590,12 @@ async fn process_extract_task(task: ExtractTask, extract_task_id: &str, download
         extract_task_id.to_string(),
         download_task_id.to_string(),
         &task,
+        &cancel_token,
     );
+    if cancel_token.is_cancelled() {
+        finish_canceled_extract(&task, extract_task_id, &filename);
+        return;
+    }
 
     send_extract_start_event(&task, &filename);
 
@@ -402,33 +605,81 @@ async fn process_extract_task(task: ExtractTask, extract_task_id: &str, download
         task.file_path
     );
 
-    let result = extract_with_7zip(&task.file_path, &task.archive_name).await;
+    let result = extract_with_7zip(&task.file_path, &task.archive_name, &cancel_token).await;
+    if cancel_token.is_cancelled() {
+        finish_canceled_extract(&task, extract_task_id, &filename);
+        return;
+    }
 
-    let final_result = retry_extract(&task, extract_task_id, result).await;
+    // 压缩包损坏时按配置重新下载，不再重试解压
+    if result.is_err() && redownload_corrupt_archive(&task, extract_task_id, &filename) {
+        send_extract_queue_update_event(&task.app_handle);
+        return;
+    }
+
+    let (final_result, retry_count) =
+        retry_extract(&task, extract_task_id, &filename, result, &cancel_token).await;
+    if cancel_token.is_cancelled() {
+        finish_canceled_extract(&task, extract_task_id, &filename);
+        return;
+    }
 
     let success = final_result.is_ok();
+
+    // 校验解压出的 VPK 文件，无效时按配置重新下载，避免损坏的附加组件导致游戏崩溃
+    let invalid_vpks = if success {
+        crate::vpk_validator::find_invalid_vpks(&MAPS_DIR.join(&task.archive_name))
+    } else {
+        Vec::new()
+    };
+    if redownload_invalid_vpks(&task, extract_task_id, &filename, &invalid_vpks) {
+        send_extract_queue_update_event(&task.app_handle);
+        return;
+    }
+
     cleanup_temp_file(&task, extract_task_id, success);
 
-    const MAX_RETRY_COUNT: u32 = 3;
-    let retry_count = if success { 0 } else { MAX_RETRY_COUNT };
-    let message = build_result_message(&final_result, retry_count, MAX_RETRY_COUNT);
+    let max_retry_count = crate::settings::current_settings().extract_max_retries;
+    let message = build_result_message(&final_result, retry_count, max_retry_count);
+    if !invalid_vpks.is_empty() {
+        let files = invalid_vpks
+            .iter()
+            .map(|vpk| format!("{}: {}", vpk.path, vpk.error))
+            .collect::<Vec<_>>()
+            .join("\n");
+        show_dialog(
+            &task.app_handle,
+            &tr!(
+                "dialog.invalid_vpks",
+                name = &task.archive_name,
+                files = files
+            ),
+            MessageDialogKind::Warning,
+            &tr!("dialog.invalid_vpks.title"),
+        );
+    }
 
-    if !success && retry_count >= MAX_RETRY_COUNT {
+    if !success {
         show_dialog(
             &task.app_handle,
             &message,
             MessageDialogKind::Error,
-            "解压失败",
+            &tr!("dialog.extract_failed.title"),
         );
     }
 
     if success {
         log_info!("解压任务 [{}] 完成: {}", extract_task_id, message);
+        crate::telemetry::record("extract.complete");
+        crate::shell_integration::record_installed_map(&task.archive_name);
+        crate::map_conflicts::check_and_notify(&task.app_handle, &task.archive_name);
+        crate::plugins::run_post_install(&task.archive_name, &MAPS_DIR.join(&task.archive_name));
     } else {
         log_error!("解压任务 [{}] 失败: {}", extract_task_id, message);
+        crate::telemetry::record("extract.failed");
     }
 
-    send_extract_complete_event(&task, success, &message, &filename);
+    send_extract_complete_event(&task, success, &message, &filename, &invalid_vpks);
     send_extract_queue_update_event(&task.app_handle);
 }
 
@@ -480,23 +731,40 @@ pub fn start_extract_queue_manager() {
         let task = task.clone();
         let task_id = task.id.clone();
 
-        tauri::async_runtime::spawn(async move {
-            process_extract_task(task, &extract_task_id, &download_task_id).await;
-
-            match EXTRACT_MANAGER.queue.lock() {
-                Ok(mut queue) => {
-                    queue.remove_active_task(&task_id);
-                    log_debug!(
-                        "解压任务 [{}] 从活跃任务集合中移除，当前活跃解压任务数: {}",
-                        extract_task_id,
-                        queue.active_tasks.len()
-                    );
-                }
-                Err(e) => {
-                    log_error!("无法获取解压队列锁以移除任务 [{}]: {}", extract_task_id, e);
+        let span = tracing::info_span!(
+            "extract",
+            task_id = %extract_task_id,
+            download_task_id = %download_task_id
+        );
+
+        tauri::async_runtime::spawn(
+            async move {
+                // 队列处理循环调用本函数时持有队列锁，在异步任务中再获取取消令牌
+                let cancel_token = EXTRACT_MANAGER
+                    .queue
+                    .lock()
+                    .ok()
+                    .and_then(|queue| queue.cancellation_token(&task_id))
+                    .unwrap_or_default();
+                process_extract_task(task, &extract_task_id, &download_task_id, cancel_token).await;
+
+                match EXTRACT_MANAGER.queue.lock() {
+                    Ok(mut queue) => {
+                        queue.remove_active_task(&task_id);
+                        log_debug!(
+                            "解压任务 [{}] 从活跃任务集合中移除，当前活跃解压任务数: {}",
+                            extract_task_id,
+                            queue.active_tasks.len()
+                        );
+                    }
+                    Err(e) => {
+                        log_error!("无法获取解压队列锁以移除任务 [{}]: {}", extract_task_id, e);
+                    }
                 }
+                crate::notifications::on_task_finished();
             }
-        });
+            .instrument(span),
+        );
     };
 
     let should_continue_fn = || !is_app_shutting_down();
@@ -513,11 +781,16 @@ pub fn start_extract_queue_manager() {
 /// # 参数
 /// - `file_path`: 要解压的文件路径
 /// - `archive_name`: 压缩包名称（用于创建子文件夹）
+/// - `cancel_token`: 解压任务的取消令牌，取消后结束7zG.exe进程
 ///
 /// # 返回值
 /// - 成功时返回包含解压成功信息的Ok
 /// - 失败时返回包含错误信息的Err
-pub async fn extract_with_7zip(file_path: &str, archive_name: &str) -> Result<String, String> {
+pub async fn extract_with_7zip(
+    file_path: &str,
+    archive_name: &str,
+    cancel_token: &CancellationToken,
+) -> Result<String, String> {
     log_debug!(
         "开始解压操作: 文件={}, 子文件夹={}",
         file_path,
@@ -532,7 +805,8 @@ pub async fn extract_with_7zip(file_path: &str, archive_name: &str) -> Result<St
     log_debug!("创建目标解压目录: {}", target_dir.display());
 
     // 如果目标目录已存在，先删除
-    if target_dir.exists() {
+    let replaced_existing = target_dir.exists();
+    if replaced_existing {
         log_debug!("目标目录已存在，先删除: {}", target_dir.display());
         if let Err(e) = std::fs::remove_dir_all(&target_dir) {
             log_warn!("删除已存在的目标目录失败: {}", e);
@@ -545,12 +819,24 @@ pub async fn extract_with_7zip(file_path: &str, archive_name: &str) -> Result<St
         return Err(format!("创建目标解压目录失败: {}", e));
     }
 
-    let args = [
-        "x",         // 解压命令
-        "-y",        // 自动确认
-        "-sccUTF-8", // 设置控制台代码页为UTF-8
-        file_path,   // 要解压的文件
+    let settings = crate::settings::current_settings();
+    let mut args = vec![
+        "x".to_string(),         // 解压命令
+        "-y".to_string(),        // 自动确认
+        "-sccUTF-8".to_string(), // 设置控制台代码页为UTF-8
     ];
+    // 限制解压使用的线程数，未配置时由7z自动决定
+    if settings.extract_threads > 0 {
+        args.push(format!("-mmt{}", settings.extract_threads));
+    }
+    // 没有标记 UTF-8 的 zip 文件名按配置的代码页解码（例如 936 为 GBK），未配置时使用系统默认代码页
+    let is_zip = std::path::Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if is_zip && settings.extract_zip_code_page > 0 {
+        args.push(format!("-mcp={}", settings.extract_zip_code_page));
+    }
+    args.push(file_path.to_string()); // 要解压的文件
 
     log_debug!(
         "执行解压命令: {} {}",
@@ -561,6 +847,10 @@ pub async fn extract_with_7zip(file_path: &str, archive_name: &str) -> Result<St
     // 执行7zG.exe命令
     let mut command = std::process::Command::new(SEVENZG_PATH.as_path());
     command.args(&args);
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(settings.extract_priority.creation_flag());
+    }
 
     // 设置工作目录为目标目录，这样7z会直接解压到这里
     command.current_dir(&target_dir);
@@ -574,19 +864,39 @@ pub async fn extract_with_7zip(file_path: &str, archive_name: &str) -> Result<St
 
     let pid = child.id();
 
+    if let Err(e) = crate::process_priority::set_affinity(&child, settings.extract_cpu_affinity) {
+        log_warn!("7zG[{}] {}", pid, e);
+    }
+
     // 获取stdout和stderr流并进行日志记录
     let stdout = child.stdout.take().ok_or("无法获取stdout流")?;
     let stderr = child.stderr.take().ok_or("无法获取stderr流")?;
 
     redirect_process_output(stdout, stderr, format!("7zG[{}]", pid));
 
-    // 等待进程结束并获取退出状态
-    let output = child
-        .wait_with_output()
-        .map_err(|e| format!("等待7zG.exe进程结束时出错: {}", e))?;
+    // 等待进程结束并获取退出状态，任务被取消时结束进程
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => return Err(format!("等待7zG.exe进程结束时出错: {}", e)),
+        }
+        if cancel_token.sleep(std::time::Duration::from_millis(200)) {
+            log_info!("解压已取消，结束7zG[{}]", pid);
+            let _ = child.kill();
+            let _ = child.wait();
+            if let Err(e) = std::fs::remove_dir_all(&target_dir) {
+                log_warn!("无法删除解压目录: {}", e);
+            }
+            if replaced_existing {
+                report_target_lost(&target_dir, "解压已取消");
+            }
+            return Err(tr!("event.extract.canceled"));
+        }
+    };
 
     // 检查命令执行结果
-    if output.status.success() {
+    if status.success() {
         // 检查解压目录是否有文件
         let file_count = match std::fs::read_dir(&target_dir) {
             Ok(entries) => entries.count(),
@@ -627,21 +937,38 @@ pub async fn extract_with_7zip(file_path: &str, archive_name: &str) -> Result<St
             if let Err(e) = std::fs::remove_dir_all(&target_dir) {
                 log_warn!("无法删除空的解压目录: {}", e);
             }
+            if replaced_existing {
+                report_target_lost(&target_dir, "解压目录为空");
+            }
             Err("解压失败: 解压目录为空，可能文件格式不支持或文件已损坏".to_string())
         }
     } else {
-        // 解析错误输出
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        log_error!("7zG.exe解压失败，stderr: {}, stdout: {}", stderr, stdout);
+        // 错误输出已经通过 redirect_process_output 写入日志
+        let error = format!("7zG.exe退出码: {}", status.code().unwrap_or(-1));
+        log_error!("7zG.exe解压失败，{}", error);
         // 清理目录
         if let Err(e) = std::fs::remove_dir_all(&target_dir) {
             log_warn!("无法删除解压目录: {}", e);
         }
-        Err(format!("解压失败: {}\n\n详细信息:\n{}", stderr, stdout))
+        if replaced_existing {
+            report_target_lost(&target_dir, &error);
+        }
+        Err(format!("解压失败: {}", error))
     }
 }
 
+// 解压前删除的原有目录无法恢复，写入 Windows 事件日志
+fn report_target_lost(target_dir: &std::path::Path, error: &str) {
+    crate::event_log::report_critical(
+        crate::event_log::CriticalEvent::ExtractTargetLost,
+        &format!(
+            "解压失败，解压前已删除原有的目录: {}\r\n{}",
+            target_dir.display(),
+            error
+        ),
+    );
+}
+
 fn validate_archieve(file_path: &str) -> Result<(), String> {
     let file = PathBuf::from(file_path);
     if !file.exists() {
@@ -0,0 +1,276 @@
+// feed_watcher.rs 模块 - 订阅地图发布的 RSS/Atom 源：定期获取 feed_urls 中的订阅源，发现新条目时发送 new-map-available 事件，
+// 开启 feed_auto_queue 时直接把条目中的下载链接加入下载队列
+//
+// 条目中的下载链接来自条目的链接、附件（enclosure）和正文中的 href，只保留内置网站适配器、插件或 host_headers
+// 支持的网站以及路径为压缩包或VPK的链接。已处理的条目ID保存在应用配置目录的 feed_state.json 中；
+// 第一次获取某个订阅源时只记录现有条目，不当作新地图。
+
+// 标准库导入
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    time::Duration,
+};
+
+// 第三方库导入
+use feed_rs::model::Entry;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Url};
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_debug, log_error, log_info, log_warn};
+
+/// 已处理条目记录文件名（位于应用配置目录下）
+const FEED_STATE_FILE: &str = "feed_state.json";
+
+/// 每个订阅源最多保留的已处理条目数
+const MAX_SEEN_ENTRIES: usize = 500;
+
+/// 获取订阅源的请求超时时间
+const FEED_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 检查订阅源设置是否变化的间隔（订阅源本身按 feed_check_interval_minutes 获取）
+const SETTINGS_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 可以直接安装的文件扩展名
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "7z", "rar", "vpk"];
+
+lazy_static! {
+    static ref HREF_REGEX: Regex = Regex::new(r#"href\s*=\s*["']([^"']+)["']"#).unwrap();
+}
+
+/// new-map-available 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewMapAvailable {
+    /// 订阅源地址
+    feed: String,
+    /// 条目ID
+    id: String,
+    /// 条目标题
+    title: Option<String>,
+    /// 条目页面
+    link: Option<String>,
+    /// 发布时间（RFC 3339）
+    published: Option<String>,
+    /// 可以安装的下载链接
+    download_urls: Vec<String>,
+    /// 是否已自动加入下载队列
+    queued: bool,
+}
+
+/// 读取已处理条目记录：订阅源地址 -> 条目ID（从旧到新）
+fn read_state(app_handle: &AppHandle) -> BTreeMap<String, Vec<String>> {
+    crate::config_manager::get_config_path(app_handle, FEED_STATE_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 保存已处理条目记录
+fn write_state(app_handle: &AppHandle, state: &BTreeMap<String, Vec<String>>) {
+    let result =
+        crate::config_manager::get_config_path(app_handle, FEED_STATE_FILE).and_then(|path| {
+            serde_json::to_string_pretty(state)
+                .map_err(|e| e.to_string())
+                .and_then(|content| fs::write(path, content).map_err(|e| e.to_string()))
+        });
+    if let Err(e) = result {
+        log_warn!("保存订阅源记录失败: {}", e);
+    }
+}
+
+/// 链接是否可以安装：受支持的网站，或路径为压缩包或VPK
+fn is_installable(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let has_archive_extension = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, ext)| {
+            ARCHIVE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        });
+    has_archive_extension || crate::host_adapter::is_known_host(url)
+}
+
+/// 提取条目中可以安装的下载链接，按出现顺序去重
+fn download_urls(entry: &Entry, base: Option<&Url>) -> Vec<String> {
+    let mut candidates: Vec<String> = entry.links.iter().map(|link| link.href.clone()).collect();
+    candidates.extend(
+        entry
+            .media
+            .iter()
+            .flat_map(|media| &media.content)
+            .filter_map(|content| content.url.as_ref().map(|url| url.to_string())),
+    );
+    if let Some(content) = &entry.content {
+        candidates.extend(content.src.iter().map(|link| link.href.clone()));
+    }
+    for text in entry
+        .summary
+        .iter()
+        .map(|summary| summary.content.as_str())
+        .chain(
+            entry
+                .content
+                .iter()
+                .filter_map(|content| content.body.as_deref()),
+        )
+    {
+        candidates.extend(
+            HREF_REGEX
+                .captures_iter(text)
+                .map(|captures| captures[1].replace("&amp;", "&")),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .filter_map(|href| match base {
+            Some(base) => base.join(href.trim()).ok(),
+            None => Url::parse(href.trim()).ok(),
+        })
+        .filter(is_installable)
+        .map(|url| url.to_string())
+        .filter(|url| seen.insert(url.clone()))
+        .collect()
+}
+
+/// 获取并解析订阅源
+async fn fetch_feed(feed_url: &str) -> Result<feed_rs::model::Feed, String> {
+    let client = crate::cert_pinning::client_builder()
+        .timeout(FEED_TIMEOUT)
+        .user_agent(crate::aria2c::DEFAULT_USER_AGENT)
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+    let response = client
+        .get(feed_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    crate::cert_pinning::verify_response(&response)?;
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    feed_rs::parser::parse(body.as_ref()).map_err(|e| format!("解析订阅源失败: {}", e))
+}
+
+/// 检查一个订阅源，返回包含下载链接的新条目数
+async fn check_feed(
+    app_handle: &AppHandle,
+    feed_url: &str,
+    state: &mut BTreeMap<String, Vec<String>>,
+    auto_queue: bool,
+) -> Result<usize, String> {
+    let feed = fetch_feed(feed_url).await?;
+    let base = Url::parse(feed_url).ok();
+
+    let first_check = !state.contains_key(feed_url);
+    let seen = state.entry(feed_url.to_string()).or_default();
+    let mut new_entries = 0;
+
+    // 订阅源通常从新到旧排列，按从旧到新的顺序处理
+    for entry in feed.entries.iter().rev() {
+        if seen.contains(&entry.id) {
+            continue;
+        }
+        seen.push(entry.id.clone());
+        if first_check {
+            continue;
+        }
+
+        let urls = download_urls(entry, base.as_ref());
+        if urls.is_empty() {
+            log_debug!(
+                "订阅源条目中没有可以安装的链接: {} ({})",
+                entry.id,
+                feed_url
+            );
+            continue;
+        }
+        new_entries += 1;
+
+        let mut queued = false;
+        if auto_queue {
+            for url in &urls {
+                match crate::commands::install(url, "", false, None, app_handle.clone()).await {
+                    Ok(_) => queued = true,
+                    Err(e) => log_error!("订阅源自动下载失败: {}, 错误: {}", url, e),
+                }
+            }
+        }
+
+        let event = NewMapAvailable {
+            feed: feed_url.to_string(),
+            id: entry.id.clone(),
+            title: entry.title.as_ref().map(|title| title.content.clone()),
+            link: entry.links.first().map(|link| link.href.clone()),
+            published: entry
+                .published
+                .or(entry.updated)
+                .map(|time| time.to_rfc3339()),
+            download_urls: urls,
+            queued,
+        };
+        log_info!(
+            "订阅源发现新地图: {} ({})",
+            event.title.as_deref().unwrap_or(&event.id),
+            feed_url
+        );
+        let _ = crate::event_dispatcher::emit(app_handle, "new-map-available", &event);
+    }
+
+    if seen.len() > MAX_SEEN_ENTRIES {
+        let excess = seen.len() - MAX_SEEN_ENTRIES;
+        seen.drain(..excess);
+    }
+    Ok(new_entries)
+}
+
+/// 检查所有订阅源，删除已不在设置中的订阅源的记录
+async fn check_all_feeds(app_handle: &AppHandle) {
+    let settings = crate::settings::current_settings();
+    let mut state = read_state(app_handle);
+    state.retain(|feed_url, _| settings.feed_urls.contains(feed_url));
+
+    for feed_url in &settings.feed_urls {
+        match check_feed(app_handle, feed_url, &mut state, settings.feed_auto_queue).await {
+            Ok(0) => log_debug!("订阅源没有新地图: {}", feed_url),
+            Ok(count) => log_info!("订阅源 {} 有 {} 个新地图", feed_url, count),
+            Err(e) => log_warn!("检查订阅源失败: {}, 错误: {}", feed_url, e),
+        }
+    }
+    write_state(app_handle, &state);
+}
+
+/// 启动订阅源检查任务 - 按 feed_check_interval_minutes 定期检查，订阅源列表变化时立即检查
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn start_feed_watcher(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_feeds = Vec::new();
+        let mut elapsed = Duration::ZERO;
+        while !is_app_shutting_down() {
+            let settings = crate::settings::current_settings();
+            let interval = Duration::from_secs(settings.feed_check_interval_minutes * 60);
+            if !settings.feed_urls.is_empty()
+                && (settings.feed_urls != last_feeds || elapsed >= interval)
+            {
+                check_all_feeds(&app_handle).await;
+                last_feeds = settings.feed_urls.clone();
+                elapsed = Duration::ZERO;
+            }
+
+            tokio::time::sleep(SETTINGS_POLL_INTERVAL).await;
+            elapsed += SETTINGS_POLL_INTERVAL;
+        }
+    });
+}
@@ -0,0 +1,192 @@
+// file_mover.rs 模块 - 移动文件和目录：同一磁盘时直接重命名；跨磁盘时分块复制并报告进度，
+// 复制完成后校验内容（SHA-256）一致再删除原文件，取消或失败时删除已复制的部分，原文件保持不变
+
+// 标准库导入
+use std::{
+    fs::{self, File},
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+
+// 第三方库导入
+use sha2::{Digest, Sha256};
+
+// 内部模块导入
+use crate::{cancellation::CancellationToken, log_info, log_warn};
+
+/// 每次复制的块大小
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 计算文件或目录的总大小（不跟随符号链接）
+fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| path_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// 计算文件的 SHA-256
+fn file_digest(path: &Path) -> Result<Vec<u8>, String> {
+    let file = File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut BufReader::new(file), &mut hasher)
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    Ok(hasher.finalize().to_vec())
+}
+
+/// 分块复制文件并校验，每复制一块调用一次 on_chunk
+fn copy_file<F: FnMut(u64)>(
+    source: &Path,
+    dest: &Path,
+    token: &CancellationToken,
+    on_chunk: &mut F,
+) -> Result<(), String> {
+    let mut reader = File::open(source).map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut writer = File::create(dest).map_err(|e| format!("创建文件失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+
+    loop {
+        if token.is_cancelled() {
+            return Err("移动已取消".to_string());
+        }
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|e| format!("写入文件失败: {}", e))?;
+        hasher.update(&buffer[..read]);
+        on_chunk(read as u64);
+    }
+    writer
+        .sync_all()
+        .map_err(|e| format!("写入文件失败: {}", e))?;
+    drop(writer);
+
+    // 保留原文件的修改时间，下载缓存和压缩包缓存按修改时间清理
+    if let Ok(modified) = fs::metadata(source).and_then(|metadata| metadata.modified()) {
+        let _ = File::options()
+            .write(true)
+            .open(dest)
+            .and_then(|file| file.set_modified(modified));
+    }
+
+    if file_digest(dest)? != hasher.finalize().to_vec() {
+        return Err(format!("复制后的文件校验失败: {}", dest.display()));
+    }
+    Ok(())
+}
+
+/// 递归复制目录
+fn copy_tree<F: FnMut(u64)>(
+    source: &Path,
+    dest: &Path,
+    token: &CancellationToken,
+    on_chunk: &mut F,
+) -> Result<(), String> {
+    let metadata = fs::symlink_metadata(source).map_err(|e| format!("读取文件信息失败: {}", e))?;
+    if !metadata.is_dir() {
+        return copy_file(source, dest, token, on_chunk);
+    }
+
+    fs::create_dir_all(dest).map_err(|e| format!("创建目录失败: {}", e))?;
+    let entries = fs::read_dir(source).map_err(|e| format!("读取目录失败: {}", e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("读取目录失败: {}", e))?;
+        copy_tree(
+            &entry.path(),
+            &dest.join(entry.file_name()),
+            token,
+            on_chunk,
+        )?;
+    }
+    Ok(())
+}
+
+/// 删除文件或目录
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// 移动文件或目录 - 同一磁盘时直接重命名，否则复制、校验后删除原文件
+///
+/// 跨磁盘复制期间检查取消令牌，取消或失败时删除已复制的部分，原文件保持不变。
+///
+/// # 参数
+/// - `source`: 原路径
+/// - `dest`: 目标路径，不能已存在
+/// - `token`: 取消令牌
+/// - `on_progress`: 进度回调，参数为（已复制字节数, 总字节数），直接重命名时只调用一次
+///
+/// # 返回值
+/// - 成功时返回Ok(())，失败或被取消时返回错误信息
+pub fn move_path<F: FnMut(u64, u64)>(
+    source: &Path,
+    dest: &Path,
+    token: &CancellationToken,
+    mut on_progress: F,
+) -> Result<(), String> {
+    if dest.exists() {
+        return Err(format!("目标已存在: {}", dest.display()));
+    }
+    let total = path_size(source);
+
+    if fs::rename(source, dest).is_ok() {
+        on_progress(total, total);
+        return Ok(());
+    }
+
+    // 不在同一磁盘（或无法重命名），改为复制
+    log_info!(
+        "跨磁盘移动: {} -> {}, 共 {} 字节",
+        source.display(),
+        dest.display(),
+        total
+    );
+    let mut copied = 0u64;
+    let result = copy_tree(source, dest, token, &mut |bytes| {
+        copied += bytes;
+        on_progress(copied, total);
+    });
+    if let Err(e) = result {
+        if dest.exists() {
+            if let Err(remove_error) = remove_path(dest) {
+                log_warn!(
+                    "删除未完成的复制失败: {}, 错误: {}",
+                    dest.display(),
+                    remove_error
+                );
+            }
+        }
+        return Err(e);
+    }
+
+    if let Err(e) = remove_path(source) {
+        if source.is_dir() {
+            // 目录可能已被部分删除，保留完整的副本
+            log_warn!("删除原目录失败: {}, 错误: {}", source.display(), e);
+            return Ok(());
+        }
+        let _ = fs::remove_file(dest);
+        return Err(format!("删除原文件失败: {}", e));
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// game_monitor.rs 模块 - 检测 Left 4 Dead 2 是否正在运行，游戏启动和退出时通知前端并调整后台下载的优先级
+
+// 标准库导入
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use tauri::AppHandle;
+use windows_sys::Win32::{
+    Foundation::{CloseHandle, INVALID_HANDLE_VALUE},
+    System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    },
+};
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_info};
+
+/// 游戏进程名
+const GAME_PROCESS_NAME: &str = "left4dead2.exe";
+
+/// 检查游戏是否运行的间隔
+const GAME_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    /// 监视线程最后一次观察到的游戏运行状态
+    static ref GAME_RUNNING: AtomicBool = AtomicBool::new(false);
+}
+
+/// 遍历进程列表，检查是否存在指定名称的进程
+fn find_process(name: &str) -> bool {
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return false;
+        }
+
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut found = false;
+        let mut has_entry = Process32FirstW(snapshot, &mut entry) != 0;
+        while has_entry {
+            let length = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            if String::from_utf16_lossy(&entry.szExeFile[..length]).eq_ignore_ascii_case(name) {
+                found = true;
+                break;
+            }
+            has_entry = Process32NextW(snapshot, &mut entry) != 0;
+        }
+
+        CloseHandle(snapshot);
+        found
+    }
+}
+
+/// 游戏是否正在运行（监视线程最后一次检查的结果）
+pub fn is_game_running() -> bool {
+    GAME_RUNNING.load(Ordering::Relaxed)
+}
+
+/// 启动游戏监视线程，状态变化时向前端发送 game-started / game-stopped 事件并调整aria2c优先级
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+pub fn start_game_monitor(app_handle: AppHandle) {
+    thread::spawn(move || {
+        while !is_app_shutting_down() {
+            let running = find_process(GAME_PROCESS_NAME);
+            if GAME_RUNNING.swap(running, Ordering::Relaxed) != running {
+                if running {
+                    log_info!("检测到游戏已启动");
+                    let _ = crate::event_dispatcher::emit(&app_handle, "game-started", ());
+                } else {
+                    log_info!("检测到游戏已退出");
+                    let _ = crate::event_dispatcher::emit(&app_handle, "game-stopped", ());
+                }
+                crate::aria2c::apply_aria2_priority();
+            }
+            thread::sleep(GAME_CHECK_INTERVAL);
+        }
+    });
+}
@@ -6,7 +6,7 @@
 use std::{
     env, fs,
     io::{BufRead, BufReader, BufWriter, Write},
-    path::Path,
+    path::{Path, PathBuf},
     process,
 };
 
@@ -29,7 +29,7 @@ mod windows {
             .iter()
             .map(|arg| {
                 if arg.contains(' ') {
-                    format!("{}", arg)
+                    format!("\"{}\"", arg)
                 } else {
                     arg.to_string()
                 }
@@ -70,6 +70,38 @@ mod windows {
             Err(format!("请求管理员权限失败，错误码: {:?}", result))
         }
     }
+
+    /// 注册 Windows 事件日志的事件源（与应用的 event_log 模块使用相同的名称），需要管理员权限
+    pub fn register_event_source() -> Result<(), String> {
+        use winreg::{
+            enums::{RegType, HKEY_LOCAL_MACHINE},
+            RegKey, RegValue,
+        };
+
+        const EVENT_SOURCE_KEY: &str =
+            r"SYSTEM\CurrentControlSet\Services\EventLog\Application\Nyaser Maps Downloader";
+        const EVENT_MESSAGE_FILE: &str =
+            r"%SystemRoot%\Microsoft.NET\Framework64\v4.0.30319\EventLogMessages.dll";
+
+        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        if hklm.open_subkey(EVENT_SOURCE_KEY).is_ok() {
+            return Ok(());
+        }
+        let (key, _) = hklm
+            .create_subkey(EVENT_SOURCE_KEY)
+            .map_err(|e| format!("注册事件源失败: {}", e))?;
+        let message_file = RegValue {
+            vtype: RegType::REG_EXPAND_SZ,
+            bytes: OsStr::new(EVENT_MESSAGE_FILE)
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .flat_map(|c| c.to_le_bytes())
+                .collect(),
+        };
+        key.set_raw_value("EventMessageFile", &message_file)
+            .and_then(|_| key.set_value("TypesSupported", &7u32))
+            .map_err(|e| format!("注册事件源失败: {}", e))
+    }
 }
 
 /// 符号链接操作类型
@@ -143,8 +175,74 @@ fn create_file_symlink(
     Ok(format!("符号链接创建成功: {}", link_path.display()))
 }
 
+/// 检查路径是否位于允许的根目录之下
+///
+/// 只比较路径所在的父目录（规范化后），避免符号链接被解析到目标位置，
+/// 同时禁止直接删除根目录本身。
+fn resolve_allowed_path(path: &str, allowed_roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("无效的路径: {}", path.display()))?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("无效的路径: {}", path.display()))?;
+    let parent = fs::canonicalize(parent)
+        .map_err(|e| format!("无法解析路径: {}, 错误: {:?}", path.display(), e))?;
+    let resolved = parent.join(file_name);
+
+    for root in allowed_roots {
+        if let Ok(root) = fs::canonicalize(root) {
+            if resolved != root && resolved.starts_with(&root) {
+                return Ok(resolved);
+            }
+        }
+    }
+
+    Err(format!("路径不在允许的目录范围内: {}", path.display()))
+}
+
+/// 删除文件、符号链接或目录
+fn delete_path(path: &str, allowed_roots: &[PathBuf]) -> Result<String, String> {
+    let path = resolve_allowed_path(path, allowed_roots)?;
+
+    let metadata = fs::symlink_metadata(&path)
+        .map_err(|e| format!("路径不存在: {}, 错误: {:?}", path.display(), e))?;
+
+    // 清除只读属性，避免因只读文件导致删除失败
+    if !metadata.file_type().is_symlink() {
+        let mut permissions = metadata.permissions();
+        if permissions.readonly() {
+            // 助手程序只在 Windows 上运行，不存在 Unix 上文件变为所有人可写的问题
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+            let _ = fs::set_permissions(&path, permissions);
+        }
+    }
+
+    if metadata.file_type().is_symlink() {
+        // 目录符号链接需要使用 remove_dir 删除
+        fs::remove_file(&path)
+            .or_else(|_| fs::remove_dir(&path))
+            .map_err(|e| format!("删除符号链接失败: {:?}", e))?;
+    } else if metadata.is_dir() {
+        fs::remove_dir_all(&path).map_err(|e| format!("删除目录失败: {:?}", e))?;
+    } else {
+        fs::remove_file(&path).map_err(|e| format!("删除文件失败: {:?}", e))?;
+    }
+
+    println!("删除成功: {}", path.display());
+    Ok(format!("删除成功: {}", path.display()))
+}
+
 /// 处理单个客户端连接
-fn handle_client(mut stream: std::net::TcpStream, server_token: &str) {
+fn handle_client(
+    mut stream: std::net::TcpStream,
+    server_token: &str,
+    allowed_roots: &std::sync::RwLock<Vec<PathBuf>>,
+    last_activity: &std::sync::Mutex<std::time::Instant>,
+) {
     // 不使用split，直接使用同一个流
     loop {
         // 读取一行消息
@@ -162,6 +260,8 @@ fn handle_client(mut stream: std::net::TcpStream, server_token: &str) {
                     // 解析消息
                     message = message.trim().to_string();
                     println!("收到消息: {}", message);
+                    // 记录活动时间
+                    *last_activity.lock().unwrap() = std::time::Instant::now();
                 }
                 Err(e) => {
                     println!("读取消息失败: {:?}", e);
@@ -257,6 +357,63 @@ fn handle_client(mut stream: std::net::TcpStream, server_token: &str) {
                                 }
                             }
                         }
+                        "delete" => {
+                            // delete命令需要args.path参数
+                            if let Some(path) = msg
+                                .get("args")
+                                .and_then(|a| a.get("path"))
+                                .and_then(|p| p.as_str())
+                            {
+                                let allowed_roots = allowed_roots.read().unwrap();
+                                match delete_path(path, &allowed_roots) {
+                                    Ok(message) => SymlinkResponse {
+                                        success: true,
+                                        message,
+                                    },
+                                    Err(error) => SymlinkResponse {
+                                        success: false,
+                                        message: error,
+                                    },
+                                }
+                            } else {
+                                SymlinkResponse {
+                                    success: false,
+                                    message: "缺少必要参数".to_string(),
+                                }
+                            }
+                        }
+                        // 更新允许删除的根目录（主程序的数据目录或 addons 目录变化后发送）
+                        "set_allowed_roots" => {
+                            if let Some(roots) = msg
+                                .get("args")
+                                .and_then(|a| a.get("roots"))
+                                .and_then(|r| r.as_array())
+                            {
+                                let roots = roots
+                                    .iter()
+                                    .filter_map(|root| root.as_str())
+                                    .map(PathBuf::from)
+                                    .collect::<Vec<_>>();
+                                for root in &roots {
+                                    println!("允许删除的目录: {}", root.display());
+                                }
+                                *allowed_roots.write().unwrap() = roots;
+                                SymlinkResponse {
+                                    success: true,
+                                    message: "已更新允许删除的目录".to_string(),
+                                }
+                            } else {
+                                SymlinkResponse {
+                                    success: false,
+                                    message: "缺少必要参数".to_string(),
+                                }
+                            }
+                        }
+                        // 保活心跳，不需要args参数
+                        "ping" => SymlinkResponse {
+                            success: true,
+                            message: "pong".to_string(),
+                        },
                         // 可以在这里添加其他不需要args的命令
                         _ => SymlinkResponse {
                             success: false,
@@ -290,9 +447,12 @@ fn handle_client(mut stream: std::net::TcpStream, server_token: &str) {
 fn start_server() {
     println!("启动符号链接服务器...");
 
-    // 从命令行参数中读取端口号和token，支持 --port/-p 和 --token/-t 参数
+    // 从命令行参数中读取端口号、token、空闲超时和允许删除的根目录
+    // 支持 --port/-p、--token/-t、--idle-timeout/-i 和 --allow/-a（可重复）参数
     let mut port = 0; // 默认随机端口
     let mut token = String::new(); // 默认空token
+    let mut idle_timeout_secs: u64 = 3; // 默认无连接3秒后退出
+    let mut allowed_roots: Vec<PathBuf> = Vec::new(); // 默认不允许删除任何路径
     let args: Vec<String> = std::env::args().collect();
 
     for i in 1..args.len() {
@@ -302,6 +462,12 @@ fn start_server() {
             }
         } else if (args[i] == "--token" || args[i] == "-t") && i + 1 < args.len() {
             token = args[i + 1].clone();
+        } else if (args[i] == "--idle-timeout" || args[i] == "-i") && i + 1 < args.len() {
+            if let Ok(secs) = args[i + 1].parse::<u64>() {
+                idle_timeout_secs = secs;
+            }
+        } else if (args[i] == "--allow" || args[i] == "-a") && i + 1 < args.len() {
+            allowed_roots.push(PathBuf::from(&args[i + 1]));
         }
     }
 
@@ -318,25 +484,32 @@ fn start_server() {
     if !token.is_empty() {
         println!("Token验证已启用");
     }
+    println!("空闲超时: {} 秒", idle_timeout_secs);
+    for root in &allowed_roots {
+        println!("允许删除的目录: {}", root.display());
+    }
 
     // 跟踪连接数
     let connection_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
     let shutdown_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
     let server_token = std::sync::Arc::new(token);
+    let allowed_roots = std::sync::Arc::new(std::sync::RwLock::new(allowed_roots));
+    // 最后一次活动时间（新连接、收到消息或连接断开）
+    let last_activity = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
 
     // 启动监控线程
     {
         let connection_count = connection_count.clone();
         let shutdown_flag = shutdown_flag.clone();
+        let last_activity = last_activity.clone();
         std::thread::spawn(move || {
+            let idle_timeout = std::time::Duration::from_secs(idle_timeout_secs);
             loop {
-                // 检查是否有连接
+                // 没有连接且空闲时间超过阈值时退出
                 if connection_count.load(std::sync::atomic::Ordering::Relaxed) == 0 {
-                    // 如果没有连接，等待一段时间后退出
-                    println!("没有客户端连接，准备退出...");
-                    std::thread::sleep(std::time::Duration::from_secs(3));
-                    if connection_count.load(std::sync::atomic::Ordering::Relaxed) == 0 {
-                        println!("确认没有客户端连接，退出服务器");
+                    let idle = last_activity.lock().unwrap().elapsed();
+                    if idle >= idle_timeout {
+                        println!("没有客户端连接且已空闲 {} 秒，退出服务器", idle.as_secs());
                         shutdown_flag.store(true, std::sync::atomic::Ordering::Relaxed);
                         std::process::exit(0);
                     }
@@ -361,9 +534,13 @@ fn start_server() {
                 // 为客户端创建一个线程
                 let connection_count = connection_count.clone();
                 let server_token = server_token.clone();
+                let allowed_roots = allowed_roots.clone();
+                let last_activity = last_activity.clone();
+                *last_activity.lock().unwrap() = std::time::Instant::now();
                 std::thread::spawn(move || {
-                    handle_client(stream, &server_token);
-                    // 连接关闭时减少连接数
+                    handle_client(stream, &server_token, &allowed_roots, &last_activity);
+                    // 连接关闭时减少连接数，并从此刻开始计算空闲时间
+                    *last_activity.lock().unwrap() = std::time::Instant::now();
                     let count = connection_count.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
                     println!("客户端连接已关闭，当前连接数: {}", count - 1);
                 });
@@ -444,6 +621,12 @@ fn main() {
         }
     }
 
+    // 尝试注册事件源（只有以管理员权限运行时才能成功），之后应用可以写入格式化的事件日志
+    #[cfg(windows)]
+    if let Err(e) = windows::register_event_source() {
+        println!("{}", e);
+    }
+
     // 直接启动服务器模式
     start_server();
 }
@@ -0,0 +1,660 @@
+// host_adapter.rs 模块 - 按下载链接所在的网站提取文件名、转换直链并提供下载所需的请求头
+//
+// 每个网站实现一个 HostAdapter，按 HOST_ADAPTERS 中的顺序匹配，最后由通用适配器兜底。
+// 支持新网站时只需添加一个适配器，不需要修改下载和解压流程。
+
+// 标准库导入
+use std::time::Duration;
+
+// 第三方库导入
+use base64::Engine;
+use lazy_static::lazy_static;
+use regex::Regex;
+use tauri::Url;
+
+// 内部模块导入
+use crate::{
+    log_debug, log_info, log_warn,
+    utils::{decode_text, percent_decode},
+};
+
+/// 添加任务前请求直链的超时时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 最多处理的确认页面次数
+const MAX_CONFIRM_PAGES: usize = 2;
+
+/// 第一方地图站点的域名（包括其子域名）
+const FIRST_PARTY_DOMAINS: &[&str] = &["nyase.ru"];
+
+lazy_static! {
+    static ref FILENAME_REGEX: Regex = Regex::new(r"\/([^\/?]+)(\?.*)?$").unwrap();
+    static ref CONTENT_DISPOSITION_EXT_REGEX: Regex =
+        Regex::new(r#"(?i)filename\*\s*=\s*(?:([\w-]+)'[^']*')?"?([^";]+)"?"#).unwrap();
+    static ref CONTENT_DISPOSITION_REGEX: Regex =
+        Regex::new(r#"(?i)filename\s*=\s*(?:"([^"]*)"|([^;]+))"#).unwrap();
+    static ref DRIVE_FORM_REGEX: Regex =
+        Regex::new(r#"<form[^>]*id="download-form"[^>]*action="([^"]+)""#).unwrap();
+    static ref DRIVE_INPUT_REGEX: Regex =
+        Regex::new(r#"<input[^>]*type="hidden"[^>]*name="([^"]+)"[^>]*value="([^"]*)""#).unwrap();
+
+    /// 已注册的网站适配器，按顺序匹配，GenericAdapter 必须位于最后
+    static ref HOST_ADAPTERS: Vec<Box<dyn HostAdapter>> = vec![
+        Box::new(BaiduPcsAdapter),
+        Box::new(DropboxAdapter),
+        Box::new(GitHubAdapter),
+        Box::new(GoogleDriveAdapter),
+        Box::new(OneDriveAdapter),
+        Box::new(GenericAdapter),
+    ];
+}
+
+/// 网站适配器 - 描述某个网站的下载链接如何处理
+pub trait HostAdapter: Send + Sync {
+    /// 适配器名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 是否处理该链接
+    fn matches(&self, url: &Url) -> bool;
+
+    /// 从链接中提取文件名
+    fn file_name(&self, url: &Url) -> Option<String> {
+        file_name_from_path(url.as_str())
+    }
+
+    /// 将分享页链接转换为可直接下载的链接，不需要转换时返回None
+    fn direct_url(&self, _url: &Url) -> Option<Url> {
+        None
+    }
+
+    /// 下载时使用的 User-Agent，返回None时使用默认值
+    fn user_agent(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// 下载时需要附加的请求头
+    fn headers(&self, _url: &Url) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// 添加任务前是否需要请求一次直链，用于处理确认页面和获取服务器返回的文件名
+    fn probe_before_queueing(&self) -> bool {
+        false
+    }
+
+    /// 直链返回的是确认页面（例如网盘的大文件病毒扫描提示）时，从页面中解析出真正的下载地址
+    fn confirm_page(&self, _html: &str, _url: &Url) -> Option<Url> {
+        None
+    }
+}
+
+/// 添加任务前解析得到的下载链接
+#[derive(Debug, Clone)]
+pub struct ResolvedLink {
+    /// 可直接下载的链接
+    pub url: String,
+    /// 服务器返回的文件名（Content-Disposition），没有时为None
+    pub file_name: Option<String>,
+}
+
+/// 从链接路径的最后一段提取文件名
+fn file_name_from_path(url: &str) -> Option<String> {
+    let caps = FILENAME_REGEX.captures(url)?;
+    let name = caps.get(1)?;
+    Some(percent_decode(name.as_str())).filter(|name| !name.is_empty())
+}
+
+/// 检查主机名是否为指定域名或其子域名
+pub(crate) fn is_host(url: &Url, domain: &str) -> bool {
+    url.host_str().is_some_and(|host| {
+        host.eq_ignore_ascii_case(domain)
+            || host.to_ascii_lowercase().ends_with(&format!(".{}", domain))
+    })
+}
+
+/// 百度网盘 PCS 直链 - 文件名保存在 fin 参数中，下载时需要使用百度网盘的 User-Agent
+struct BaiduPcsAdapter;
+
+impl HostAdapter for BaiduPcsAdapter {
+    fn name(&self) -> &'static str {
+        "baidupcs"
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        is_host(url, "baidupcs.com") && url.path().starts_with("/file/")
+    }
+
+    fn file_name(&self, url: &Url) -> Option<String> {
+        // query_pairs 会把 + 解码为空格，与网盘生成链接时的编码方式一致
+        url.query_pairs()
+            .find(|(key, _)| key == "fin")
+            .map(|(_, value)| value.to_string())
+            .filter(|name| !name.is_empty())
+    }
+
+    fn user_agent(&self) -> Option<&'static str> {
+        Some("pan.baidu.com")
+    }
+}
+
+/// Dropbox 分享链接 - dl=0 打开预览页，dl=1 直接下载
+struct DropboxAdapter;
+
+impl HostAdapter for DropboxAdapter {
+    fn name(&self) -> &'static str {
+        "dropbox"
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        is_host(url, "dropbox.com")
+    }
+
+    fn direct_url(&self, url: &Url) -> Option<Url> {
+        if url
+            .query_pairs()
+            .any(|(key, value)| key == "dl" && value == "1")
+        {
+            return None;
+        }
+
+        let mut direct = url.clone();
+        let pairs = url
+            .query_pairs()
+            .filter(|(key, _)| key != "dl")
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<Vec<_>>();
+        direct
+            .query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair("dl", "1");
+        Some(direct)
+    }
+}
+
+/// GitHub 文件页面 - github.com/{owner}/{repo}/blob/{ref}/{path} 转换为 raw.githubusercontent.com 直链
+struct GitHubAdapter;
+
+impl HostAdapter for GitHubAdapter {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| host.eq_ignore_ascii_case("github.com"))
+    }
+
+    fn direct_url(&self, url: &Url) -> Option<Url> {
+        let segments = url.path_segments()?.collect::<Vec<_>>();
+        match segments.as_slice() {
+            [owner, repo, "blob", rest @ ..] if rest.len() >= 2 => Url::parse(&format!(
+                "https://raw.githubusercontent.com/{}/{}/{}",
+                owner,
+                repo,
+                rest.join("/")
+            ))
+            .ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Google Drive 分享链接 - 转换为 drive.usercontent.google.com 下载地址，大文件会先返回病毒扫描确认页面
+struct GoogleDriveAdapter;
+
+impl GoogleDriveAdapter {
+    /// 从分享链接中提取文件ID，支持 /file/d/{id}/view 和 ?id={id} 两种格式
+    fn file_id(url: &Url) -> Option<String> {
+        let segments = url.path_segments()?.collect::<Vec<_>>();
+        if let Some(index) = segments.iter().position(|segment| *segment == "d") {
+            if let Some(id) = segments.get(index + 1).filter(|id| !id.is_empty()) {
+                return Some(id.to_string());
+            }
+        }
+        url.query_pairs()
+            .find(|(key, _)| key == "id")
+            .map(|(_, value)| value.to_string())
+    }
+}
+
+impl HostAdapter for GoogleDriveAdapter {
+    fn name(&self) -> &'static str {
+        "google-drive"
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        url.host_str().is_some_and(|host| {
+            [
+                "drive.google.com",
+                "docs.google.com",
+                "drive.usercontent.google.com",
+            ]
+            .iter()
+            .any(|domain| host.eq_ignore_ascii_case(domain))
+        })
+    }
+
+    fn file_name(&self, _url: &Url) -> Option<String> {
+        // 链接中只有文件ID，文件名在添加任务时从服务器响应中获取
+        None
+    }
+
+    fn direct_url(&self, url: &Url) -> Option<Url> {
+        if url
+            .host_str()
+            .is_some_and(|host| host.eq_ignore_ascii_case("drive.usercontent.google.com"))
+        {
+            return None;
+        }
+
+        let id = Self::file_id(url)?;
+        let mut direct = Url::parse("https://drive.usercontent.google.com/download").ok()?;
+        direct
+            .query_pairs_mut()
+            .append_pair("id", &id)
+            .append_pair("export", "download");
+        Some(direct)
+    }
+
+    fn probe_before_queueing(&self) -> bool {
+        true
+    }
+
+    fn confirm_page(&self, html: &str, url: &Url) -> Option<Url> {
+        let action = DRIVE_FORM_REGEX
+            .captures(html)?
+            .get(1)?
+            .as_str()
+            .replace("&amp;", "&");
+        let mut confirmed = url.join(&action).ok()?;
+        {
+            let mut pairs = confirmed.query_pairs_mut();
+            for caps in DRIVE_INPUT_REGEX.captures_iter(html) {
+                pairs.append_pair(&caps[1], &caps[2].replace("&amp;", "&"));
+            }
+        }
+        Some(confirmed)
+    }
+}
+
+/// OneDrive 分享链接 - 个人版使用分享 API 转换为直链，SharePoint（商业版）链接追加 download=1
+struct OneDriveAdapter;
+
+impl HostAdapter for OneDriveAdapter {
+    fn name(&self) -> &'static str {
+        "onedrive"
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        is_host(url, "1drv.ms")
+            || is_host(url, "onedrive.live.com")
+            || is_host(url, "sharepoint.com")
+    }
+
+    fn file_name(&self, _url: &Url) -> Option<String> {
+        // 分享链接中没有文件名，在添加任务时从服务器响应中获取
+        None
+    }
+
+    fn direct_url(&self, url: &Url) -> Option<Url> {
+        if is_host(url, "sharepoint.com") {
+            if url.query_pairs().any(|(key, _)| key == "download") {
+                return None;
+            }
+            let mut direct = url.clone();
+            direct.query_pairs_mut().append_pair("download", "1");
+            return Some(direct);
+        }
+
+        // https://learn.microsoft.com/onedrive/developer/rest-api/api/shares_get
+        let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(url.as_str());
+        Url::parse(&format!(
+            "https://api.onedrive.com/v1.0/shares/u!{}/root/content",
+            encoded
+        ))
+        .ok()
+    }
+
+    fn probe_before_queueing(&self) -> bool {
+        true
+    }
+}
+
+/// 通用适配器 - 匹配所有链接，使用路径最后一段作为文件名
+struct GenericAdapter;
+
+impl HostAdapter for GenericAdapter {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn matches(&self, _url: &Url) -> bool {
+        true
+    }
+}
+
+/// 查找处理该链接的适配器
+///
+/// # 参数
+/// - `url`: 已解析的下载链接
+///
+/// # 返回值
+/// - 第一个匹配的适配器，没有专用适配器时返回通用适配器
+pub fn adapter_for(url: &Url) -> &'static dyn HostAdapter {
+    HOST_ADAPTERS
+        .iter()
+        .find(|adapter| adapter.matches(url))
+        .map(|adapter| adapter.as_ref())
+        .unwrap_or(&GenericAdapter)
+}
+
+/// 是否为已知的地图下载网站 - 第一方站点、有专用适配器的网站，或在 host_headers 中配置过的网站
+///
+/// # 参数
+/// - `url`: 已解析的下载链接
+pub fn is_known_host(url: &Url) -> bool {
+    FIRST_PARTY_DOMAINS
+        .iter()
+        .any(|domain| is_host(url, domain))
+        || adapter_for(url).name() != "generic"
+        || crate::settings::current_settings()
+            .host_headers
+            .keys()
+            .any(|domain| is_host(url, domain.trim_start_matches('.')))
+}
+
+/// 从下载链接中提取文件名
+///
+/// # 参数
+/// - `url`: 下载链接
+///
+/// # 返回值
+/// - 提取到的文件名，无法提取时返回None
+pub fn file_name(url: &str) -> Option<String> {
+    match Url::parse(url) {
+        Ok(parsed) => adapter_for(&parsed).file_name(&parsed),
+        Err(_) => file_name_from_path(url),
+    }
+}
+
+/// 获取可直接下载的链接 - 分享页链接会被转换为直链，其他链接原样返回
+///
+/// # 参数
+/// - `url`: 下载链接
+pub fn direct_url(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    let adapter = adapter_for(&parsed);
+    match adapter.direct_url(&parsed) {
+        Some(direct) => {
+            log_debug!("[{}] 转换为直链: {} -> {}", adapter.name(), url, direct);
+            direct.to_string()
+        }
+        None => url.to_string(),
+    }
+}
+
+/// 获取下载链接在备用镜像上的地址（config.json 的 download_mirrors），按配置顺序排列，不包括原地址
+///
+/// # 参数
+/// - `url`: 下载链接
+///
+/// # 返回值
+/// - 将主机名替换为各个镜像后的链接，没有配置镜像时返回空列表
+pub fn mirror_urls(url: &str) -> Vec<String> {
+    let Ok(parsed) = Url::parse(url) else {
+        return Vec::new();
+    };
+
+    let mut mirrors = Vec::new();
+    for (domain, hosts) in &crate::settings::current_settings().download_mirrors {
+        if !is_host(&parsed, domain.trim_start_matches('.')) {
+            continue;
+        }
+        for host in hosts {
+            let mut mirror = parsed.clone();
+            if mirror.set_host(Some(host.trim())).is_err() {
+                log_warn!("忽略无效的镜像主机名: {}", host);
+                continue;
+            }
+            let mirror = mirror.to_string();
+            if mirror != url && !mirrors.contains(&mirror) {
+                mirrors.push(mirror);
+            }
+        }
+    }
+    mirrors
+}
+
+/// 获取配置中该链接所在网站的附加请求头（config.json 的 host_headers）
+fn configured_headers(url: &Url) -> Vec<(String, String)> {
+    crate::settings::current_settings()
+        .host_headers
+        .iter()
+        .filter(|(domain, _)| is_host(url, domain.trim_start_matches('.')))
+        .flat_map(|(_, headers)| headers.clone())
+        .collect()
+}
+
+/// 下载该链接时使用的 User-Agent 和附加请求头
+///
+/// 请求头来自匹配的适配器和配置中的 host_headers，同名请求头以配置为准（不区分大小写）；
+/// 名称或值中包含换行等非法字符的请求头会被忽略
+///
+/// # 参数
+/// - `url`: 下载链接
+///
+/// # 返回值
+/// - (User-Agent, 请求头列表)，User-Agent 为None时使用默认值
+pub fn request_options(url: &str) -> (Option<&'static str>, Vec<(String, String)>) {
+    let Ok(parsed) = Url::parse(url) else {
+        return (None, Vec::new());
+    };
+
+    let adapter = adapter_for(&parsed);
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for (name, value) in adapter
+        .headers(&parsed)
+        .into_iter()
+        .chain(configured_headers(&parsed))
+    {
+        let name = name.trim().to_string();
+        let value = value.trim().to_string();
+        let is_valid = !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_graphic() && c != ':')
+            && !value.chars().any(|c| c.is_control());
+        if !is_valid {
+            log_warn!("忽略无效的请求头: {}", name);
+            continue;
+        }
+
+        headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        headers.push((name, value));
+    }
+
+    (adapter.user_agent(), headers)
+}
+
+/// 从 Content-Disposition 响应头中提取文件名，优先使用 RFC 5987 编码的 filename*（按其中声明的字符集解码）
+///
+/// # 参数
+/// - `value`: Content-Disposition 响应头的值，可以用 header_text 从响应头转换
+pub fn content_disposition_file_name(value: &str) -> Option<String> {
+    if let Some(caps) = CONTENT_DISPOSITION_EXT_REGEX.captures(value) {
+        let bytes = urlencoding::decode_binary(caps[2].trim().as_bytes());
+        let decoded = caps
+            .get(1)
+            .and_then(|charset| encoding_rs::Encoding::for_label(charset.as_str().as_bytes()))
+            .filter(|encoding| *encoding != encoding_rs::UTF_8)
+            .map(|encoding| encoding.decode_without_bom_handling(&bytes).0.into_owned())
+            .unwrap_or_else(|| decode_text(&bytes));
+        if !decoded.is_empty() {
+            return Some(decoded);
+        }
+    }
+
+    let caps = CONTENT_DISPOSITION_REGEX.captures(value)?;
+    caps.get(1)
+        .or_else(|| caps.get(2))
+        .map(|name| name.as_str().trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// 将响应头的值转换为字符串 - 很多服务器在 filename 中直接使用 UTF-8 或 GBK 字节，
+/// HeaderValue::to_str 只接受 ASCII，这里按 decode_text 解码
+pub fn header_text(value: &reqwest::header::HeaderValue) -> String {
+    decode_text(value.as_bytes())
+}
+
+/// 请求一次直链，返回确认页面解析出的下一个地址，或服务器返回的文件名
+async fn probe(
+    client: &reqwest::Client,
+    adapter: &dyn HostAdapter,
+    url: &Url,
+) -> Result<(Option<Url>, Option<String>), String> {
+    let response = client
+        .get(url.clone())
+        .header(reqwest::header::RANGE, "bytes=0-0")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::cert_pinning::verify_response(&response)?;
+
+    let file_name = response
+        .headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .map(header_text)
+        .as_deref()
+        .and_then(content_disposition_file_name);
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+
+    if file_name.is_none() && is_html {
+        let final_url = response.url().clone();
+        let html = response.text().await.map_err(|e| e.to_string())?;
+        return Ok((adapter.confirm_page(&html, &final_url), None));
+    }
+    Ok((None, file_name))
+}
+
+/// 添加任务前解析分享链接 - 转换为直链，处理确认页面，并获取服务器返回的文件名
+///
+/// 只有需要的网站（probe_before_queueing）才会发送请求，请求失败时使用转换后的直链；
+/// 插件处理的网站由插件解析
+///
+/// # 参数
+/// - `url`: 下载链接
+///
+/// # 返回值
+/// - 解析后的链接和文件名
+pub async fn resolve_share_link(url: &str) -> ResolvedLink {
+    // 插件处理的网站优先使用插件的解析结果
+    if let Some(resolved) = crate::plugins::resolve(url).await {
+        crate::telemetry::record("plugins.resolve");
+        return resolved;
+    }
+
+    let unresolved = || ResolvedLink {
+        url: direct_url(url),
+        file_name: None,
+    };
+
+    let Ok(parsed) = Url::parse(url) else {
+        return unresolved();
+    };
+    let adapter = adapter_for(&parsed);
+    crate::telemetry::record(&format!("host_adapter.{}", adapter.name()));
+    if !adapter.probe_before_queueing() {
+        return unresolved();
+    }
+
+    let client = match crate::cert_pinning::client_builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            log_warn!("创建HTTP客户端失败，跳过分享链接解析: {}", e);
+            return unresolved();
+        }
+    };
+
+    let mut current = adapter.direct_url(&parsed).unwrap_or(parsed);
+    let mut file_name = None;
+    for _ in 0..=MAX_CONFIRM_PAGES {
+        match probe(&client, adapter, &current).await {
+            Ok((Some(next), _)) => {
+                log_debug!("[{}] 跳过确认页面: {} -> {}", adapter.name(), current, next);
+                current = next;
+            }
+            Ok((None, name)) => {
+                file_name = name;
+                break;
+            }
+            Err(e) => {
+                log_warn!(
+                    "[{}] 解析分享链接失败: {}, 错误: {}",
+                    adapter.name(),
+                    url,
+                    e
+                );
+                break;
+            }
+        }
+    }
+
+    log_info!(
+        "[{}] 分享链接已解析: {} -> {} (文件名: {})",
+        adapter.name(),
+        url,
+        current,
+        file_name.as_deref().unwrap_or("未知")
+    );
+    ResolvedLink {
+        url: current.to_string(),
+        file_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_disposition_utf8_ext() {
+        assert_eq!(
+            content_disposition_file_name(
+                "attachment; filename=\"fallback.zip\"; filename*=UTF-8''%E5%9C%B0%E5%9B%BE.zip"
+            )
+            .as_deref(),
+            Some("地图.zip")
+        );
+    }
+
+    #[test]
+    fn content_disposition_gbk_ext() {
+        assert_eq!(
+            content_disposition_file_name("attachment; filename*=GBK''%B5%D8%CD%BC.zip").as_deref(),
+            Some("地图.zip")
+        );
+    }
+
+    #[test]
+    fn content_disposition_plain_filename() {
+        assert_eq!(
+            content_disposition_file_name("attachment; filename=\"map.zip\"").as_deref(),
+            Some("map.zip")
+        );
+        assert_eq!(
+            content_disposition_file_name("attachment; filename=map.zip; size=1").as_deref(),
+            Some("map.zip")
+        );
+        assert_eq!(content_disposition_file_name("inline"), None);
+    }
+}
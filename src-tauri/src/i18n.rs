@@ -0,0 +1,142 @@
+// i18n.rs 模块 - 后端用户可见文本（对话框、错误信息、事件消息）的本地化，语言包以 JSON 形式嵌入程序
+
+// 标准库导入
+use std::{collections::HashMap, sync::RwLock};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use tauri::{AppHandle, Emitter};
+
+// 内部模块导入
+use crate::{
+    error::{AppError, AppResult},
+    log_info,
+};
+
+/// 默认语言，缺少翻译时回退到该语言
+pub const DEFAULT_LANGUAGE: &str = "zh-CN";
+
+/// 内置语言包：语言代码和 locales 目录下对应的 JSON 文件
+const CATALOGS: &[(&str, &str)] = &[
+    ("zh-CN", include_str!("../locales/zh-CN.json")),
+    ("en-US", include_str!("../locales/en-US.json")),
+];
+
+lazy_static! {
+    /// 已解析的语言包：语言代码 -> (键 -> 文本)
+    static ref MESSAGES: HashMap<&'static str, HashMap<String, String>> = CATALOGS
+        .iter()
+        .map(|(language, content)| {
+            let messages = serde_json::from_str(content)
+                .unwrap_or_else(|e| panic!("语言包 {} 格式无效: {}", language, e));
+            (*language, messages)
+        })
+        .collect();
+
+    /// 当前使用的语言
+    static ref CURRENT_LANGUAGE: RwLock<&'static str> = RwLock::new(DEFAULT_LANGUAGE);
+}
+
+/// 将语言代码解析为内置语言包的代码
+///
+/// 先按完整代码匹配（不区分大小写，`_` 视为 `-`），再按主语言匹配，例如 `en` 匹配 `en-US`
+///
+/// # 参数
+/// - `language`: 语言代码
+///
+/// # 返回值
+/// - 支持该语言时返回内置语言包的代码，否则返回None
+pub fn resolve_language(language: &str) -> Option<&'static str> {
+    let language = language.trim().replace('_', "-");
+    let primary = language.split('-').next().unwrap_or_default();
+
+    CATALOGS
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| code.eq_ignore_ascii_case(&language))
+        .or_else(|| {
+            CATALOGS.iter().map(|(code, _)| *code).find(|code| {
+                code.split('-')
+                    .next()
+                    .is_some_and(|p| p.eq_ignore_ascii_case(primary))
+            })
+        })
+}
+
+/// 获取当前使用的语言
+pub fn current_language() -> &'static str {
+    *CURRENT_LANGUAGE.read().unwrap()
+}
+
+/// 切换当前语言，不支持的语言回退到默认语言
+///
+/// # 参数
+/// - `language`: 语言代码
+pub fn apply_language(language: &str) {
+    let resolved = resolve_language(language).unwrap_or(DEFAULT_LANGUAGE);
+    *CURRENT_LANGUAGE.write().unwrap() = resolved;
+}
+
+/// 获取指定键的翻译文本，并替换文本中的 `{name}` 占位符
+///
+/// 当前语言缺少该键时使用默认语言，默认语言也没有时返回键本身
+///
+/// # 参数
+/// - `key`: 文本键
+/// - `args`: 占位符名称和值
+pub fn translate(key: &str, args: &[(&str, String)]) -> String {
+    let message = [current_language(), DEFAULT_LANGUAGE]
+        .iter()
+        .find_map(|language| {
+            MESSAGES
+                .get(language)
+                .and_then(|messages| messages.get(key))
+        })
+        .cloned()
+        .unwrap_or_else(|| key.to_string());
+
+    args.iter().fold(message, |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// 本地化宏 - `tr!("key")` 或 `tr!("key", name = value, ...)`
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// 设置界面语言 - 保存到 config.json 并立即生效，随后向所有窗口发送 language-changed 事件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `language`: 语言代码，例如 zh-CN、en-US
+///
+/// # 返回值
+/// - 成功时返回实际使用的语言代码
+/// - 不支持该语言时返回AppError::InvalidInput
+#[tauri::command]
+pub fn set_language(app_handle: AppHandle, language: String) -> AppResult<String> {
+    let resolved = resolve_language(&language).ok_or_else(|| {
+        AppError::InvalidInput(tr!("error.unsupported_language", language = language))
+    })?;
+
+    crate::config_manager::merge_config(
+        &app_handle,
+        "config.json",
+        serde_json::json!({ "language": resolved }),
+    )
+    .map_err(AppError::Config)?;
+
+    apply_language(resolved);
+    log_info!("界面语言已修改为: {}", resolved);
+
+    let _ = app_handle.emit("language-changed", resolved);
+
+    Ok(resolved.to_string())
+}
@@ -9,7 +9,7 @@ use std::{
 // 第三方库导入
 use lazy_static::lazy_static;
 use serde_json;
-use tauri::{App, AppHandle, Emitter, Manager, PhysicalPosition, WebviewWindow};
+use tauri::{App, AppHandle, Manager, PhysicalPosition, WebviewWindow};
 use tauri_plugin_dialog::MessageDialogKind;
 
 // 定义全局变量
@@ -101,7 +101,7 @@ use crate::{
     config_manager::get_data_dir,
     dialog_manager::{show_blocking_dialog, show_dialog},
     dir_manager::{get_l4d2_addons_dir, set_global_addons_dir},
-    download_manager, log_error, log_info, log_warn,
+    download_manager, log_debug, log_error, log_info, log_warn,
 };
 
 /// 将窗口在屏幕上居中
@@ -151,6 +151,27 @@ pub fn update_window_title(app_handle: &AppHandle, title: &str) {
     }
 }
 
+/// 获取日志目录 - 优先使用 nmd_data/logs，未配置数据目录时使用应用数据目录下的 logs
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn get_logs_dir(app_handle: &AppHandle) -> Option<std::path::PathBuf> {
+    let data_dir = crate::dir_manager::DIR_MANAGER
+        .lock()
+        .ok()
+        .and_then(|manager| manager.as_ref().map(|dm| dm.data_dir()))
+        .filter(|dir| !dir.as_os_str().is_empty());
+
+    match data_dir {
+        Some(dir) => Some(dir.join("logs")),
+        None => app_handle
+            .path()
+            .app_local_data_dir()
+            .ok()
+            .map(|dir| dir.join("logs")),
+    }
+}
+
 /// 初始化应用程序 - 设置临时目录、获取L4D2目录、更新窗口标题等操作
 ///
 /// 此函数负责应用程序的初始化工作，包括：
@@ -184,6 +205,9 @@ pub fn initialize_app(app: &App) -> Result<(), Box<dyn std::error::Error>> {
     // 保存全局应用句柄，用于资源清理时关闭窗口
     *GLOBAL_APP_HANDLE.write().unwrap() = Some(app_handle.clone());
 
+    // 迁移配置文件到当前格式版本
+    crate::settings::migrate_config_file(&app_handle);
+
     // 读取数据存储目录配置
     let nmd_data_dir = get_data_dir(app_handle.clone())?;
 
@@ -206,9 +230,9 @@ pub fn initialize_app(app: &App) -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("初始化目录管理器失败: {}", e);
             show_dialog(
                 &app_handle,
-                &format!("初始化目录管理器失败: {}", e),
+                &crate::tr!("dialog.dir_manager_init_failed", error = e),
                 MessageDialogKind::Error,
-                "初始化失败",
+                &crate::tr!("dialog.init_failed.title"),
             );
             return Err(e.into());
         }
@@ -217,20 +241,85 @@ pub fn initialize_app(app: &App) -> Result<(), Box<dyn std::error::Error>> {
     // 设置全局目录管理器
     *crate::dir_manager::DIR_MANAGER.lock().unwrap() = Some(dir_manager);
 
+    // 加载并应用用户设置（并发数、下载目录等）
+    let settings = crate::settings::load_settings(&app_handle);
+
+    // 启用文件日志，写入数据目录下的 logs 目录
+    if let Some(logs_dir) = get_logs_dir(&app_handle) {
+        if let Err(e) = crate::log_utils::init_file_logger(logs_dir) {
+            log_warn!("启用文件日志失败: {}", e);
+        }
+    }
+    crate::settings::apply_settings(&settings);
+    crate::dir_manager::apply_download_dir(settings.download_dir.as_deref());
+
+    // 调试版本中监视前端源码，修改后通知窗口重新加载
+    crate::dev_reload::start_source_watcher(app_handle.clone());
+
+    // 加载插件目录中的第三方插件
+    crate::plugins::load_plugins(&app_handle);
+
+    // 用户开启匿名使用统计时定期提交
+    crate::telemetry::start_telemetry_uploader();
+
+    // 定期检查订阅的地图发布源
+    crate::feed_watcher::start_feed_watcher(app_handle.clone());
+
+    // 监视配置文件修改，在运行时应用新配置
+    crate::settings::start_config_watcher(app_handle.clone());
+
+    // 注册 Windows 事件日志的事件源（需要管理员权限，失败时事件仍会写入应用程序日志）
+    if let Err(e) = crate::event_log::register_event_source() {
+        log_debug!("{}", e);
+    }
+
+    // 监视网络连接状态，断网时下载任务等待网络恢复
+    crate::network_monitor::start_network_monitor(app_handle.clone());
+
+    // 监视游戏运行状态，游戏运行时降低后台下载的优先级
+    crate::game_monitor::start_game_monitor(app_handle.clone());
+
+    // 监视下载队列和解压队列的处理循环，停止时重新启动
+    crate::queue_watchdog::start_queue_watchdog(app_handle.clone());
+
+    // 生成任务栏跳转列表
+    crate::shell_integration::refresh_jump_list();
+
     // 尝试加载之前保存的下载队列
     if let Err(e) = download_manager::load_download_queue() {
         eprintln!("加载下载队列失败: {}", e);
         log_warn!("加载下载队列失败: {}", e);
     }
 
+    // 记录本次会话，检查上次是否没有正常退出
+    crate::session::start_session(&app_handle);
+
+    // 按保留天数清理下载缓存目录，保留未完成的下载；压缩包缓存同时按大小上限清理
+    // 同时清理上次崩溃或被强制结束时残留在系统临时目录中的文件
+    let retention_days = settings.cache_retention_days;
+    let archive_cache_max_mb = settings.archive_cache_max_mb;
+    std::thread::spawn(move || {
+        let removed_temp = crate::dir_manager::clean_stale_temp_entries();
+        if removed_temp > 0 {
+            log_info!("已清理 {} 个残留的临时文件", removed_temp);
+        }
+
+        let keep = download_manager::partial_download_files();
+        let removed = crate::dir_manager::clean_download_cache(retention_days, &keep)
+            + crate::archive_cache::enforce_limits(retention_days, archive_cache_max_mb);
+        if removed > 0 {
+            log_info!("已清理 {} 个过期的缓存文件", removed);
+        }
+    });
+
     // 尝试自动获取 Left 4 Dead 2 的addons目录
     log_info!("开始查找 Left 4 Dead 2 addons 目录...");
     match get_l4d2_addons_dir() {
         Ok(addons_dir) => {
             log_info!("成功找到 L4D2 addons 目录: {}", addons_dir);
             // 发送目录更改事件到前端
-            let _ = app.emit_to(
-                "main",
+            let _ = crate::event_dispatcher::emit(
+                app.handle(),
                 "extract-dir-changed",
                 &serde_json::json!({
                     "newDir": addons_dir,
@@ -255,11 +344,13 @@ pub fn initialize_app(app: &App) -> Result<(), Box<dyn std::error::Error>> {
             // 初始化检查完成，没有错误，显示主窗口
             log_info!("准备显示主窗口...");
             if let Some(window) = app.get_webview_window("main") {
-                log_info!("找到主窗口，开始居中和显示...");
-                // 使窗口在屏幕上居中
-                if let Err(e) = center_window_on_screen(&window) {
-                    eprintln!("无法将窗口居中: {:?}", e);
-                    log_error!("无法将窗口居中: {:?}", e);
+                log_info!("找到主窗口，开始恢复位置和显示...");
+                // 恢复上次关闭时的位置和大小，没有记录时使窗口在屏幕上居中
+                if !crate::window_state::restore_window_geometry(&window) {
+                    if let Err(e) = center_window_on_screen(&window) {
+                        eprintln!("无法将窗口居中: {:?}", e);
+                        log_error!("无法将窗口居中: {:?}", e);
+                    }
                 }
                 if let Err(e) = window.show() {
                     eprintln!("无法显示窗口: {:?}", e);
@@ -274,7 +365,12 @@ pub fn initialize_app(app: &App) -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => {
             log_error!("查找 L4D2 addons 目录失败: {}", e);
             // 在退出前显示一个错误对话框，提示无法找到L4D2目录
-            show_blocking_dialog(&app.handle(), &e, "错误", MessageDialogKind::Error);
+            show_blocking_dialog(
+                app.handle(),
+                &e,
+                &crate::tr!("dialog.error.title"),
+                MessageDialogKind::Error,
+            );
             // 显示对话框后，立即退出应用程序
             exit(1);
         }
@@ -341,6 +437,9 @@ pub fn cleanup_app_resources_for_restart() {
         log_error!("保存下载队列失败: {}", e);
     }
 
+    // 正常退出，删除会话标记
+    crate::session::end_session();
+
     // 清理aria2c资源
     cleanup_aria2c_resources();
 
@@ -375,6 +474,9 @@ fn cleanup_app_resources_impl() {
         log_error!("保存下载队列失败: {}", e);
     }
 
+    // 正常退出，删除会话标记
+    crate::session::end_session();
+
     // 清理aria2c资源
     cleanup_aria2c_resources();
 
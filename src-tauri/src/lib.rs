@@ -10,22 +10,61 @@ use tauri::{
     AppHandle, Emitter, Manager, Runtime, UriSchemeContext, UriSchemeResponder, Url,
 };
 use tauri_plugin_deep_link::DeepLinkExt;
-use tauri_plugin_updater::UpdaterExt;
 use mime_guess;
 
 // 导入子模块
+mod archive_cache;
+mod aria2_instance;
 mod aria2c;
+mod cancellation;
+mod catalog;
+mod cert_pinning;
+mod cli_output;
 mod commands;
 mod config_manager;
+mod crash_reporter;
+mod data_migration;
+mod dev_reload;
 mod dialog_manager;
 mod dir_manager;
+mod doh;
 mod download_manager;
+mod download_policy;
+mod downloads_window;
+mod error;
+mod event_dispatcher;
+mod event_log;
+mod explorer;
 mod extract_manager;
+mod feed_watcher;
+mod file_mover;
+mod game_monitor;
+mod host_adapter;
+mod i18n;
 mod init;
 mod log_utils;
+mod map_conflicts;
+mod network_monitor;
+mod notifications;
+mod plugins;
+mod process_priority;
 mod queue_manager;
+mod queue_watchdog;
+mod quick_install;
+mod resume_validator;
+mod self_test;
+mod server_ping;
+mod session;
+mod settings;
+mod shell_integration;
+mod support_bundle;
 mod symlink_manager;
+mod telemetry;
+mod update_manager;
+mod url_resolver;
 mod utils;
+mod vpk_validator;
+mod window_state;
 
 /// 从Assets中获取资源路径
 ///
@@ -109,27 +148,60 @@ fn build_error_response() -> Response<Vec<u8>> {
         .unwrap()
 }
 
+/// 注入脚本中的运行时配置占位符，提供脚本时替换为 injection_config 的 JSON
+const INJECTION_CONFIG_PLACEHOLDER: &str = "\"__NMD_INJECTION_CONFIG__\"";
+
+/// 为注入到网页的脚本（plugin 目录）填入运行时配置
+fn template_injected_script(path: &str, content: Vec<u8>) -> Vec<u8> {
+    if !path.starts_with("plugin/") || !path.ends_with(".js") {
+        return content;
+    }
+    let Ok(script) = String::from_utf8(content.clone()) else {
+        return content;
+    };
+    if !script.contains(INJECTION_CONFIG_PLACEHOLDER) {
+        return content;
+    }
+    let Some(app_handle) = GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    else {
+        return content;
+    };
+
+    let config = commands::injection_config(&app_handle).to_string();
+    script
+        .replace(INJECTION_CONFIG_PLACEHOLDER, &config)
+        .into_bytes()
+}
+
 fn handle_asset_request(path: &str, responder: UriSchemeResponder) {
     log_info!("asset协议请求: {}", path);
 
     let path = path.to_string();
 
     async_runtime::spawn(async move {
-        let file_content = match crate::get_assets_path(&format!("assets/{}", path)) {
-            Ok(resource_path) => {
-                log_info!("资源路径: {:?}", resource_path);
-                std::fs::read(&resource_path)
-            }
-            Err(e) => {
-                log_error!("获取资源路径失败: {:?}", e);
-                Err(Error::new(ErrorKind::NotFound, e))
-            }
+        // 调试版本直接读取前端源码，修改后不需要重新运行 minify
+        let file_content = match crate::dev_reload::source_asset_path(&path) {
+            Some(source_path) => std::fs::read(&source_path),
+            None => match crate::get_assets_path(&format!("assets/{}", path)) {
+                Ok(resource_path) => {
+                    log_info!("资源路径: {:?}", resource_path);
+                    std::fs::read(&resource_path)
+                }
+                Err(e) => {
+                    log_error!("获取资源路径失败: {:?}", e);
+                    Err(Error::new(ErrorKind::NotFound, e))
+                }
+            },
         };
 
         match file_content {
             Ok(content) => {
                 let content_type = get_content_type(&path);
-                let response = build_response(content, content_type);
+                let response =
+                    build_response(template_injected_script(&path, content), content_type);
                 responder.respond(response);
             }
             Err(error) => {
@@ -265,38 +337,101 @@ fn handle_deep_link(app: AppHandle, args: Vec<String>) {
     }
 }
 
+/// 可以通过命令行参数（例如资源管理器的“打开方式”）直接安装的压缩包扩展名，与前端拖拽支持的格式一致
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "7z", "zip", "rar", "tar", "gz", "bz2", "xz", "arj", "cab", "chm", "cpio", "deb", "dmg", "iso",
+    "lzh", "lzma", "msi", "nsis", "rpm", "udf", "wim", "xar", "z",
+];
+
+/// 处理命令行中的本地压缩包路径，添加到解压队列（与拖拽文件的安装流程相同）
+///
+/// # 参数
+/// - `app`: Tauri应用句柄
+/// - `args`: 命令行参数（第一个参数为程序路径，会被跳过）
+/// - `cwd`: 解析相对路径使用的工作目录
+fn handle_local_files(app: AppHandle, args: &[String], cwd: &std::path::Path) {
+    let files = args
+        .iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-') && !arg.starts_with("nmd://"))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| {
+                    ARCHIVE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+                })
+        });
+
+    for file in files {
+        let file_path = file.to_string_lossy().to_string();
+        log_info!("收到本地压缩包: {}", file_path);
+        if let Err(e) = commands::extract_dropped_file(file_path.clone(), app.clone()) {
+            log_error!("添加本地压缩包到解压队列失败: {}, 错误: {}", file_path, e);
+        }
+    }
+}
+
 // 主入口函数
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // 初始化日志系统
+    log_utils::init_tracing();
+
+    // 安装崩溃处理程序
+    crash_reporter::install_crash_handlers();
+
     // 启动信号处理
     handle_signals();
 
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
             let handle = app.app_handle().clone();
             let window = &app.webview_windows()["main"];
             if window.is_minimized().unwrap() {
                 window.unminimize().unwrap();
             }
             window.set_focus().unwrap();
+            handle_local_files(handle.clone(), &args, std::path::Path::new(&cwd));
+            shell_integration::handle_jump_list_args(&handle, &args);
             handle_deep_link(handle, args);
         }))
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(quick_install::handle_shortcut)
+                .build(),
+        )
         // 注册自定义asset协议
         .register_asynchronous_uri_scheme_protocol("asset", asset_protocol_handler)
         .invoke_handler(tauri::generate_handler![
             commands::install,
+            commands::get_remote_map_info,
             commands::open_filemanager_window,
             commands::open_serverlist_window,
+            commands::open_downloads_window,
             commands::open_server_window,
             commands::get_maps,
             commands::delete_map_file,
             commands::delete_group,
             commands::cancel_download,
             commands::refresh_download_queue,
+            commands::get_queue_state,
+            commands::get_download_details,
             commands::cancel_all_downloads,
+            commands::pause_all_downloads,
+            commands::resume_all_downloads,
+            commands::set_download_priority,
+            commands::pause_download,
+            commands::resume_download,
+            commands::restart_download_engine,
+            commands::get_engine_status,
+            commands::get_global_options,
+            commands::set_global_option,
+            commands::change_task_option,
             commands::frontend_loaded,
             commands::deep_link_ready,
             commands::get_file_symlinks,
@@ -311,15 +446,52 @@ pub fn run() {
             commands::refresh_extract_queue,
             commands::cancel_extract,
             commands::cancel_all_extracts,
+            commands::get_app_info,
+            commands::get_injection_config,
+            commands::get_managed_entries,
+            commands::get_disk_usage,
+            commands::rename_map_file,
+            commands::move_map_file,
             config_manager::read_config,
             config_manager::write_config,
             config_manager::delete_config,
             dialog_manager::show_directory_dialog,
+            dialog_manager::get_progress_dialog_state,
+            dialog_manager::cancel_progress_dialog,
+            data_migration::migrate_data_dir,
+            settings::get_settings,
+            settings::set_settings,
+            settings::export_settings,
+            settings::import_settings,
+            update_manager::check_for_update,
+            update_manager::install_update,
+            update_manager::rollback_update,
+            crash_reporter::get_crash_reports,
+            crash_reporter::submit_crash_report,
+            log_utils::set_log_level,
+            log_utils::get_log_level,
+            log_utils::get_recent_logs,
+            log_utils::set_log_stream_enabled,
+            i18n::set_language,
+            support_bundle::create_support_bundle,
+            self_test::run_self_test,
+            self_test::repair_resources,
+            explorer::open_path_in_explorer,
+            explorer::reveal_map,
+            plugins::list_plugins,
+            catalog::get_catalog_categories,
+            catalog::search_catalog,
+            catalog::get_map_details,
+            plugins::reload_plugins,
+            server_ping::ping_server,
         ])
         // 处理不同窗口的关闭请求
         .on_window_event(|window, event| match event {
             tauri::WindowEvent::CloseRequested { api, .. } => {
                 // 根据窗口标签执行不同的操作
+                // 记住窗口关闭前的位置和大小
+                window_state::save_window_geometry(window);
+
                 if window.label() == "main" {
                     // main窗口关闭时：隐藏窗口并清理资源
                     window.hide().unwrap();
@@ -339,11 +511,14 @@ pub fn run() {
         })
         // 添加应用启动时的初始化逻辑
         .setup(|app| {
+            // 使用 --json 启动时以 NDJSON 输出事件
+            cli_output::install(app.handle());
+
             if !cfg!(debug_assertions) {
                 {
                     let handle = app.handle().clone();
                     tauri::async_runtime::spawn(async move {
-                        update(handle).await.unwrap();
+                        update_manager::check_update_on_startup(handle).await;
                     });
                 }
                 {
@@ -368,29 +543,3 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
-
-async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
-    if let Some(update) = app.updater()?.check().await? {
-        let mut downloaded = 0;
-
-        // alternatively we could also call update.download() and update.install() separately
-        update
-            .download_and_install(
-                |chunk_length, content_length| {
-                    downloaded += chunk_length;
-                    log_info!("downloaded {downloaded} from {content_length:?}");
-                },
-                || {
-                    log_info!("download finished");
-                },
-            )
-            .await?;
-
-        log_info!("更新安装成功，应用即将重启");
-        init::cleanup_app_resources_for_restart();
-        app.restart();
-    } else {
-        log_info!("更新检查完成，未发现可用更新");
-    }
-    Ok(())
-}
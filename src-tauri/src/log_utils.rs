@@ -1,10 +1,133 @@
 // 日志工具模块
 // 此模块包含所有日志相关的辅助函数和宏定义
 use chrono::DateTime;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use std::{
-    io::BufRead,
+    collections::VecDeque,
+    fmt::Write as _,
+    fs,
+    io::{BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU8, Ordering},
+        mpsc, Mutex,
+    },
     time::{Duration, SystemTime},
 };
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    Layer,
+};
+
+/// 日志文件名
+const LOG_FILE_NAME: &str = "nmd.log";
+
+/// 单个日志文件的最大大小，超过后轮转
+const LOG_FILE_MAX_SIZE: u64 = 5 * 1024 * 1024;
+
+/// 保留的历史日志文件数量（nmd.log.1 ~ nmd.log.5）
+const LOG_FILE_MAX_BACKUPS: usize = 5;
+
+/// 日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+impl Default for LogLevel {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            LogLevel::Debug
+        } else {
+            LogLevel::Info
+        }
+    }
+}
+
+impl LogLevel {
+    /// 从日志宏使用的级别标签解析日志级别
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+
+    /// 从 AtomicU8 中保存的数值还原日志级别
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => LogLevel::Error,
+            2 => LogLevel::Warn,
+            3 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+/// 下载进度的日志详细程度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressVerbosity {
+    /// 不记录下载进度
+    Off,
+    /// 只在发送进度事件（进度变化）时记录一行
+    #[default]
+    Changes,
+    /// 每次查询都记录进度详情，调试级别时还会输出完整的事件内容
+    Full,
+}
+
+/// 当前生效的日志级别，高于该级别的日志不会被记录
+static MAX_LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Debug as u8);
+
+/// 文件日志记录器 - 日志行通过通道发送到后台线程写入，避免阻塞调用方
+struct FileLogger {
+    sender: mpsc::Sender<String>,
+}
+
+/// 内存中保留的最近日志行数
+pub const RECENT_LOG_CAPACITY: usize = 1000;
+
+/// 是否向前端实时推送日志行（log-line 事件）
+static LOG_STREAM_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 最近的日志行，用于崩溃报告和前端诊断面板
+lazy_static! {
+    static ref RECENT_LOGS: Mutex<VecDeque<String>> =
+        Mutex::new(VecDeque::with_capacity(RECENT_LOG_CAPACITY));
+    static ref FILE_LOGGER: Mutex<Option<FileLogger>> = Mutex::new(None);
+}
+
+/// 获取最近的日志行（按时间顺序）
+///
+/// 使用 try_lock 获取，避免在崩溃处理过程中死锁
+///
+/// # 参数
+/// - `limit`: 最多返回的行数
+pub fn recent_log_lines(limit: usize) -> Vec<String> {
+    match RECENT_LOGS.try_lock() {
+        Ok(logs) => logs
+            .iter()
+            .skip(logs.len().saturating_sub(limit))
+            .cloned()
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
 
 /// 辅助函数：获取当前时间的格式化字符串
 pub fn get_current_time() -> String {
@@ -17,34 +140,379 @@ pub fn get_current_time() -> String {
     time.format("%Y-%m-%d %H:%M:%S.%3f").to_string()
 }
 
+/// 获取当前生效的日志级别
+pub fn max_log_level() -> LogLevel {
+    LogLevel::from_u8(MAX_LOG_LEVEL.load(Ordering::Relaxed))
+}
+
+/// 修改当前生效的日志级别
+pub fn set_max_log_level(level: LogLevel) {
+    MAX_LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// 检查指定级别的日志是否需要记录，日志宏在格式化消息前调用
+pub fn is_level_enabled(level: LogLevel) -> bool {
+    level as u8 <= MAX_LOG_LEVEL.load(Ordering::Relaxed)
+}
+
 /// 记录日志的辅助函数
 pub fn log_message(level: &str, message: &str) {
+    if LogLevel::from_label(level).is_some_and(|level| !is_level_enabled(level)) {
+        return;
+    }
+
     let timestamp = get_current_time();
-    println!("[{}] [{}] {}", timestamp, level, message);
+    let line = format!("[{}] [{}] {}", timestamp, level, message);
+    // --json 模式下标准输出只保留 NDJSON 行，日志改为输出到标准错误
+    if crate::cli_output::is_json_output_enabled() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+
+    if let Ok(logger) = FILE_LOGGER.lock() {
+        if let Some(logger) = logger.as_ref() {
+            let _ = logger.sender.send(line.clone());
+        }
+    }
+
+    if LOG_STREAM_ENABLED.load(Ordering::Relaxed) {
+        emit_log_line(level, &timestamp, message);
+    }
+
+    if let Ok(mut logs) = RECENT_LOGS.lock() {
+        if logs.len() >= RECENT_LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+}
+
+/// 向所有窗口发送 log-line 事件
+fn emit_log_line(level: &str, timestamp: &str, message: &str) {
+    use tauri::Emitter;
+
+    if let Ok(guard) = crate::init::GLOBAL_APP_HANDLE.try_read() {
+        if let Some(app_handle) = guard.as_ref() {
+            let _ = app_handle.emit(
+                "log-line",
+                &serde_json::json!({
+                    "timestamp": timestamp,
+                    "level": level,
+                    "message": message,
+                }),
+            );
+        }
+    }
+}
+
+/// 获取最近的日志 - 供前端诊断面板使用
+///
+/// # 参数
+/// - `limit`: 最多返回的行数，默认返回全部缓存的日志
+///
+/// # 返回值
+/// - 返回按时间顺序排列的日志行
+#[tauri::command]
+pub fn get_recent_logs(limit: Option<usize>) -> Vec<String> {
+    recent_log_lines(limit.unwrap_or(RECENT_LOG_CAPACITY))
+}
+
+/// 开启或关闭日志实时推送，开启后每条日志会以 log-line 事件发送到前端
+///
+/// # 参数
+/// - `enabled`: 是否开启
+#[tauri::command]
+pub fn set_log_stream_enabled(enabled: bool) {
+    LOG_STREAM_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 轮转日志文件：nmd.log -> nmd.log.1 -> ... -> nmd.log.N，超出数量的最旧文件被删除
+fn rotate_log_files(logs_dir: &Path) {
+    let backup_path = |index: usize| logs_dir.join(format!("{}.{}", LOG_FILE_NAME, index));
+
+    let _ = fs::remove_file(backup_path(LOG_FILE_MAX_BACKUPS));
+    for index in (1..LOG_FILE_MAX_BACKUPS).rev() {
+        let _ = fs::rename(backup_path(index), backup_path(index + 1));
+    }
+    let _ = fs::rename(logs_dir.join(LOG_FILE_NAME), backup_path(1));
+}
+
+/// 打开日志文件（追加模式）
+fn open_log_file(logs_dir: &Path) -> std::io::Result<BufWriter<fs::File>> {
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(logs_dir.join(LOG_FILE_NAME))
+        .map(BufWriter::new)
+}
+
+/// 日志写入线程 - 按大小轮转日志文件，每批日志写入后刷新
+fn run_log_writer(logs_dir: PathBuf, receiver: mpsc::Receiver<String>) {
+    let mut size = fs::metadata(logs_dir.join(LOG_FILE_NAME))
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    let mut writer = match open_log_file(&logs_dir) {
+        Ok(writer) => writer,
+        Err(e) => {
+            eprintln!("无法打开日志文件: {:?}", e);
+            return;
+        }
+    };
+
+    // 发送端全部释放后（记录器被替换或关闭）退出线程
+    while let Ok(line) = receiver.recv() {
+        let mut lines = vec![line];
+        lines.extend(receiver.try_iter());
+
+        for line in lines {
+            if size >= LOG_FILE_MAX_SIZE {
+                let _ = writer.flush();
+                rotate_log_files(&logs_dir);
+                writer = match open_log_file(&logs_dir) {
+                    Ok(writer) => writer,
+                    Err(e) => {
+                        eprintln!("无法打开日志文件: {:?}", e);
+                        return;
+                    }
+                };
+                size = 0;
+            }
+            if writeln!(writer, "{}", line).is_ok() {
+                size += line.len() as u64 + 1;
+            }
+        }
+        let _ = writer.flush();
+    }
+}
+
+/// 初始化文件日志 - 日志写入 `logs_dir/nmd.log`，重复调用会替换之前的日志目录
+///
+/// 初始化前已产生的最近日志会先写入文件。
+///
+/// # 参数
+/// - `logs_dir`: 日志目录
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+/// - 失败时返回包含错误信息的Err
+pub fn init_file_logger(logs_dir: PathBuf) -> Result<(), String> {
+    fs::create_dir_all(&logs_dir).map_err(|e| format!("无法创建日志目录: {:?}", e))?;
+
+    let (sender, receiver) = mpsc::channel::<String>();
+    let is_first_init = FILE_LOGGER
+        .lock()
+        .map(|logger| logger.is_none())
+        .unwrap_or(false);
+    if is_first_init {
+        for line in recent_log_lines(RECENT_LOG_CAPACITY) {
+            let _ = sender.send(line);
+        }
+    }
+
+    let thread_logs_dir = logs_dir.clone();
+    std::thread::spawn(move || run_log_writer(thread_logs_dir, receiver));
+
+    *FILE_LOGGER
+        .lock()
+        .map_err(|e| format!("无法获取日志记录器锁: {:?}", e))? = Some(FileLogger { sender });
+
+    crate::log_info!("文件日志已启用: {}", logs_dir.join(LOG_FILE_NAME).display());
+    Ok(())
+}
+
+/// 设置日志级别 - 运行时临时生效，重启后恢复为配置中的 log_level
+///
+/// # 参数
+/// - `level`: 日志级别（error/warn/info/debug）
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+#[tauri::command]
+pub fn set_log_level(level: LogLevel) -> crate::error::AppResult<String> {
+    set_max_log_level(level);
+    crate::log_info!("日志级别已修改为: {:?}", level);
+    Ok(format!("日志级别已修改为: {:?}", level))
+}
+
+/// 获取当前日志级别
+///
+/// # 返回值
+/// - 返回当前生效的日志级别
+#[tauri::command]
+pub fn get_log_level() -> LogLevel {
+    max_log_level()
 }
 
 /// 日志宏定义 - 信息级别
 #[macro_export]
 macro_rules! log_info {
-    ($($arg:tt)*) => { $crate::log_utils::log_message("INFO", &format!($($arg)*)); };
+    ($($arg:tt)*) => {
+        if $crate::log_utils::is_level_enabled($crate::log_utils::LogLevel::Info) {
+            ::tracing::info!($($arg)*);
+        }
+    };
 }
 
 /// 日志宏定义 - 警告级别
 #[macro_export]
 macro_rules! log_warn {
-    ($($arg:tt)*) => { $crate::log_utils::log_message("WARN", &format!($($arg)*)) };
+    ($($arg:tt)*) => {
+        if $crate::log_utils::is_level_enabled($crate::log_utils::LogLevel::Warn) {
+            ::tracing::warn!($($arg)*);
+        }
+    };
 }
 
 /// 日志宏定义 - 错误级别
 #[macro_export]
 macro_rules! log_error {
-    ($($arg:tt)*) => { $crate::log_utils::log_message("ERROR", &format!($($arg)*)) };
+    ($($arg:tt)*) => {
+        if $crate::log_utils::is_level_enabled($crate::log_utils::LogLevel::Error) {
+            ::tracing::error!($($arg)*);
+        }
+    };
 }
 
 /// 日志宏定义 - 调试级别
 #[macro_export]
 macro_rules! log_debug {
-    ($($arg:tt)*) => { $crate::log_utils::log_message("DEBUG", &format!($($arg)*)) };
+    ($($arg:tt)*) => {
+        if $crate::log_utils::is_level_enabled($crate::log_utils::LogLevel::Debug) {
+            ::tracing::debug!($($arg)*);
+        }
+    };
+}
+
+/// 收集 span/event 字段的访问器，message 字段单独保存，其余字段按 `name=value` 保存
+#[derive(Default)]
+struct FieldCollector {
+    message: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl FieldCollector {
+    /// 将字段格式化为 `name=value name=value`
+    fn format_fields(fields: &[(&'static str, String)]) -> String {
+        fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Visit for FieldCollector {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name(), format!("{:?}", value)));
+        }
+    }
+}
+
+/// 保存在 span 扩展中的字段
+struct SpanFields(Vec<(&'static str, String)>);
+
+/// tracing 日志层 - 将本应用的事件连同所在 span 的字段（task_id、gid 等）交给 log_message 输出
+struct NmdLogLayer;
+
+impl<S> Layer<S> for NmdLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut collector = FieldCollector::default();
+        attrs.record(&mut collector);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(collector.fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut collector = FieldCollector::default();
+        values.record(&mut collector);
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            match extensions.get_mut::<SpanFields>() {
+                Some(fields) => {
+                    // 同名字段（例如重新添加任务后的新 GID）覆盖旧值
+                    for (name, value) in collector.fields {
+                        match fields.0.iter_mut().find(|(existing, _)| *existing == name) {
+                            Some(field) => field.1 = value,
+                            None => fields.0.push((name, value)),
+                        }
+                    }
+                }
+                None => extensions.insert(SpanFields(collector.fields)),
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // 只处理本应用产生的事件，依赖库的 tracing 事件不输出
+        if !event
+            .metadata()
+            .target()
+            .starts_with(env!("CARGO_CRATE_NAME"))
+        {
+            return;
+        }
+
+        let mut collector = FieldCollector::default();
+        event.record(&mut collector);
+
+        let mut message = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(fields) = span.extensions().get::<SpanFields>() {
+                    if !fields.0.is_empty() {
+                        let _ = write!(
+                            message,
+                            "[{} {}] ",
+                            span.name(),
+                            FieldCollector::format_fields(&fields.0)
+                        );
+                    }
+                }
+            }
+        }
+        message.push_str(&collector.message);
+        if !collector.fields.is_empty() {
+            let _ = write!(
+                message,
+                " {}",
+                FieldCollector::format_fields(&collector.fields)
+            );
+        }
+
+        let level = match *event.metadata().level() {
+            Level::ERROR => "ERROR",
+            Level::WARN => "WARN",
+            Level::INFO => "INFO",
+            _ => "DEBUG",
+        };
+        log_message(level, &message);
+    }
+}
+
+/// 初始化 tracing - 安装全局订阅者，所有日志宏通过 tracing 事件输出
+///
+/// 应在应用启动时最先调用
+pub fn init_tracing() {
+    let subscriber = tracing_subscriber::registry().with(NmdLogLayer);
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        eprintln!("tracing 全局订阅者已存在，跳过初始化");
+    }
 }
 
 /// 重定向子进程的标准输出和标准错误到日志系统
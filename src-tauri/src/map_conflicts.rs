@@ -0,0 +1,171 @@
+// map_conflicts.rs 模块 - 解压完成后检查新地图的VPK文件名是否与其他地图组或 addons 目录中已有的VPK重名
+//
+// 不同地图包中的同名VPK同时挂载时游戏只会加载其中一个，检测到重名时发送 map-conflict 事件，
+// 列出双方的来源和挂载状态，由用户通过 mount_file / unmount_file 选择保留哪一个。
+
+// 标准库导入
+use std::path::Path;
+
+// 第三方库导入
+use serde::Serialize;
+use tauri::AppHandle;
+
+// 内部模块导入
+use crate::{dir_manager::DIR_MANAGER, log_warn, utils::same_entry_name};
+
+/// 与新地图重名的VPK来源
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConflictSource {
+    /// 来源类型："group" 为其他地图组，"addons" 为直接放在 addons 目录中的文件
+    source: &'static str,
+    /// 地图组名称（来源为 addons 时为None）
+    group: Option<String>,
+    /// 文件名
+    file: String,
+    /// 文件路径
+    path: String,
+    /// 是否已挂载（addons 中的文件始终会被游戏加载）
+    mounted: bool,
+}
+
+/// map-conflict 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MapConflict {
+    /// 新安装的地图组
+    group: String,
+    /// 新地图中重名的文件
+    file: String,
+    /// 新地图的该文件是否已挂载
+    mounted: bool,
+    /// 重名的其他来源
+    conflicts: Vec<ConflictSource>,
+}
+
+/// 目录中的VPK文件名
+fn vpk_files(dir: &Path) -> Vec<String> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| {
+                    Path::new(name)
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("vpk"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 查找地图组中与其他来源重名的VPK文件
+///
+/// # 参数
+/// - `maps_dir`: maps 目录
+/// - `addons_dir`: addons 目录
+/// - `group_name`: 新安装的地图组
+fn find_conflicts(maps_dir: &Path, addons_dir: &Path, group_name: &str) -> Vec<MapConflict> {
+    let is_mounted = |group: &str, file: &str| {
+        addons_dir
+            .join(crate::commands::map_link_name(group, file))
+            .exists()
+    };
+
+    // 其他地图组中的VPK: (组名, 文件名)
+    let other_groups = std::fs::read_dir(maps_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|group| !same_entry_name(group, group_name))
+                .flat_map(|group| {
+                    vpk_files(&maps_dir.join(&group))
+                        .into_iter()
+                        .map(move |file| (group.clone(), file))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    // addons 目录中不是由本程序创建的VPK
+    let addons_files = vpk_files(addons_dir)
+        .into_iter()
+        .filter(|file| !file.starts_with("nmd_link_"))
+        .collect::<Vec<_>>();
+
+    vpk_files(&maps_dir.join(group_name))
+        .into_iter()
+        .filter_map(|file| {
+            let mut conflicts = other_groups
+                .iter()
+                .filter(|(_, other)| same_entry_name(other, &file))
+                .map(|(group, other)| ConflictSource {
+                    source: "group",
+                    group: Some(group.clone()),
+                    file: other.clone(),
+                    path: maps_dir.join(group).join(other).display().to_string(),
+                    mounted: is_mounted(group, other),
+                })
+                .collect::<Vec<_>>();
+            conflicts.extend(
+                addons_files
+                    .iter()
+                    .filter(|other| same_entry_name(other, &file))
+                    .map(|other| ConflictSource {
+                        source: "addons",
+                        group: None,
+                        file: other.clone(),
+                        path: addons_dir.join(other).display().to_string(),
+                        mounted: true,
+                    }),
+            );
+
+            if conflicts.is_empty() {
+                return None;
+            }
+            Some(MapConflict {
+                group: group_name.to_string(),
+                mounted: is_mounted(group_name, &file),
+                file,
+                conflicts,
+            })
+        })
+        .collect()
+}
+
+/// 检查新安装的地图组是否与已有的VPK重名，重名时发送 map-conflict 事件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `group_name`: 新安装的地图组
+pub fn check_and_notify(app_handle: &AppHandle, group_name: &str) {
+    let (maps_dir, addons_dir) = match DIR_MANAGER.lock() {
+        Ok(manager) => match manager.as_ref() {
+            Some(dir_manager) => (dir_manager.maps_dir(), dir_manager.addons_dir().cloned()),
+            None => return,
+        },
+        Err(_) => return,
+    };
+    let Some(addons_dir) = addons_dir else {
+        return;
+    };
+
+    for conflict in find_conflicts(&maps_dir, &addons_dir, group_name) {
+        log_warn!(
+            "地图组 {} 中的 {} 与已有文件重名: {}",
+            conflict.group,
+            conflict.file,
+            conflict
+                .conflicts
+                .iter()
+                .map(|source| source.path.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let _ = crate::event_dispatcher::emit(app_handle, "map-conflict", &conflict);
+    }
+}
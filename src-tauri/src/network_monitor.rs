@@ -0,0 +1,55 @@
+// network_monitor.rs 模块 - 检测网络连接状态，断网时下载任务进入"等待网络"状态，网络恢复后自动继续
+
+// 标准库导入
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use tauri::AppHandle;
+use windows_sys::Win32::Networking::WinInet::InternetGetConnectedState;
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_info, log_warn};
+
+/// 检查网络状态的间隔
+const NETWORK_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+lazy_static! {
+    /// 监视线程最后一次观察到的网络状态
+    static ref NETWORK_ONLINE: AtomicBool = AtomicBool::new(true);
+}
+
+/// 检查当前是否有可用的网络连接（WinINet）
+///
+/// # 返回值
+/// - 系统报告存在网络连接时返回true
+pub fn is_online() -> bool {
+    let mut flags = 0u32;
+    unsafe { InternetGetConnectedState(&mut flags, 0) != 0 }
+}
+
+/// 启动网络状态监视线程，状态变化时向前端发送 network-offline / network-online 事件
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+pub fn start_network_monitor(app_handle: AppHandle) {
+    thread::spawn(move || {
+        while !is_app_shutting_down() {
+            let online = is_online();
+            if NETWORK_ONLINE.swap(online, Ordering::Relaxed) != online {
+                if online {
+                    log_info!("网络连接已恢复");
+                    let _ = crate::event_dispatcher::emit(&app_handle, "network-online", ());
+                } else {
+                    log_warn!("网络连接已断开");
+                    let _ = crate::event_dispatcher::emit(&app_handle, "network-offline", ());
+                }
+            }
+            thread::sleep(NETWORK_CHECK_INTERVAL);
+        }
+    });
+}
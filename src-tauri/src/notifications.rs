@@ -0,0 +1,85 @@
+// notifications.rs 模块 - 队列中最后一个任务结束时播放提示音（系统提示音或用户提供的 wav 文件）
+//
+// 提示音在后端播放，窗口最小化或隐藏时也能听到。
+
+// 标准库导入
+use std::{ffi::OsStr, os::windows::ffi::OsStrExt, path::Path};
+
+// 第三方库导入
+use serde::{Deserialize, Serialize};
+use windows_sys::Win32::Media::Audio::{
+    PlaySoundW, SND_ALIAS, SND_ASYNC, SND_FILENAME, SND_NODEFAULT,
+};
+
+// 内部模块导入
+use crate::{log_debug, log_warn};
+
+/// 系统提示音使用的声音别名（控制面板"声音"中的"通知"）
+const SYSTEM_SOUND_ALIAS: &str = "SystemNotification";
+
+/// 完成提示音类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionSound {
+    /// 不播放
+    #[default]
+    Off,
+    /// 系统提示音
+    System,
+    /// completion_sound_file 指定的 wav 文件
+    File,
+}
+
+/// 播放声音
+///
+/// # 参数
+/// - `sound`: 声音别名或 wav 文件路径
+/// - `flags`: PlaySoundW 标志
+///
+/// # 返回值
+/// - 开始播放时返回true
+fn play_sound(sound: &OsStr, flags: u32) -> bool {
+    let sound = sound
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<_>>();
+    unsafe { PlaySoundW(sound.as_ptr(), std::ptr::null_mut(), flags | SND_ASYNC) != 0 }
+}
+
+/// 播放系统提示音
+fn play_system_sound() -> bool {
+    play_sound(OsStr::new(SYSTEM_SOUND_ALIAS), SND_ALIAS | SND_NODEFAULT)
+}
+
+/// 按配置播放完成提示音，wav 文件不存在或无法播放时改用系统提示音
+pub fn play_completion_sound() {
+    let settings = crate::settings::current_settings();
+    let played = match settings.completion_sound {
+        CompletionSound::Off => return,
+        CompletionSound::System => play_system_sound(),
+        CompletionSound::File => {
+            let file = settings
+                .completion_sound_file
+                .as_deref()
+                .map(Path::new)
+                .filter(|path| path.is_file());
+            if file.is_none() {
+                log_warn!("完成提示音文件不存在: {:?}", settings.completion_sound_file);
+            }
+            file.is_some_and(|path| play_sound(path.as_os_str(), SND_FILENAME | SND_NODEFAULT))
+                || play_system_sound()
+        }
+    };
+
+    if !played {
+        log_warn!("播放完成提示音失败");
+    }
+}
+
+/// 任务结束后调用，下载队列（包括等待重试的任务）和解压队列都已清空时播放完成提示音
+pub fn on_task_finished() {
+    if crate::update_manager::queues_idle() && !crate::download_manager::has_pending_retries() {
+        log_debug!("所有任务已完成");
+        play_completion_sound();
+    }
+}
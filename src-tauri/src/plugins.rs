@@ -0,0 +1,421 @@
+// plugins.rs 模块 - 插件：从应用配置目录的 plugins 子目录加载第三方插件，为新的下载网站解析直链，或在地图安装完成后执行操作
+//
+// 每个插件是 plugins 下的一个目录，包含 plugin.json 清单和可选的可执行文件：
+// - hosts 中的网站（包括其子域名）的链接先按 rewrites 中的正则表达式改写，再交给可执行文件解析
+// - 可执行文件从标准输入读取一个 JSON 请求，向标准输出写入一个 JSON 响应：
+//   - {"action": "resolve", "url": 链接} -> {"url": 直链, "fileName": 文件名}，两项都可以省略，返回 {} 表示不处理
+//   - {"action": "post_install", "group": 地图组, "path": 地图目录} -> 输出被忽略（清单中 postInstall 为 true 时才会调用）
+// 插件按目录名顺序匹配，第一个处理了链接的插件生效；插件出错时使用内置的网站适配器。
+
+// 标准库导入
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::RwLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Url};
+
+// 内部模块导入
+use crate::{
+    error::AppResult, host_adapter::ResolvedLink, log_debug, log_error, log_info, log_warn,
+};
+
+/// 插件目录名（位于应用配置目录下）
+const PLUGINS_DIR: &str = "plugins";
+
+/// 插件清单文件名
+const MANIFEST_FILE: &str = "plugin.json";
+
+/// 插件可执行文件的最长运行时间
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    /// 已加载的插件
+    static ref PLUGINS: RwLock<Vec<Plugin>> = RwLock::new(Vec::new());
+}
+
+/// 链接改写规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RewriteRule {
+    /// 匹配链接的正则表达式
+    pattern: String,
+    /// 替换文本，可以使用 $1 等引用捕获组
+    replacement: String,
+}
+
+/// 插件清单（plugin.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginManifest {
+    /// 插件名称
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+    /// 插件处理的网站域名（包括其子域名）
+    #[serde(default)]
+    hosts: Vec<String>,
+    /// 链接改写规则，按顺序应用
+    #[serde(default)]
+    rewrites: Vec<RewriteRule>,
+    /// 插件目录中的可执行文件名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    executable: Option<String>,
+    /// 地图安装完成后是否调用可执行文件
+    #[serde(default)]
+    post_install: bool,
+}
+
+/// 已加载的插件
+#[derive(Debug, Clone)]
+struct Plugin {
+    manifest: PluginManifest,
+    /// 编译后的改写规则
+    rewrites: Vec<(Regex, String)>,
+    /// 可执行文件的完整路径
+    executable: Option<PathBuf>,
+    /// 插件目录
+    dir: PathBuf,
+}
+
+impl Plugin {
+    /// 是否处理该链接
+    fn handles(&self, url: &Url) -> bool {
+        self.manifest.hosts.iter().any(|domain| {
+            let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+            !domain.is_empty() && crate::host_adapter::is_host(url, &domain)
+        })
+    }
+}
+
+/// list_plugins 返回的插件信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    name: String,
+    version: String,
+    description: String,
+    hosts: Vec<String>,
+    has_executable: bool,
+    post_install: bool,
+    path: String,
+}
+
+/// 可执行文件对 resolve 请求的响应
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolveResponse {
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    file_name: Option<String>,
+}
+
+/// 获取插件目录
+fn plugins_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    crate::config_manager::get_config_path(app_handle, PLUGINS_DIR)
+}
+
+/// 读取一个插件目录
+fn load_plugin(dir: &Path) -> Result<Plugin, String> {
+    let content = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("读取 {} 失败: {}", MANIFEST_FILE, e))?;
+    let manifest: PluginManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("解析 {} 失败: {}", MANIFEST_FILE, e))?;
+    if manifest.name.trim().is_empty() {
+        return Err("插件名称不能为空".to_string());
+    }
+
+    let rewrites = manifest
+        .rewrites
+        .iter()
+        .map(|rule| {
+            Regex::new(&rule.pattern)
+                .map(|regex| (regex, rule.replacement.clone()))
+                .map_err(|e| format!("无效的改写规则 {}: {}", rule.pattern, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 可执行文件必须位于插件目录中
+    let executable = match &manifest.executable {
+        Some(name) => {
+            let path = dir
+                .join(name)
+                .canonicalize()
+                .map_err(|e| format!("找不到可执行文件 {}: {}", name, e))?;
+            let dir = dir.canonicalize().map_err(|e| e.to_string())?;
+            if !path.starts_with(&dir) || !path.is_file() {
+                return Err(format!("可执行文件 {} 必须位于插件目录中", name));
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    Ok(Plugin {
+        manifest,
+        rewrites,
+        executable,
+        dir: dir.to_path_buf(),
+    })
+}
+
+/// 从插件目录加载所有插件，替换已加载的插件；无法加载的插件会被跳过
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 加载的插件数
+pub fn load_plugins(app_handle: &AppHandle) -> usize {
+    let mut plugins = Vec::new();
+    if let Ok(dir) = plugins_dir(app_handle) {
+        let mut dirs = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        dirs.sort();
+
+        for plugin_dir in dirs {
+            match load_plugin(&plugin_dir) {
+                Ok(plugin) => {
+                    log_info!(
+                        "已加载插件: {} {} ({})",
+                        plugin.manifest.name,
+                        plugin.manifest.version,
+                        plugin_dir.display()
+                    );
+                    plugins.push(plugin);
+                }
+                Err(e) => log_warn!("加载插件失败: {}, 错误: {}", plugin_dir.display(), e),
+            }
+        }
+    }
+
+    let count = plugins.len();
+    if let Ok(mut loaded) = PLUGINS.write() {
+        *loaded = plugins;
+    }
+    count
+}
+
+/// 已加载插件的副本（运行插件时不持有锁）
+fn loaded_plugins() -> Vec<Plugin> {
+    PLUGINS
+        .read()
+        .map(|plugins| plugins.clone())
+        .unwrap_or_default()
+}
+
+/// 运行插件的可执行文件：写入请求，读取标准输出中的 JSON 响应，超时时终止进程
+fn run_executable(plugin: &Plugin, executable: &Path, request: &Value) -> Result<Value, String> {
+    let mut command = Command::new(executable);
+    command
+        .current_dir(&plugin.dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW 标志
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("启动插件失败: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(request.to_string().as_bytes());
+        // 关闭标准输入，插件读到 EOF 后开始处理
+    }
+
+    let mut stdout = child.stdout.take();
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_string(&mut output);
+        }
+        output
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= PLUGIN_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "插件运行超过 {} 秒，已终止",
+                    PLUGIN_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("等待插件结束失败: {}", e)),
+        }
+    };
+
+    let output = reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("插件退出码: {}", status.code().unwrap_or(-1)));
+    }
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(json!({}));
+    }
+    serde_json::from_str(output).map_err(|e| format!("解析插件输出失败: {}", e))
+}
+
+/// 由插件解析下载链接
+///
+/// # 参数
+/// - `url`: 下载链接
+///
+/// # 返回值
+/// - 第一个处理了该链接的插件的解析结果，没有插件处理时返回None
+pub async fn resolve(url: &str) -> Option<ResolvedLink> {
+    let parsed = Url::parse(url).ok()?;
+    let plugins = loaded_plugins()
+        .into_iter()
+        .filter(|plugin| plugin.handles(&parsed))
+        .collect::<Vec<_>>();
+    if plugins.is_empty() {
+        return None;
+    }
+
+    let url = url.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        for plugin in plugins {
+            let name = &plugin.manifest.name;
+            let rewritten =
+                plugin
+                    .rewrites
+                    .iter()
+                    .fold(url.clone(), |current, (regex, replacement)| {
+                        regex.replace(&current, replacement.as_str()).into_owned()
+                    });
+
+            let Some(executable) = &plugin.executable else {
+                if rewritten != url {
+                    log_info!("[插件 {}] 链接已改写: {} -> {}", name, url, rewritten);
+                    return Some(ResolvedLink {
+                        url: rewritten,
+                        file_name: None,
+                    });
+                }
+                continue;
+            };
+
+            let request = json!({ "action": "resolve", "url": rewritten });
+            let response = match run_executable(&plugin, executable, &request).and_then(|value| {
+                serde_json::from_value::<ResolveResponse>(value).map_err(|e| e.to_string())
+            }) {
+                Ok(response) => response,
+                Err(e) => {
+                    log_warn!("[插件 {}] 解析链接失败: {}, 错误: {}", name, url, e);
+                    continue;
+                }
+            };
+
+            let resolved_url = response
+                .url
+                .filter(|resolved| Url::parse(resolved).is_ok())
+                .unwrap_or_else(|| rewritten.clone());
+            if resolved_url == url && response.file_name.is_none() {
+                log_debug!("[插件 {}] 未处理链接: {}", name, url);
+                continue;
+            }
+            log_info!(
+                "[插件 {}] 链接已解析: {} -> {} (文件名: {})",
+                name,
+                url,
+                resolved_url,
+                response.file_name.as_deref().unwrap_or("未知")
+            );
+            return Some(ResolvedLink {
+                url: resolved_url,
+                file_name: response.file_name,
+            });
+        }
+        None
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// 地图安装完成后在后台调用声明了 postInstall 的插件
+///
+/// # 参数
+/// - `group_name`: 地图组名称
+/// - `group_dir`: 地图组目录
+pub fn run_post_install(group_name: &str, group_dir: &Path) {
+    let plugins = loaded_plugins()
+        .into_iter()
+        .filter(|plugin| plugin.manifest.post_install)
+        .collect::<Vec<_>>();
+    if plugins.is_empty() {
+        return;
+    }
+
+    let request = json!({
+        "action": "post_install",
+        "group": group_name,
+        "path": group_dir.display().to_string(),
+    });
+    thread::spawn(move || {
+        for plugin in plugins {
+            let Some(executable) = &plugin.executable else {
+                continue;
+            };
+            match run_executable(&plugin, executable, &request) {
+                Ok(_) => log_info!("[插件 {}] 安装后操作已完成", plugin.manifest.name),
+                Err(e) => log_error!("[插件 {}] 安装后操作失败: {}", plugin.manifest.name, e),
+            }
+        }
+    });
+}
+
+/// 获取已加载的插件列表
+#[tauri::command]
+pub fn list_plugins() -> AppResult<Vec<PluginInfo>> {
+    Ok(loaded_plugins()
+        .into_iter()
+        .map(|plugin| PluginInfo {
+            has_executable: plugin.executable.is_some(),
+            path: plugin.dir.display().to_string(),
+            name: plugin.manifest.name,
+            version: plugin.manifest.version,
+            description: plugin.manifest.description,
+            hosts: plugin.manifest.hosts,
+            post_install: plugin.manifest.post_install,
+        })
+        .collect())
+}
+
+/// 重新从插件目录加载插件
+///
+/// # 返回值
+/// - 加载的插件列表
+#[tauri::command]
+pub fn reload_plugins(app_handle: AppHandle) -> AppResult<Vec<PluginInfo>> {
+    let count = load_plugins(&app_handle);
+    log_info!("已重新加载 {} 个插件", count);
+    list_plugins()
+}
@@ -0,0 +1,133 @@
+// process_priority.rs 模块 - 外部进程（7z、aria2c）的 CPU 优先级、IO 优先级和处理器亲和性设置，避免后台任务占满 CPU 导致游戏卡顿
+
+// 标准库导入
+use std::os::windows::io::AsRawHandle;
+
+// 第三方库导入
+use serde::{Deserialize, Serialize};
+use windows_sys::{
+    Wdk::System::Threading::{ProcessIoPriority, PROCESSINFOCLASS},
+    Win32::{
+        Foundation::CloseHandle,
+        System::Threading::{
+            OpenProcess, SetPriorityClass, SetProcessAffinityMask, BELOW_NORMAL_PRIORITY_CLASS,
+            IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+        },
+    },
+};
+
+// windows-sys 没有导出 NtSetInformationProcess，直接从 ntdll 链接
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtSetInformationProcess(
+        process_handle: windows_sys::Win32::Foundation::HANDLE,
+        process_information_class: PROCESSINFOCLASS,
+        process_information: *const std::ffi::c_void,
+        process_information_length: u32,
+    ) -> i32;
+}
+
+/// IO 优先级：低（后台）和正常，对应 IO_PRIORITY_HINT 中的 IoPriorityLow / IoPriorityNormal
+const IO_PRIORITY_LOW: u32 = 1;
+const IO_PRIORITY_NORMAL: u32 = 2;
+
+/// 进程优先级，按从低到高的顺序排列
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessPriority {
+    /// 低（只在系统空闲时运行）
+    Idle,
+    /// 低于正常
+    BelowNormal,
+    /// 正常
+    #[default]
+    Normal,
+}
+
+impl ProcessPriority {
+    /// 对应的进程创建标志，与 CREATE_NO_WINDOW 等标志按位或后传给 creation_flags
+    pub fn creation_flag(self) -> u32 {
+        match self {
+            ProcessPriority::Idle => IDLE_PRIORITY_CLASS,
+            ProcessPriority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+            ProcessPriority::Normal => NORMAL_PRIORITY_CLASS,
+        }
+    }
+}
+
+/// 限制进程只能在指定的处理器上运行
+///
+/// # 参数
+/// - `child`: 已启动的子进程
+/// - `mask`: 处理器掩码，第 n 位表示第 n 个逻辑处理器，为 0 时不限制
+///
+/// # 返回值
+/// - 设置成功或不需要设置时返回Ok(())
+pub fn set_affinity(child: &std::process::Child, mask: u64) -> Result<(), String> {
+    if mask == 0 {
+        return Ok(());
+    }
+
+    let result = unsafe { SetProcessAffinityMask(child.as_raw_handle(), mask as usize) };
+    if result == 0 {
+        return Err(format!(
+            "设置处理器亲和性失败: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// 修改运行中进程的 CPU 优先级和 IO 优先级
+///
+/// # 参数
+/// - `pid`: 进程ID
+/// - `priority`: CPU 优先级
+/// - `low_io`: 是否使用低 IO 优先级
+///
+/// # 返回值
+/// - 成功时返回Ok(())
+pub fn set_process_priority(
+    pid: u32,
+    priority: ProcessPriority,
+    low_io: bool,
+) -> Result<(), String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return Err(format!(
+                "打开进程 {} 失败: {}",
+                pid,
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        let result = if SetPriorityClass(handle, priority.creation_flag()) == 0 {
+            Err(format!(
+                "设置进程 {} 的优先级失败: {}",
+                pid,
+                std::io::Error::last_os_error()
+            ))
+        } else {
+            let io_priority = if low_io {
+                IO_PRIORITY_LOW
+            } else {
+                IO_PRIORITY_NORMAL
+            };
+            let status = NtSetInformationProcess(
+                handle,
+                ProcessIoPriority,
+                &io_priority as *const u32 as *const std::ffi::c_void,
+                std::mem::size_of::<u32>() as u32,
+            );
+            if status < 0 {
+                Err(format!("设置进程 {} 的IO优先级失败: 0x{:08X}", pid, status))
+            } else {
+                Ok(())
+            }
+        };
+
+        CloseHandle(handle);
+        result
+    }
+}
@@ -1,20 +1,122 @@
+// queue_manager.rs 模块 - 下载队列和解压队列共用的任务队列：按优先级排列等待中的任务（同一优先级按加入顺序），
+// 记录每个任务的状态（等待中、进行中、已暂停、失败），并通过 QueueStore 保存和恢复队列
+
 // 标准库导入
 use std::{
     collections::{HashMap, VecDeque},
+    fs,
+    path::PathBuf,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 // 第三方库导入
-use tokio::time;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::{sync::Notify, time};
 
 // 内部模块导入
-use crate::log_debug;
+use crate::{cancellation::CancellationToken, log_debug};
+
+/// 任务优先级，优先级高的任务先开始
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    /// 等待开始
+    #[default]
+    Waiting,
+    /// 正在进行
+    Active,
+    /// 已暂停，恢复前不会开始
+    Paused,
+    /// 已失败，重新排队前不会开始
+    Failed,
+}
+
+/// 保存的任务 - 任务本身的字段和状态、优先级保存在同一个对象中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry<T> {
+    #[serde(flatten)]
+    pub task: T,
+    #[serde(default)]
+    pub state: TaskState,
+    #[serde(default)]
+    pub priority: TaskPriority,
+}
+
+impl<T> QueueEntry<T> {
+    /// 转换保存的任务内容，状态和优先级不变
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> QueueEntry<U> {
+        QueueEntry {
+            task: f(self.task),
+            state: self.state,
+            priority: self.priority,
+        }
+    }
+}
+
+/// 队列的保存位置
+pub trait QueueStore<T> {
+    /// 保存队列中的任务
+    fn save(&self, entries: &[QueueEntry<T>]) -> Result<(), String>;
+
+    /// 读取保存的任务，没有保存过时返回空列表
+    fn load(&self) -> Result<Vec<QueueEntry<T>>, String>;
+}
+
+/// 保存为 JSON 文件的队列：{"tasks": [...]}，队列为空时删除文件
+pub struct JsonFileStore {
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedQueue<T> {
+    tasks: Vec<QueueEntry<T>>,
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> QueueStore<T> for JsonFileStore {
+    fn save(&self, entries: &[QueueEntry<T>]) -> Result<(), String> {
+        if entries.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("无法删除空的队列文件: {:?}", e))
+                }
+                _ => Ok(()),
+            };
+        }
+
+        let content = serde_json::to_string_pretty(&SavedQueue {
+            tasks: entries.to_vec(),
+        })
+        .map_err(|e| format!("无法序列化队列: {:?}", e))?;
+        fs::write(&self.path, content).map_err(|e| format!("无法写入队列文件: {:?}", e))
+    }
+
+    fn load(&self) -> Result<Vec<QueueEntry<T>>, String> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&self.path).map_err(|e| format!("无法读取队列文件: {:?}", e))?;
+        serde_json::from_str::<SavedQueue<T>>(&content)
+            .map(|saved| saved.tasks)
+            .map_err(|e| format!("无法反序列化队列: {:?}", e))
+    }
+}
 
 /// 任务队列结构体 - 管理各类任务的队列和处理状态
 #[derive(Debug)]
 pub struct TaskQueue<T> {
-    /// 任务队列，按顺序存储待处理的任务ID
+    /// 任务队列，按优先级（同一优先级按加入顺序）存储待处理的任务ID
     pub waiting_tasks: VecDeque<String>,
     /// 标记队列处理是否已启动
     pub processing_started: bool,
@@ -22,19 +124,29 @@ pub struct TaskQueue<T> {
     pub max_concurrent_tasks: u32,
     /// 当前活跃任务的ID集合
     pub active_tasks: VecDeque<String>,
+    /// 已暂停的任务ID
+    pub paused_tasks: VecDeque<String>,
+    /// 已失败的任务ID
+    pub failed_tasks: VecDeque<String>,
     /// 所有任务的映射，存储完整的任务对象
     pub tasks: HashMap<String, T>,
+    /// 任务的优先级，没有记录的任务为 Normal
+    priorities: HashMap<String, TaskPriority>,
+    /// 任务的取消令牌，任务从队列中移除前一直保留
+    tokens: HashMap<String, CancellationToken>,
+    /// 唤醒正在等待的队列处理循环，修改并发数后立即检查队列
+    wake: Arc<Notify>,
+    /// 队列处理循环最后一次运行的时间，看门狗据此判断处理循环是否已停止
+    heartbeat: Option<Instant>,
+    /// 队列处理循环的代数，重新启动处理时递增，旧的处理循环发现代数变化后退出
+    generation: u64,
+    /// 判断等待中的任务现在能否开始（参数为该任务和进行中的任务），不能开始的任务保留在队列中，先开始后面的任务
+    dispatch_filter: Option<fn(&T, &[&T]) -> bool>,
 }
 
 impl<T> Default for TaskQueue<T> {
     fn default() -> Self {
-        Self {
-            waiting_tasks: VecDeque::new(),
-            processing_started: false,
-            max_concurrent_tasks: 1,
-            active_tasks: VecDeque::new(),
-            tasks: HashMap::new(),
-        }
+        Self::new(1)
     }
 }
 
@@ -46,34 +158,235 @@ impl<T> TaskQueue<T> {
             processing_started: false,
             max_concurrent_tasks,
             active_tasks: VecDeque::new(),
+            paused_tasks: VecDeque::new(),
+            failed_tasks: VecDeque::new(),
             tasks: HashMap::new(),
+            priorities: HashMap::new(),
+            tokens: HashMap::new(),
+            wake: Arc::new(Notify::new()),
+            heartbeat: None,
+            generation: 0,
+            dispatch_filter: None,
+        }
+    }
+
+    /// 设置任务分配条件，见 dispatch_filter
+    pub fn with_dispatch_filter(mut self, filter: fn(&T, &[&T]) -> bool) -> Self {
+        self.dispatch_filter = Some(filter);
+        self
+    }
+
+    /// 修改最大并发任务数，队列处理正在运行时也可以调用
+    ///
+    /// 提高并发数时唤醒队列处理循环立即启动更多等待中的任务；
+    /// 降低并发数时不会中断进行中的任务，超出的任务完成后才会启动新任务。
+    ///
+    /// # 参数
+    /// - `max_concurrent_tasks`: 新的最大并发任务数，最小为1
+    pub fn set_max_concurrent(&mut self, max_concurrent_tasks: u32) {
+        let max_concurrent_tasks = max_concurrent_tasks.max(1);
+        if max_concurrent_tasks == self.max_concurrent_tasks {
+            return;
+        }
+        log_debug!(
+            "最大并发任务数: {} -> {}，当前活跃任务数: {}",
+            self.max_concurrent_tasks,
+            max_concurrent_tasks,
+            self.active_tasks.len()
+        );
+        let raised = max_concurrent_tasks > self.max_concurrent_tasks;
+        self.max_concurrent_tasks = max_concurrent_tasks;
+        if raised && self.processing_started {
+            self.wake.notify_one();
+        }
+    }
+
+    /// 任务的优先级
+    pub fn priority(&self, task_id: &str) -> TaskPriority {
+        self.priorities.get(task_id).copied().unwrap_or_default()
+    }
+
+    /// 按优先级把任务ID插入等待队列，排在同一优先级的已有任务之后
+    fn enqueue(&mut self, task_id: String) {
+        let priority = self.priority(&task_id);
+        let index = self
+            .waiting_tasks
+            .iter()
+            .position(|id| self.priority(id) < priority)
+            .unwrap_or(self.waiting_tasks.len());
+        self.waiting_tasks.insert(index, task_id);
+    }
+
+    /// 从所有状态的ID列表中移除任务ID
+    fn detach(&mut self, task_id: &str) {
+        for list in [
+            &mut self.waiting_tasks,
+            &mut self.active_tasks,
+            &mut self.paused_tasks,
+            &mut self.failed_tasks,
+        ] {
+            list.retain(|id| id != task_id);
         }
     }
 
     /// 添加任务到队列
     pub fn add_task(&mut self, task_id: String, task: T) {
+        self.add_task_with_priority(task_id, task, TaskPriority::Normal);
+    }
+
+    /// 按指定优先级添加任务到队列
+    pub fn add_task_with_priority(&mut self, task_id: String, task: T, priority: TaskPriority) {
+        self.detach(&task_id);
         self.tasks.insert(task_id.clone(), task);
-        self.waiting_tasks.push_back(task_id);
+        self.priorities.insert(task_id.clone(), priority);
+        self.tokens
+            .insert(task_id.clone(), CancellationToken::new());
+        self.enqueue(task_id);
+    }
+
+    /// 获取任务的取消令牌，正在处理任务的循环通过它检查任务是否已被取消
+    pub fn cancellation_token(&self, task_id: &str) -> Option<CancellationToken> {
+        self.tokens.get(task_id).cloned()
+    }
+
+    /// 取消任务 - 只设置取消令牌，任务由处理它的循环结束后移除
+    ///
+    /// # 参数
+    /// - `task_id`: 任务ID
+    /// - `reason`: 取消原因
+    ///
+    /// # 返回值
+    /// - 任务不存在或已经取消时返回false
+    pub fn cancel_task(&self, task_id: &str, reason: &str) -> bool {
+        self.tokens
+            .get(task_id)
+            .is_some_and(|token| token.cancel(reason))
+    }
+
+    /// 修改任务的优先级，等待中的任务按新的优先级重新排列
+    ///
+    /// # 返回值
+    /// - 任务不存在时返回false
+    pub fn set_priority(&mut self, task_id: &str, priority: TaskPriority) -> bool {
+        if !self.tasks.contains_key(task_id) {
+            return false;
+        }
+        self.priorities.insert(task_id.to_string(), priority);
+        if let Some(index) = self.waiting_tasks.iter().position(|id| id == task_id) {
+            if let Some(task_id) = self.waiting_tasks.remove(index) {
+                self.enqueue(task_id);
+            }
+        }
+        true
+    }
+
+    /// 任务的状态，任务不存在时返回None
+    pub fn task_state(&self, task_id: &str) -> Option<TaskState> {
+        let contains = |list: &VecDeque<String>| list.iter().any(|id| id == task_id);
+        if contains(&self.active_tasks) {
+            Some(TaskState::Active)
+        } else if contains(&self.waiting_tasks) {
+            Some(TaskState::Waiting)
+        } else if contains(&self.paused_tasks) {
+            Some(TaskState::Paused)
+        } else if contains(&self.failed_tasks) {
+            Some(TaskState::Failed)
+        } else {
+            None
+        }
     }
 
-    /// 添加多个任务到队列
-    pub fn add_tasks(&mut self, tasks: Vec<(String, T)>) {
-        for (task_id, task) in tasks {
-            self.add_task(task_id, task);
+    /// 按顺序获取处于指定状态的任务
+    pub fn tasks_in(&self, state: TaskState) -> impl Iterator<Item = (&String, &T)> {
+        let list = match state {
+            TaskState::Waiting => &self.waiting_tasks,
+            TaskState::Active => &self.active_tasks,
+            TaskState::Paused => &self.paused_tasks,
+            TaskState::Failed => &self.failed_tasks,
+        };
+        list.iter()
+            .filter_map(|id| self.tasks.get(id).map(|task| (id, task)))
+    }
+
+    /// 暂停等待中的任务
+    ///
+    /// # 返回值
+    /// - 任务不在等待中时返回false
+    pub fn pause_task(&mut self, task_id: &str) -> bool {
+        if self.task_state(task_id) != Some(TaskState::Waiting) {
+            return false;
         }
+        self.detach(task_id);
+        self.paused_tasks.push_back(task_id.to_string());
+        true
+    }
+
+    /// 恢复已暂停的任务或重新排队失败的任务，按优先级回到等待队列
+    ///
+    /// # 返回值
+    /// - 任务既没有暂停也没有失败时返回false
+    pub fn requeue_task(&mut self, task_id: &str) -> bool {
+        if !matches!(
+            self.task_state(task_id),
+            Some(TaskState::Paused | TaskState::Failed)
+        ) {
+            return false;
+        }
+        self.detach(task_id);
+        self.enqueue(task_id.to_string());
+        true
+    }
+
+    /// 移除没有在进行中的任务（等待中、已暂停或失败）
+    ///
+    /// # 返回值
+    /// - 被移除的任务，任务不存在或正在进行时返回None
+    pub fn remove_pending_task(&mut self, task_id: &str) -> Option<T> {
+        match self.task_state(task_id) {
+            None | Some(TaskState::Active) => None,
+            Some(_) => {
+                self.detach(task_id);
+                self.priorities.remove(task_id);
+                if let Some(token) = self.tokens.remove(task_id) {
+                    token.cancel("normal");
+                }
+                self.tasks.remove(task_id)
+            }
+        }
+    }
+
+    /// 移除所有没有在进行中的任务（等待中、已暂停和失败），进行中的任务不受影响
+    ///
+    /// # 返回值
+    /// - 被移除的任务数
+    pub fn clear_pending_tasks(&mut self) -> usize {
+        let mut count = 0;
+        for list in [
+            &mut self.waiting_tasks,
+            &mut self.paused_tasks,
+            &mut self.failed_tasks,
+        ] {
+            for task_id in std::mem::take(list) {
+                self.priorities.remove(&task_id);
+                if let Some(token) = self.tokens.remove(&task_id) {
+                    token.cancel("normal");
+                }
+                self.tasks.remove(&task_id);
+                count += 1;
+            }
+        }
+        count
     }
 
     /// 清空队列中的所有任务
     pub fn clear_tasks(&mut self) {
         self.waiting_tasks.clear();
         self.active_tasks.clear();
+        self.paused_tasks.clear();
+        self.failed_tasks.clear();
         self.tasks.clear();
-    }
-
-    /// 用新任务替换当前队列中的所有任务
-    pub fn replace_tasks(&mut self, tasks: Vec<(String, T)>) {
-        self.clear_tasks();
-        self.add_tasks(tasks);
+        self.priorities.clear();
+        self.tokens.clear();
     }
 
     /// 检查是否可以启动新任务
@@ -81,18 +394,27 @@ impl<T> TaskQueue<T> {
         self.active_tasks.len() < self.max_concurrent_tasks as usize
     }
 
-    /// 从队列中取出一个任务并标记为活跃
+    /// 从队列中取出一个任务并标记为活跃，设置了分配条件时跳过暂时不能开始的任务
     pub fn take_next_task(&mut self) -> Option<String> {
-        if self.can_start_new_task() {
-            if let Some(task_id) = self.waiting_tasks.pop_front() {
-                self.active_tasks.push_back(task_id.clone());
-                Some(task_id)
-            } else {
-                None
-            }
-        } else {
-            None
+        if !self.can_start_new_task() {
+            return None;
         }
+        let index = match self.dispatch_filter {
+            Some(filter) => {
+                let active = self
+                    .active_tasks
+                    .iter()
+                    .filter_map(|id| self.tasks.get(id))
+                    .collect::<Vec<_>>();
+                self.waiting_tasks
+                    .iter()
+                    .position(|id| self.tasks.get(id).is_none_or(|task| filter(task, &active)))?
+            }
+            None => 0,
+        };
+        let task_id = self.waiting_tasks.remove(index)?;
+        self.active_tasks.push_back(task_id.clone());
+        Some(task_id)
     }
 
     /// 从活跃任务集合中移除任务
@@ -102,12 +424,80 @@ impl<T> TaskQueue<T> {
         }
         // 从任务映射中移除
         self.tasks.remove(task_id);
+        self.priorities.remove(task_id);
+        self.tokens.remove(task_id);
+    }
+
+    /// 队列处理标记为已启动、但处理循环超过指定时间没有运行时，返回距上次运行的时间
+    ///
+    /// # 参数
+    /// - `timeout`: 处理循环允许的最长无响应时间
+    pub fn stalled_for(&self, timeout: Duration) -> Option<Duration> {
+        if !self.processing_started {
+            return None;
+        }
+        let elapsed = self.heartbeat.map(|heartbeat| heartbeat.elapsed())?;
+        (elapsed > timeout).then_some(elapsed)
+    }
+
+    /// 放弃当前的处理循环，标记队列处理未启动。旧的处理循环恢复运行后会发现代数变化并退出
+    pub fn reset_processing(&mut self) {
+        self.processing_started = false;
+        self.heartbeat = None;
+        self.generation += 1;
+    }
+
+    /// 检查队列是否空闲（没有等待中和进行中的任务）
+    pub fn is_idle(&self) -> bool {
+        self.waiting_tasks.is_empty() && self.active_tasks.is_empty()
     }
 
     /// 通过ID查找任务
     pub fn find_task(&self, task_id: &str) -> Option<&T> {
         self.tasks.get(task_id)
     }
+
+    /// 获取需要保存的任务：进行中的任务在前，然后是等待中、已暂停和失败的任务
+    pub fn entries(&self) -> Vec<QueueEntry<T>>
+    where
+        T: Clone,
+    {
+        [
+            TaskState::Active,
+            TaskState::Waiting,
+            TaskState::Paused,
+            TaskState::Failed,
+        ]
+        .into_iter()
+        .flat_map(|state| {
+            self.tasks_in(state).map(move |(id, task)| QueueEntry {
+                task: task.clone(),
+                state,
+                priority: self.priority(id),
+            })
+        })
+        .collect()
+    }
+
+    /// 恢复保存的任务，上次进行中的任务重新回到等待队列
+    ///
+    /// # 参数
+    /// - `entries`: (任务ID, 保存的任务)
+    pub fn restore(&mut self, entries: Vec<(String, QueueEntry<T>)>) {
+        for (task_id, entry) in entries {
+            self.add_task_with_priority(task_id.clone(), entry.task, entry.priority);
+            match entry.state {
+                TaskState::Paused => {
+                    self.pause_task(&task_id);
+                }
+                TaskState::Failed => {
+                    self.detach(&task_id);
+                    self.failed_tasks.push_back(task_id);
+                }
+                TaskState::Waiting | TaskState::Active => {}
+            }
+        }
+    }
 }
 
 /// 处理队列的通用函数
@@ -124,11 +514,14 @@ pub async fn process_queue<T: std::marker::Send + 'static>(
     should_continue_fn: impl Fn() -> bool + 'static,
 ) {
     // 标记队列处理已启动
-    {
+    let (wake, generation) = {
         let mut q = queue.lock().unwrap();
         q.processing_started = true;
+        q.heartbeat = Some(Instant::now());
+        q.generation += 1;
         log_debug!("队列处理已启动，最大并发任务数: {}", q.max_concurrent_tasks);
-    }
+        (q.wake.clone(), q.generation)
+    };
 
     // 创建一个持续运行的循环，定期检查队列并启动新任务
     loop {
@@ -142,6 +535,13 @@ pub async fn process_queue<T: std::marker::Send + 'static>(
         let (task_to_process, has_waiting_tasks, has_active_tasks, can_start) = {
             let mut q = queue.lock().unwrap();
 
+            // 看门狗已经启动了新的处理循环
+            if q.generation != generation {
+                log_debug!("队列处理已由新的处理循环接管，退出循环");
+                return;
+            }
+            q.heartbeat = Some(Instant::now());
+
             // 检查是否有任务
             let has_waiting_tasks = !q.waiting_tasks.is_empty();
             let has_active_tasks = !q.active_tasks.is_empty();
@@ -151,6 +551,7 @@ pub async fn process_queue<T: std::marker::Send + 'static>(
             if !has_waiting_tasks && !has_active_tasks {
                 log_debug!("队列中没有任务，退出队列处理循环");
                 q.processing_started = false;
+                q.heartbeat = None;
                 return;
             }
 
@@ -178,6 +579,13 @@ pub async fn process_queue<T: std::marker::Send + 'static>(
             if let Some(task) = q.find_task(&task_id) {
                 process_task_fn(task);
             }
+
+            // 还有空闲的并发数时立即启动下一个任务（例如刚提高了并发数）
+            if !q.waiting_tasks.is_empty() && q.can_start_new_task() {
+                drop(q);
+                std::thread::yield_now();
+                continue;
+            }
         }
 
         // 根据队列状态调整等待时间
@@ -192,9 +600,9 @@ pub async fn process_queue<T: std::marker::Send + 'static>(
             sleep_duration // 只有等待任务时，使用默认等待时间
         };
 
-        // 为了避免CPU占用过高，让出当前线程的执行权
+        // 为了避免CPU占用过高，让出当前线程的执行权；修改并发数时提前唤醒
         std::thread::yield_now();
-        time::sleep(Duration::from_millis(sleep_time)).await;
+        let _ = time::timeout(Duration::from_millis(sleep_time), wake.notified()).await;
     }
 }
 
@@ -216,6 +624,13 @@ impl<T: std::marker::Send + 'static> QueueManager<T> {
         self.queue.lock().unwrap().add_task(task_id, task);
     }
 
+    /// 在运行时修改最大并发任务数，见 TaskQueue::set_max_concurrent
+    pub fn set_max_concurrent(&self, max_concurrent_tasks: u32) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.set_max_concurrent(max_concurrent_tasks);
+        }
+    }
+
     /// 启动队列处理
     pub fn start_processing(
         &self,
@@ -237,3 +652,155 @@ impl<T: std::marker::Send + 'static> QueueManager<T> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Job {
+        host: String,
+    }
+
+    fn job(host: &str) -> Job {
+        Job {
+            host: host.to_string(),
+        }
+    }
+
+    fn waiting_ids<T>(queue: &TaskQueue<T>) -> Vec<&str> {
+        queue.waiting_tasks.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn waiting_tasks_ordered_by_priority_then_insertion() {
+        let mut queue = TaskQueue::new(4);
+        queue.add_task("a".to_string(), job("x"));
+        queue.add_task_with_priority("b".to_string(), job("x"), TaskPriority::Low);
+        queue.add_task_with_priority("c".to_string(), job("x"), TaskPriority::High);
+        queue.add_task("d".to_string(), job("x"));
+        assert_eq!(waiting_ids(&queue), ["c", "a", "d", "b"]);
+
+        assert!(queue.set_priority("b", TaskPriority::High));
+        assert_eq!(waiting_ids(&queue), ["c", "b", "a", "d"]);
+
+        let started = std::iter::from_fn(|| queue.take_next_task()).collect::<Vec<_>>();
+        assert_eq!(started, ["c", "b", "a", "d"]);
+    }
+
+    #[test]
+    fn take_next_task_respects_max_concurrent() {
+        let mut queue = TaskQueue::new(1);
+        queue.add_task("a".to_string(), job("x"));
+        queue.add_task("b".to_string(), job("x"));
+        assert_eq!(queue.take_next_task().as_deref(), Some("a"));
+        assert_eq!(queue.take_next_task(), None);
+
+        queue.remove_active_task("a");
+        assert_eq!(queue.take_next_task().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn paused_tasks_are_skipped_until_requeued() {
+        let mut queue = TaskQueue::new(2);
+        queue.add_task("a".to_string(), job("x"));
+        queue.add_task_with_priority("b".to_string(), job("x"), TaskPriority::High);
+
+        assert!(queue.pause_task("b"));
+        assert_eq!(queue.task_state("b"), Some(TaskState::Paused));
+        assert!(!queue.pause_task("b"));
+        assert_eq!(queue.take_next_task().as_deref(), Some("a"));
+        assert!(!queue.pause_task("a"), "进行中的任务不能暂停");
+
+        assert!(queue.requeue_task("b"));
+        assert_eq!(queue.task_state("b"), Some(TaskState::Waiting));
+        assert!(!queue.requeue_task("b"));
+        assert_eq!(queue.take_next_task().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn dispatch_filter_skips_tasks_that_cannot_start() {
+        let one_per_host =
+            |task: &Job, active: &[&Job]| !active.iter().any(|other| other.host == task.host);
+        let mut queue = TaskQueue::new(3).with_dispatch_filter(one_per_host);
+        queue.add_task("a".to_string(), job("x"));
+        queue.add_task("b".to_string(), job("x"));
+        queue.add_task("c".to_string(), job("y"));
+
+        assert_eq!(queue.take_next_task().as_deref(), Some("a"));
+        assert_eq!(queue.take_next_task().as_deref(), Some("c"));
+        assert_eq!(queue.take_next_task(), None);
+        assert_eq!(waiting_ids(&queue), ["b"]);
+
+        queue.remove_active_task("a");
+        assert_eq!(queue.take_next_task().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn entries_restore_round_trip() {
+        let mut queue = TaskQueue::new(1);
+        queue.add_task("a".to_string(), job("a"));
+        assert_eq!(queue.take_next_task().as_deref(), Some("a"));
+        queue.add_task_with_priority("w".to_string(), job("w"), TaskPriority::High);
+        queue.add_task_with_priority("p".to_string(), job("p"), TaskPriority::Low);
+        queue.pause_task("p");
+        queue.add_task("f".to_string(), job("f"));
+        queue.detach("f");
+        queue.failed_tasks.push_back("f".to_string());
+
+        let entries = queue.entries();
+        let states = entries
+            .iter()
+            .map(|entry| (entry.task.host.as_str(), entry.state, entry.priority))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            [
+                ("a", TaskState::Active, TaskPriority::Normal),
+                ("w", TaskState::Waiting, TaskPriority::High),
+                ("p", TaskState::Paused, TaskPriority::Low),
+                ("f", TaskState::Failed, TaskPriority::Normal),
+            ]
+        );
+
+        // 经过 JSON 序列化后恢复，上次进行中的任务回到等待队列
+        let json = serde_json::to_string(&entries).unwrap();
+        let saved = serde_json::from_str::<Vec<QueueEntry<Job>>>(&json).unwrap();
+        let mut restored = TaskQueue::new(1);
+        restored.restore(
+            saved
+                .into_iter()
+                .map(|entry| (entry.task.host.clone(), entry))
+                .collect(),
+        );
+
+        assert_eq!(waiting_ids(&restored), ["w", "a"]);
+        assert_eq!(restored.task_state("p"), Some(TaskState::Paused));
+        assert_eq!(restored.priority("p"), TaskPriority::Low);
+        assert_eq!(restored.task_state("f"), Some(TaskState::Failed));
+        assert_eq!(restored.find_task("f"), Some(&job("f")));
+    }
+
+    #[test]
+    fn json_file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("nmd-queue-test-{}.json", std::process::id()));
+        let store = JsonFileStore { path: path.clone() };
+        let entries = vec![QueueEntry {
+            task: job("x"),
+            state: TaskState::Paused,
+            priority: TaskPriority::High,
+        }];
+
+        store.save(&entries).unwrap();
+        let loaded: Vec<QueueEntry<Job>> = store.load().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].task, job("x"));
+        assert_eq!(loaded[0].state, TaskState::Paused);
+        assert_eq!(loaded[0].priority, TaskPriority::High);
+
+        // 队列为空时删除文件
+        QueueStore::<Job>::save(&store, &[]).unwrap();
+        assert!(!path.exists());
+        assert!(QueueStore::<Job>::load(&store).unwrap().is_empty());
+    }
+}
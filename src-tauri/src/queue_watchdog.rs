@@ -0,0 +1,132 @@
+// queue_watchdog.rs 模块 - 队列看门狗：下载队列和解压队列的处理循环每次运行都会更新心跳时间，
+// 处理循环因 panic 或其他原因停止后队列中的任务会一直积压，看门狗定期检查心跳，
+// 发现处理循环停止时重新启动处理，并向前端发送 queue-watchdog-restarted 诊断事件
+
+// 标准库导入
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+// 第三方库导入
+use serde::Serialize;
+use tauri::AppHandle;
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_error, log_info, log_warn, queue_manager::TaskQueue};
+
+/// 检查心跳的间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 处理循环超过此时间没有运行时视为已停止（处理循环最长每2秒运行一次）
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 看门狗重新启动处理循环时发送的诊断事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchdogRestart {
+    /// 队列名称：download 或 extract
+    queue: &'static str,
+    /// 重新启动的原因：stalled（心跳超时）或 poisoned（处理循环持有队列锁时 panic）
+    reason: &'static str,
+    /// 距处理循环上次运行的秒数
+    stalled_secs: u64,
+    /// 等待中的任务数
+    waiting_tasks: usize,
+    /// 进行中的任务数
+    active_tasks: usize,
+}
+
+/// 获取队列锁，处理循环持有锁时 panic 导致锁中毒时清除中毒标记
+///
+/// # 返回值
+/// - 队列锁和锁是否曾经中毒
+fn lock_queue<T>(queue: &Arc<Mutex<TaskQueue<T>>>) -> (MutexGuard<'_, TaskQueue<T>>, bool) {
+    match queue.lock() {
+        Ok(guard) => (guard, false),
+        Err(poisoned) => {
+            queue.clear_poison();
+            (poisoned.into_inner(), true)
+        }
+    }
+}
+
+/// 检查队列的处理循环，已停止时标记队列处理未启动
+///
+/// # 返回值
+/// - 需要重新启动处理时返回诊断事件
+fn check_queue<T>(name: &'static str, queue: &Arc<Mutex<TaskQueue<T>>>) -> Option<WatchdogRestart> {
+    let (mut q, poisoned) = lock_queue(queue);
+    let stalled = q.stalled_for(HEARTBEAT_TIMEOUT);
+    if !poisoned && stalled.is_none() {
+        return None;
+    }
+    if q.is_idle() {
+        // 没有任务需要处理，下次添加任务时会正常启动处理
+        q.reset_processing();
+        return None;
+    }
+
+    let restart = WatchdogRestart {
+        queue: name,
+        reason: if poisoned { "poisoned" } else { "stalled" },
+        stalled_secs: stalled.unwrap_or_default().as_secs(),
+        waiting_tasks: q.waiting_tasks.len(),
+        active_tasks: q.active_tasks.len(),
+    };
+    q.reset_processing();
+    Some(restart)
+}
+
+/// 重新启动队列处理并发送诊断事件
+fn restart_queue(app_handle: &AppHandle, restart: WatchdogRestart) {
+    log_warn!(
+        "{} 队列处理循环已停止（原因: {}，{} 秒无响应，等待中: {}，进行中: {}），重新启动处理",
+        restart.queue,
+        restart.reason,
+        restart.stalled_secs,
+        restart.waiting_tasks,
+        restart.active_tasks
+    );
+
+    match restart.queue {
+        "download" => {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                crate::download_manager::process_download_queue(app_handle).await;
+            });
+        }
+        _ => crate::extract_manager::start_extract_queue_manager(),
+    }
+
+    if let Err(e) = crate::event_dispatcher::emit(app_handle, "queue-watchdog-restarted", restart) {
+        log_error!("发送队列看门狗事件失败: {}", e);
+    }
+}
+
+/// 启动队列看门狗线程
+///
+/// # 参数
+/// - `app_handle`: 应用句柄
+pub fn start_queue_watchdog(app_handle: AppHandle) {
+    thread::spawn(move || {
+        log_info!("队列看门狗已启动");
+        loop {
+            thread::sleep(WATCHDOG_INTERVAL);
+            if is_app_shutting_down() {
+                break;
+            }
+
+            if let Some(restart) = check_queue("download", &crate::download_manager::DOWNLOAD_QUEUE)
+            {
+                restart_queue(&app_handle, restart);
+            }
+            if let Some(restart) =
+                check_queue("extract", &crate::extract_manager::EXTRACT_MANAGER.queue)
+            {
+                restart_queue(&app_handle, restart);
+            }
+        }
+    });
+}
@@ -0,0 +1,135 @@
+// quick_install.rs 模块 - 全局快捷键快速安装：按下快捷键后读取剪贴板中的链接，校验网站后直接加入下载队列，并用系统通知提示结果
+//
+// 快捷键通过 config.json 的 quick_install_shortcut 配置（例如 "CommandOrControl+Alt+V"），为空时不注册。
+
+// 标准库导入
+use std::sync::Mutex;
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use tauri::{AppHandle, Url};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
+
+// 内部模块导入
+use crate::{host_adapter, init::GLOBAL_APP_HANDLE, log_error, log_info, log_warn, tr};
+
+lazy_static! {
+    /// 当前已注册的快捷键
+    static ref REGISTERED_SHORTCUT: Mutex<Option<Shortcut>> = Mutex::new(None);
+}
+
+/// 按配置注册快速安装快捷键，配置变化时替换之前注册的快捷键
+///
+/// # 参数
+/// - `shortcut`: 快捷键，例如 "CommandOrControl+Alt+V"，为空时取消注册
+pub fn apply_shortcut(shortcut: &str) {
+    let Some(app_handle) = GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    else {
+        return;
+    };
+
+    let shortcut = shortcut.trim();
+    let new_shortcut = if shortcut.is_empty() {
+        None
+    } else {
+        match shortcut.parse::<Shortcut>() {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                log_warn!("快速安装快捷键 {} 无效: {}", shortcut, e);
+                None
+            }
+        }
+    };
+
+    let mut registered = REGISTERED_SHORTCUT.lock().unwrap();
+    if *registered == new_shortcut {
+        return;
+    }
+
+    let global_shortcut = app_handle.global_shortcut();
+    if let Some(old) = registered.take() {
+        if let Err(e) = global_shortcut.unregister(old) {
+            log_warn!("取消注册快速安装快捷键失败: {}", e);
+        }
+    }
+    if let Some(new_shortcut) = new_shortcut {
+        match global_shortcut.register(new_shortcut) {
+            Ok(()) => {
+                log_info!("已注册快速安装快捷键: {}", shortcut);
+                *registered = Some(new_shortcut);
+            }
+            // 快捷键可能已被其他程序占用
+            Err(e) => log_warn!("注册快速安装快捷键 {} 失败: {}", shortcut, e),
+        }
+    }
+}
+
+/// 全局快捷键回调，在 global-shortcut 插件中注册
+pub fn handle_shortcut(app_handle: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed
+        || *REGISTERED_SHORTCUT.lock().unwrap() != Some(*shortcut)
+    {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        match install_from_clipboard(&app_handle).await {
+            Ok(url) => notify(
+                &app_handle,
+                &tr!("quick_install.queued.title"),
+                &tr!("quick_install.queued.message", url = url),
+            ),
+            Err(message) => notify(&app_handle, &tr!("quick_install.failed.title"), &message),
+        }
+    });
+}
+
+/// 读取剪贴板中的链接并加入下载队列
+///
+/// # 返回值
+/// - 成功时返回加入队列的链接
+/// - 失败时返回显示给用户的错误信息
+async fn install_from_clipboard(app_handle: &AppHandle) -> Result<String, String> {
+    let text = app_handle
+        .clipboard()
+        .read_text()
+        .map_err(|_| tr!("quick_install.clipboard_empty"))?;
+    let text = text.trim();
+
+    let url = Url::parse(text)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| tr!("quick_install.invalid_url"))?;
+    if !host_adapter::is_known_host(&url) {
+        log_warn!("快速安装: 不支持的网站 {}", url);
+        return Err(tr!(
+            "quick_install.unknown_host",
+            host = url.host_str().unwrap_or_default()
+        ));
+    }
+
+    log_info!("快速安装: 从剪贴板添加下载 {}", url);
+    crate::commands::install(url.as_str(), "", false, None, app_handle.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(url.to_string())
+}
+
+/// 显示系统通知
+fn notify(app_handle: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        log_error!("显示通知失败: {}", e);
+    }
+}
@@ -0,0 +1,253 @@
+// resume_validator.rs 模块 - 继续未完成的下载前检查服务器上的文件是否已变化，避免把新旧两个版本拼成损坏的压缩包
+//
+// 开始新的下载时记录服务器返回的 ETag / Last-Modified，继续下载前用 If-None-Match / If-Modified-Since
+// 发送条件请求：服务器返回 304 或校验值一致时继续下载，否则删除临时文件重新下载。
+// 服务器不提供校验值或请求失败时无法判断，仍然继续下载。
+// 开始新的下载时的同一请求还会取得 Content-Disposition 中的文件名，用于显示和解压目录。
+
+// 标准库导入
+use std::{collections::HashMap, fs, path::PathBuf, sync::Mutex, time::Duration};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use reqwest::{header, StatusCode};
+use serde::{Deserialize, Serialize};
+
+// 内部模块导入
+use crate::{cert_pinning, host_adapter, log_debug, log_info, log_warn};
+
+/// 校验值记录文件名，与下载队列文件位于同一目录
+const VALIDATORS_FILE: &str = "partial_validators.json";
+
+/// 请求超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    /// 保护校验值记录文件的读写
+    static ref VALIDATORS_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// 服务器返回的文件校验值
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// 校验值记录文件路径
+fn validators_file_path() -> Result<PathBuf, String> {
+    crate::download_manager::get_download_queue_file_path()
+        .map(|path| path.with_file_name(VALIDATORS_FILE))
+}
+
+/// 读取URL到校验值的映射
+fn read_validators() -> HashMap<String, Validators> {
+    validators_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 写入URL到校验值的映射，映射为空时删除文件
+fn write_validators(validators: &HashMap<String, Validators>) {
+    let path = match validators_file_path() {
+        Ok(path) => path,
+        Err(e) => {
+            log_warn!("无法保存下载校验值记录: {}", e);
+            return;
+        }
+    };
+
+    let result = if validators.is_empty() {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    } else {
+        serde_json::to_string_pretty(validators)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()))
+    };
+    if let Err(e) = result {
+        log_warn!("保存下载校验值记录失败: {}", e);
+    }
+}
+
+/// 请求文件的第一个字节，返回响应状态、校验值和 Content-Disposition 中的文件名
+///
+/// # 参数
+/// - `url`: 下载URL
+/// - `conditional`: 发送条件请求时使用的校验值
+async fn fetch_validators(
+    url: &str,
+    conditional: Option<&Validators>,
+) -> Result<(StatusCode, Validators, Option<String>), String> {
+    let (user_agent, headers) = host_adapter::request_options(url);
+    let client = cert_pinning::client_builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(user_agent.unwrap_or(crate::aria2c::DEFAULT_USER_AGENT))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+    let mut request = client
+        .get(host_adapter::direct_url(url))
+        .header(header::RANGE, "bytes=0-0");
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    if let Some(validators) = conditional {
+        if let Some(etag) = &validators.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    cert_pinning::verify_response(&response)?;
+
+    let header_value = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let validators = Validators {
+        etag: header_value(header::ETAG),
+        last_modified: header_value(header::LAST_MODIFIED),
+    };
+    let file_name = response
+        .headers()
+        .get(header::CONTENT_DISPOSITION)
+        .map(host_adapter::header_text)
+        .as_deref()
+        .and_then(host_adapter::content_disposition_file_name);
+    Ok((response.status(), validators, file_name))
+}
+
+/// 开始新的下载时记录服务器返回的校验值，请求失败或服务器不提供校验值时不记录，同时获取服务器提供的文件名
+///
+/// # 参数
+/// - `url`: 下载URL
+///
+/// # 返回值
+/// - 服务器通过 Content-Disposition 提供的文件名
+pub async fn record(url: &str) -> Option<String> {
+    let (validators, file_name) = match fetch_validators(url, None).await {
+        Ok((status, validators, file_name)) if status.is_success() => (validators, file_name),
+        Ok((status, _, _)) => {
+            log_debug!("获取下载校验值失败 ({}): {}", status, url);
+            forget(url);
+            return None;
+        }
+        Err(e) => {
+            log_debug!("获取下载校验值失败: {}, 错误: {}", url, e);
+            forget(url);
+            return None;
+        }
+    };
+    if validators.is_empty() {
+        log_debug!("服务器未提供可用的校验值: {}", url);
+        forget(url);
+        return file_name;
+    }
+
+    log_debug!("记录下载校验值: {} -> {:?}", url, validators);
+    if let Ok(_guard) = VALIDATORS_LOCK.lock() {
+        let mut all = read_validators();
+        all.insert(url.to_string(), validators);
+        write_validators(&all);
+    }
+    file_name
+}
+
+/// 继续未完成的下载前检查服务器上的文件是否未变化
+///
+/// # 参数
+/// - `url`: 下载URL
+///
+/// # 返回值
+/// - 文件已变化时返回false；未变化或无法判断时返回true
+pub async fn is_unchanged(url: &str) -> bool {
+    let stored = {
+        let Ok(_guard) = VALIDATORS_LOCK.lock() else {
+            return true;
+        };
+        read_validators().remove(url)
+    };
+    let Some(stored) = stored else {
+        log_debug!("没有下载校验值记录，无法检查文件是否变化: {}", url);
+        return true;
+    };
+
+    let (status, current) = match fetch_validators(url, Some(&stored)).await {
+        Ok((status, current, _)) => (status, current),
+        Err(e) => {
+            log_warn!("检查文件是否变化失败，继续下载: {}, 错误: {}", url, e);
+            return true;
+        }
+    };
+
+    let unchanged = if status == StatusCode::NOT_MODIFIED {
+        true
+    } else if !status.is_success() {
+        // 链接失效等错误交给下载过程处理
+        true
+    } else if let (Some(stored), Some(current)) = (&stored.etag, &current.etag) {
+        stored == current
+    } else if let (Some(stored), Some(current)) = (&stored.last_modified, &current.last_modified) {
+        stored == current
+    } else {
+        true
+    };
+
+    if !unchanged {
+        log_info!(
+            "服务器上的文件已变化: {}, 原校验值: {:?}, 当前校验值: {:?}",
+            url,
+            stored,
+            current
+        );
+    }
+    unchanged
+}
+
+/// 删除URL的校验值记录
+///
+/// # 参数
+/// - `url`: 下载URL
+pub fn forget(url: &str) {
+    if let Ok(_guard) = VALIDATORS_LOCK.lock() {
+        let mut all = read_validators();
+        if all.remove(url).is_some() {
+            write_validators(&all);
+        }
+    }
+}
+
+/// 只保留满足条件的URL的校验值记录
+///
+/// # 参数
+/// - `keep`: 判断是否保留的函数
+pub fn retain(keep: impl Fn(&str) -> bool) {
+    if let Ok(_guard) = VALIDATORS_LOCK.lock() {
+        let mut all = read_validators();
+        let before = all.len();
+        all.retain(|url, _| keep(url));
+        if all.len() != before {
+            write_validators(&all);
+        }
+    }
+}
@@ -0,0 +1,358 @@
+// self_test.rs 模块 - 内置自检（aria2c RPC、7z 解压、addons 目录写入权限和符号链接权限）和资源修复
+
+// 标准库导入
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
+
+// 第三方库导入
+use serde::Serialize;
+
+// 内部模块导入
+use crate::{
+    aria2c::{Aria2RpcManager, ARIA2C_PATH, ARIA2_RPC_MANAGER},
+    dir_manager::DIR_MANAGER,
+    error::AppResult,
+    extract_manager::{SEVENZG_PATH, SEVENZ_PATH},
+    log_info, log_warn, tr,
+};
+
+/// 内置的测试压缩包（仅存储模式，包含 self-test.txt，内容为 SELF_TEST_CONTENT）
+const SELF_TEST_ARCHIVE: &[u8] = &[
+    0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x58, 0x5C, 0xCA,
+    0x38, 0xE1, 0x0D, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x00, 0x73, 0x65,
+    0x6C, 0x66, 0x2D, 0x74, 0x65, 0x73, 0x74, 0x2E, 0x74, 0x78, 0x74, 0x6E, 0x6D, 0x64, 0x20, 0x73,
+    0x65, 0x6C, 0x66, 0x2D, 0x74, 0x65, 0x73, 0x74, 0x50, 0x4B, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x58, 0x5C, 0xCA, 0x38, 0xE1, 0x0D, 0x00, 0x00, 0x00,
+    0x0D, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x80, 0x01, 0x00, 0x00, 0x00, 0x00, 0x73, 0x65, 0x6C, 0x66, 0x2D, 0x74, 0x65, 0x73, 0x74, 0x2E,
+    0x74, 0x78, 0x74, 0x50, 0x4B, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x3B,
+    0x00, 0x00, 0x00, 0x38, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// 测试压缩包中的文件名
+const SELF_TEST_FILE_NAME: &str = "self-test.txt";
+
+/// 测试压缩包中文件的内容
+const SELF_TEST_CONTENT: &str = "nmd self-test";
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestCheck {
+    /// 检查项标识（aria2c、sevenzip、addons_writable、symlink）
+    pub id: &'static str,
+    /// 检查项名称
+    pub name: String,
+    /// 是否通过
+    pub passed: bool,
+    /// 结果说明或失败原因
+    pub message: String,
+    /// 耗时（毫秒）
+    pub duration_ms: u64,
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    /// 全部检查项是否都通过
+    pub passed: bool,
+    /// 各检查项结果，按执行顺序排列
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// 程序依赖的外部文件状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceStatus {
+    /// 文件名
+    pub name: String,
+    /// 文件路径
+    pub path: String,
+    /// 文件是否存在且不为空
+    pub present: bool,
+}
+
+/// 资源修复报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    /// 各外部文件的状态
+    pub resources: Vec<ResourceStatus>,
+    /// 已修复的问题
+    pub fixed: Vec<String>,
+    /// 无法自动修复的问题
+    pub unresolved: Vec<String>,
+}
+
+/// 根据检查结果生成检查项
+fn make_check(id: &'static str, started: Instant, result: Result<String, String>) -> SelfTestCheck {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let name = tr!(&format!("self_test.{}", id));
+    match result {
+        Ok(message) => {
+            log_info!("自检通过 [{}]: {}", id, message);
+            SelfTestCheck {
+                id,
+                name,
+                passed: true,
+                message,
+                duration_ms,
+            }
+        }
+        Err(message) => {
+            log_warn!("自检失败 [{}]: {}", id, message);
+            SelfTestCheck {
+                id,
+                name,
+                passed: false,
+                message,
+                duration_ms,
+            }
+        }
+    }
+}
+
+/// 创建自检用的临时目录
+fn create_temp_dir(parent: &Path, name: &str) -> Result<PathBuf, String> {
+    let dir = parent.join(format!(".nmd-{}-{}", name, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).map_err(|e| format!("无法创建临时目录 {}: {:?}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// 获取已配置的 addons 目录
+fn configured_addons_dir() -> Result<PathBuf, String> {
+    DIR_MANAGER
+        .lock()
+        .map_err(|e| format!("无法获取目录管理器锁: {:?}", e))?
+        .as_ref()
+        .and_then(|dm| dm.addons_dir().cloned())
+        .ok_or_else(|| tr!("self_test.addons_not_configured"))
+}
+
+/// 检查 aria2c 能否启动并响应 RPC 请求
+///
+/// 已有运行中的 RPC 服务器时直接检查它，否则启动一个临时实例，检查完成后关闭
+async fn check_aria2c() -> Result<String, String> {
+    let running = ARIA2_RPC_MANAGER
+        .lock()
+        .map_err(|e| format!("无法获取RPC管理器锁: {:?}", e))?
+        .clone();
+
+    match running {
+        Some(manager) => {
+            let version = manager.get_version().await?;
+            Ok(tr!("self_test.aria2c_running", version = version))
+        }
+        None => {
+            let mut manager = tokio::task::spawn_blocking(Aria2RpcManager::new)
+                .await
+                .map_err(|e| format!("启动aria2c失败: {:?}", e))??;
+            let result = manager.get_version().await;
+            manager.shutdown();
+            Ok(tr!("self_test.aria2c_spawned", version = result?))
+        }
+    }
+}
+
+/// 检查 7z 能否解压内置的测试压缩包
+fn check_sevenzip() -> Result<String, String> {
+    let temp_dir = create_temp_dir(&std::env::temp_dir(), "self-test")?;
+
+    let result = (|| {
+        let archive_path = temp_dir.join("self-test.zip");
+        fs::write(&archive_path, SELF_TEST_ARCHIVE)
+            .map_err(|e| format!("无法写入测试压缩包: {:?}", e))?;
+
+        let output_dir = temp_dir.join("out");
+        let mut command = Command::new(SEVENZ_PATH.as_path());
+        command
+            .arg("x")
+            .arg("-y")
+            .arg("-sccUTF-8")
+            .arg(format!("-o{}", output_dir.display()))
+            .arg(&archive_path);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(0x08000000); // CREATE_NO_WINDOW 标志
+        }
+
+        let output = command
+            .output()
+            .map_err(|e| format!("无法启动7z.exe进程: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "7z.exe解压失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let content = fs::read_to_string(output_dir.join(SELF_TEST_FILE_NAME))
+            .map_err(|e| format!("无法读取解压后的文件: {:?}", e))?;
+        if content != SELF_TEST_CONTENT {
+            return Err("解压后的文件内容不正确".to_string());
+        }
+        Ok(tr!("self_test.sevenzip_ok"))
+    })();
+
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// 检查 addons 目录是否可写
+fn check_addons_writable(addons_dir: &Path) -> Result<String, String> {
+    if !addons_dir.is_dir() {
+        return Err(tr!("self_test.addons_missing", path = addons_dir.display()));
+    }
+
+    let probe = addons_dir.join(format!(".nmd-write-test-{}", uuid::Uuid::new_v4()));
+    fs::write(&probe, SELF_TEST_CONTENT).map_err(|e| {
+        tr!(
+            "self_test.addons_not_writable",
+            path = addons_dir.display(),
+            error = format!("{:?}", e)
+        )
+    })?;
+    let _ = fs::remove_file(&probe);
+
+    Ok(tr!("self_test.addons_ok", path = addons_dir.display()))
+}
+
+/// 检查当前进程能否直接创建符号链接（未开启开发者模式且未以管理员运行时，挂载需要通过 helper 提权）
+fn check_symlink(addons_dir: &Path) -> Result<String, String> {
+    let temp_dir = create_temp_dir(addons_dir, "symlink-test")?;
+
+    let result = (|| {
+        let target = temp_dir.join(SELF_TEST_FILE_NAME);
+        fs::write(&target, SELF_TEST_CONTENT).map_err(|e| format!("无法写入测试文件: {:?}", e))?;
+
+        let link = temp_dir.join("self-test-link.txt");
+        match std::os::windows::fs::symlink_file(&target, &link) {
+            Ok(()) if link.is_symlink() => Ok(tr!("self_test.symlink_ok")),
+            Ok(()) => Err("创建的文件不是符号链接".to_string()),
+            Err(e) => Err(tr!("self_test.symlink_denied", error = format!("{:?}", e))),
+        }
+    })();
+
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// 运行自检 - 检查 aria2c 启动和 RPC、7z 解压、addons 目录写入权限和符号链接权限
+///
+/// 各检查项相互独立，某项失败不会影响其余检查项的执行
+///
+/// # 返回值
+/// - 自检报告，包含每个检查项的结果，供前端渲染为检查清单
+#[tauri::command(async)]
+pub async fn run_self_test() -> AppResult<SelfTestReport> {
+    log_info!("开始运行自检");
+    let mut checks = Vec::new();
+
+    let started = Instant::now();
+    checks.push(make_check("aria2c", started, check_aria2c().await));
+
+    let started = Instant::now();
+    let result = tokio::task::spawn_blocking(check_sevenzip)
+        .await
+        .unwrap_or_else(|e| Err(format!("{:?}", e)));
+    checks.push(make_check("sevenzip", started, result));
+
+    let started = Instant::now();
+    let addons_dir = configured_addons_dir();
+    checks.push(make_check(
+        "addons_writable",
+        started,
+        addons_dir
+            .clone()
+            .and_then(|dir| check_addons_writable(&dir)),
+    ));
+
+    let started = Instant::now();
+    checks.push(make_check(
+        "symlink",
+        started,
+        addons_dir.and_then(|dir| check_symlink(&dir)),
+    ));
+
+    let passed = checks.iter().all(|check| check.passed);
+    log_info!(
+        "自检完成: {}/{} 项通过",
+        checks.iter().filter(|check| check.passed).count(),
+        checks.len()
+    );
+
+    Ok(SelfTestReport { passed, checks })
+}
+
+/// 获取程序依赖的外部文件路径（随安装包释放到安装目录，可能被杀毒软件删除或隔离）
+fn resource_paths() -> Vec<PathBuf> {
+    let mut paths = vec![
+        ARIA2C_PATH.to_path_buf(),
+        SEVENZ_PATH.to_path_buf(),
+        SEVENZG_PATH.to_path_buf(),
+        SEVENZ_PATH.with_file_name("7z.dll"),
+    ];
+    if let Ok(exe) = std::env::current_exe() {
+        paths.push(exe.with_file_name("helper.exe"));
+    }
+    paths
+}
+
+/// 修复资源 - 检查 aria2c、7z 和 helper 等外部文件，并重新初始化 aria2c RPC 管理器
+///
+/// 外部文件随安装包释放到安装目录，程序内没有备份，缺失时只能提示重新安装；
+/// RPC 管理器在没有活跃下载时会被关闭，下一次下载时使用重新检查过的文件启动
+///
+/// # 返回值
+/// - 修复报告，列出各文件状态、已修复和无法自动修复的问题
+#[tauri::command(async)]
+pub async fn repair_resources() -> AppResult<RepairReport> {
+    log_info!("开始修复资源");
+    let mut fixed = Vec::new();
+    let mut unresolved = Vec::new();
+
+    let resources: Vec<ResourceStatus> = resource_paths()
+        .into_iter()
+        .map(|path| {
+            let present = fs::metadata(&path).is_ok_and(|meta| meta.is_file() && meta.len() > 0);
+            ResourceStatus {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: path.to_string_lossy().to_string(),
+                present,
+            }
+        })
+        .collect();
+
+    for resource in resources.iter().filter(|resource| !resource.present) {
+        log_warn!("外部文件缺失: {}", resource.path);
+        unresolved.push(tr!("repair.resource_missing", path = resource.path));
+    }
+
+    match tokio::task::spawn_blocking(crate::aria2c::reinitialize_rpc_manager).await {
+        Ok(Ok(true)) => fixed.push(tr!("repair.rpc_reinitialized")),
+        Ok(Ok(false)) => {}
+        Ok(Err(e)) => unresolved.push(tr!("repair.rpc_skipped", error = e)),
+        Err(e) => unresolved.push(tr!("repair.rpc_skipped", error = format!("{:?}", e))),
+    }
+
+    log_info!(
+        "资源修复完成: {} 项已修复, {} 项无法自动修复",
+        fixed.len(),
+        unresolved.len()
+    );
+
+    Ok(RepairReport {
+        resources,
+        fixed,
+        unresolved,
+    })
+}
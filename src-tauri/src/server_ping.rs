@@ -0,0 +1,212 @@
+// server_ping.rs 模块 - 测量服务器延迟：游戏服务器地址（host:port）使用 A2S_INFO 查询的往返时间，
+// 网页地址（http/https）使用建立 TCP 连接的时间，供服务器列表按延迟排序
+//
+// ICMP 需要管理员权限或额外的系统接口，这里不使用。
+
+// 标准库导入
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+// 第三方库导入
+use serde::Serialize;
+use tauri::Url;
+use tokio::{
+    net::{lookup_host, TcpStream, UdpSocket},
+    time::timeout,
+};
+
+// 内部模块导入
+use crate::{
+    error::{AppError, AppResult},
+    log_debug, tr,
+};
+
+/// 未指定端口时游戏服务器的默认端口
+const DEFAULT_GAME_PORT: u16 = 27015;
+
+/// 每次测量的超时时间
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 默认测量次数
+const DEFAULT_SAMPLES: u32 = 4;
+
+/// 最多测量次数
+const MAX_SAMPLES: u32 = 10;
+
+/// 两次测量之间的间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A2S_INFO 请求
+const A2S_INFO_REQUEST: &[u8] = b"\xFF\xFF\xFF\xFFTSource Engine Query\0";
+
+/// 服务器返回的 challenge 响应类型（需要带上 challenge 重新请求）
+const S2C_CHALLENGE: u8 = 0x41;
+
+/// A2S_INFO 响应类型
+const S2A_INFO: u8 = 0x49;
+
+/// 测量方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PingMethod {
+    /// A2S_INFO 查询
+    A2s,
+    /// TCP 连接
+    Tcp,
+}
+
+/// ping_server 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PingResult {
+    pub address: String,
+    /// 实际测量的地址
+    pub resolved: String,
+    pub method: PingMethod,
+    /// 每次测量的延迟（毫秒），超时或失败为None
+    pub samples: Vec<Option<f64>>,
+    pub min: Option<f64>,
+    pub avg: Option<f64>,
+    pub max: Option<f64>,
+    /// 失败的次数
+    pub lost: u32,
+}
+
+/// 解析地址，返回 (主机, 端口, 测量方式)
+fn parse_address(address: &str) -> AppResult<(String, u16, PingMethod)> {
+    let address = address.trim();
+    if let Ok(url) = Url::parse(address) {
+        if matches!(url.scheme(), "http" | "https") {
+            let host = url.host_str().ok_or_else(|| {
+                AppError::InvalidInput(tr!("error.invalid_server_address", address = address))
+            })?;
+            let port = url.port_or_known_default().unwrap_or(443);
+            return Ok((host.to_string(), port, PingMethod::Tcp));
+        }
+    }
+
+    // host:port 或只有主机名，IPv6 地址需要写成 [::1]:27015
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') || host.starts_with('[') => {
+            let port = port
+                .parse()
+                .map_err(|_| AppError::InvalidInput(tr!("error.invalid_port", port = port)))?;
+            (host.trim_matches(['[', ']']), port)
+        }
+        _ => (address, DEFAULT_GAME_PORT),
+    };
+    if host.is_empty() {
+        return Err(AppError::InvalidInput(tr!(
+            "error.invalid_server_address",
+            address = address
+        )));
+    }
+    Ok((host.to_string(), port, PingMethod::A2s))
+}
+
+/// 发送一次 A2S_INFO 查询，返回往返时间；服务器要求 challenge 时带上 challenge 重新查询，只计算最后一次往返
+async fn a2s_round_trip(socket: &UdpSocket) -> Result<Duration, String> {
+    let mut request = A2S_INFO_REQUEST.to_vec();
+    let mut buffer = [0u8; 1400];
+    for _ in 0..2 {
+        let started = Instant::now();
+        socket.send(&request).await.map_err(|e| e.to_string())?;
+        let length = timeout(PING_TIMEOUT, socket.recv(&mut buffer))
+            .await
+            .map_err(|_| "超时".to_string())?
+            .map_err(|e| e.to_string())?;
+        let elapsed = started.elapsed();
+
+        if length < 5 || buffer[..4] != [0xFF; 4] {
+            return Err("无效的响应".to_string());
+        }
+        match buffer[4] {
+            S2A_INFO => return Ok(elapsed),
+            S2C_CHALLENGE if length >= 9 => {
+                request = A2S_INFO_REQUEST.to_vec();
+                request.extend_from_slice(&buffer[5..9]);
+            }
+            other => return Err(format!("未知的响应类型: {:#x}", other)),
+        }
+    }
+    Err("服务器没有返回服务器信息".to_string())
+}
+
+/// 建立一次 TCP 连接，返回连接时间
+async fn tcp_round_trip(address: SocketAddr) -> Result<Duration, String> {
+    let started = Instant::now();
+    timeout(PING_TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| "超时".to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(started.elapsed())
+}
+
+/// 测量服务器延迟命令
+///
+/// # 参数
+/// - `address`: 游戏服务器地址（host:port，默认端口 27015）或网页地址（http/https）
+/// - `samples`: 测量次数（默认 4，最多 10）
+///
+/// # 返回值
+/// - 每次测量的延迟和最小、平均、最大值
+#[tauri::command(async)]
+pub async fn ping_server(address: String, samples: Option<u32>) -> AppResult<PingResult> {
+    let (host, port, method) = parse_address(&address)?;
+    let target = lookup_host((host.as_str(), port))
+        .await
+        .map_err(|e| {
+            AppError::InvalidInput(tr!("error.resolve_server_failed", host = host, error = e))
+        })?
+        .next()
+        .ok_or_else(|| AppError::InvalidInput(tr!("error.server_not_resolved", host = host)))?;
+
+    let socket = match method {
+        PingMethod::A2s => {
+            let bind = if target.is_ipv4() {
+                "0.0.0.0:0"
+            } else {
+                "[::]:0"
+            };
+            let socket = UdpSocket::bind(bind).await?;
+            socket.connect(target).await?;
+            Some(socket)
+        }
+        PingMethod::Tcp => None,
+    };
+
+    let count = samples.unwrap_or(DEFAULT_SAMPLES).clamp(1, MAX_SAMPLES);
+    let mut results = Vec::new();
+    for i in 0..count {
+        if i > 0 {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+        }
+        let result = match &socket {
+            Some(socket) => a2s_round_trip(socket).await,
+            None => tcp_round_trip(target).await,
+        };
+        match result {
+            Ok(elapsed) => results.push(Some(elapsed.as_secs_f64() * 1000.0)),
+            Err(e) => {
+                log_debug!("测量延迟失败: {} ({}), 错误: {}", address, target, e);
+                results.push(None);
+            }
+        }
+    }
+
+    let succeeded = results.iter().flatten().copied().collect::<Vec<_>>();
+    let avg =
+        (!succeeded.is_empty()).then(|| succeeded.iter().sum::<f64>() / succeeded.len() as f64);
+    Ok(PingResult {
+        address,
+        resolved: target.to_string(),
+        method,
+        min: succeeded.iter().copied().reduce(f64::min),
+        max: succeeded.iter().copied().reduce(f64::max),
+        avg,
+        lost: (results.len() - succeeded.len()) as u32,
+        samples: results,
+    })
+}
@@ -0,0 +1,172 @@
+// session.rs 模块 - 意外退出后恢复会话
+//
+// 运行期间在应用数据目录中保存会话标记（session.json，记录打开的窗口），并定期保存下载队列；正常退出时删除标记。
+// 启动时标记仍然存在说明上次没有正常退出（崩溃、被强制结束或断电），前端加载完成后询问用户是否恢复上次的会话：
+// 重新打开上次打开的窗口并继续下载队列中的任务。
+
+// 标准库导入
+use std::{path::PathBuf, sync::Mutex, time::Duration};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_debug, log_error, log_info, log_warn};
+
+/// 会话标记文件名
+const SESSION_FILE_NAME: &str = "session.json";
+
+/// 定期保存会话标记和下载队列的间隔
+const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 意外退出后可以重新打开的窗口
+const RESTORABLE_WINDOWS: &[&str] = &["filemanager", "serverlist", "downloads"];
+
+/// 会话标记
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMarker {
+    /// 应用进程ID
+    pub pid: u32,
+    /// 启动时间（Unix 时间戳，秒）
+    pub started_at: u64,
+    /// 打开的窗口标签
+    pub open_windows: Vec<String>,
+}
+
+lazy_static! {
+    /// 上次没有正常退出时留下的会话标记，询问用户后取出
+    static ref UNCLEAN_SESSION: Mutex<Option<SessionMarker>> = Mutex::new(None);
+}
+
+/// 获取会话标记文件路径 - 使用应用数据目录，与数据目录配置无关
+fn session_file(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_local_data_dir()
+        .ok()
+        .map(|dir| dir.join(SESSION_FILE_NAME))
+}
+
+/// 写入会话标记
+fn write_marker(app_handle: &AppHandle, marker: &SessionMarker) {
+    let Some(path) = session_file(app_handle) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    match serde_json::to_string_pretty(marker) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&path, content) {
+                log_warn!("保存会话标记失败: {}", e);
+            }
+        }
+        Err(e) => log_error!("序列化会话标记失败: {}", e),
+    }
+}
+
+/// 获取当前打开（可见）的窗口
+fn open_windows(app_handle: &AppHandle) -> Vec<String> {
+    RESTORABLE_WINDOWS
+        .iter()
+        .filter(|label| {
+            app_handle
+                .get_webview_window(label)
+                .is_some_and(|window| window.is_visible().unwrap_or(false))
+        })
+        .map(|label| label.to_string())
+        .collect()
+}
+
+/// 开始会话 - 启动时调用
+///
+/// 读取上次的会话标记（仍存在说明上次没有正常退出），然后写入本次的标记，
+/// 并启动定期保存会话标记和下载队列的线程，意外退出时也能恢复下载队列
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn start_session(app_handle: &AppHandle) {
+    let previous = session_file(app_handle)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<SessionMarker>(&content).ok())
+        .filter(|marker| marker.pid != std::process::id());
+    if let Some(marker) = previous {
+        log_warn!(
+            "上次运行（进程ID={}）没有正常退出，打开的窗口: {:?}",
+            marker.pid,
+            marker.open_windows
+        );
+        *UNCLEAN_SESSION.lock().unwrap() = Some(marker);
+    }
+
+    let mut marker = SessionMarker {
+        pid: std::process::id(),
+        started_at: chrono::Utc::now().timestamp().max(0) as u64,
+        open_windows: Vec::new(),
+    };
+    write_marker(app_handle, &marker);
+
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SESSION_SAVE_INTERVAL);
+        if is_app_shutting_down() {
+            break;
+        }
+
+        marker.open_windows = open_windows(&app_handle);
+        write_marker(&app_handle, &marker);
+        if let Err(e) = crate::download_manager::save_download_queue() {
+            log_warn!("定期保存下载队列失败: {}", e);
+        }
+        log_debug!("已保存会话标记和下载队列");
+    });
+}
+
+/// 结束会话 - 正常退出（包括更新重启）时调用，删除会话标记
+pub fn end_session() {
+    let Some(app_handle) = crate::init::GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    else {
+        return;
+    };
+    if let Some(path) = session_file(&app_handle) {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log_warn!("删除会话标记失败: {}", e);
+            }
+        }
+    }
+}
+
+/// 取出上次意外退出时留下的会话标记，只能取出一次
+///
+/// # 返回值
+/// - 上次没有正常退出时返回Some
+pub fn take_unclean_session() -> Option<SessionMarker> {
+    UNCLEAN_SESSION.lock().unwrap().take()
+}
+
+/// 重新打开上次会话中打开的窗口
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `session`: 上次的会话标记
+pub fn restore_windows(app_handle: &AppHandle, session: &SessionMarker) {
+    for label in &session.open_windows {
+        let result = match label.as_str() {
+            "filemanager" => crate::commands::open_filemanager_window(app_handle.clone()),
+            "serverlist" => crate::commands::open_serverlist_window(app_handle.clone()),
+            "downloads" => crate::commands::open_downloads_window(app_handle.clone()),
+            _ => continue,
+        };
+        match result {
+            Ok(_) => log_info!("已恢复窗口: {}", label),
+            Err(e) => log_warn!("恢复窗口失败: {}, 错误: {}", label, e),
+        }
+    }
+}
@@ -0,0 +1,1040 @@
+// settings.rs 模块 - 管理应用运行时设置（并发数等），基于 config_manager 持久化到 config.json
+
+// 标准库导入
+use std::{
+    collections::BTreeMap,
+    fs,
+    sync::{Mutex, RwLock},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter};
+
+// 内部模块导入
+use crate::{
+    download_manager::DOWNLOAD_QUEUE,
+    error::{AppError, AppResult},
+    extract_manager::{RetryStrategy, EXTRACT_MANAGER},
+    log_error, log_info,
+    log_utils::{LogLevel, ProgressVerbosity},
+    log_warn,
+    notifications::CompletionSound,
+    process_priority::ProcessPriority,
+    update_manager::UpdateChannel,
+};
+
+/// 最大并发下载数上限
+pub const MAX_CONCURRENT_DOWNLOADS_LIMIT: u32 = 8;
+
+/// 最大并发解压数上限
+pub const MAX_CONCURRENT_EXTRACTS_LIMIT: u32 = 4;
+
+/// 下载最多尝试次数上限
+pub const MAX_DOWNLOAD_ATTEMPTS_LIMIT: u32 = 10;
+
+/// download-progress 事件最小间隔的允许范围（毫秒）
+const MIN_PROGRESS_EVENT_INTERVAL_MS: u64 = 100;
+const MAX_PROGRESS_EVENT_INTERVAL_MS: u64 = 10_000;
+
+/// 导出配置时打包的配置文件（配置目录中由应用读写的文件，不存在时跳过）
+const BUNDLED_CONFIG_FILES: &[&str] = &["config.json"];
+
+/// 配置导出文件的格式标识
+const SETTINGS_BUNDLE_FORMAT: &str = "nyaser-maps-downloader-settings";
+
+/// 配置文件变更检查间隔
+const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 默认的快速安装快捷键
+const DEFAULT_QUICK_INSTALL_SHORTCUT: &str = "CommandOrControl+Alt+V";
+
+/// 下载停滞判定时间的允许范围（秒）
+const MIN_STALL_TIMEOUT_SECONDS: u64 = 5;
+const MAX_STALL_TIMEOUT_SECONDS: u64 = 600;
+
+/// 订阅源检查间隔的允许范围（分钟）
+const MIN_FEED_CHECK_INTERVAL_MINUTES: u64 = 5;
+const MAX_FEED_CHECK_INTERVAL_MINUTES: u64 = 24 * 60;
+
+/// 当前配置文件格式版本
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// 配置迁移步骤，第 i 个函数负责把版本 i 的配置迁移到版本 i + 1
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v0_to_v1];
+
+/// 按网站限制下载 - config.json 的 host_limits 中每个域名的配置，为 0 的项不限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostLimit {
+    /// 同一网站最多同时进行的下载数
+    pub max_downloads: u32,
+    /// 每个下载连接到该网站的最大连接数（aria2c 的 max-connection-per-server 和 split，最大为 16）
+    pub max_connections: u32,
+}
+
+/// 应用设置结构体 - 字段直接对应 config.json 中的同名键
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 配置文件格式版本
+    pub schema_version: u32,
+    /// 数据存储目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nmd_data: Option<String>,
+    /// 自定义下载目录，未设置时使用 nmd_data/.cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<String>,
+    /// 下载缓存目录中的文件保留天数，超过后在启动时清理（未完成的下载除外）
+    pub cache_retention_days: u32,
+    /// 解压成功后将压缩包按校验和保存到缓存，重新安装时不再下载
+    pub archive_cache_enabled: bool,
+    /// 压缩包缓存的总大小上限（MB），为 0 时只按保留天数清理
+    pub archive_cache_max_mb: u64,
+    /// 最大并发下载数
+    pub max_concurrent_downloads: u32,
+    /// 最大并发解压数
+    pub max_concurrent_extracts: u32,
+    /// 下载队列中最多等待的任务数，超过时拒绝添加新任务（避免大量链接一次性加入队列），为 0 时不限制
+    pub max_waiting_downloads: u32,
+    /// 下载失败后最多尝试的次数（包括第一次），任务可以单独指定
+    pub max_download_attempts: u32,
+    /// 下载速度持续为 0 达到该时间（秒）视为停滞，执行下一个恢复步骤
+    pub stall_timeout_seconds: u64,
+    /// 停滞时先暂停再继续任务（重新建立连接）的次数
+    pub stall_unpause_attempts: u32,
+    /// 暂停再继续无效后，从备用镜像（没有配置镜像时从原地址）重新添加任务的次数，用完后判定下载失败
+    pub stall_mirror_attempts: u32,
+    /// 第一次重试前的等待时间（秒），之后每次翻倍
+    pub retry_backoff_seconds: u64,
+    /// 重试等待时间的上限（秒）
+    pub max_retry_backoff_seconds: u64,
+    /// 解压失败后的最大重试次数
+    pub extract_max_retries: u32,
+    /// 解压重试的基础等待时间（秒）
+    pub extract_retry_delay_seconds: u64,
+    /// 解压重试等待时间的增长方式
+    pub extract_retry_strategy: RetryStrategy,
+    /// 7z 解压进程的优先级
+    pub extract_priority: ProcessPriority,
+    /// 7z 解压使用的线程数（-mmt），为 0 时由 7z 自动决定
+    pub extract_threads: u32,
+    /// 7z 解压进程可以使用的处理器掩码（第 n 位表示第 n 个逻辑处理器），为 0 时不限制
+    pub extract_cpu_affinity: u64,
+    /// 解压没有标记 UTF-8 的 zip 文件时文件名使用的代码页（例如 936 为 GBK），为 0 时使用系统默认代码页
+    pub extract_zip_code_page: u32,
+    /// aria2c 进程的优先级
+    pub aria2_priority: ProcessPriority,
+    /// aria2c 使用低 IO 优先级
+    pub aria2_low_io_priority: bool,
+    /// 游戏运行时将 aria2c 降为低于正常的 CPU 优先级和低 IO 优先级
+    pub aria2_background_while_gaming: bool,
+    /// 压缩包损坏时重新下载，而不是重试解压（受下载最多尝试次数限制）
+    pub redownload_corrupt_archives: bool,
+    /// 解压出的 VPK 文件无效时重新下载（受下载最多尝试次数限制）
+    pub redownload_invalid_vpks: bool,
+    /// 日志级别
+    pub log_level: LogLevel,
+    /// 下载进度的日志详细程度
+    pub progress_verbosity: ProgressVerbosity,
+    /// 发送 download-progress 事件的最小间隔（毫秒），同一间隔内的多次更新会被合并
+    pub progress_event_interval_ms: u64,
+    /// 进度变化（百分点）达到该值才发送 download-progress 事件
+    pub progress_event_min_delta: f64,
+    /// 界面语言（后端生成的对话框、错误信息等）
+    pub language: String,
+    /// 软件更新通道
+    pub update_channel: UpdateChannel,
+    /// 发现更新后是否自动下载，并在队列空闲时自动安装
+    pub auto_install_updates: bool,
+    /// 删除地图文件和分组时移动到回收站，而不是永久删除
+    pub use_recycle_bin: bool,
+    /// 是否提交匿名使用统计（默认关闭，需要用户明确开启）
+    pub telemetry_enabled: bool,
+    /// 匿名使用统计的提交地址，未设置时不提交
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telemetry_endpoint: Option<String>,
+    /// 崩溃报告提交地址，未设置时不允许提交
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crash_report_endpoint: Option<String>,
+    /// helper 无连接时的空闲退出时间（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helper_idle_timeout: Option<u64>,
+    /// 按网站附加的下载请求头：域名（包括其子域名） -> 请求头名称 -> 值，例如 Referer
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub host_headers: BTreeMap<String, BTreeMap<String, String>>,
+    /// 按网站限制并行下载数和连接数：域名（包括其子域名） -> 限制，例如限制百度网盘同时只下载一个文件
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub host_limits: BTreeMap<String, HostLimit>,
+    /// 下载停滞时使用的备用镜像：域名（包括其子域名） -> 镜像主机名列表，按顺序尝试，路径保持不变
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub download_mirrors: BTreeMap<String, Vec<String>>,
+    /// 第一方站点（maps.nyase.ru、op.nyase.ru）证书公钥的固定值（"sha256/<Base64>"），为空时不启用证书固定
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub certificate_pins: Vec<String>,
+    /// aria2c 使用的 CA 证书文件（PEM），未设置时使用系统证书
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_certificate: Option<String>,
+    /// 下载策略：只允许从这些域名（包括其子域名）下载，为空时不限制
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// 下载策略：禁止从这些域名（包括其子域名）下载，优先于 allowed_hosts
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub denied_hosts: Vec<String>,
+    /// 下载策略：单个文件的大小上限（MB），为 0 时不限制
+    pub max_download_size_mb: u64,
+    /// 订阅的地图发布 RSS/Atom 源
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub feed_urls: Vec<String>,
+    /// 订阅源有新地图时直接加入下载队列，而不只是通知
+    pub feed_auto_queue: bool,
+    /// 检查订阅源的间隔（分钟）
+    pub feed_check_interval_minutes: u64,
+    /// DNS-over-HTTPS 服务器（JSON API），配置后下载时使用 DoH 解析域名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doh_server: Option<String>,
+    /// 从剪贴板快速安装的全局快捷键，例如 "CommandOrControl+Alt+V"，为空时不注册
+    pub quick_install_shortcut: String,
+    /// 队列中最后一个任务结束时播放的提示音
+    pub completion_sound: CompletionSound,
+    /// completion_sound 为 file 时播放的 wav 文件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_sound_file: Option<String>,
+    /// 未识别的配置项，原样保留（供前端存储自定义配置）
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            nmd_data: None,
+            download_dir: None,
+            cache_retention_days: 7,
+            archive_cache_enabled: false,
+            archive_cache_max_mb: 2048,
+            max_concurrent_downloads: 1,
+            max_concurrent_extracts: 1,
+            max_waiting_downloads: 100,
+            max_download_attempts: 3,
+            stall_timeout_seconds: 10,
+            stall_unpause_attempts: 2,
+            stall_mirror_attempts: 3,
+            retry_backoff_seconds: 10,
+            max_retry_backoff_seconds: 300,
+            extract_max_retries: 3,
+            extract_retry_delay_seconds: 2,
+            extract_retry_strategy: RetryStrategy::default(),
+            extract_priority: ProcessPriority::default(),
+            extract_threads: 0,
+            extract_cpu_affinity: 0,
+            extract_zip_code_page: 0,
+            aria2_priority: ProcessPriority::default(),
+            aria2_low_io_priority: false,
+            aria2_background_while_gaming: true,
+            redownload_corrupt_archives: false,
+            redownload_invalid_vpks: false,
+            log_level: LogLevel::default(),
+            progress_verbosity: ProgressVerbosity::default(),
+            progress_event_interval_ms: 500,
+            progress_event_min_delta: 0.1,
+            language: crate::i18n::DEFAULT_LANGUAGE.to_string(),
+            update_channel: UpdateChannel::default(),
+            auto_install_updates: false,
+            use_recycle_bin: true,
+            telemetry_enabled: false,
+            telemetry_endpoint: None,
+            crash_report_endpoint: None,
+            helper_idle_timeout: None,
+            host_headers: BTreeMap::new(),
+            host_limits: BTreeMap::new(),
+            download_mirrors: BTreeMap::new(),
+            certificate_pins: Vec::new(),
+            ca_certificate: None,
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            max_download_size_mb: 0,
+            feed_urls: Vec::new(),
+            feed_auto_queue: false,
+            feed_check_interval_minutes: 60,
+            doh_server: None,
+            quick_install_shortcut: DEFAULT_QUICK_INSTALL_SHORTCUT.to_string(),
+            completion_sound: CompletionSound::default(),
+            completion_sound_file: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// 将各项设置限制在允许的范围内
+    pub fn normalized(mut self) -> Self {
+        self.schema_version = CURRENT_SCHEMA_VERSION;
+        self.max_concurrent_downloads = self
+            .max_concurrent_downloads
+            .clamp(1, MAX_CONCURRENT_DOWNLOADS_LIMIT);
+        self.max_concurrent_extracts = self
+            .max_concurrent_extracts
+            .clamp(1, MAX_CONCURRENT_EXTRACTS_LIMIT);
+        self.max_download_attempts = self
+            .max_download_attempts
+            .clamp(1, MAX_DOWNLOAD_ATTEMPTS_LIMIT);
+        self.progress_event_interval_ms = self.progress_event_interval_ms.clamp(
+            MIN_PROGRESS_EVENT_INTERVAL_MS,
+            MAX_PROGRESS_EVENT_INTERVAL_MS,
+        );
+        self.progress_event_min_delta = self.progress_event_min_delta.clamp(0.0, 100.0);
+        self.stall_timeout_seconds = self
+            .stall_timeout_seconds
+            .clamp(MIN_STALL_TIMEOUT_SECONDS, MAX_STALL_TIMEOUT_SECONDS);
+        self.feed_check_interval_minutes = self.feed_check_interval_minutes.clamp(
+            MIN_FEED_CHECK_INTERVAL_MINUTES,
+            MAX_FEED_CHECK_INTERVAL_MINUTES,
+        );
+        self.max_retry_backoff_seconds = self
+            .max_retry_backoff_seconds
+            .max(self.retry_backoff_seconds);
+        self.language = crate::i18n::resolve_language(&self.language)
+            .unwrap_or(crate::i18n::DEFAULT_LANGUAGE)
+            .to_string();
+        self
+    }
+}
+
+/// v0 -> v1: 早期版本的 write_config 不做任何校验，
+/// 删除类型不正确的已知配置项，让其回退为默认值
+fn migrate_v0_to_v1(config: &mut Map<String, Value>) {
+    for key in [
+        "nmd_data",
+        "download_dir",
+        "crash_report_endpoint",
+        "telemetry_endpoint",
+        "ca_certificate",
+        "doh_server",
+        "quick_install_shortcut",
+        "completion_sound_file",
+    ] {
+        if config.get(key).is_some_and(|v| !v.is_string()) {
+            log_warn!("配置项 {} 类型无效，已移除", key);
+            config.remove(key);
+        }
+    }
+    for key in [
+        "max_concurrent_downloads",
+        "max_concurrent_extracts",
+        "cache_retention_days",
+        "helper_idle_timeout",
+        "max_download_attempts",
+        "retry_backoff_seconds",
+        "max_retry_backoff_seconds",
+        "extract_max_retries",
+        "extract_retry_delay_seconds",
+        "extract_threads",
+        "progress_event_interval_ms",
+        "extract_cpu_affinity",
+        "archive_cache_max_mb",
+        "extract_zip_code_page",
+        "max_download_size_mb",
+        "feed_check_interval_minutes",
+        "stall_timeout_seconds",
+        "stall_unpause_attempts",
+        "stall_mirror_attempts",
+        "max_waiting_downloads",
+    ] {
+        if config.get(key).is_some_and(|v| !v.is_u64()) {
+            log_warn!("配置项 {} 类型无效，已移除", key);
+            config.remove(key);
+        }
+    }
+    for key in [
+        "auto_install_updates",
+        "use_recycle_bin",
+        "redownload_corrupt_archives",
+        "redownload_invalid_vpks",
+        "aria2_low_io_priority",
+        "aria2_background_while_gaming",
+        "archive_cache_enabled",
+        "telemetry_enabled",
+        "feed_auto_queue",
+    ] {
+        if config.get(key).is_some_and(|v| !v.is_boolean()) {
+            log_warn!("配置项 {} 类型无效，已移除", key);
+            config.remove(key);
+        }
+    }
+    for key in [
+        "certificate_pins",
+        "allowed_hosts",
+        "denied_hosts",
+        "feed_urls",
+    ] {
+        if config
+            .get(key)
+            .is_some_and(|v| serde_json::from_value::<Vec<String>>(v.clone()).is_err())
+        {
+            log_warn!("配置项 {} 无效，已移除", key);
+            config.remove(key);
+        }
+    }
+    if config
+        .get("progress_event_min_delta")
+        .is_some_and(|v| !v.is_number())
+    {
+        log_warn!("配置项 progress_event_min_delta 类型无效，已移除");
+        config.remove("progress_event_min_delta");
+    }
+    if config.get("host_headers").is_some_and(|v| {
+        serde_json::from_value::<BTreeMap<String, BTreeMap<String, String>>>(v.clone()).is_err()
+    }) {
+        log_warn!("配置项 host_headers 无效，已移除");
+        config.remove("host_headers");
+    }
+    if config
+        .get("host_limits")
+        .is_some_and(|v| serde_json::from_value::<BTreeMap<String, HostLimit>>(v.clone()).is_err())
+    {
+        log_warn!("配置项 host_limits 无效，已移除");
+        config.remove("host_limits");
+    }
+    if config.get("download_mirrors").is_some_and(|v| {
+        serde_json::from_value::<BTreeMap<String, Vec<String>>>(v.clone()).is_err()
+    }) {
+        log_warn!("配置项 download_mirrors 无效，已移除");
+        config.remove("download_mirrors");
+    }
+    if config
+        .get("log_level")
+        .is_some_and(|v| serde_json::from_value::<LogLevel>(v.clone()).is_err())
+    {
+        log_warn!("配置项 log_level 无效，已移除");
+        config.remove("log_level");
+    }
+    if config
+        .get("progress_verbosity")
+        .is_some_and(|v| serde_json::from_value::<ProgressVerbosity>(v.clone()).is_err())
+    {
+        log_warn!("配置项 progress_verbosity 无效，已移除");
+        config.remove("progress_verbosity");
+    }
+    if config
+        .get("update_channel")
+        .is_some_and(|v| serde_json::from_value::<UpdateChannel>(v.clone()).is_err())
+    {
+        log_warn!("配置项 update_channel 无效，已移除");
+        config.remove("update_channel");
+    }
+    if config
+        .get("extract_retry_strategy")
+        .is_some_and(|v| serde_json::from_value::<RetryStrategy>(v.clone()).is_err())
+    {
+        log_warn!("配置项 extract_retry_strategy 无效，已移除");
+        config.remove("extract_retry_strategy");
+    }
+    if config
+        .get("extract_priority")
+        .is_some_and(|v| serde_json::from_value::<ProcessPriority>(v.clone()).is_err())
+    {
+        log_warn!("配置项 extract_priority 无效，已移除");
+        config.remove("extract_priority");
+    }
+    if config
+        .get("aria2_priority")
+        .is_some_and(|v| serde_json::from_value::<ProcessPriority>(v.clone()).is_err())
+    {
+        log_warn!("配置项 aria2_priority 无效，已移除");
+        config.remove("aria2_priority");
+    }
+    if config
+        .get("completion_sound")
+        .is_some_and(|v| serde_json::from_value::<CompletionSound>(v.clone()).is_err())
+    {
+        log_warn!("配置项 completion_sound 无效，已移除");
+        config.remove("completion_sound");
+    }
+}
+
+/// 按格式版本依次执行迁移步骤
+///
+/// # 返回值
+/// - 配置版本低于当前版本并已迁移时返回true；版本高于当前版本时不做修改，返回false
+fn migrate_schema(config: &mut Map<String, Value>) -> bool {
+    let version = config
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+
+    if version > CURRENT_SCHEMA_VERSION as usize {
+        log_warn!(
+            "配置版本 {} 高于当前支持的版本 {}，将尝试按当前格式读取",
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+        return false;
+    }
+
+    for (step, migrate) in MIGRATIONS.iter().enumerate().skip(version) {
+        log_info!("迁移配置: v{} -> v{}", step, step + 1);
+        migrate(config);
+    }
+    config.insert("schema_version".to_string(), CURRENT_SCHEMA_VERSION.into());
+    version < CURRENT_SCHEMA_VERSION as usize
+}
+
+/// 逐项检查配置，移除无法解析为对应设置类型的配置项，让其回退为默认值，其他配置项保持不变
+///
+/// # 返回值
+/// - 移除了配置项时返回true
+fn remove_invalid_fields(config: &mut Map<String, Value>) -> bool {
+    let invalid = config
+        .iter()
+        .filter(|(key, value)| {
+            let field = Map::from_iter([(key.to_string(), (*value).clone())]);
+            serde_json::from_value::<Settings>(Value::Object(field)).is_err()
+        })
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+
+    for key in &invalid {
+        log_warn!("配置项 {} 无效，已移除: {}", key, config[key]);
+        config.remove(key);
+    }
+    !invalid.is_empty()
+}
+
+/// 将配置迁移到当前格式版本，并移除无效的配置项
+///
+/// # 参数
+/// - `config`: 原始配置内容
+///
+/// # 返回值
+/// - 成功时返回迁移后的配置以及是否需要写回配置文件（发生了迁移或移除了无效的配置项；
+///   版本高于当前版本时只在内存中移除无效的配置项，不写回）
+/// - 配置不是JSON对象时返回Err(String)
+pub fn migrate_config(config: Value) -> Result<(Value, bool), String> {
+    let mut map = match config {
+        Value::Object(map) => map,
+        _ => return Err("配置内容必须是JSON对象".to_string()),
+    };
+
+    let newer = map
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .is_some_and(|version| version > CURRENT_SCHEMA_VERSION as u64);
+    let migrated = migrate_schema(&mut map);
+    let removed = remove_invalid_fields(&mut map);
+
+    Ok((Value::Object(map), !newer && (migrated || removed)))
+}
+
+/// 迁移并读取配置内容，无效的配置项使用默认值
+///
+/// # 参数
+/// - `config`: 原始配置内容
+///
+/// # 返回值
+/// - 成功时返回校验后的设置
+/// - 配置不是JSON对象时返回包含错误信息的Err(String)
+pub fn parse_settings(config: Value) -> Result<Settings, String> {
+    let (config, _) = migrate_config(config)?;
+    serde_json::from_value::<Settings>(config)
+        .map(Settings::normalized)
+        .map_err(|e| format!("配置格式无效: {}", e))
+}
+
+/// 校验要写入的配置内容，返回规范化后的配置
+///
+/// 与 parse_settings 不同，任何一个配置项无效都会拒绝写入。
+///
+/// # 参数
+/// - `config`: 要写入的配置内容
+///
+/// # 返回值
+/// - 成功时返回规范化后的配置（包含 schema_version）
+/// - 配置格式无效时返回包含错误信息的Err(String)
+pub fn validate_config(config: Value) -> Result<Value, String> {
+    let mut map = match config {
+        Value::Object(map) => map,
+        _ => return Err("配置内容必须是JSON对象".to_string()),
+    };
+    migrate_schema(&mut map);
+    let settings = serde_json::from_value::<Settings>(Value::Object(map))
+        .map(Settings::normalized)
+        .map_err(|e| format!("配置格式无效: {}", e))?;
+    serde_json::to_value(&settings).map_err(|e| format!("无法序列化配置: {:?}", e))
+}
+
+/// 启动时迁移 config.json
+///
+/// 无效的配置项会被移除（回退为默认值），其他配置项保持不变，修改前的文件备份到 config.json.bak；
+/// 只有文件不是有效的JSON对象时才备份后重置整个配置文件，避免启动失败
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn migrate_config_file(app_handle: &AppHandle) {
+    let config_path = match crate::config_manager::get_config_path(app_handle, "config.json") {
+        Ok(path) => path,
+        Err(e) => {
+            log_warn!("{}", e);
+            return;
+        }
+    };
+    if !config_path.exists() {
+        return;
+    }
+
+    // 读取失败（例如文件被占用）时不修改配置文件
+    let content = match fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            log_warn!("读取配置文件失败，跳过迁移: {:?}", e);
+            return;
+        }
+    };
+    let backup_path = config_path.with_extension("json.bak");
+
+    let parsed = serde_json::from_str::<Value>(&content)
+        .map_err(|e| format!("无法解析配置文件: {}", e))
+        .and_then(migrate_config);
+
+    match parsed {
+        Ok((config, true)) => {
+            if let Err(e) = fs::write(&backup_path, &content) {
+                log_warn!("备份配置文件失败: {:?}", e);
+            }
+            match serde_json::to_string_pretty(&config)
+                .map_err(|e| e.to_string())
+                .and_then(|content| fs::write(&config_path, content).map_err(|e| e.to_string()))
+            {
+                Ok(_) => log_info!(
+                    "配置文件已迁移到版本 {}，原文件已备份到 {:?}",
+                    CURRENT_SCHEMA_VERSION,
+                    backup_path
+                ),
+                Err(e) => log_warn!("写入迁移后的配置文件失败: {}", e),
+            }
+        }
+        Ok((_, false)) => {}
+        Err(e) => {
+            log_warn!("配置文件无效，已备份到 {:?} 并重置: {}", backup_path, e);
+            if let Err(e) = fs::rename(&config_path, &backup_path) {
+                log_error!("备份无效配置文件失败: {:?}", e);
+            }
+        }
+    }
+}
+
+// 当前生效的设置
+lazy_static! {
+    static ref CURRENT_SETTINGS: RwLock<Settings> = RwLock::new(Settings::default());
+
+    /// 应用自己最后一次写入 config.json 后文件的修改时间，配置文件监视线程据此跳过应用自己的写入
+    static ref OWN_CONFIG_WRITE: Mutex<Option<SystemTime>> = Mutex::new(None);
+}
+
+/// 获取当前生效的设置副本
+pub fn current_settings() -> Settings {
+    CURRENT_SETTINGS
+        .read()
+        .map(|settings| settings.clone())
+        .unwrap_or_default()
+}
+
+/// 从 config.json 加载设置
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 返回加载到的设置，读取或解析失败时返回默认设置
+pub fn load_settings(app_handle: &AppHandle) -> Settings {
+    match crate::config_manager::read_config(app_handle.clone(), "config.json") {
+        Ok(config) => match parse_settings(config) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log_warn!("解析设置失败，使用默认设置: {}", e);
+                Settings::default()
+            }
+        },
+        Err(e) => {
+            log_warn!("读取设置失败，使用默认设置: {}", e);
+            Settings::default()
+        }
+    }
+}
+
+/// 将设置应用到运行中的下载队列和解压队列
+///
+/// 提高并发数时队列处理循环会被唤醒并立即启动更多任务，
+/// 降低并发数时已在运行的任务会继续执行直到完成。
+pub fn apply_settings(settings: &Settings) {
+    if let Ok(mut queue) = DOWNLOAD_QUEUE.lock() {
+        queue.set_max_concurrent(settings.max_concurrent_downloads);
+    }
+    EXTRACT_MANAGER.set_max_concurrent(settings.max_concurrent_extracts);
+    crate::log_utils::set_max_log_level(settings.log_level);
+    crate::i18n::apply_language(&settings.language);
+    crate::quick_install::apply_shortcut(&settings.quick_install_shortcut);
+    if let Ok(mut current) = CURRENT_SETTINGS.write() {
+        *current = settings.clone();
+    }
+    crate::aria2c::apply_aria2_priority();
+    log_info!(
+        "设置已应用: 最大并发下载数={}, 最大并发解压数={}",
+        settings.max_concurrent_downloads,
+        settings.max_concurrent_extracts
+    );
+}
+
+/// 获取配置文件的最后修改时间
+fn config_modified_time(app_handle: &AppHandle) -> Option<SystemTime> {
+    crate::config_manager::get_config_path(app_handle, "config.json")
+        .ok()
+        .and_then(|path| fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok())
+}
+
+/// 记录应用自己写入了 config.json - 写入后已在运行时应用，配置文件监视线程不再重复应用
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn record_own_config_write(app_handle: &AppHandle) {
+    if let Ok(mut own_write) = OWN_CONFIG_WRITE.lock() {
+        *own_write = config_modified_time(app_handle);
+    }
+}
+
+/// 启动配置文件监视线程 - 检测到 config.json 被修改后在运行时应用新配置
+///
+/// 数据目录变化时重新初始化目录管理器，并发数变化时应用到运行中的队列和aria2c，
+/// 随后向所有窗口广播 config-changed 事件。应用自己通过 write_config / merge_config 的写入不会触发。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub fn start_config_watcher(app_handle: AppHandle) {
+    thread::spawn(move || {
+        let mut last_modified = config_modified_time(&app_handle);
+        let mut last_settings = load_settings(&app_handle);
+
+        loop {
+            thread::sleep(CONFIG_WATCH_INTERVAL);
+
+            let modified = config_modified_time(&app_handle);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            // 应用自己的写入（设置、语言、数据目录等）已经在写入时应用，只更新比较基准
+            if modified.is_some() && *OWN_CONFIG_WRITE.lock().unwrap() == modified {
+                last_settings = load_settings(&app_handle);
+                continue;
+            }
+
+            let config = match crate::config_manager::read_config(app_handle.clone(), "config.json")
+                .map_err(String::from)
+                .and_then(parse_settings)
+            {
+                Ok(settings) => settings,
+                Err(e) => {
+                    // 文件可能正在被编辑器写入，等待下一次修改
+                    log_warn!("配置文件已修改但无法解析，忽略本次修改: {}", e);
+                    continue;
+                }
+            };
+
+            if config == last_settings {
+                continue;
+            }
+            log_info!("检测到配置文件修改，重新加载配置");
+
+            // 数据目录变化
+            if config.nmd_data != last_settings.nmd_data {
+                if let Some(ref data_dir) = config.nmd_data {
+                    if let Err(e) = crate::config_manager::reload_data_dir(&app_handle, data_dir) {
+                        log_error!("重新加载数据目录失败: {}", e);
+                    } else {
+                        log_info!("数据目录已切换为: {}", data_dir);
+                    }
+                }
+            }
+
+            // 下载目录变化
+            if config.download_dir != last_settings.download_dir {
+                crate::dir_manager::apply_download_dir(config.download_dir.as_deref());
+            }
+
+            // 并发数变化
+            if config.max_concurrent_downloads != current_settings().max_concurrent_downloads
+                || config.max_concurrent_extracts != current_settings().max_concurrent_extracts
+            {
+                let max_concurrent_downloads = config.max_concurrent_downloads;
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) =
+                        crate::aria2c::set_max_concurrent_downloads(max_concurrent_downloads).await
+                    {
+                        log_warn!("修改aria2c最大并发下载数失败: {}", e);
+                    }
+                });
+            }
+            apply_settings(&config);
+
+            let _ = app_handle.emit("config-changed", &config);
+            last_settings = config;
+        }
+    });
+}
+
+/// 获取应用设置
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 成功时返回当前设置
+#[tauri::command]
+pub fn get_settings(app_handle: AppHandle) -> AppResult<Settings> {
+    Ok(load_settings(&app_handle))
+}
+
+/// 修改应用设置 - 保存到 config.json 并立即应用到运行中的队列
+///
+/// 只写入调用方传入的配置项，其他配置项保持不变；download_dir 只有显式传入 null 时才会被清除。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `settings`: 要修改的配置项（config.json 中的键 -> 新值）
+///
+/// # 返回值
+/// - 成功时返回实际生效的设置（已限制在允许范围内）
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn set_settings(
+    app_handle: AppHandle,
+    settings: Map<String, Value>,
+) -> AppResult<Settings> {
+    let mut changes = settings;
+    // 数据目录只能通过 write_config 修改，以便同时重新初始化目录管理器
+    changes.remove("nmd_data");
+    changes.remove("schema_version");
+    log_info!("接收到修改设置请求: {:?}", changes);
+
+    // 与当前配置合并后整体校验，任何一个配置项无效都不保存
+    let current = crate::config_manager::read_config(app_handle.clone(), "config.json")?;
+    let (mut merged, _) = migrate_config(current).map_err(AppError::Config)?;
+    if let Some(object) = merged.as_object_mut() {
+        object.extend(changes.clone());
+    }
+    let validated = validate_config(merged).map_err(AppError::InvalidInput)?;
+    let settings = serde_json::from_value::<Settings>(validated.clone())
+        .map_err(|e| AppError::Config(format!("无法解析设置: {:?}", e)))?;
+
+    // 校验新的自定义下载目录的写入权限和剩余空间；传入 null 时清除自定义下载目录
+    let download_dir_changed = changes.contains_key("download_dir");
+    if let Some(download_dir) = settings
+        .download_dir
+        .as_ref()
+        .filter(|_| download_dir_changed)
+    {
+        crate::dir_manager::validate_download_dir(std::path::Path::new(download_dir))
+            .map_err(AppError::InvalidInput)?;
+    }
+
+    // 只写入传入的配置项，使用规范化后的值；规范化后不存在的配置项（例如传入 null 的可选配置）写入 null
+    let patch = changes
+        .keys()
+        .map(|key| {
+            (
+                key.clone(),
+                validated.get(key).cloned().unwrap_or(Value::Null),
+            )
+        })
+        .collect::<Map<String, Value>>();
+
+    // 先调整aria2c的全局并发限制（提高并发下载数时避免任务在aria2c内部排队），失败时不保存设置
+    crate::aria2c::set_max_concurrent_downloads(settings.max_concurrent_downloads)
+        .await
+        .map_err(AppError::Download)?;
+
+    // 合并写入 config.json，保留其他配置项；写入失败时恢复aria2c原来的并发限制
+    if let Err(e) =
+        crate::config_manager::merge_config(&app_handle, "config.json", Value::Object(patch))
+    {
+        let previous = current_settings().max_concurrent_downloads;
+        if let Err(e) = crate::aria2c::set_max_concurrent_downloads(previous).await {
+            log_warn!("恢复aria2c最大并发下载数失败: {}", e);
+        }
+        return Err(AppError::Config(e));
+    }
+
+    apply_settings(&settings);
+    if download_dir_changed {
+        crate::dir_manager::apply_download_dir(settings.download_dir.as_deref());
+    }
+
+    let _ = crate::event_dispatcher::emit(&app_handle, "settings-changed", &settings);
+
+    Ok(settings)
+}
+
+/// 导出全部配置 - 将配置目录中的配置文件（config.json）打包到一个文件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `path`: 导出文件路径
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回AppError
+#[tauri::command]
+pub fn export_settings(app_handle: AppHandle, path: String) -> AppResult<String> {
+    log_info!("导出配置到: {}", path);
+
+    let mut configs = Map::new();
+    for config_name in BUNDLED_CONFIG_FILES {
+        let config_path = crate::config_manager::get_config_path(&app_handle, config_name)
+            .map_err(AppError::Config)?;
+        if config_path.exists() {
+            let config = crate::config_manager::read_config(app_handle.clone(), config_name)?;
+            configs.insert(config_name.to_string(), config);
+        }
+    }
+
+    let bundle = serde_json::json!({
+        "format": SETTINGS_BUNDLE_FORMAT,
+        "schemaVersion": CURRENT_SCHEMA_VERSION,
+        "appVersion": app_handle.package_info().version.to_string(),
+        "exportedAt": chrono::Local::now().to_rfc3339(),
+        "configs": configs,
+    });
+
+    let content = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::Config(format!("无法序列化配置: {:?}", e)))?;
+    fs::write(&path, content)
+        .map_err(|e| AppError::FileOperation(format!("无法写入导出文件: {:?}", e)))?;
+
+    Ok(format!("配置已导出到: {}", path))
+}
+
+/// 导入全部配置 - 从 export_settings 导出的文件恢复配置
+///
+/// 导入的 config.json 会先经过校验和版本迁移，写入后由配置文件监视线程在运行时应用。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `path`: 导入文件路径
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回AppError
+#[tauri::command]
+pub fn import_settings(app_handle: AppHandle, path: String) -> AppResult<String> {
+    log_info!("从文件导入配置: {}", path);
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::FileOperation(format!("无法读取导入文件: {:?}", e)))?;
+    let bundle: Value = serde_json::from_str(&content)
+        .map_err(|e| AppError::InvalidInput(format!("无法解析导入文件: {:?}", e)))?;
+
+    if bundle.get("format").and_then(|v| v.as_str()) != Some(SETTINGS_BUNDLE_FORMAT) {
+        return Err(AppError::InvalidInput(
+            "导入文件不是有效的配置导出文件".to_string(),
+        ));
+    }
+
+    let configs = bundle
+        .get("configs")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| AppError::InvalidInput("导入文件中没有配置内容".to_string()))?;
+
+    // 先全部校验，避免只导入一部分
+    let mut validated = Vec::new();
+    for (config_name, config) in configs {
+        if !BUNDLED_CONFIG_FILES.contains(&config_name.as_str()) {
+            log_warn!("忽略未知的配置文件: {}", config_name);
+            continue;
+        }
+        let config = if config_name == "config.json" {
+            validate_config(config.clone()).map_err(AppError::InvalidInput)?
+        } else {
+            config.clone()
+        };
+        validated.push((config_name, config));
+    }
+
+    for (config_name, config) in &validated {
+        let config_path = crate::config_manager::get_config_path(&app_handle, config_name)
+            .map_err(AppError::Config)?;
+        if let Some(config_dir) = config_path.parent() {
+            fs::create_dir_all(config_dir)
+                .map_err(|e| AppError::Config(format!("无法创建配置目录: {:?}", e)))?;
+        }
+        let content = serde_json::to_string_pretty(config)
+            .map_err(|e| AppError::Config(format!("无法序列化配置: {:?}", e)))?;
+        fs::write(&config_path, content)
+            .map_err(|e| AppError::Config(format!("无法写入配置文件 {}: {:?}", config_name, e)))?;
+        log_info!("已导入配置文件: {}", config_name);
+    }
+
+    Ok(format!("已导入 {} 个配置文件", validated.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrate_drops_only_invalid_key() {
+        let config = json!({
+            "schema_version": 1,
+            "nmd_data": "D:\\nmd_data",
+            "download_dir": "E:\\downloads",
+            "language": 1,
+            "max_concurrent_downloads": 3,
+        });
+        let (config, changed) = migrate_config(config).unwrap();
+        assert!(changed);
+        assert!(config.get("language").is_none());
+        assert_eq!(config["nmd_data"], "D:\\nmd_data");
+        assert_eq!(config["download_dir"], "E:\\downloads");
+        assert_eq!(config["max_concurrent_downloads"], 3);
+    }
+
+    #[test]
+    fn migrate_keeps_valid_config_unchanged() {
+        let config = json!({ "schema_version": 1, "max_concurrent_downloads": 2, "theme": "dark" });
+        let (migrated, changed) = migrate_config(config.clone()).unwrap();
+        assert!(!changed);
+        assert_eq!(migrated, config);
+    }
+
+    #[test]
+    fn parse_settings_defaults_invalid_key() {
+        let settings = parse_settings(json!({
+            "nmd_data": "D:\\nmd_data",
+            "certificate_pins": "sha256/abc",
+            "log_level": "loud",
+            "max_concurrent_extracts": 2,
+        }))
+        .unwrap();
+        assert_eq!(settings.nmd_data.as_deref(), Some("D:\\nmd_data"));
+        assert!(settings.certificate_pins.is_empty());
+        assert_eq!(settings.log_level, Settings::default().log_level);
+        assert_eq!(settings.max_concurrent_extracts, 2);
+    }
+
+    #[test]
+    fn parse_settings_rejects_non_object() {
+        assert!(parse_settings(json!([1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn validate_config_rejects_invalid_key() {
+        assert!(validate_config(json!({ "schema_version": 1, "language": 1 })).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+// shell_integration.rs 模块 - 维护任务栏跳转列表：打开文件管理器、暂停/恢复下载，以及最近安装的地图（挂载后启动游戏）
+//
+// 跳转列表中的项目以命令行参数重新启动本程序，由单实例插件转发给正在运行的实例处理。
+
+// 标准库导入
+use std::{path::Path, sync::Mutex, thread};
+
+// 第三方库导入
+use lazy_static::lazy_static;
+use tauri::AppHandle;
+use windows::{
+    core::{Interface, HSTRING, PROPVARIANT},
+    Win32::{
+        Storage::EnhancedStorage::PKEY_Title,
+        System::Com::{
+            CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+            COINIT_APARTMENTTHREADED,
+        },
+        UI::Shell::{
+            Common::{IObjectArray, IObjectCollection},
+            DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
+            PropertiesSystem::IPropertyStore,
+            ShellLink,
+        },
+    },
+};
+
+// 内部模块导入
+use crate::{config_manager, init::GLOBAL_APP_HANDLE, log_debug, log_error, log_info, tr};
+
+/// 打开文件管理器的命令行参数
+const OPEN_FILE_MANAGER_ARG: &str = "--open-file-manager";
+
+/// 暂停所有下载的命令行参数
+const PAUSE_DOWNLOADS_ARG: &str = "--pause-downloads";
+
+/// 恢复所有下载的命令行参数
+const RESUME_DOWNLOADS_ARG: &str = "--resume-downloads";
+
+/// 挂载地图分组并启动游戏的命令行参数，后面跟分组名称
+const LAUNCH_MAP_ARG: &str = "--launch-map";
+
+/// 配置文件中保存最近安装地图的键
+const RECENT_MAPS_KEY: &str = "recent_maps";
+
+/// 跳转列表中显示的最近安装地图数
+const MAX_RECENT_MAPS: usize = 5;
+
+/// 启动 Left 4 Dead 2 的 Steam 链接
+const LAUNCH_GAME_URL: &str = "steam://rungameid/550";
+
+lazy_static! {
+    /// 保证同一时间只有一个线程在写入跳转列表
+    static ref JUMP_LIST_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// 跳转列表中的一项
+struct JumpListItem {
+    title: String,
+    arguments: String,
+}
+
+/// 读取最近安装的地图分组，最新的在前
+fn read_recent_maps(app_handle: &AppHandle) -> Vec<String> {
+    config_manager::read_config(app_handle.clone(), "config.json")
+        .ok()
+        .and_then(|config| config.get(RECENT_MAPS_KEY).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// 记录新安装的地图分组并更新跳转列表
+///
+/// # 参数
+/// - `group_name`: 地图分组（maps 目录下的子目录）名称
+pub fn record_installed_map(group_name: &str) {
+    let Some(app_handle) = GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    else {
+        return;
+    };
+
+    let mut recent = read_recent_maps(&app_handle);
+    recent.retain(|name| !crate::utils::same_entry_name(name, group_name));
+    recent.insert(0, group_name.to_string());
+    recent.truncate(MAX_RECENT_MAPS);
+
+    if let Err(e) = config_manager::merge_config(
+        &app_handle,
+        "config.json",
+        serde_json::json!({ RECENT_MAPS_KEY: recent }),
+    ) {
+        log_error!("保存最近安装的地图失败: {}", e);
+    }
+    refresh_jump_list();
+}
+
+/// 创建一个以指定参数启动本程序的快捷方式
+fn create_link(exe: &Path, item: &JumpListItem) -> windows::core::Result<IShellLinkW> {
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(&HSTRING::from(exe.as_os_str()))?;
+        link.SetArguments(&HSTRING::from(item.arguments.as_str()))?;
+        link.SetIconLocation(&HSTRING::from(exe.as_os_str()), 0)?;
+
+        // 跳转列表显示的是快捷方式的标题属性
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &PROPVARIANT::from(item.title.as_str()))?;
+        store.Commit()?;
+        Ok(link)
+    }
+}
+
+/// 创建包含多个快捷方式的集合
+fn create_collection(exe: &Path, items: &[JumpListItem]) -> windows::core::Result<IObjectArray> {
+    unsafe {
+        let collection: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for item in items {
+            collection.AddObject(&create_link(exe, item)?)?;
+        }
+        collection.cast()
+    }
+}
+
+/// 写入跳转列表
+fn build_jump_list(
+    exe: &Path,
+    tasks: &[JumpListItem],
+    recent_maps: &[JumpListItem],
+) -> windows::core::Result<()> {
+    unsafe {
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut min_slots = 0u32;
+        let _removed: IObjectArray = list.BeginList(&mut min_slots)?;
+
+        if !recent_maps.is_empty() {
+            list.AppendCategory(
+                &HSTRING::from(tr!("jump_list.recent_maps")),
+                &create_collection(exe, recent_maps)?,
+            )?;
+        }
+        list.AddUserTasks(&create_collection(exe, tasks)?)?;
+        list.CommitList()
+    }
+}
+
+/// 在后台线程中重新生成跳转列表（暂停状态、最近安装的地图或界面语言变化后调用）
+pub fn refresh_jump_list() {
+    let Some(app_handle) = GLOBAL_APP_HANDLE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    else {
+        return;
+    };
+
+    thread::spawn(move || {
+        let _guard = JUMP_LIST_LOCK.lock().unwrap();
+        let exe = match std::env::current_exe() {
+            Ok(exe) => exe,
+            Err(e) => {
+                log_error!("获取程序路径失败，无法更新跳转列表: {}", e);
+                return;
+            }
+        };
+
+        let tasks = vec![
+            JumpListItem {
+                title: tr!("jump_list.open_file_manager"),
+                arguments: OPEN_FILE_MANAGER_ARG.to_string(),
+            },
+            if crate::aria2c::is_downloads_paused() {
+                JumpListItem {
+                    title: tr!("jump_list.resume_downloads"),
+                    arguments: RESUME_DOWNLOADS_ARG.to_string(),
+                }
+            } else {
+                JumpListItem {
+                    title: tr!("jump_list.pause_downloads"),
+                    arguments: PAUSE_DOWNLOADS_ARG.to_string(),
+                }
+            },
+        ];
+        let recent_maps = read_recent_maps(&app_handle)
+            .into_iter()
+            .map(|group_name| JumpListItem {
+                arguments: format!("{} \"{}\"", LAUNCH_MAP_ARG, group_name),
+                title: group_name,
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        }
+        match build_jump_list(&exe, &tasks, &recent_maps) {
+            Ok(()) => log_debug!("跳转列表已更新"),
+            Err(e) => log_error!("更新跳转列表失败: {}", e),
+        }
+        unsafe {
+            CoUninitialize();
+        }
+    });
+}
+
+/// 暂停或恢复所有下载，并通知前端和更新跳转列表
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `paused`: true 为暂停，false 为恢复
+pub async fn set_downloads_paused(app_handle: &AppHandle, paused: bool) -> Result<(), String> {
+    crate::aria2c::set_downloads_paused(paused).await?;
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
+        "download-queue-paused",
+        &serde_json::json!({ "paused": paused }),
+    );
+    refresh_jump_list();
+    Ok(())
+}
+
+/// 挂载地图分组并启动游戏
+async fn launch_map(group_name: &str) -> Result<(), String> {
+    crate::commands::mount_group(group_name.to_string())
+        .await
+        .map_err(|e| e.to_string())?;
+    std::process::Command::new("explorer")
+        .arg(LAUNCH_GAME_URL)
+        .spawn()
+        .map_err(|e| format!("启动游戏失败: {}", e))?;
+    Ok(())
+}
+
+/// 处理跳转列表传入的命令行参数
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `args`: 命令行参数
+pub fn handle_jump_list_args(app_handle: &AppHandle, args: &[String]) {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            OPEN_FILE_MANAGER_ARG => {
+                log_info!("跳转列表: 打开文件管理器");
+                if let Err(e) = crate::commands::open_filemanager_window(app_handle.clone()) {
+                    log_error!("打开文件管理器失败: {}", e);
+                }
+            }
+            PAUSE_DOWNLOADS_ARG | RESUME_DOWNLOADS_ARG => {
+                let paused = arg == PAUSE_DOWNLOADS_ARG;
+                log_info!("跳转列表: {}所有下载", if paused { "暂停" } else { "恢复" });
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = set_downloads_paused(&app_handle, paused).await {
+                        log_error!("{}", e);
+                    }
+                });
+            }
+            LAUNCH_MAP_ARG => {
+                let Some(group_name) = args.next().cloned() else {
+                    continue;
+                };
+                log_info!("跳转列表: 挂载 {} 并启动游戏", group_name);
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = launch_map(&group_name).await {
+                        log_error!("挂载地图并启动游戏失败: {}", e);
+                    }
+                });
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,361 @@
+// support_bundle.rs 模块 - 生成诊断包（日志、脱敏配置、队列快照、工具版本和环境信息），供用户附加到问题反馈中
+
+// 标准库导入
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// 第三方库导入
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+// 内部模块导入
+use crate::{
+    aria2c::ARIA2C_PATH,
+    download_manager::DOWNLOAD_QUEUE,
+    error::{AppError, AppResult},
+    extract_manager::{EXTRACT_MANAGER, SEVENZ_PATH},
+    log_info, log_utils, log_warn,
+    queue_manager::TaskState,
+    tr,
+};
+
+/// 当前应用版本
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 诊断包中附带的内存日志行数（文件日志未启用时也能提供最近的日志）
+const BUNDLE_RECENT_LOG_LINES: usize = 1000;
+
+/// 配置项名称包含这些关键字时，值会被替换为 <redacted>
+const SENSITIVE_KEYWORDS: &[&str] = &[
+    "token",
+    "password",
+    "secret",
+    "cookie",
+    "auth",
+    "api_key",
+    "apikey",
+    "private_key",
+];
+
+/// 将文本中的用户目录替换为 %USERPROFILE%，避免诊断包泄露用户名
+fn sanitize_text(text: &str) -> String {
+    match std::env::var("USERPROFILE") {
+        Ok(profile) if !profile.is_empty() => text
+            .replace(&profile, "%USERPROFILE%")
+            .replace(&profile.replace('\\', "/"), "%USERPROFILE%")
+            .replace(&profile.replace('\\', "\\\\"), "%USERPROFILE%"),
+        _ => text.to_string(),
+    }
+}
+
+/// 去掉 URL 中的查询参数和片段，避免泄露下载链接中的签名或令牌
+fn sanitize_url(url: &str) -> String {
+    match tauri::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        Err(_) => url.split(['?', '#']).next().unwrap_or_default().to_string(),
+    }
+}
+
+/// 对配置内容脱敏：隐藏敏感字段，替换路径中的用户目录
+fn sanitize_config(config: Value) -> Value {
+    match config {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    let value = if SENSITIVE_KEYWORDS.iter().any(|k| lower.contains(k)) {
+                        Value::String("<redacted>".to_string())
+                    } else {
+                        sanitize_config(value)
+                    };
+                    (key, value)
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(sanitize_config).collect()),
+        Value::String(text) => Value::String(sanitize_text(&text)),
+        other => other,
+    }
+}
+
+/// 运行外部程序并返回输出的第一行非空文本，用于获取工具版本
+fn first_output_line(program: &Path, args: &[&str]) -> String {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW 标志
+    }
+
+    match command.output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("<无输出>")
+            .to_string(),
+        Err(e) => format!("<无法运行: {}>", e),
+    }
+}
+
+/// 从注册表读取 Windows 版本信息
+fn windows_version_info() -> Value {
+    let hklm = winreg::RegKey::predef(winreg::enums::HKEY_LOCAL_MACHINE);
+    match hklm.open_subkey("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion") {
+        Ok(key) => {
+            let read = |name: &str| key.get_value::<String, _>(name).ok();
+            json!({
+                "productName": read("ProductName"),
+                "displayVersion": read("DisplayVersion"),
+                "currentBuild": read("CurrentBuild"),
+                "ubr": key.get_value::<u32, _>("UBR").ok(),
+            })
+        }
+        Err(e) => json!({ "error": format!("{:?}", e) }),
+    }
+}
+
+/// 收集环境信息
+fn collect_environment(app_handle: &AppHandle) -> Value {
+    let (data_dir, addons_dir, cache_dir) = match crate::dir_manager::DIR_MANAGER.lock() {
+        Ok(manager) => match manager.as_ref() {
+            Some(dm) => (
+                Some(dm.data_dir().to_string_lossy().to_string()),
+                dm.addons_dir().map(|dir| dir.to_string_lossy().to_string()),
+                Some(dm.cache_dir().to_string_lossy().to_string()),
+            ),
+            None => (None, None, None),
+        },
+        Err(_) => (None, None, None),
+    };
+    let steam_path = crate::dir_manager::get_steam_install_path();
+
+    let environment = json!({
+        "appVersion": APP_VERSION,
+        "tauriVersion": tauri::VERSION,
+        "generatedAt": chrono::Local::now().to_rfc3339(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "windows": windows_version_info(),
+        "language": crate::i18n::current_language(),
+        "logLevel": log_utils::max_log_level(),
+        "steamPath": steam_path.as_ref().ok(),
+        "steamPathError": steam_path.as_ref().err(),
+        "dataDir": data_dir,
+        "addonsDir": addons_dir,
+        "cacheDir": cache_dir,
+        "appConfigDir": app_handle.path().app_config_dir().ok(),
+        "aria2cVersion": first_output_line(ARIA2C_PATH.as_path(), &["--version"]),
+        "sevenZipVersion": first_output_line(SEVENZ_PATH.as_path(), &["i"]),
+    });
+    sanitize_config(environment)
+}
+
+/// 生成下载队列和解压队列的快照
+fn collect_queue_snapshot() -> Value {
+    let downloads = match DOWNLOAD_QUEUE.lock() {
+        Ok(queue) => {
+            let tasks = |state| {
+                queue
+                    .tasks_in(state)
+                    .map(|(id, task)| {
+                        json!({
+                            "id": task.id,
+                            "url": sanitize_url(&task.url),
+                            "filename": task.filename,
+                            "savepath": task.savepath,
+                            "saveonly": task.saveonly,
+                            "priority": queue.priority(id),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            };
+            json!({
+                "maxConcurrentTasks": queue.max_concurrent_tasks,
+                "processingStarted": queue.processing_started,
+                "activeTasks": tasks(TaskState::Active),
+                "waitingTasks": tasks(TaskState::Waiting),
+                "pausedTasks": tasks(TaskState::Paused),
+            })
+        }
+        Err(e) => json!({ "error": format!("{:?}", e) }),
+    };
+
+    let extracts = match EXTRACT_MANAGER.queue.lock() {
+        Ok(queue) => {
+            let tasks = |state| {
+                queue
+                    .tasks_in(state)
+                    .map(|(_, task)| {
+                        json!({
+                            "id": task.id,
+                            "filePath": task.file_path,
+                            "archiveName": task.archive_name,
+                            "downloadTaskId": task.download_task_id,
+                            "isDraggedFile": task.is_dragged_file,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            };
+            json!({
+                "maxConcurrentTasks": queue.max_concurrent_tasks,
+                "processingStarted": queue.processing_started,
+                "activeTasks": tasks(TaskState::Active),
+                "waitingTasks": tasks(TaskState::Waiting),
+            })
+        }
+        Err(e) => json!({ "error": format!("{:?}", e) }),
+    };
+
+    sanitize_config(json!({ "downloads": downloads, "extracts": extracts }))
+}
+
+/// 将诊断内容写入暂存目录
+fn write_bundle_contents(app_handle: &AppHandle, staging_dir: &Path) -> Result<(), String> {
+    let write_json = |name: &str, value: &Value| -> Result<(), String> {
+        let content = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("无法序列化 {}: {:?}", name, e))?;
+        fs::write(staging_dir.join(name), content)
+            .map_err(|e| format!("无法写入 {}: {:?}", name, e))
+    };
+
+    // 日志文件（包括轮转的旧日志）
+    let logs_staging_dir = staging_dir.join("logs");
+    fs::create_dir_all(&logs_staging_dir).map_err(|e| format!("无法创建暂存目录: {:?}", e))?;
+    if let Some(logs_dir) = crate::init::get_logs_dir(app_handle) {
+        for entry in fs::read_dir(&logs_dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("nmd.log") {
+                continue;
+            }
+            match fs::read(entry.path()) {
+                Ok(content) => {
+                    let content = sanitize_text(&String::from_utf8_lossy(&content));
+                    let _ = fs::write(logs_staging_dir.join(&name), content);
+                }
+                Err(e) => log_warn!("读取日志文件 {} 失败: {:?}", name, e),
+            }
+        }
+    }
+    let recent_logs = log_utils::recent_log_lines(BUNDLE_RECENT_LOG_LINES).join("\n");
+    fs::write(
+        logs_staging_dir.join("recent.log"),
+        sanitize_text(&recent_logs),
+    )
+    .map_err(|e| format!("无法写入 recent.log: {:?}", e))?;
+
+    // 脱敏后的配置
+    let config = crate::config_manager::read_config(app_handle.clone(), "config.json")
+        .unwrap_or_else(|e| json!({ "error": e.to_string() }));
+    write_json("config.json", &sanitize_config(config))?;
+
+    write_json("queues.json", &collect_queue_snapshot())?;
+    write_json("environment.json", &collect_environment(app_handle))?;
+
+    Ok(())
+}
+
+/// 使用 7z 将暂存目录打包为 zip 文件
+fn zip_directory(staging_dir: &Path, output_path: &Path) -> Result<(), String> {
+    if output_path.exists() {
+        fs::remove_file(output_path).map_err(|e| format!("无法覆盖已有文件: {:?}", e))?;
+    }
+
+    let mut command = Command::new(SEVENZ_PATH.as_path());
+    command
+        .arg("a")
+        .arg("-tzip")
+        .arg("-sccUTF-8")
+        .arg(output_path)
+        .arg(staging_dir.join("*"));
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW 标志
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("无法启动7z.exe进程: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "7z.exe打包失败: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+/// 获取默认的诊断包输出目录 - 优先使用 nmd_data/support，未配置数据目录时使用应用数据目录
+fn default_bundle_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    let data_dir = crate::dir_manager::DIR_MANAGER
+        .lock()
+        .ok()
+        .and_then(|manager| manager.as_ref().map(|dm| dm.data_dir()))
+        .filter(|dir| !dir.as_os_str().is_empty());
+
+    match data_dir {
+        Some(dir) => Some(dir.join("support")),
+        None => app_handle
+            .path()
+            .app_local_data_dir()
+            .ok()
+            .map(|dir| dir.join("support")),
+    }
+}
+
+/// 生成诊断包 - 打包最近的日志、脱敏后的配置、队列快照、aria2c/7z 版本和环境信息
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `path`: 输出文件路径（可选，未指定时保存到数据目录下的 support 目录）
+///
+/// # 返回值
+/// - 成功时返回诊断包文件路径
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn create_support_bundle(
+    app_handle: AppHandle,
+    path: Option<String>,
+) -> AppResult<String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let output_path = match path {
+        Some(path) => PathBuf::from(path),
+        None => default_bundle_dir(&app_handle)
+            .ok_or_else(|| AppError::NotConfigured(tr!("error.support_bundle_dir_unavailable")))?
+            .join(format!("nmd-support-{}.zip", stamp)),
+    };
+    log_info!("开始生成诊断包: {}", output_path.display());
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            AppError::FileOperation(tr!(
+                "error.support_bundle_failed",
+                error = format!("{:?}", e)
+            ))
+        })?;
+    }
+
+    let staging_dir =
+        std::env::temp_dir().join(format!("nmd-support-{}-{}", stamp, std::process::id()));
+    let result = fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("无法创建暂存目录: {:?}", e))
+        .and_then(|_| write_bundle_contents(&app_handle, &staging_dir))
+        .and_then(|_| zip_directory(&staging_dir, &output_path));
+    let _ = fs::remove_dir_all(&staging_dir);
+
+    result.map_err(|e| AppError::FileOperation(tr!("error.support_bundle_failed", error = e)))?;
+
+    log_info!("诊断包已生成: {}", output_path.display());
+    Ok(output_path.to_string_lossy().to_string())
+}
@@ -3,11 +3,11 @@ use std::{
     fs,
     io::{BufRead, BufWriter, Read, Write},
     net::TcpStream,
-    path::Path,
+    path::{Path, PathBuf},
     process::Command,
     sync::Mutex,
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -27,6 +27,8 @@ struct GlobalState {
     helper_port: Option<u16>,
     long_connection: Option<TcpStream>,
     helper_token: Option<String>,
+    /// 运行中的 helper 允许删除的根目录
+    allowed_roots: Vec<PathBuf>,
 }
 
 lazy_static::lazy_static! {
@@ -35,9 +37,19 @@ lazy_static::lazy_static! {
         helper_port: None,
         long_connection: None,
         helper_token: None,
+        allowed_roots: Vec::new(),
     });
+
+    /// 长连接请求锁，保证同一时间只有一个请求在等待响应
+    static ref REQUEST_LOCK: Mutex<()> = Mutex::new(());
 }
 
+/// helper 无连接时的默认空闲退出时间（秒），可通过 config.json 的 helper_idle_timeout 配置
+const DEFAULT_HELPER_IDLE_TIMEOUT_SECS: u64 = 600;
+
+/// 保活心跳间隔
+const HELPER_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
 /// 找到可用的端口号
 fn find_available_port() -> Option<u16> {
     // 尝试绑定到 127.0.0.1:0，让系统分配一个可用的端口号
@@ -130,11 +142,24 @@ pub async fn ensure_server_running() -> Result<(), String> {
         state.helper_token = Some(token.clone());
     }
 
+    // 构建启动参数，允许 helper 删除 addons 目录和数据目录下的文件
+    let mut args = vec![
+        "--port".to_string(),
+        port.to_string(),
+        "--token".to_string(),
+        token,
+        "--idle-timeout".to_string(),
+        get_helper_idle_timeout().to_string(),
+    ];
+    let allowed_roots = get_allowed_delete_roots();
+    for root in &allowed_roots {
+        args.push("--allow".to_string());
+        args.push(root.to_string_lossy().to_string());
+    }
+    GLOBAL_STATE.lock().unwrap().allowed_roots = allowed_roots;
+
     // 启动服务器
-    match Command::new(&helper_path)
-        .args(["--port", &port.to_string(), "--token", &token])
-        .spawn()
-    {
+    match Command::new(&helper_path).args(&args).spawn() {
         Ok(mut child) => {
             log_info!("helper 服务器已启动，进程ID: {:?}", child.id());
 
@@ -146,13 +171,17 @@ pub async fn ensure_server_running() -> Result<(), String> {
                             if code == 0x5 {
                                 // 退出码 0x5 表示访问被拒绝，通常是因为用户取消了 UAC 提升
                                 log_error!("用户取消了 UAC 权限提升，无法建立符号链接");
+                                crate::event_log::report_critical(
+                                    crate::event_log::CriticalEvent::ElevationDenied,
+                                    "helper 权限提升被拒绝，无法建立符号链接",
+                                );
 
                                 // 显示弹窗提示用户
                                 if let Ok(guard) = GLOBAL_APP_HANDLE.read() {
                                     if let Some(app_handle) = guard.as_ref() {
                                         show_blocking_dialog(
                                             app_handle,
-                                            "您的系统要求以管理员权限建立符号链接\n\n获取管理员权限失败，无法完成挂载操作",
+                                            &crate::tr!("dialog.symlink_elevation_failed"),
                                             "Nyaser Maps Downloader",
                                             MessageDialogKind::Error,
                                         );
@@ -210,6 +239,35 @@ pub async fn ensure_server_running() -> Result<(), String> {
     }
 }
 
+/// 获取 helper 空闲超时配置（秒）
+fn get_helper_idle_timeout() -> u64 {
+    let app_handle = match GLOBAL_APP_HANDLE.read() {
+        Ok(guard) => guard.clone(),
+        Err(_) => None,
+    };
+
+    app_handle
+        .and_then(|handle| crate::settings::load_settings(&handle).helper_idle_timeout)
+        .unwrap_or(DEFAULT_HELPER_IDLE_TIMEOUT_SECS)
+}
+
+/// 获取允许 helper 删除的根目录（addons 目录和 nmd_data 数据目录）
+fn get_allowed_delete_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Ok(manager) = crate::dir_manager::DIR_MANAGER.lock() {
+        if let Some(dir_manager) = manager.as_ref() {
+            if let Some(addons_dir) = dir_manager.addons_dir() {
+                roots.push(addons_dir.clone());
+            }
+            let data_dir = dir_manager.data_dir();
+            if !data_dir.as_os_str().is_empty() {
+                roots.push(data_dir);
+            }
+        }
+    }
+    roots
+}
+
 /// 建立与 helper 服务器的长连接
 fn establish_long_connection() {
     log_info!("建立与 helper 服务器的长连接");
@@ -246,7 +304,17 @@ fn establish_long_connection() {
 
                 // 保持连接打开，直到连接断开
                 let mut buffer = [0; 1024];
+                let mut last_ping = Instant::now();
                 loop {
+                    // 与请求发送互斥，避免读取到其他请求的响应
+                    let _guard = match REQUEST_LOCK.try_lock() {
+                        Ok(guard) => guard,
+                        Err(_) => {
+                            thread::sleep(Duration::from_secs(1));
+                            continue;
+                        }
+                    };
+
                     // 设置非阻塞读取
                     if let Err(e) = stream.set_nonblocking(true) {
                         log_info!("设置非阻塞读取失败: {:?}, 重新连接...", e);
@@ -299,6 +367,21 @@ fn establish_long_connection() {
                         break;
                     }
 
+                    // 定期发送保活心跳，及时发现失效的连接
+                    if last_ping.elapsed() >= HELPER_KEEPALIVE_INTERVAL {
+                        last_ping = Instant::now();
+                        if let Err(e) = send_keepalive_ping(&stream) {
+                            log_info!("保活心跳失败: {}, 重新连接...", e);
+                            {
+                                let mut state = GLOBAL_STATE.lock().unwrap();
+                                state.long_connection = None;
+                            }
+                            break;
+                        }
+                    }
+
+                    drop(_guard);
+
                     // 保持连接打开
                     thread::sleep(Duration::from_secs(1));
                 }
@@ -324,6 +407,44 @@ fn establish_long_connection() {
     }
 }
 
+/// 通过长连接发送保活心跳
+///
+/// 调用方需持有 `REQUEST_LOCK`，并确保连接处于阻塞模式
+fn send_keepalive_ping(stream: &TcpStream) -> Result<(), String> {
+    let token = {
+        let state = GLOBAL_STATE.lock().unwrap();
+        state.helper_token.clone().unwrap_or_default()
+    };
+    let message = serde_json::json!({ "cmd": "ping", "token": token });
+
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .map_err(|e| format!("设置读取超时失败: {:?}", e))?;
+    stream
+        .set_write_timeout(Some(Duration::from_secs(5)))
+        .map_err(|e| format!("设置写入超时失败: {:?}", e))?;
+
+    let mut writer = BufWriter::new(stream);
+    writeln!(writer, "{}", message).map_err(|e| format!("发送心跳失败: {:?}", e))?;
+    writer
+        .flush()
+        .map_err(|e| format!("刷新缓冲区失败: {:?}", e))?;
+
+    let mut reader = std::io::BufReader::new(stream);
+    let mut response_json = String::new();
+    reader
+        .read_line(&mut response_json)
+        .map_err(|e| format!("读取心跳响应失败: {:?}", e))?;
+
+    let response: SymlinkResponse =
+        serde_json::from_str(&response_json).map_err(|e| format!("解析心跳响应失败: {:?}", e))?;
+    if response.success {
+        Ok(())
+    } else {
+        Err(response.message)
+    }
+}
+
 /// 获取与 helper 服务器的长连接
 fn get_long_connection() -> Option<std::net::TcpStream> {
     let state = GLOBAL_STATE.lock().unwrap();
@@ -340,6 +461,25 @@ fn get_long_connection() -> Option<std::net::TcpStream> {
     None
 }
 
+/// helper 服务器状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HelperStatus {
+    /// helper 服务器端口（尚未启动过时为 None）
+    pub port: Option<u16>,
+    /// 是否已建立长连接
+    pub connected: bool,
+}
+
+/// 获取 helper 服务器状态
+pub fn get_helper_status() -> HelperStatus {
+    let state = GLOBAL_STATE.lock().unwrap();
+    HelperStatus {
+        port: state.helper_port,
+        connected: state.long_connection.is_some(),
+    }
+}
+
 /// 发送消息到 helper 服务器
 async fn send_message_to_server(
     message_map: serde_json::Map<String, serde_json::Value>,
@@ -347,22 +487,63 @@ async fn send_message_to_server(
     // 确保服务器已启动
     ensure_server_running().await?;
 
-    // 添加token到消息中
-    let mut message_with_token = message_map;
     let token = {
         let state = GLOBAL_STATE.lock().unwrap();
         state.helper_token.clone().unwrap_or_default()
     };
+
+    // helper 启动后数据目录或 addons 目录可能已变化（迁移数据目录、修改 nmd_data、设置 addons 目录），先更新允许删除的根目录
+    if let Err(e) = sync_allowed_delete_roots(&token) {
+        log_warn!("更新 helper 允许删除的目录失败: {}", e);
+    }
+
+    // 添加token到消息中
+    let mut message_with_token = message_map;
     message_with_token.insert("token".to_string(), serde_json::Value::String(token));
 
     // 序列化消息
     let message_json = serde_json::to_string(&message_with_token)
         .map_err(|e| format!("序列化消息失败: {:?}", e))?;
 
+    send_request(&message_json)
+}
+
+/// 当前允许删除的根目录与 helper 启动时不同时，发送给运行中的 helper
+fn sync_allowed_delete_roots(token: &str) -> Result<(), String> {
+    let roots = get_allowed_delete_roots();
+    if GLOBAL_STATE.lock().unwrap().allowed_roots == roots {
+        return Ok(());
+    }
+
+    log_info!("允许 helper 删除的目录已变化，更新为: {:?}", roots);
+    let message = serde_json::json!({
+        "cmd": "set_allowed_roots",
+        "token": token,
+        "args": {
+            "roots": roots
+                .iter()
+                .map(|root| root.to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+        },
+    });
+    let response = send_request(&message.to_string())?;
+    if !response.success {
+        return Err(response.message);
+    }
+
+    GLOBAL_STATE.lock().unwrap().allowed_roots = roots;
+    Ok(())
+}
+
+/// 通过长连接发送一条消息并读取响应
+fn send_request(message_json: &str) -> Result<SymlinkResponse, String> {
     // 尝试使用长连接发送消息
     if let Some(stream) = get_long_connection() {
         log_info!("使用长连接发送消息到 helper 服务器");
 
+        // 与保活心跳互斥，确保读取到的是本次请求的响应
+        let _guard = REQUEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
         // 设置超时
         stream
             .set_read_timeout(Some(Duration::from_secs(10)))
@@ -561,3 +742,128 @@ pub fn delete_file_symlink(link_path: &str) -> Result<String, String> {
     log_info!("文件符号链接删除成功: {}", link_path);
     Ok(format!("符号链接删除成功: {}", link_path))
 }
+
+/// 通过 helper 服务器以管理员权限删除文件、符号链接或目录
+///
+/// helper 只允许删除 addons 目录和数据目录下的路径
+///
+/// # 参数
+/// - `path`: 要删除的路径
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回包含错误信息的Err(String)
+pub async fn delete_path_elevated(path: &str) -> Result<String, String> {
+    let mut args_map = serde_json::Map::new();
+    args_map.insert(
+        "path".to_string(),
+        serde_json::Value::String(path.to_string()),
+    );
+
+    let mut message_map = serde_json::Map::new();
+    message_map.insert(
+        "cmd".to_string(),
+        serde_json::Value::String("delete".to_string()),
+    );
+    message_map.insert("args".to_string(), serde_json::Value::Object(args_map));
+
+    match send_message_to_server(message_map).await {
+        Ok(response) => {
+            if response.success {
+                log_info!("通过 helper 删除成功: {}", response.message);
+                Ok(response.message)
+            } else {
+                log_error!("通过 helper 删除失败: {}", response.message);
+                Err(response.message)
+            }
+        }
+        Err(e) => {
+            log_error!("与服务器通信失败: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+/// 删除文件或目录，权限不足时自动通过 helper 提权删除
+///
+/// # 参数
+/// - `path`: 要删除的文件或目录路径
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回包含错误信息的Err(String)
+pub async fn remove_path(path: &Path) -> Result<String, String> {
+    let result = if path.is_dir() && !path.is_symlink() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+
+    match result {
+        Ok(_) => Ok(format!("删除成功: {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            log_warn!("删除被拒绝，尝试通过 helper 提权删除: {}", path.display());
+            crate::telemetry::record("feature.elevated_delete");
+            delete_path_elevated(&path.to_string_lossy()).await
+        }
+        Err(e) => {
+            log_error!("删除失败: {:?}, 错误: {:?}", path, e);
+            Err(format!("删除失败: {:?}", e))
+        }
+    }
+}
+
+/// 将文件或目录移动到回收站（通过 SHFileOperationW，不显示确认和进度界面）
+///
+/// # 参数
+/// - `path`: 要删除的文件或目录路径
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回包含错误信息的Err(String)
+pub fn move_to_recycle_bin(path: &Path) -> Result<String, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FO_DELETE,
+        SHFILEOPSTRUCTW,
+    };
+
+    // SHFileOperationW 需要绝对路径，且路径列表以两个 0 结尾
+    let absolute = std::path::absolute(path).map_err(|e| format!("无法解析路径: {:?}", e))?;
+    let wide_path: Vec<u16> = absolute.as_os_str().encode_wide().chain([0, 0]).collect();
+
+    let mut operation: SHFILEOPSTRUCTW = unsafe { std::mem::zeroed() };
+    operation.wFunc = FO_DELETE;
+    operation.pFrom = wide_path.as_ptr();
+    operation.fFlags = (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT) as u16;
+
+    let result = unsafe { SHFileOperationW(&mut operation) };
+    if result != 0 {
+        log_error!("移动到回收站失败: {:?}, 错误码: {:#x}", absolute, result);
+        return Err(format!("移动到回收站失败，错误码: {:#x}", result));
+    }
+    if operation.fAnyOperationsAborted != 0 {
+        return Err("移动到回收站的操作被中止".to_string());
+    }
+
+    Ok(format!("已移动到回收站: {}", absolute.display()))
+}
+
+/// 删除地图文件或分组 - 开启 use_recycle_bin 设置时移动到回收站，否则永久删除（权限不足时通过 helper 提权）
+///
+/// # 参数
+/// - `path`: 要删除的文件或目录路径
+///
+/// # 返回值
+/// - 成功时返回包含成功信息的Ok(String)
+/// - 失败时返回包含错误信息的Err(String)
+pub async fn delete_path(path: &Path) -> Result<String, String> {
+    if crate::settings::current_settings().use_recycle_bin {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || move_to_recycle_bin(&path))
+            .await
+            .map_err(|e| format!("{:?}", e))?
+    } else {
+        remove_path(path).await
+    }
+}
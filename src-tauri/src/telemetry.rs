@@ -0,0 +1,111 @@
+// telemetry.rs 模块 - 匿名使用统计：统计安装、失败和各项功能的使用次数，定期批量提交到配置的地址
+//
+// 默认关闭，只有用户在设置中开启 telemetry_enabled 并配置 telemetry_endpoint 后才会记录和提交。
+// 提交的内容只有应用版本和各计数器的值，不包含链接、文件名、路径或任何可以识别用户的信息。
+
+// 标准库导入
+use std::{collections::BTreeMap, sync::Mutex, time::Duration};
+
+// 第三方库导入
+use chrono::Utc;
+use lazy_static::lazy_static;
+
+// 内部模块导入
+use crate::{init::is_app_shutting_down, log_debug, log_info, log_warn};
+
+/// 提交统计的间隔
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// 提交请求超时时间
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 应用版本
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+lazy_static! {
+    /// 尚未提交的计数：计数器名称 -> 次数
+    static ref COUNTERS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+}
+
+/// 是否已开启使用统计并配置了提交地址
+fn upload_endpoint() -> Option<String> {
+    let settings = crate::settings::current_settings();
+    if !settings.telemetry_enabled {
+        return None;
+    }
+    settings
+        .telemetry_endpoint
+        .filter(|endpoint| !endpoint.trim().is_empty())
+}
+
+/// 记录一次使用，未开启使用统计时不记录
+///
+/// # 参数
+/// - `counter`: 计数器名称，例如 "install.queued"、"host_adapter.baidupcs"
+pub fn record(counter: &str) {
+    if upload_endpoint().is_none() {
+        return;
+    }
+    if let Ok(mut counters) = COUNTERS.lock() {
+        *counters.entry(counter.to_string()).or_default() += 1;
+    }
+}
+
+/// 提交并清空当前的计数，提交失败时把计数放回，下次一起提交
+async fn upload() {
+    let Some(endpoint) = upload_endpoint() else {
+        // 关闭使用统计后丢弃尚未提交的计数
+        if let Ok(mut counters) = COUNTERS.lock() {
+            counters.clear();
+        }
+        return;
+    };
+    let counters = match COUNTERS.lock() {
+        Ok(mut counters) if !counters.is_empty() => std::mem::take(&mut *counters),
+        _ => return,
+    };
+
+    let payload = serde_json::json!({
+        "version": APP_VERSION,
+        "timestamp": Utc::now().to_rfc3339(),
+        "counters": counters,
+    });
+    let result = match crate::cert_pinning::client_builder()
+        .timeout(UPLOAD_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client
+            .post(&endpoint)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    match result {
+        Ok(_) => log_debug!("已提交使用统计: {} 项", counters.len()),
+        Err(e) => {
+            log_warn!("提交使用统计失败，稍后重试: {}", e);
+            if let Ok(mut pending) = COUNTERS.lock() {
+                for (counter, count) in counters {
+                    *pending.entry(counter).or_default() += count;
+                }
+            }
+        }
+    }
+}
+
+/// 启动使用统计的定期提交任务
+pub fn start_telemetry_uploader() {
+    if upload_endpoint().is_some() {
+        log_info!("已开启匿名使用统计");
+    }
+    tauri::async_runtime::spawn(async {
+        while !is_app_shutting_down() {
+            tokio::time::sleep(UPLOAD_INTERVAL).await;
+            upload().await;
+        }
+    });
+}
@@ -0,0 +1,687 @@
+// update_manager.rs 模块 - 负责软件更新的检查、下载和安装，支持 stable/beta 更新通道
+
+// 标准库导入
+use std::{
+    fs,
+    io::{Cursor, Read},
+    path::PathBuf,
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// 第三方库导入
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use lazy_static::lazy_static;
+use minisign_verify::{PublicKey, Signature};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+// 内部模块导入
+use crate::{
+    error::{AppError, AppResult},
+    init, log_error, log_info, log_warn, tr,
+};
+
+/// 等待队列空闲时的检查间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 在此时间内再次检查更新时直接使用上次的结果
+const CHECK_RESULT_TTL: Duration = Duration::from_secs(60);
+
+/// 更新通道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// 正式版
+    #[default]
+    Stable,
+    /// 测试版
+    Beta,
+}
+
+/// 可用更新信息，发送给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    /// 新版本号
+    pub version: String,
+    /// 当前版本号
+    pub current_version: String,
+    /// 更新说明
+    pub notes: Option<String>,
+    /// 发布时间
+    pub date: Option<String>,
+    /// 更新通道
+    pub channel: UpdateChannel,
+}
+
+// 最近一次检查到的可用更新
+lazy_static! {
+    static ref PENDING_UPDATE: Mutex<Option<Update>> = Mutex::new(None);
+
+    /// 同一时间只进行一次更新检查，检查期间的其他请求等待并复用其结果
+    static ref CHECK_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+
+    /// 最近一次检查的时间、更新通道和结果
+    static ref LAST_CHECK: Mutex<Option<(Instant, UpdateChannel, Option<UpdateInfo>)>> = Mutex::new(None);
+
+    /// 是否正在下载或安装更新
+    static ref INSTALL_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+}
+
+/// 正在下载或安装更新的标记，离开作用域时清除
+struct InstallGuard;
+
+impl InstallGuard {
+    /// 标记开始安装，已经有安装在进行时返回None
+    fn acquire() -> Option<Self> {
+        INSTALL_IN_PROGRESS
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| InstallGuard)
+    }
+}
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        INSTALL_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 获取 tauri.conf.json 中 plugins.updater.betaEndpoints 配置的 beta 通道更新检查地址
+/// （stable 通道使用 plugins.updater.endpoints）
+fn get_beta_endpoints(app_handle: &AppHandle) -> Result<Vec<Url>, String> {
+    let endpoints = app_handle
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("betaEndpoints"))
+        .and_then(|endpoints| endpoints.as_array())
+        .map(|endpoints| {
+            endpoints
+                .iter()
+                .filter_map(|endpoint| endpoint.as_str())
+                .map(|endpoint| {
+                    Url::parse(endpoint).map_err(|e| format!("无效的更新地址: {:?}", e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    if endpoints.is_empty() {
+        return Err("未配置 beta 通道的更新地址".to_string());
+    }
+    Ok(endpoints)
+}
+
+/// 检查指定通道是否有可用更新
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+/// - `channel`: 更新通道
+///
+/// # 返回值
+/// - 成功时返回可用更新（没有更新时为None）
+/// - 失败时返回包含错误信息的Err(String)
+async fn check_channel(
+    app_handle: &AppHandle,
+    channel: UpdateChannel,
+) -> Result<Option<Update>, String> {
+    let updater = match channel {
+        UpdateChannel::Stable => app_handle.updater(),
+        UpdateChannel::Beta => app_handle
+            .updater_builder()
+            .endpoints(get_beta_endpoints(app_handle)?)
+            .and_then(|builder| builder.build()),
+    }
+    .map_err(|e| format!("初始化更新程序失败: {:?}", e))?;
+
+    updater
+        .check()
+        .await
+        .map_err(|e| format!("检查更新失败: {:?}", e))
+}
+
+/// 检查更新 - 使用配置中的更新通道，发现更新时发送 update-available 事件
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 成功时返回可用更新信息（没有更新时为None）
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn check_for_update(app_handle: AppHandle) -> AppResult<Option<UpdateInfo>> {
+    let channel = crate::settings::load_settings(&app_handle).update_channel;
+
+    // 已有检查在进行时等待它完成，然后复用其结果
+    let _check_guard = CHECK_LOCK.lock().await;
+    if let Some((checked_at, last_channel, info)) = LAST_CHECK.lock().unwrap().clone() {
+        let pending = PENDING_UPDATE.lock().unwrap().is_some();
+        if last_channel == channel
+            && checked_at.elapsed() < CHECK_RESULT_TTL
+            && (info.is_none() || pending)
+        {
+            log_info!("刚刚检查过更新，使用上次的检查结果");
+            return Ok(info);
+        }
+    }
+
+    log_info!("检查更新，更新通道: {:?}", channel);
+
+    let update = check_channel(&app_handle, channel)
+        .await
+        .map_err(AppError::Update)?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+        channel,
+    });
+
+    *PENDING_UPDATE.lock().unwrap() = update;
+    *LAST_CHECK.lock().unwrap() = Some((Instant::now(), channel, info.clone()));
+
+    match info {
+        Some(ref info) => {
+            log_info!("发现可用更新: {} -> {}", info.current_version, info.version);
+            let _ = crate::event_dispatcher::emit(&app_handle, "update-available", info);
+        }
+        None => log_info!("更新检查完成，未发现可用更新"),
+    }
+
+    Ok(info)
+}
+
+/// 下载并安装更新 - 通过 update-progress 事件报告下载进度，安装完成后重启应用
+///
+/// 如果之前没有调用 check_for_update，会先检查一次更新；
+/// 下载完成后会等待下载和解压队列空闲再安装。同一时间只能有一个安装在进行。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 没有可用更新时返回Ok(())
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn install_update(app_handle: AppHandle) -> AppResult<()> {
+    let Some(_install_guard) = InstallGuard::acquire() else {
+        log_warn!("更新已在下载或安装中，忽略重复的安装请求");
+        return Err(AppError::Busy(tr!("error.update_in_progress")));
+    };
+
+    if PENDING_UPDATE.lock().unwrap().is_none() {
+        check_for_update(app_handle.clone()).await?;
+    }
+    let Some(update) = PENDING_UPDATE.lock().unwrap().take() else {
+        log_info!("没有可用更新，无需安装");
+        return Ok(());
+    };
+    // 安装包被取走后，之后的检查需要重新获取更新
+    *LAST_CHECK.lock().unwrap() = None;
+
+    log_info!("开始下载更新: {}", update.version);
+
+    // 优先尝试增量更新，失败时回退到完整安装包
+    let bytes = match download_patch_update(&app_handle, &update).await {
+        Ok(Some(bytes)) => bytes,
+        Ok(None) => download_full_update(&app_handle, &update)
+            .await
+            .map_err(AppError::Update)?,
+        Err(e) => {
+            log_warn!("增量更新失败，回退到完整安装包: {}", e);
+            download_full_update(&app_handle, &update)
+                .await
+                .map_err(AppError::Update)?
+        }
+    };
+    let _ = crate::event_dispatcher::emit(&app_handle, "update-downloaded", &());
+
+    // 缓存安装包，作为下次增量更新的基础版本
+    if let Err(e) = cache_installer(&app_handle, &update, &bytes) {
+        log_warn!("缓存安装包失败: {}", e);
+    }
+    // 缓存当前版本的安装包，供 rollback_update 使用
+    if let Err(e) = cache_current_installer(&app_handle, &update).await {
+        log_warn!("缓存当前版本的安装包失败，将无法回滚: {}", e);
+    }
+
+    // 安装更新会重启应用，等待下载和解压队列空闲，避免中断正在进行的任务
+    wait_for_queues_idle(&app_handle).await;
+
+    // 记录更新前的版本，供 rollback_update 使用；Windows 上 install 会启动安装程序并直接退出应用，必须在此之前写入
+    if let Err(e) = write_update_history(&app_handle, &update) {
+        log_warn!("写入更新记录失败: {}", e);
+    }
+
+    if let Err(e) = update.install(bytes) {
+        log_error!("安装更新失败: {:?}", e);
+        remove_update_history(&app_handle);
+        return Err(AppError::Update(tr!(
+            "error.install_update_failed",
+            error = format!("{:?}", e)
+        )));
+    }
+
+    log_info!("更新安装成功，应用即将重启");
+    init::cleanup_app_resources_for_restart();
+    app_handle.restart();
+}
+
+/// 发送更新下载进度事件
+fn emit_update_progress(app_handle: &AppHandle, downloaded: u64, content_length: Option<u64>) {
+    let _ = crate::event_dispatcher::emit(
+        app_handle,
+        "update-progress",
+        &serde_json::json!({
+            "downloaded": downloaded,
+            "contentLength": content_length,
+        }),
+    );
+}
+
+/// 下载完整安装包（由更新插件校验签名）
+async fn download_full_update(app_handle: &AppHandle, update: &Update) -> Result<Vec<u8>, String> {
+    let mut downloaded: u64 = 0;
+    update
+        .download(
+            |chunk_length, content_length| {
+                downloaded += chunk_length as u64;
+                emit_update_progress(app_handle, downloaded, content_length);
+            },
+            || {
+                log_info!("更新下载完成");
+            },
+        )
+        .await
+        .map_err(|e| {
+            log_error!("下载更新失败: {:?}", e);
+            format!("下载更新失败: {:?}", e)
+        })
+}
+
+/// 获取安装包缓存目录
+fn installer_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_local_data_dir()
+        .map(|dir| dir.join("updates"))
+        .map_err(|e| format!("无法获取应用数据目录: {:?}", e))
+}
+
+/// 获取指定版本的缓存安装包路径
+fn cached_installer_path(app_handle: &AppHandle, version: &str) -> Result<PathBuf, String> {
+    Ok(installer_cache_dir(app_handle)?.join(format!("installer-{}.bin", version)))
+}
+
+/// 缓存新版本安装包，只保留当前版本和新版本
+fn cache_installer(app_handle: &AppHandle, update: &Update, bytes: &[u8]) -> Result<(), String> {
+    let cache_dir = installer_cache_dir(app_handle)?;
+    fs::create_dir_all(&cache_dir).map_err(|e| format!("无法创建安装包缓存目录: {:?}", e))?;
+
+    let keep = [
+        cached_installer_path(app_handle, &update.current_version)?,
+        cached_installer_path(app_handle, &update.version)?,
+    ];
+    if let Ok(entries) = fs::read_dir(&cache_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_installer = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("installer-"));
+            if is_installer && !keep.contains(&path) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    fs::write(&keep[1], bytes).map_err(|e| format!("无法写入安装包缓存: {:?}", e))
+}
+
+/// 下载当前版本的安装包到缓存 - 当前版本不是通过本程序更新安装时，本地没有它的安装包，无法回滚
+///
+/// 各版本安装包的下载地址只有版本号不同，用当前版本号替换新版本下载地址中的版本号得到当前版本的地址，
+/// 并使用同名的 .sig 签名文件校验下载的安装包。
+async fn cache_current_installer(app_handle: &AppHandle, update: &Update) -> Result<(), String> {
+    let path = cached_installer_path(app_handle, &update.current_version)?;
+    if path.exists() {
+        return Ok(());
+    }
+
+    let download_url = update.download_url.as_str();
+    if !download_url.contains(&update.version) {
+        return Err(format!(
+            "无法推断当前版本安装包的下载地址: {}",
+            download_url
+        ));
+    }
+    let url = download_url.replace(&update.version, &update.current_version);
+    log_info!("下载当前版本的安装包: {}", url);
+
+    let client = Client::new();
+    let signature = client
+        .get(format!("{}.sig", url))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("下载签名失败: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("下载签名失败: {}", e))?;
+    let bytes = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("下载安装包失败: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("下载安装包失败: {}", e))?;
+    verify_signature(&bytes, &signature, &get_update_pubkey(app_handle)?)?;
+
+    fs::create_dir_all(installer_cache_dir(app_handle)?)
+        .map_err(|e| format!("无法创建安装包缓存目录: {:?}", e))?;
+    fs::write(&path, &bytes).map_err(|e| format!("无法写入安装包缓存: {:?}", e))
+}
+
+/// 更新记录 - 保存在安装包缓存目录的 history.json 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateHistory {
+    /// 更新前的版本
+    previous_version: String,
+    /// 更新后的版本
+    installed_version: String,
+    /// 更新时间
+    installed_at: String,
+}
+
+/// 写入更新记录
+fn write_update_history(app_handle: &AppHandle, update: &Update) -> Result<(), String> {
+    let history = UpdateHistory {
+        previous_version: update.current_version.clone(),
+        installed_version: update.version.clone(),
+        installed_at: chrono::Local::now().to_rfc3339(),
+    };
+    let content = serde_json::to_string_pretty(&history)
+        .map_err(|e| format!("无法序列化更新记录: {:?}", e))?;
+    let cache_dir = installer_cache_dir(app_handle)?;
+    fs::create_dir_all(&cache_dir).map_err(|e| format!("无法创建安装包缓存目录: {:?}", e))?;
+    fs::write(cache_dir.join("history.json"), content)
+        .map_err(|e| format!("无法写入更新记录: {:?}", e))
+}
+
+/// 删除更新记录 - 安装失败时调用
+fn remove_update_history(app_handle: &AppHandle) {
+    if let Ok(dir) = installer_cache_dir(app_handle) {
+        let _ = fs::remove_file(dir.join("history.json"));
+    }
+}
+
+/// 读取更新记录
+fn read_update_history(app_handle: &AppHandle) -> Result<UpdateHistory, String> {
+    let content = fs::read_to_string(installer_cache_dir(app_handle)?.join("history.json"))
+        .map_err(|_| tr!("error.no_update_history"))?;
+    serde_json::from_str(&content).map_err(|e| {
+        tr!(
+            "error.parse_update_history_failed",
+            error = format!("{:?}", e)
+        )
+    })
+}
+
+/// 获取 tauri.conf.json 中配置的更新公钥
+fn get_update_pubkey(app_handle: &AppHandle) -> Result<String, String> {
+    app_handle
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .map(|pubkey| pubkey.to_string())
+        .ok_or_else(|| "未配置更新公钥".to_string())
+}
+
+/// 使用更新公钥校验数据的 minisign 签名（公钥和签名均为 base64 编码）
+fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let decode = |value: &str| -> Result<String, String> {
+        let bytes = BASE64_STANDARD
+            .decode(value.trim())
+            .map_err(|e| format!("base64 解码失败: {:?}", e))?;
+        String::from_utf8(bytes).map_err(|e| format!("无效的 UTF-8 内容: {:?}", e))
+    };
+
+    let public_key =
+        PublicKey::decode(&decode(pubkey)?).map_err(|e| format!("无效的更新公钥: {:?}", e))?;
+    let signature =
+        Signature::decode(&decode(signature)?).map_err(|e| format!("无效的签名: {:?}", e))?;
+
+    public_key
+        .verify(data, &signature, true)
+        .map_err(|e| format!("签名校验失败: {:?}", e))
+}
+
+/// 尝试通过增量补丁获取新版本安装包
+///
+/// 更新清单（latest.json）中 `patches` 字段按基础版本号列出补丁，例如：
+/// `"patches": { "3.3.1": { "url": "...", "signature": "..." } }`。
+/// 补丁本身和还原出的安装包都会校验签名。
+///
+/// # 返回值
+/// - 成功时返回还原出的完整安装包
+/// - 没有可用补丁或本地没有基础版本安装包时返回Ok(None)
+/// - 下载、校验或还原失败时返回Err(String)
+async fn download_patch_update(
+    app_handle: &AppHandle,
+    update: &Update,
+) -> Result<Option<Vec<u8>>, String> {
+    let patch = match update
+        .raw_json
+        .get("patches")
+        .and_then(|patches| patches.get(&update.current_version))
+    {
+        Some(patch) => patch,
+        None => return Ok(None),
+    };
+
+    let base_path = cached_installer_path(app_handle, &update.current_version)?;
+    if !base_path.exists() {
+        log_info!("本地没有当前版本的安装包，无法使用增量更新");
+        return Ok(None);
+    }
+
+    let url = patch
+        .get("url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "补丁缺少 url 字段".to_string())?;
+    let patch_signature = patch
+        .get("signature")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "补丁缺少 signature 字段".to_string())?;
+    let pubkey = get_update_pubkey(app_handle)?;
+
+    log_info!(
+        "下载增量补丁: {} -> {}",
+        update.current_version,
+        update.version
+    );
+
+    let mut response = Client::new()
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("下载补丁失败: {}", e))?;
+    let content_length = response.content_length();
+    let mut patch_bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("下载补丁失败: {}", e))?
+    {
+        patch_bytes.extend_from_slice(&chunk);
+        emit_update_progress(app_handle, patch_bytes.len() as u64, content_length);
+    }
+
+    verify_signature(&patch_bytes, patch_signature, &pubkey)?;
+
+    let base = fs::File::open(&base_path).map_err(|e| format!("无法读取基础安装包: {:?}", e))?;
+    let mut reader = bipatch::Reader::new(Cursor::new(patch_bytes), base)
+        .map_err(|e| format!("无法解析补丁: {:?}", e))?;
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("应用补丁失败: {:?}", e))?;
+
+    // 还原出的安装包必须与完整安装包的签名一致
+    verify_signature(&bytes, &update.signature, &pubkey)?;
+
+    log_info!("增量更新还原完成，安装包大小: {} 字节", bytes.len());
+    Ok(Some(bytes))
+}
+
+/// 回滚更新 - 重新安装更新前的版本
+///
+/// 只有通过本程序更新过时才能回滚；更新时会缓存更新前版本的安装包，安装包在下载时已校验过签名。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+///
+/// # 返回值
+/// - 成功时启动安装程序并退出应用
+/// - 失败时返回AppError
+#[tauri::command(async)]
+pub async fn rollback_update(app_handle: AppHandle) -> AppResult<()> {
+    let history = read_update_history(&app_handle).map_err(AppError::Update)?;
+    let current_version = app_handle.package_info().version.to_string();
+    if history.installed_version != current_version {
+        return Err(AppError::Update(tr!(
+            "error.update_history_mismatch",
+            recorded = history.installed_version,
+            current = current_version
+        )));
+    }
+
+    let installer_path =
+        cached_installer_path(&app_handle, &history.previous_version).map_err(AppError::Update)?;
+    let bytes = fs::read(&installer_path).map_err(|_| {
+        AppError::NotFound(tr!(
+            "error.rollback_installer_not_found",
+            version = history.previous_version
+        ))
+    })?;
+
+    log_info!(
+        "回滚更新: {} -> {}",
+        current_version,
+        history.previous_version
+    );
+
+    // 安装会退出应用，等待下载和解压队列空闲
+    wait_for_queues_idle(&app_handle).await;
+
+    // 根据文件头判断安装包类型（MSI 为 OLE 复合文档格式，NSIS 为可执行文件）
+    let is_msi = bytes.starts_with(&[0xD0, 0xCF, 0x11, 0xE0]);
+    let extension = if is_msi { "msi" } else { "exe" };
+    let setup_path = std::env::temp_dir().join(format!(
+        "nmd-rollback-{}.{}",
+        history.previous_version, extension
+    ));
+    fs::write(&setup_path, &bytes).map_err(|e| {
+        AppError::FileOperation(tr!(
+            "error.write_installer_failed",
+            error = format!("{:?}", e)
+        ))
+    })?;
+
+    let mut command = if is_msi {
+        let mut command = Command::new("msiexec.exe");
+        command
+            .arg("/i")
+            .arg(&setup_path)
+            .args(["/passive", "AUTOLAUNCHAPP=True"]);
+        command
+    } else {
+        let mut command = Command::new(&setup_path);
+        command.args(["/P", "/R"]);
+        command
+    };
+    command.spawn().map_err(|e| {
+        AppError::Update(tr!(
+            "error.launch_installer_failed",
+            error = format!("{:?}", e)
+        ))
+    })?;
+
+    // 回滚后旧版本不再需要更新记录
+    if let Ok(cache_dir) = installer_cache_dir(&app_handle) {
+        let _ = fs::remove_file(cache_dir.join("history.json"));
+    }
+
+    log_info!("安装程序已启动，应用即将退出");
+    init::cleanup_app_resources_for_restart();
+    app_handle.exit(0);
+    Ok(())
+}
+
+/// 检查下载队列和解压队列是否都已空闲
+pub fn queues_idle() -> bool {
+    let downloads_idle = crate::download_manager::DOWNLOAD_QUEUE
+        .lock()
+        .map(|queue| queue.is_idle())
+        .unwrap_or(true);
+    let extracts_idle = crate::extract_manager::EXTRACT_MANAGER
+        .queue
+        .lock()
+        .map(|queue| queue.is_idle())
+        .unwrap_or(true);
+    downloads_idle && extracts_idle
+}
+
+/// 等待下载队列和解压队列空闲，等待期间发送一次 update-waiting-for-idle 事件
+async fn wait_for_queues_idle(app_handle: &AppHandle) {
+    if queues_idle() {
+        return;
+    }
+
+    log_info!("有正在进行的任务，等待队列空闲后安装更新");
+    let _ = crate::event_dispatcher::emit(app_handle, "update-waiting-for-idle", &());
+
+    while !queues_idle() {
+        tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+    }
+
+    log_info!("队列已空闲，开始安装更新");
+}
+
+/// 启动时检查更新 - 只发送 update-available 通知，由用户确认后调用 install_update
+///
+/// 开启了 auto_install_updates 设置时会在后台下载，并在队列空闲后自动安装。
+///
+/// # 参数
+/// - `app_handle`: Tauri应用句柄
+pub async fn check_update_on_startup(app_handle: AppHandle) {
+    match check_for_update(app_handle.clone()).await {
+        Ok(Some(_)) => {
+            if crate::settings::load_settings(&app_handle).auto_install_updates {
+                if let Err(e) = install_update(app_handle).await {
+                    log_error!("{}", e);
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log_error!("{}", e),
+    }
+}
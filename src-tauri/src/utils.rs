@@ -1,53 +1,184 @@
-use regex::Regex;
-use urlencoding::decode;
+// 第三方库导入
+use unicode_normalization::UnicodeNormalization;
 
-lazy_static::lazy_static! {
-    static ref BAIDUPCS_URL_REGEX: Regex = Regex::new(r"^https?://.+\.baidupcs\.com/file/.+$").unwrap();
-    static ref FIN_PARAM_REGEX: Regex = Regex::new(r"&fin=([^&]+)").unwrap();
-    static ref FILENAME_REGEX: Regex = Regex::new(r"\/([^\/?]+)(\?.*)?$").unwrap();
+/// 从下载链接中提取文件名 - 按链接所在网站交给对应的 HostAdapter 处理
+pub fn get_file_name(url: &str) -> Option<String> {
+    crate::host_adapter::file_name(url)
+}
+
+/// 将字节解码为字符串 - 优先按 UTF-8 解码，不是有效的 UTF-8 时按 GB18030 解码
+///
+/// 部分中文网站的链接和响应头使用 GBK 编码的文件名，按 UTF-8 解码会得到乱码
+///
+/// # 参数
+/// - `bytes`: 要解码的字节
+pub fn decode_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::GB18030
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned(),
+    }
 }
 
-/// 检查URL是否为百度PCS链接
-pub fn is_baidupcs_link(url: &str) -> bool {
-    BAIDUPCS_URL_REGEX.is_match(url)
+/// 解码URL中的百分号编码，解码后的字节按 decode_text 转换为字符串
+///
+/// # 参数
+/// - `text`: 百分号编码的文本
+pub fn percent_decode(text: &str) -> String {
+    decode_text(&urlencoding::decode_binary(text.as_bytes()))
 }
 
-/// 从百度PCS链接中提取文件名
-pub fn get_file_name_from_baidupcs(url: &str) -> Option<String> {
-    if let Some(caps) = FIN_PARAM_REGEX.captures(url) {
-        if let Some(encoded_name) = caps.get(1) {
-            let encoded_str = encoded_name.as_str();
-            let fixed_encoded = encoded_str.replace('+', "%20");
-            if let Ok(decoded) = decode(&fixed_encoded) {
-                return Some(decoded.to_string());
-            } else {
-                if let Ok(decoded) = decode(encoded_str) {
-                    return Some(decoded.to_string());
-                }
-            }
-        }
-    }
-    None
+/// 两个文件名或目录名在 Windows 上是否指向同一项 - 不区分大小写，并统一 Unicode 形式后比较
+///
+/// # 参数
+/// - `a`: 第一个名称
+/// - `b`: 第二个名称
+pub fn same_entry_name(a: &str, b: &str) -> bool {
+    a == b
+        || a.nfc()
+            .flat_map(char::to_lowercase)
+            .eq(b.nfc().flat_map(char::to_lowercase))
 }
 
-pub fn get_file_name_from_pathname(url: &str) -> Option<String> {
-    if let Some(caps) = FILENAME_REGEX.captures(url) {
-        if let Some(name) = caps.get(1) {
-            if let Ok(decoded) = decode(name.as_str()) {
-                return Some(decoded.to_string());
-            }
-        }
+/// 在目录中查找与名称相同（按 same_entry_name 比较）的项，返回其实际的名称
+///
+/// # 参数
+/// - `dir`: 要查找的目录
+/// - `name`: 名称
+///
+/// # 返回值
+/// - 目录中已存在的同名项的实际名称，不存在时返回None
+pub fn find_existing_entry(dir: &std::path::Path, name: &str) -> Option<String> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .find(|existing| same_entry_name(existing, name))
+}
+
+/// Windows 保留的设备名，不能用作文件名（不区分大小写，带扩展名也不行）
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 文件名最大长度（UTF-16 码元数，与 Windows 的路径长度限制一致），给目录路径和去重后缀留出余量
+const MAX_FILE_NAME_UNITS: usize = 120;
+
+/// 将文件名转换为可以在 Windows 上安全使用的形式
+///
+/// 统一为 NFC 形式（macOS 上打包的文件名通常是分解形式），替换非法字符和控制字符，
+/// 去掉结尾的点和空格，避开保留设备名，并限制长度（保留扩展名）
+///
+/// # 参数
+/// - `name`: 原始文件名
+///
+/// # 返回值
+/// - 处理后的文件名，结果为空时返回None
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let replaced: String = name
+        .nfc()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let mut sanitized = replaced.trim().trim_end_matches(['.', ' ']).to_string();
+
+    if sanitized.is_empty() || sanitized.chars().all(|c| c == '_' || c == '.') {
+        return None;
+    }
+
+    if sanitized.encode_utf16().count() > MAX_FILE_NAME_UNITS {
+        let path = std::path::Path::new(&sanitized);
+        let extension = path
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .filter(|ext| ext.chars().count() <= 10)
+            .unwrap_or_default();
+        // 按 UTF-16 码元截断，扩展平面的字符（如 emoji）占两个码元，不会被从中间截断
+        let mut remaining = MAX_FILE_NAME_UNITS - extension.encode_utf16().count();
+        let stem: String = sanitized
+            .chars()
+            .take_while(|c| {
+                let units = c.len_utf16();
+                let fits = units <= remaining;
+                remaining = remaining.saturating_sub(units);
+                fits
+            })
+            .collect();
+        sanitized = format!("{}{}", stem.trim_end_matches(['.', ' ']), extension);
     }
-    None
+
+    let stem = sanitized.split('.').next().unwrap_or_default();
+    if RESERVED_FILE_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem.trim()))
+    {
+        sanitized = format!("_{}", sanitized);
+    }
+
+    Some(sanitized)
 }
 
-pub fn get_file_name(url: &str) -> Option<String> {
-    // 检查是否为百度PCS链接
-    if is_baidupcs_link(url) {
-        // 从百度PCS链接中提取文件名
-        get_file_name_from_baidupcs(url)
-    } else {
-        // 使用正则表达式从URL中提取文件名
-        get_file_name_from_pathname(url)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decode_utf8() {
+        assert_eq!(percent_decode("%E5%9C%B0%E5%9B%BE.zip"), "地图.zip");
+    }
+
+    #[test]
+    fn percent_decode_gbk() {
+        // “地图”的 GBK 编码，不是有效的 UTF-8
+        assert_eq!(percent_decode("%B5%D8%CD%BC.zip"), "地图.zip");
+    }
+
+    #[test]
+    fn decode_text_prefers_utf8() {
+        assert_eq!(decode_text("地图".as_bytes()), "地图");
+        assert_eq!(decode_text(&[0xB5, 0xD8, 0xCD, 0xBC]), "地图");
+    }
+
+    #[test]
+    fn sanitize_normalizes_to_nfc() {
+        // “é” 的分解形式（e + U+0301）
+        let name = sanitize_file_name("cafe\u{301}.vpk").unwrap();
+        assert_eq!(name, "caf\u{e9}.vpk");
+    }
+
+    #[test]
+    fn sanitize_replaces_invalid_characters() {
+        assert_eq!(
+            sanitize_file_name("a<b>:c?.zip. ").as_deref(),
+            Some("a_b__c_.zip")
+        );
+        assert_eq!(sanitize_file_name("con.zip").as_deref(), Some("_con.zip"));
+        assert_eq!(sanitize_file_name(" .. "), None);
+    }
+
+    #[test]
+    fn sanitize_truncates_and_keeps_extension() {
+        let name = sanitize_file_name(&format!("{}.zip", "地".repeat(200))).unwrap();
+        assert!(name.ends_with(".zip"));
+        assert_eq!(name.encode_utf16().count(), MAX_FILE_NAME_UNITS);
+    }
+
+    #[test]
+    fn sanitize_does_not_split_surrogate_pairs() {
+        // 每个 emoji 占两个 UTF-16 码元，去掉扩展名后剩余的码元数为奇数
+        let name = sanitize_file_name(&format!("{}.7z", "😀".repeat(100))).unwrap();
+        let stem = name.strip_suffix(".7z").unwrap();
+        assert!(stem.chars().all(|c| c == '😀'));
+        assert_eq!(
+            name.encode_utf16().count(),
+            MAX_FILE_NAME_UNITS - 1,
+            "奇数个剩余码元时少截取一个字符，而不是截断代理对"
+        );
     }
 }
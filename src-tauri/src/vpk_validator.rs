@@ -0,0 +1,241 @@
+// vpk_validator.rs 模块 - 校验解压出的 VPK 文件：检查文件头签名（0x55aa1234）和版本，
+// 并完整解析目录树，确认每个条目都能读取且数据位于文件范围内，避免损坏的附加组件导致游戏崩溃
+
+// 标准库导入
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+// 第三方库导入
+use serde::Serialize;
+
+// 内部模块导入
+use crate::log_warn;
+
+/// VPK 文件头签名
+const VPK_SIGNATURE: u32 = 0x55aa_1234;
+
+/// 条目数据位于目录文件本身（而不是 _000.vpk 等分卷文件）时的分卷索引
+const DIR_ARCHIVE_INDEX: u16 = 0x7fff;
+
+/// 条目结束标记
+const ENTRY_TERMINATOR: u16 = 0xffff;
+
+/// 目录树大小的上限，超过时视为文件头损坏
+const MAX_TREE_SIZE: u32 = 256 * 1024 * 1024;
+
+/// 无效的 VPK 文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidVpk {
+    /// 相对于解压目录的路径
+    pub path: String,
+    /// 无效原因
+    pub error: String,
+}
+
+/// 目录树读取器
+struct TreeReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl TreeReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| format!("目录树在偏移 {} 处意外结束", self.pos))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// 读取以0结尾的字符串
+    fn string(&mut self) -> Result<String, String> {
+        let rest = &self.data[self.pos..];
+        let len = rest
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or_else(|| format!("目录树在偏移 {} 处缺少字符串结束符", self.pos))?;
+        let value = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(value)
+    }
+}
+
+/// 读取文件头中的 u32
+fn read_u32(reader: &mut impl Read) -> Result<u32, String> {
+    let mut bytes = [0u8; 4];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|_| "文件头不完整".to_string())?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// 解析目录树，返回条目数量
+fn parse_tree(tree: &[u8], data_size: u64) -> Result<usize, String> {
+    let mut reader = TreeReader { data: tree, pos: 0 };
+    let mut entries = 0;
+
+    loop {
+        let extension = reader.string()?;
+        if extension.is_empty() {
+            break;
+        }
+        loop {
+            let directory = reader.string()?;
+            if directory.is_empty() {
+                break;
+            }
+            loop {
+                let name = reader.string()?;
+                if name.is_empty() {
+                    break;
+                }
+                let _crc = reader.u32()?;
+                let preload_bytes = reader.u16()?;
+                let archive_index = reader.u16()?;
+                let offset = reader.u32()?;
+                let length = reader.u32()?;
+                if reader.u16()? != ENTRY_TERMINATOR {
+                    return Err(format!(
+                        "条目 {}/{}.{} 的结束标记无效",
+                        directory, name, extension
+                    ));
+                }
+                reader.take(preload_bytes as usize)?;
+                if archive_index == DIR_ARCHIVE_INDEX && offset as u64 + length as u64 > data_size {
+                    return Err(format!(
+                        "条目 {}/{}.{} 的数据超出文件范围",
+                        directory, name, extension
+                    ));
+                }
+                entries += 1;
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// 校验 VPK 文件
+///
+/// 检查文件头签名和版本，解析整个目录树；数据位于本文件中的条目还会检查是否超出文件范围。
+///
+/// # 参数
+/// - `path`: VPK 文件路径
+///
+/// # 返回值
+/// - 有效时返回Ok(条目数量)，无效时返回错误信息
+pub fn validate_vpk(path: &Path) -> Result<usize, String> {
+    let file = File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| format!("读取文件信息失败: {}", e))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let signature = read_u32(&mut reader)?;
+    if signature != VPK_SIGNATURE {
+        return Err(format!("文件头签名无效: 0x{:08x}", signature));
+    }
+    let version = read_u32(&mut reader)?;
+    let tree_size = read_u32(&mut reader)?;
+    let (header_size, data_size) = match version {
+        1 => (12u64, None),
+        2 => {
+            let data_size = read_u32(&mut reader)?;
+            // 跳过分卷MD5、本文件MD5和签名部分的大小
+            for _ in 0..3 {
+                read_u32(&mut reader)?;
+            }
+            (28u64, Some(data_size as u64))
+        }
+        _ => return Err(format!("不支持的版本: {}", version)),
+    };
+    if tree_size > MAX_TREE_SIZE || header_size + tree_size as u64 > file_size {
+        return Err(format!("目录树大小无效: {}", tree_size));
+    }
+    let available = file_size - header_size - tree_size as u64;
+    let data_size = data_size.unwrap_or(available);
+    if data_size > available {
+        return Err("文件数据不完整".to_string());
+    }
+
+    let mut tree = vec![0u8; tree_size as usize];
+    reader
+        .read_exact(&mut tree)
+        .map_err(|e| format!("读取目录树失败: {}", e))?;
+    parse_tree(&tree, data_size)
+}
+
+/// 判断是否为分卷数据文件（例如 pak01_000.vpk），分卷文件没有文件头，不单独校验
+fn is_archive_part(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.rsplit_once('_'))
+        .is_some_and(|(_, index)| index.len() == 3 && index.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// 递归收集目录中的 VPK 文件
+fn collect_vpks(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_vpks(&path, files);
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("vpk"))
+            && !is_archive_part(&path)
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// 校验目录中的所有 VPK 文件
+///
+/// # 参数
+/// - `dir`: 解压目录
+///
+/// # 返回值
+/// - 无效的 VPK 文件列表，全部有效时为空
+pub fn find_invalid_vpks(dir: &Path) -> Vec<InvalidVpk> {
+    let mut files = Vec::new();
+    collect_vpks(dir, &mut files);
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let error = validate_vpk(&path).err()?;
+            let relative = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            log_warn!("VPK 文件无效: {}, 原因: {}", path.display(), error);
+            Some(InvalidVpk {
+                path: relative,
+                error,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,165 @@
+// window_state.rs 模块 - 记住主窗口、文件管理器和服务器列表窗口的位置和大小，保存在 config.json 的 window_state 中
+
+// 第三方库导入
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Position, Size};
+
+// 内部模块导入
+use crate::{config_manager, log_debug, log_error, log_warn};
+
+/// 配置文件中保存窗口状态的键
+const WINDOW_STATE_KEY: &str = "window_state";
+
+/// 需要记住位置和大小的窗口
+const TRACKED_WINDOWS: &[&str] = &["main", "filemanager", "serverlist", "downloads"];
+
+/// 恢复位置时窗口与显示器至少要重叠的像素数，避免窗口恢复到已断开的显示器上
+const MIN_VISIBLE_PIXELS: i64 = 100;
+
+/// 窗口位置和大小（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// 关闭时是否处于最大化状态（此时其余字段为最大化之前的位置和大小）
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// 读取保存的全部窗口状态
+fn read_window_states(app_handle: &AppHandle) -> Map<String, Value> {
+    config_manager::read_config(app_handle.clone(), "config.json")
+        .ok()
+        .and_then(|config| match config.get(WINDOW_STATE_KEY) {
+            Some(Value::Object(map)) => Some(map.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// 读取指定窗口保存的位置和大小
+fn read_window_geometry(app_handle: &AppHandle, label: &str) -> Option<WindowGeometry> {
+    read_window_states(app_handle)
+        .get(label)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+/// 检查窗口区域是否与任一显示器有足够的重叠
+fn is_on_screen(window: &tauri::WebviewWindow, geometry: &WindowGeometry) -> bool {
+    let monitors = match window.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            log_warn!("获取显示器列表失败: {:?}", e);
+            return false;
+        }
+    };
+
+    let (left, top) = (geometry.x as i64, geometry.y as i64);
+    let (right, bottom) = (left + geometry.width as i64, top + geometry.height as i64);
+
+    monitors.iter().any(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        let (m_left, m_top) = (position.x as i64, position.y as i64);
+        let (m_right, m_bottom) = (m_left + size.width as i64, m_top + size.height as i64);
+
+        let overlap_width = right.min(m_right) - left.max(m_left);
+        let overlap_height = bottom.min(m_bottom) - top.max(m_top);
+        overlap_width >= MIN_VISIBLE_PIXELS && overlap_height >= MIN_VISIBLE_PIXELS
+    })
+}
+
+/// 保存窗口的位置和大小 - 在窗口关闭（隐藏）前调用
+///
+/// 最小化的窗口不保存；最大化的窗口只记录最大化状态，保留之前保存的位置和大小
+///
+/// # 参数
+/// - `window`: 要保存的窗口
+pub fn save_window_geometry(window: &tauri::Window) {
+    let label = window.label();
+    if !TRACKED_WINDOWS.contains(&label) || window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let app_handle = window.app_handle();
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = if maximized {
+        match read_window_geometry(app_handle, label) {
+            Some(previous) => WindowGeometry {
+                maximized: true,
+                ..previous
+            },
+            None => return,
+        }
+    } else {
+        match (window.outer_position(), window.inner_size()) {
+            (Ok(position), Ok(size)) => WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized: false,
+            },
+            _ => return,
+        }
+    };
+
+    let mut states = read_window_states(app_handle);
+    states.insert(
+        label.to_string(),
+        serde_json::to_value(geometry).unwrap_or_default(),
+    );
+    if let Err(e) = config_manager::merge_config(
+        app_handle,
+        "config.json",
+        serde_json::json!({ WINDOW_STATE_KEY: states }),
+    ) {
+        log_error!("保存{}窗口位置失败: {}", label, e);
+    } else {
+        log_debug!("已保存{}窗口位置: {:?}", label, geometry);
+    }
+}
+
+/// 恢复窗口上次关闭时的位置和大小
+///
+/// # 参数
+/// - `window`: 要恢复的窗口
+///
+/// # 返回值
+/// - 成功恢复时返回true；没有保存的位置或保存的位置不在任何显示器上时返回false
+pub fn restore_window_geometry(window: &tauri::WebviewWindow) -> bool {
+    let label = window.label();
+    let geometry = match read_window_geometry(window.app_handle(), label) {
+        Some(geometry) => geometry,
+        None => return false,
+    };
+
+    if !is_on_screen(window, &geometry) {
+        log_warn!("{}窗口保存的位置不在任何显示器上，忽略", label);
+        return false;
+    }
+
+    if let Err(e) = window.set_size(Size::Physical(PhysicalSize {
+        width: geometry.width,
+        height: geometry.height,
+    })) {
+        log_error!("恢复{}窗口大小失败: {:?}", label, e);
+    }
+    if let Err(e) = window.set_position(Position::Physical(PhysicalPosition {
+        x: geometry.x,
+        y: geometry.y,
+    })) {
+        log_error!("恢复{}窗口位置失败: {:?}", label, e);
+    }
+    if geometry.maximized {
+        if let Err(e) = window.maximize() {
+            log_error!("恢复{}窗口最大化状态失败: {:?}", label, e);
+        }
+    }
+
+    log_debug!("已恢复{}窗口位置: {:?}", label, geometry);
+    true
+}
@@ -0,0 +1,290 @@
+const {
+  core: { invoke },
+  event: { listen },
+} = window.__TAURI__;
+
+/** 任务ID -> 任务信息 */
+const tasks = new Map();
+
+/** 任务状态显示文本 */
+const STATE_TEXT = {
+  waiting: "等待中",
+  paused: "已暂停",
+  active: "下载中",
+  retrying: "等待重试",
+  finished: "已完成",
+  failed: "失败",
+};
+
+/**
+ * 格式化速度
+ * @param {number} bytesPerSecond - 每秒字节数
+ * @returns {string} 速度文本
+ */
+function formatSpeed(bytesPerSecond) {
+  if (!bytesPerSecond) return "0 KB/s";
+  if (bytesPerSecond >= 1024 * 1024)
+    return `${(bytesPerSecond / 1024 / 1024).toFixed(2)} MB/s`;
+  return `${(bytesPerSecond / 1024).toFixed(1)} KB/s`;
+}
+
+/**
+ * 格式化剩余时间
+ * @param {number} seconds - 剩余秒数
+ * @returns {string} 剩余时间文本
+ */
+function formatEta(seconds) {
+  if (!seconds) return "--";
+  const h = Math.floor(seconds / 3600);
+  const m = Math.floor((seconds % 3600) / 60);
+  const s = seconds % 60;
+  return h > 0 ? `${h}时${m}分` : m > 0 ? `${m}分${s}秒` : `${s}秒`;
+}
+
+/**
+ * 将 download-task-detail 事件内容转换为列表中的任务信息
+ * @param {object} detail - 事件内容
+ * @returns {object} 任务信息
+ */
+function fromDetail(detail) {
+  const task = detail.task || {};
+  const finished = detail.finished;
+  const state =
+    detail.state === "finished" && finished && !finished.success
+      ? "failed"
+      : detail.state;
+  return {
+    id: detail.taskId,
+    url: task.url || finished?.url || "",
+    filename: task.filename || finished?.filename,
+    state,
+    attempt: task.attempt,
+    maxAttempts: detail.maxAttempts,
+    progress: detail.progress || {},
+    message: finished?.message,
+  };
+}
+
+/**
+ * 从 get_queue_state 的结果重建任务列表
+ * @param {object} state - 下载队列完整状态
+ */
+function loadQueueState(state) {
+  tasks.clear();
+  state.active.forEach((task) =>
+    tasks.set(task.id, { ...task, state: "active", progress: task }),
+  );
+  state.retrying.forEach((task) =>
+    tasks.set(task.id, { ...task, state: "retrying" }),
+  );
+  state.waiting.forEach((task) =>
+    tasks.set(task.id, { ...task, state: "waiting" }),
+  );
+  state.paused.forEach((task) =>
+    tasks.set(task.id, { ...task, state: "paused" }),
+  );
+  state.finished.forEach((task) =>
+    tasks.set(task.id, {
+      ...task,
+      state: task.success ? "finished" : "failed",
+    }),
+  );
+  renderTaskList();
+}
+
+/**
+ * 生成任务的统计信息文本
+ * @param {object} task - 任务信息
+ * @returns {string} 统计信息
+ */
+function taskStats(task) {
+  const progress = task.progress || {};
+  switch (task.state) {
+    case "active":
+      return `${Number(progress.completedSize || 0).toFixed(2)} / ${Number(
+        progress.totalSize || 0,
+      ).toFixed(2)} MB · ${formatSpeed(progress.downloadSpeed)} · 剩余 ${formatEta(
+        progress.eta,
+      )}`;
+    case "retrying":
+      return task.maxAttempts
+        ? `第 ${task.attempt + 1}/${task.maxAttempts} 次尝试`
+        : "";
+    case "finished":
+    case "failed":
+      return task.message || "";
+    default:
+      return "";
+  }
+}
+
+/**
+ * 渲染下载列表
+ */
+function renderTaskList() {
+  const taskList = document.getElementById("taskList");
+  const template = document.getElementById("taskItemTemplate");
+
+  if (tasks.size === 0) {
+    taskList.innerHTML = '<p class="loading">暂无下载任务</p>';
+    return;
+  }
+
+  taskList.innerHTML = "";
+
+  tasks.forEach((task) => {
+    const clone = template.content.cloneNode(true);
+    const taskItem = clone.querySelector(".task-item");
+
+    taskItem.setAttribute("data-id", task.id);
+    taskItem.setAttribute("data-state", task.state);
+    taskItem.querySelector(".task-name").textContent =
+      task.filename || task.url;
+    taskItem.querySelector(".task-state").textContent =
+      STATE_TEXT[task.state] || task.state;
+    taskItem.querySelector(".task-url").textContent = task.url;
+
+    const percent =
+      task.state === "finished" ? 100 : Number(task.progress?.progress) || 0;
+    taskItem.querySelector(".task-progress-bar").style.width = `${percent}%`;
+    taskItem.querySelector(".task-stats").textContent = taskStats(task);
+
+    const pauseButton = taskItem.querySelector(".pause-btn");
+    pauseButton.textContent = task.state === "paused" ? "继续" : "暂停";
+    pauseButton.addEventListener("click", () => {
+      togglePause(task);
+    });
+
+    taskItem.querySelector(".cancel-btn").addEventListener("click", () => {
+      cancelTask(task.id);
+    });
+
+    taskList.appendChild(clone);
+  });
+}
+
+/**
+ * 取消下载任务
+ * @param {string} taskId - 任务ID
+ */
+async function cancelTask(taskId) {
+  try {
+    await invoke("cancel_download", { taskId });
+  } catch (error) {
+    console.error("取消下载失败:", error);
+    alert(`取消下载失败: ${error.message || error}`);
+  }
+}
+
+/**
+ * 暂停等待中的任务或恢复已暂停的任务
+ * @param {object} task - 任务信息
+ */
+async function togglePause(task) {
+  const command =
+    task.state === "paused" ? "resume_download" : "pause_download";
+  try {
+    await invoke(command, { taskId: task.id });
+  } catch (error) {
+    console.error("暂停或恢复下载失败:", error);
+    alert(`操作失败: ${error.message || error}`);
+  }
+}
+
+/**
+ * 显示下载引擎状态：运行中为绿色，崩溃或RPC接口没有响应为红色，未运行为灰色
+ * @param {string} state - running、error 或 stopped
+ * @param {string} text - 状态文本
+ */
+function showEngineStatus(state, text) {
+  const engineStatus = document.getElementById("engineStatus");
+  engineStatus.dataset.state = state;
+  engineStatus.textContent = text;
+}
+
+async function refreshEngineStatus() {
+  try {
+    const status = await invoke("get_engine_status");
+    if (status.running && status.rpcReachable) {
+      showEngineStatus("running", `下载引擎运行中 (aria2 ${status.version})`);
+    } else if (status.pid || status.transfers > 0) {
+      showEngineStatus("error", "下载引擎没有响应");
+    } else {
+      showEngineStatus("stopped", "下载引擎未运行");
+    }
+  } catch (error) {
+    console.error("获取下载引擎状态失败:", error);
+  }
+}
+
+/**
+ * 重新启动下载引擎（aria2c），用于修复卡住的下载
+ * @param {HTMLButtonElement} button - 重新启动按钮
+ */
+async function restartEngine(button) {
+  button.disabled = true;
+  try {
+    const count = await invoke("restart_download_engine");
+    alert(
+      count > 0
+        ? `下载引擎已重新启动，${count} 个任务将从断点继续`
+        : "没有需要恢复的下载任务",
+    );
+  } catch (error) {
+    console.error("重新启动下载引擎失败:", error);
+    alert(`重新启动下载引擎失败: ${error.message || error}`);
+  } finally {
+    button.disabled = false;
+  }
+}
+
+async function refresh() {
+  try {
+    loadQueueState(await invoke("get_queue_state"));
+  } catch (error) {
+    console.error("获取下载列表失败:", error);
+  }
+}
+
+async function main() {
+  const restartButton = document.getElementById("restartEngine");
+  restartButton.addEventListener("click", () => restartEngine(restartButton));
+
+  // 窗口隐藏期间不会收到任务详情事件，重新显示时重新获取完整状态
+  document.addEventListener("visibilitychange", () => {
+    if (document.visibilityState === "visible") refresh();
+  });
+
+  listen("download-task-detail", ({ payload }) => {
+    if (payload.state === "removed") {
+      tasks.delete(payload.taskId);
+    } else {
+      tasks.set(payload.taskId, fromDetail(payload));
+    }
+    renderTaskList();
+  });
+
+  // 任务被取消或队列被整理时只有队列事件，重新获取完整状态
+  listen("download-queue-update", refresh);
+
+  // 下载引擎崩溃时立即显示，重新启动或关闭后重新获取状态
+  listen("engine-status-changed", ({ payload }) => {
+    if (payload.state === "crashed") {
+      showEngineStatus("error", "下载引擎已崩溃");
+    } else {
+      refreshEngineStatus();
+    }
+  });
+
+  // 调试版本中本窗口的前端源码被修改时重新加载页面
+  listen("assets-reload", ({ payload }) => {
+    if (payload.files.some((file) => file.startsWith("downloads/"))) {
+      location.reload();
+    }
+  });
+
+  refreshEngineStatus();
+  return refresh();
+}
+
+main();
@@ -915,6 +915,22 @@ async function changeDataDir() {
   }
 }
 
+// 在资源管理器中打开数据目录
+async function openDataDir() {
+  try {
+    const config = await invoke("read_config", { configName: "config.json" });
+    if (config.nmd_data) {
+      await invoke("open_path_in_explorer", { path: config.nmd_data });
+    }
+  } catch (error) {
+    console.error("打开数据目录失败:", error);
+    await dialog.message(`打开数据目录失败: ${error.message || error}`, {
+      kind: "error",
+      title: "打开失败",
+    });
+  }
+}
+
 // 初始加载文件列表
 !(async function () {
   await loadDataDir();
@@ -923,6 +939,7 @@ async function changeDataDir() {
   document
     .getElementById("changeDirBtn")
     .addEventListener("click", changeDataDir);
+  document.getElementById("openDirBtn").addEventListener("click", openDataDir);
 
   // 使用事件委托处理分组删除按钮点击
   document.getElementById("fileList").addEventListener("click", async (e) => {
@@ -985,6 +1002,24 @@ async function changeDataDir() {
       }
     }
 
+    // 处理在资源管理器中显示分组按钮点击
+    const revealBtn = e.target.closest(".group-reveal-btn");
+    if (revealBtn) {
+      const groupName = decodeURIComponent(
+        revealBtn.getAttribute("data-group"),
+      );
+      try {
+        await invoke("reveal_map", { mapName: groupName });
+      } catch (error) {
+        console.error("打开地图位置失败:", error);
+        await dialog.message(`打开地图位置失败: ${error.message || error}`, {
+          kind: "error",
+          title: "打开失败",
+        });
+      }
+      return;
+    }
+
     // 处理组挂载/卸载按钮点击
     const mountBtn = e.target.closest(".group-mount-btn");
     if (mountBtn) {
@@ -1138,6 +1173,13 @@ async function changeDataDir() {
     .getElementById("batchDeleteBtn")
     .addEventListener("click", batchDeleteFiles);
 
+  // 调试版本中本窗口的前端源码被修改时重新加载页面
+  listen("assets-reload", ({ payload }) => {
+    if (payload.files.some((file) => file.startsWith("filemanager/"))) {
+      location.reload();
+    }
+  });
+
   // 初始加载文件列表
   loadFileList(2);
 })();
@@ -1,4 +1,20 @@
 !(function () {
+  // 运行时配置（版本、语言、数据目录、功能开关），后端提供脚本时替换占位符
+  window.__NMD_INJECTION_CONFIG__ = "__NMD_INJECTION_CONFIG__";
+
+  /**
+   * 获取运行时配置，占位符未被替换或需要最新配置时向后端获取
+   * @param {boolean} refresh - 是否重新获取
+   * @returns {Promise<object>} 运行时配置
+   */
+  async function getInjectionConfig(refresh = false) {
+    const config = window.__NMD_INJECTION_CONFIG__;
+    if (!refresh && config && typeof config === "object") return config;
+    window.__NMD_INJECTION_CONFIG__ =
+      await window.__TAURI__.core.invoke("get_injection_config");
+    return window.__NMD_INJECTION_CONFIG__;
+  }
+
   try {
     // 数据存储目录配置检查
     async function checkDataDirConfig() {
@@ -268,21 +284,35 @@
     // 将下载链接传递给后端处理
     async function handleDownloadLink(url, savepath = "", saveonly = false) {
       try {
-        let result = await window.__TAURI__.core.invoke("install", {
+        // 任务加入队列后立即返回，下载结果通过事件通知
+        let task = await window.__TAURI__.core.invoke("install", {
           url: url,
           savepath: savepath,
           saveonly: saveonly,
         });
+        console.log(
+          "Nyaser Maps Downloader: 已加入下载队列:",
+          task.filename,
+          task.taskId,
+        );
         return true;
       } catch (error) {
         console.error("Nyaser Maps Downloader: 处理下载链接失败:", error);
         // 为依赖错误提供更详细的帮助信息
         let errorMessage = error.message || "未知错误";
 
-        // 显示错误警告信息
-        warningDisplay.textContent = "错误: 下载失败 - " + errorMessage;
+        // 显示错误警告信息，被下载策略拒绝时直接显示拒绝原因，队列已满时显示为警告
+        const queueFull = error.code === "QUEUE_FULL";
+        warningDisplay.textContent =
+          error.code === "POLICY_DENIED"
+            ? "已被下载策略阻止: " + errorMessage
+            : queueFull
+              ? errorMessage
+              : "错误: 下载失败 - " + errorMessage;
         warningDisplay.style.display = "block";
-        warningDisplay.style.background = "rgba(244, 67, 54, 0.9)"; // 红色背景表示错误
+        warningDisplay.style.background = queueFull
+          ? "rgba(255, 152, 0, 0.9)" // 橙色背景表示警告
+          : "rgba(244, 67, 54, 0.9)"; // 红色背景表示错误
 
         // 10秒后自动隐藏错误警告
         setTimeout(() => {
@@ -456,6 +486,13 @@
                 `<path d="M98 351c-17.7 0-32-14.3-32-32V192.8c0-52.9 43.1-96 96-96h221c52.9 0 96 43.1 96 96V255c0 17.7-14.3 32-32 32s-32-14.3-32-32v-62.2c0-17.6-14.4-32-32-32H162c-17.6 0-32 14.4-32 32V319c0 17.6-14.3 32-32 32z" fill="#1890ff" p-id="4791"></path><path d="M864 926.6H383c-17.7 0-32-14.3-32-32s14.3-32 32-32h481c17.6 0 32-14.4 32-32V319c0-17.6-14.4-32-32-32H447c-17.7 0-32-14.3-32-32s14.3-32 32-32h417c52.9 0 96 43.1 96 96v511.7c0 52.9-43.1 95.9-96 95.9z" fill="#1890ff" p-id="4792"></path><path d="M383 926.6H162c-52.9 0-96-43.1-96-96V319c0-17.7 14.3-32 32-32s32 14.3 32 32v511.7c0 17.6 14.4 32 32 32h221c17.7 0 32 14.3 32 32 0 17.6-14.3 31.9-32 31.9zM768.1 511.2H256c-17.7 0-32-14.3-32-32s14.3-32 32-32h512.1c17.7 0 32 14.3 32 32s-14.3 32-32 32z" fill="#1890ff" p-id="4793"></path><path d="M768.1 703H256c-17.7 0-32-14.3-32-32s14.3-32 32-32h512.1c17.7 0 32 14.3 32 32s-14.3 32-32 32z" fill="#1890ff" p-id="4794"></path>`,
                 () => window.__TAURI__.core.invoke("open_filemanager_window"),
               );
+
+              // 创建下载列表按钮
+              createToolbarButton(
+                "downloads-button",
+                `<path d="M480 96h64v498.7l137.4-137.4 45.2 45.3L512 717.3 297.4 502.6l45.2-45.3L480 594.7V96z" fill="#1890ff"></path><path d="M160 640h64v192h576V640h64v256H160V640z" fill="#1890ff"></path>`,
+                () => window.__TAURI__.core.invoke("open_downloads_window"),
+              );
             }
           }
         }
@@ -783,7 +820,8 @@
       // 监听解压完成事件
       const extractCompleteUnlisten = listen("extract-complete", (event) => {
         // 接收到解压完成事件
-        const { filename, success, message, taskId } = event.payload || {};
+        const { filename, success, message, taskId, invalidVpks } =
+          event.payload || {};
         if (!taskId) return; // 如果没有taskId，忽略此事件
 
         // 对文件名进行URL解码
@@ -791,7 +829,22 @@
           ? decodeURIComponent(filename)
           : "未知文件";
 
-        if (success) {
+        if (success && invalidVpks && invalidVpks.length > 0) {
+          // 解压完成但有无效的VPK文件
+          warningDisplay.textContent =
+            "解压完成，但有 " +
+            invalidVpks.length +
+            " 个VPK文件已损坏: " +
+            decodedFilename;
+          warningDisplay.style.background = "rgba(255, 152, 0, 0.9)";
+
+          // 10秒后隐藏提示
+          setTimeout(() => {
+            warningDisplay.style.display = "none";
+          }, 10000);
+
+          console.warn("Nyaser Maps Downloader: 无效的VPK文件:", invalidVpks);
+        } else if (success) {
           // 显示解压完成提示
           warningDisplay.textContent = "解压完成: " + decodedFilename;
           warningDisplay.style.background = "rgba(76, 175, 80, 0.9)";
@@ -854,6 +907,18 @@
         });
       });
 
+      // 监听服务器提供文件名事件，更新显示的文件名
+      const filenameResolvedUnlisten = listen(
+        "download-filename-resolved",
+        (event) => {
+          const { taskId, filename } = event.payload || {};
+          const task = activeTasks.get(taskId);
+          if (!task || !filename) return;
+
+          task.filename.textContent = filename;
+        },
+      );
+
       // 监听下载任务添加事件
       const taskAddUnlisten = listen("download-task-add", (event) => {
         // 接收到下载任务添加事件
@@ -1031,9 +1096,15 @@
             const positionElement = document.createElement("div");
             positionElement.className = "nmd-queue-task-position";
             // 如果有活跃任务，位置为 index + 1；否则位置为 index（因为即将开始下载）
-            const position = hasActiveTasks ? index + 1 : 0;
+            const position = hasActiveTasks ? task.position || index + 1 : 0;
+            // 后端按进行中任务的剩余时间估算的开始时间（秒），无法估算时为 null
+            const minutes = Math.ceil((task.estimatedStart || 0) / 60);
             positionElement.textContent =
-              position > 0 ? `#${position}` : "即将开始";
+              position > 0
+                ? task.estimatedStart > 0
+                  ? `#${position} · 约${minutes}分钟后开始`
+                  : `#${position}`
+                : "即将开始";
 
             // 组装元素
             queueTaskElement.appendChild(filenameElement);
@@ -1292,6 +1363,77 @@
         }, 8000);
       });
 
+      // 调试版本中注入脚本被修改时重新加载页面
+      const assetsReloadUnlisten = listen("assets-reload", (event) => {
+        const { files } = event.payload || {};
+        if (files?.some((file) => file.startsWith("plugin/"))) {
+          location.reload();
+        }
+      });
+
+      // 监听订阅源发现新地图事件
+      const newMapUnlisten = listen("new-map-available", (event) => {
+        const { title, id, downloadUrls, queued } = event.payload || {};
+        if (!downloadUrls?.length) return;
+
+        warningDisplay.textContent = queued
+          ? `订阅源发现新地图，已加入下载队列: ${title || id}`
+          : `订阅源发现新地图: ${title || id}`;
+        warningDisplay.style.display = "block";
+        setTimeout(() => {
+          warningDisplay.style.display = "none";
+        }, 8000);
+      });
+
+      // 监听地图文件重名事件，由用户选择保留哪一个挂载
+      const mapConflictUnlisten = listen("map-conflict", async (event) => {
+        const { group, file, conflicts } = event.payload || {};
+        if (!group || !conflicts?.length) return;
+
+        const sources = conflicts
+          .map((source) =>
+            source.source === "group"
+              ? `地图 ${source.group} 中的 ${source.file}${source.mounted ? "（已挂载）" : ""}`
+              : `addons 目录中的 ${source.file}`,
+          )
+          .join("\n");
+        const keepNew = await window.__TAURI__.dialog.confirm(
+          `新安装的地图 ${group} 中的 ${file} 与以下文件重名，同时挂载时游戏只会加载其中一个：\n${sources}\n\n请选择保留哪一个挂载。`,
+          {
+            title: "地图文件重名",
+            okLabel: "保留新地图",
+            cancelLabel: "保留原有文件",
+          },
+        );
+
+        try {
+          if (keepNew) {
+            // addons 目录中的文件不是由本程序挂载的，需要用户自行处理
+            for (const source of conflicts) {
+              if (source.source === "group" && source.mounted) {
+                await window.__TAURI__.core.invoke("unmount_file", {
+                  groupName: source.group,
+                  fileName: source.file,
+                });
+              }
+            }
+          } else {
+            await window.__TAURI__.core.invoke("unmount_file", {
+              groupName: group,
+              fileName: file,
+            });
+          }
+        } catch (error) {
+          console.error("处理地图文件重名失败:", error);
+          warningDisplay.textContent =
+            "警告: 处理地图文件重名失败: " + (error.message || error);
+          warningDisplay.style.display = "block";
+          setTimeout(() => {
+            warningDisplay.style.display = "none";
+          }, 8000);
+        }
+      });
+
       // 监听下载失败事件
       const downloadFailedUnlisten = listen("download-failed", (event) => {
         // 接收到下载失败事件
@@ -1382,12 +1524,16 @@
         extractStartUnlisten,
         extractCompleteUnlisten,
         taskStartUnlisten,
+        filenameResolvedUnlisten,
         downloadFailedUnlisten,
         taskAddUnlisten,
         queueUpdateUnlisten,
         extractQueueUpdateUnlisten,
         dirChangedUnlisten,
         gameDirWarningUnlisten,
+        mapConflictUnlisten,
+        assetsReloadUnlisten,
+        newMapUnlisten,
         cancelDownloadUnlisten,
       ].forEach((fn) => window.addEventListener("beforeunload", fn));
 
@@ -1610,7 +1756,10 @@
           if (window.__TAURI__) {
             await window.__TAURI__.core.invoke("frontend_loaded");
             await window.__TAURI__.core.invoke("deep_link_ready");
-            console.log("Nyaser Maps Downloader: 已通知后端前端加载完成");
+            const config = await getInjectionConfig();
+            console.log(
+              `Nyaser Maps Downloader ${config.version}: 已通知后端前端加载完成`,
+            );
           }
         } catch (error) {
           console.error(
@@ -0,0 +1,64 @@
+const {
+  core: { invoke },
+  event: { listen },
+} = window.__TAURI__;
+
+/** 进度对话框ID，由后端在窗口地址中传入 */
+const dialogId = new URLSearchParams(location.search).get("id");
+
+/**
+ * 显示进度状态
+ * @param {object} state - 后端发送的进度状态
+ */
+function render(state) {
+  document.getElementById("title").textContent = state.title;
+  document.title = state.title;
+  if (state.message) {
+    document.getElementById("message").textContent = state.message;
+  }
+
+  const percent =
+    state.total > 0 ? Math.min(100, (state.current / state.total) * 100) : 0;
+  document.getElementById("progressBar").style.width = `${percent}%`;
+  document.getElementById("count").textContent =
+    state.total > 0 ? `${state.current} / ${state.total}` : "";
+
+  const cancelButton = document.getElementById("cancel");
+  cancelButton.style.display = state.cancellable ? "" : "none";
+  if (state.cancelled) {
+    cancelButton.disabled = true;
+    cancelButton.textContent = "正在取消...";
+  }
+}
+
+/**
+ * 请求取消当前操作
+ * @param {HTMLButtonElement} button - 取消按钮
+ */
+async function cancel(button) {
+  button.disabled = true;
+  button.textContent = "正在取消...";
+  try {
+    await invoke("cancel_progress_dialog", { id: dialogId });
+  } catch (error) {
+    console.error("取消操作失败:", error);
+    button.disabled = false;
+    button.textContent = "取消";
+  }
+}
+
+async function main() {
+  const cancelButton = document.getElementById("cancel");
+  cancelButton.addEventListener("click", () => cancel(cancelButton));
+
+  listen("progress-dialog-update", ({ payload }) => render(payload));
+
+  // 窗口加载前发送的进度不会收到，加载完成后获取一次最新状态
+  try {
+    render(await invoke("get_progress_dialog_state", { id: dialogId }));
+  } catch (error) {
+    console.error("获取进度失败:", error);
+  }
+}
+
+main();
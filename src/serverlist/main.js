@@ -59,7 +59,10 @@ function renderServerList(servers) {
     }
 
     serverItem.querySelector(".server-name").textContent = server.name;
-    serverItem.querySelector(".server-url").textContent = server.url;
+    serverItem.querySelector(".server-url").textContent =
+      server.ping == null
+        ? server.url
+        : `${server.url} · ${Math.round(server.ping)} ms`;
 
     const openBtn = serverItem.querySelector(".open-btn");
     openBtn.addEventListener("click", (e) => {
@@ -92,14 +95,44 @@ async function openServerWindow(url, name, icon) {
     });
   } catch (error) {
     console.error("打开窗口失败:", error);
-    alert(`打开窗口失败: ${error}`);
+    alert(`打开窗口失败: ${error.message || error}`);
   }
 }
 
+/**
+ * 测量所有服务器的延迟，按延迟从低到高重新渲染列表（无法连接的排在最后）
+ * @param {Array} servers - 服务器列表数据
+ */
+async function sortServersByPing(servers) {
+  const { core: { invoke } } = window.__TAURI__;
+  const measured = await Promise.all(
+    servers.map((server) =>
+      invoke("ping_server", {
+        address: server.address || server.url,
+        samples: 3,
+      })
+        .then((result) => ({ ...server, ping: result.avg }))
+        .catch(() => ({ ...server, ping: null })),
+    ),
+  );
+  measured.sort((a, b) => (a.ping ?? Infinity) - (b.ping ?? Infinity));
+  renderServerList(measured);
+}
+
 async function main() {
+  // 调试版本中本窗口的前端源码被修改时重新加载页面
+  window.__TAURI__.event.listen("assets-reload", ({ payload }) => {
+    if (payload.files.some((file) => file.startsWith("serverlist/"))) {
+      location.reload();
+    }
+  });
+
   return import(getAssets("serverlist/list.json"), {
     with: { type: "json" },
-  }).then(({ default: servers }) => renderServerList(servers));
+  }).then(({ default: servers }) => {
+    renderServerList(servers);
+    return sortServersByPing(servers);
+  });
 }
 
 main();